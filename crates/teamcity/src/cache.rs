@@ -0,0 +1,409 @@
+use super::types::BuildType;
+use crate::error::Result;
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::{info, warn};
+
+pub const DEFAULT_CACHE_MAX_SIZE: u64 = 10 * 1024 * 1024;
+const CACHE_FILE_PREFIX: &str = "build_configs_";
+const CACHE_FILE_EXTENSION: &str = ".json.gz";
+// cache files were plain JSON before they were gzipped
+const UNCOMPRESSED_CACHE_FILE_EXTENSION: &str = ".json";
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub(super) struct PersistentCacheEntry<T> {
+    pub(super) data: T,
+    timestamp: u64,
+    ttl_seconds: u64,
+    // when the entry was last read or written, the least recently used ones are evicted first
+    #[serde(default)]
+    last_used: u64,
+}
+
+impl<T> PersistentCacheEntry<T> {
+    pub(super) fn new(data: T, ttl: Duration) -> Self {
+        let timestamp = now_secs();
+
+        Self {
+            data,
+            timestamp,
+            ttl_seconds: ttl.as_secs(),
+            last_used: timestamp,
+        }
+    }
+
+    fn is_expired(&self) -> bool {
+        self.is_older_than(Duration::from_secs(self.ttl_seconds))
+    }
+
+    pub(super) fn is_older_than(&self, ttl: Duration) -> bool {
+        now_secs() > self.timestamp + ttl.as_secs()
+    }
+
+    pub(super) fn touch(&mut self) {
+        self.last_used = now_secs();
+    }
+}
+
+/// Build configurations of each project, stored gzipped in one file per server
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub(super) struct PersistentCache {
+    pub(super) entries: HashMap<String, PersistentCacheEntry<Vec<BuildType>>>,
+}
+
+impl PersistentCache {
+    /// Cache stored in `path` without its expired entries, an empty one if it can't be read
+    pub(super) async fn load(path: &Path) -> Self {
+        info!("Loading cache from {}", path.display());
+        match Self::read(path).await {
+            Ok(mut cache) => {
+                cache.entries.retain(|_, entry| !entry.is_expired());
+                cache
+            }
+            Err(_) => Self::default(),
+        }
+    }
+
+    async fn read(path: &Path) -> Result<Self> {
+        Self::decode(&async_fs::read(path).await?)
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self> {
+        let mut json = String::new();
+        GzDecoder::new(bytes).read_to_string(&mut json)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    /// Applies `change` to the cache stored in `path` as it is now and writes it back, evicting the
+    /// least recently used entries until it takes at most `max_size` bytes. Other instances
+    /// updating the same file wait on its lock, so their entries are merged rather than
+    /// overwritten, and readers only ever see a complete file.
+    pub(super) async fn update(
+        path: &Path,
+        max_size: u64,
+        change: impl FnOnce(&mut PersistentCache) + Send + 'static,
+    ) -> Result<()> {
+        let path = path.to_path_buf();
+        tokio::task::spawn_blocking(move || Self::update_blocking(&path, max_size, change))
+            .await
+            .map_err(std::io::Error::other)?
+    }
+
+    fn update_blocking(
+        path: &Path,
+        max_size: u64,
+        change: impl FnOnce(&mut PersistentCache),
+    ) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let lock = File::create(path.with_extension("lock"))?;
+        lock.lock()?;
+
+        let mut cache = match std::fs::read(path) {
+            Ok(bytes) => Self::decode(&bytes).unwrap_or_else(|e| {
+                warn!("Replacing unreadable cache {}: {}", path.display(), e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        };
+        cache.entries.retain(|_, entry| !entry.is_expired());
+        change(&mut cache);
+        let content = cache.encode_within(max_size)?;
+
+        // renaming replaces the file at once, a reader never sees half of it
+        let tmp = path.with_extension(format!("tmp{}", std::process::id()));
+        std::fs::write(&tmp, content)?;
+        std::fs::rename(&tmp, path)?;
+        remove_uncompressed(path);
+        Ok(())
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&serde_json::to_vec(self)?)?;
+        Ok(encoder.finish()?)
+    }
+
+    /// Encodes the cache, evicting the least recently used entries until it takes at most
+    /// `max_size` bytes
+    fn encode_within(&mut self, max_size: u64) -> Result<Vec<u8>> {
+        let content = self.encode()?;
+        if content.len() as u64 <= max_size || self.entries.is_empty() {
+            return Ok(content);
+        }
+
+        // each entry is compressed once on its own; entries compress better together, so their
+        // sizes are scaled down to the share of the whole file they take
+        let mut sizes = Vec::with_capacity(self.entries.len());
+        for (key, entry) in &self.entries {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(&serde_json::to_vec(&(key, entry))?)?;
+            sizes.push((entry.last_used, key.clone(), encoder.finish()?.len() as u64));
+        }
+        let total: u64 = sizes.iter().map(|(_, _, size)| size).sum();
+        let ratio = content.len() as f64 / total.max(1) as f64;
+        let mut estimate = content.len() as f64;
+
+        sizes.sort();
+        let mut by_recency = sizes.into_iter();
+        while estimate > max_size as f64 {
+            let Some((_, least_recent, size)) = by_recency.next() else {
+                break;
+            };
+            warn!(
+                "Cache is over {} bytes, evicting {}",
+                max_size, least_recent
+            );
+            self.entries.remove(&least_recent);
+            estimate -= size as f64 * ratio;
+        }
+        let mut content = self.encode()?;
+
+        // the estimate can be a little off, the last entries go one at a time
+        while content.len() as u64 > max_size {
+            let Some((_, least_recent, _)) = by_recency.next() else {
+                break;
+            };
+            warn!(
+                "Cache is over {} bytes, evicting {}",
+                max_size, least_recent
+            );
+            self.entries.remove(&least_recent);
+            content = self.encode()?;
+        }
+        Ok(content)
+    }
+}
+
+/// Cache file of the server at `base_url` in `dir`, one per server so profiles don't overwrite
+/// each other's caches
+pub(super) fn cache_file_path(dir: &Path, base_url: &str) -> PathBuf {
+    let server = base_url
+        .split_once("://")
+        .map_or(base_url, |(_, rest)| rest)
+        .trim_end_matches('/');
+    let name: String = server
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    dir.join(format!(
+        "{}{}{}",
+        CACHE_FILE_PREFIX, name, CACHE_FILE_EXTENSION
+    ))
+}
+
+/// Removes the plain JSON file left next to the gzipped cache file at `path` by older versions
+fn remove_uncompressed(path: &Path) {
+    let uncompressed = path.with_extension("");
+    match std::fs::remove_file(&uncompressed) {
+        Ok(()) => info!("Removed uncompressed cache {}", uncompressed.display()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => warn!(
+            "Failed to remove uncompressed cache {}: {}",
+            uncompressed.display(),
+            e
+        ),
+    }
+}
+
+/// Cache files of all servers in `dir`
+async fn cache_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    files_with_extension(dir, CACHE_FILE_EXTENSION).await
+}
+
+/// Cache files with the name of a server's cache file ending in `extension` in `dir`
+async fn files_with_extension(dir: &Path, extension: &str) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut entries = match async_fs::read_dir(dir).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(files),
+        Err(e) => return Err(e.into()),
+    };
+    while let Some(entry) = entries.next().await {
+        let path = entry?.path();
+        let is_cache_file = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with(CACHE_FILE_PREFIX) && name.ends_with(extension));
+        if is_cache_file {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Size and entries of a server's cache file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheFileStats {
+    pub path: PathBuf,
+    pub size: u64,
+    pub entries: usize,
+    pub expired: usize,
+}
+
+/// Stats of the cache file of every server in `dir`
+pub async fn cache_stats(dir: &Path) -> Result<Vec<CacheFileStats>> {
+    let mut stats = Vec::new();
+    for path in cache_files(dir).await? {
+        let size = async_fs::metadata(&path).await?.len();
+        let (entries, expired) = match PersistentCache::read(&path).await {
+            Ok(cache) => (
+                cache.entries.len(),
+                cache.entries.values().filter(|e| e.is_expired()).count(),
+            ),
+            Err(e) => {
+                warn!("Failed to read cache {}: {}", path.display(), e);
+                (0, 0)
+            }
+        };
+        stats.push(CacheFileStats {
+            path,
+            size,
+            entries,
+            expired,
+        });
+    }
+    Ok(stats)
+}
+
+/// Removes the cache files of all servers in `dir`, along with uncompressed ones of older versions,
+/// returns how many there were
+pub async fn clear_cache(dir: &Path) -> Result<usize> {
+    let mut files = cache_files(dir).await?;
+    files.extend(files_with_extension(dir, UNCOMPRESSED_CACHE_FILE_EXTENSION).await?);
+    for path in &files {
+        async_fs::remove_file(path).await?;
+    }
+    Ok(files.len())
+}
+
+/// Drops the expired entries of every cache file in `dir` and shrinks each to `max_size` bytes,
+/// removing uncompressed ones of older versions, returns the stats of the pruned files
+pub async fn prune_cache(dir: &Path, max_size: u64) -> Result<Vec<CacheFileStats>> {
+    for path in cache_files(dir).await? {
+        PersistentCache::update(&path, max_size, |_| {}).await?;
+    }
+    for path in files_with_extension(dir, UNCOMPRESSED_CACHE_FILE_EXTENSION).await? {
+        async_fs::remove_file(&path).await?;
+    }
+    cache_stats(dir).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(last_used: u64) -> PersistentCacheEntry<Vec<BuildType>> {
+        let build_types = (0..50)
+            .map(|i| BuildType {
+                id: format!("Project_Build{}_{}", i, last_used),
+                name: format!("Build {} of a rather long configuration name", i),
+                ..BuildType::default()
+            })
+            .collect();
+        PersistentCacheEntry {
+            last_used,
+            ..PersistentCacheEntry::new(build_types, Duration::from_secs(3600))
+        }
+    }
+
+    #[test]
+    fn cache_file_is_named_after_the_server() {
+        let dir = Path::new("/cache");
+        assert_eq!(
+            cache_file_path(dir, "https://tc.example.com/"),
+            dir.join("build_configs_tc_example_com.json.gz")
+        );
+        assert_eq!(
+            cache_file_path(dir, "http://ci.local:8111/teamcity"),
+            dir.join("build_configs_ci_local_8111_teamcity.json.gz")
+        );
+    }
+
+    #[test]
+    fn least_recently_used_entries_are_evicted_to_fit() {
+        let mut cache = PersistentCache::default();
+        for (key, last_used) in [("old", 1), ("recent", 3), ("middle", 2)] {
+            cache.entries.insert(key.to_string(), entry(last_used));
+        }
+        let full_size = cache.encode().unwrap().len() as u64;
+
+        let content = cache.encode_within(full_size).unwrap();
+        assert_eq!(content.len() as u64, full_size);
+        assert_eq!(cache.entries.len(), 3);
+
+        let content = cache.encode_within(full_size - 1).unwrap();
+        assert!((content.len() as u64) < full_size);
+        assert_eq!(content.len(), cache.encode().unwrap().len());
+        assert!(!cache.entries.contains_key("old"));
+        assert!(cache.entries.contains_key("recent"));
+
+        let mut json = String::new();
+        GzDecoder::new(content.as_slice())
+            .read_to_string(&mut json)
+            .unwrap();
+        let decoded: PersistentCache = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.entries.len(), cache.entries.len());
+    }
+
+    #[test]
+    fn updates_are_merged_with_the_file() {
+        let dir = std::env::temp_dir().join(format!("t9s-cache-{}", std::process::id()));
+        let path = cache_file_path(&dir, "https://tc.example.com");
+
+        // two instances that loaded the cache before either of them saved
+        PersistentCache::update_blocking(&path, DEFAULT_CACHE_MAX_SIZE, |cache| {
+            cache.entries.insert("project_A".to_string(), entry(1));
+        })
+        .unwrap();
+        PersistentCache::update_blocking(&path, DEFAULT_CACHE_MAX_SIZE, |cache| {
+            cache.entries.insert("project_B".to_string(), entry(2));
+        })
+        .unwrap();
+        let cache = PersistentCache::decode(&std::fs::read(&path).unwrap()).unwrap();
+        let mut keys: Vec<&String> = cache.entries.keys().collect();
+        keys.sort();
+        assert_eq!(keys, vec!["project_A", "project_B"]);
+
+        PersistentCache::update_blocking(&path, DEFAULT_CACHE_MAX_SIZE, |cache| {
+            cache.entries.remove("project_A");
+        })
+        .unwrap();
+        let cache = PersistentCache::decode(&std::fs::read(&path).unwrap()).unwrap();
+        assert!(!cache.entries.contains_key("project_A"));
+        assert!(cache.entries.contains_key("project_B"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn uncompressed_cache_files_are_removed_on_save() {
+        let dir = std::env::temp_dir().join(format!("t9s-cache-legacy-{}", std::process::id()));
+        let path = cache_file_path(&dir, "https://tc.example.com");
+        let uncompressed = dir.join("build_configs_tc_example_com.json");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(&uncompressed, "{}").unwrap();
+
+        PersistentCache::update_blocking(&path, DEFAULT_CACHE_MAX_SIZE, |_| {}).unwrap();
+
+        assert!(path.exists());
+        assert!(!uncompressed.exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,57 @@
+use serde::Deserialize;
+use std::path::PathBuf;
+
+use crate::DEFAULT_BUILD_COUNT;
+
+/// Builds fetched by [`TeamCityClient::get_builds_by_project`](crate::TeamCityClient::get_builds_by_project)
+#[derive(Clone, Debug, Deserialize)]
+pub struct BuildsConfig {
+    /// Number of builds fetched, the most recent first
+    #[serde(default = "BuildsConfig::default_count")]
+    pub count: u32,
+    /// Fields of each build requested from the REST API, replacing the default ones
+    #[serde(default)]
+    pub fields: Option<String>,
+    /// Build locator dimensions added to the fetch, e.g. `status:FAILURE`
+    #[serde(default)]
+    pub locator: Vec<String>,
+}
+
+impl BuildsConfig {
+    fn default_count() -> u32 {
+        DEFAULT_BUILD_COUNT
+    }
+}
+
+impl Default for BuildsConfig {
+    fn default() -> Self {
+        Self {
+            count: Self::default_count(),
+            fields: None,
+            locator: Vec::new(),
+        }
+    }
+}
+
+/// Connection settings for self-hosted servers behind proxies or with an internal PKI
+#[derive(Clone, Debug, Deserialize, Default)]
+pub struct HttpConfig {
+    /// Proxy for all requests, e.g. `http://proxy.example.com:3128`
+    #[serde(default)]
+    pub proxy: Option<String>,
+    /// PEM file with root certificates to trust in addition to the system ones
+    #[serde(default)]
+    pub ca_bundle: Option<PathBuf>,
+    /// Skips certificate validation, only for servers with certificates that can't be trusted
+    /// otherwise
+    #[serde(default)]
+    pub danger_accept_invalid_certs: bool,
+    #[serde(default)]
+    pub connect_timeout_secs: Option<u64>,
+    /// Time for a whole request, including reading the response
+    #[serde(default)]
+    pub request_timeout_secs: Option<u64>,
+    /// Requests sent to the server per second at most, unlimited if unset
+    #[serde(default)]
+    pub max_requests_per_second: Option<f64>,
+}
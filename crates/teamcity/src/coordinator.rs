@@ -0,0 +1,304 @@
+use crate::error::{Result, TeamCityError};
+use futures::FutureExt;
+use futures::future::{BoxFuture, Shared};
+use reqwest::{Method, RequestBuilder, Response, StatusCode};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::task::AbortHandle;
+use tokio::time::Instant;
+use tracing::debug;
+
+type SharedBody = Shared<BoxFuture<'static, Result<Arc<String>>>>;
+type AuthFailureReport = Arc<dyn Fn(StatusCode) + Send + Sync>;
+
+/// What makes two requests the same: requests sent with other credentials or headers don't share
+/// a response
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct RequestKey {
+    method: Method,
+    url: String,
+    headers: Vec<(String, Vec<u8>)>,
+}
+
+impl RequestKey {
+    fn of(request: &reqwest::Request) -> Self {
+        let mut headers: Vec<(String, Vec<u8>)> = request
+            .headers()
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.as_bytes().to_vec()))
+            .collect();
+        headers.sort();
+        Self {
+            method: request.method().clone(),
+            url: request.url().to_string(),
+            headers,
+        }
+    }
+}
+
+/// A shared request in flight with the number of callers waiting for it
+struct InFlight {
+    // tells the request apart from a later one of the same key
+    id: u64,
+    body: SharedBody,
+    waiters: usize,
+    task: AbortHandle,
+}
+
+type InFlightRequests = Arc<Mutex<HashMap<RequestKey, InFlight>>>;
+
+/// One caller waiting for a shared request, aborting the request when the last one is dropped
+/// before it completes
+struct Waiter {
+    in_flight: InFlightRequests,
+    key: RequestKey,
+    id: u64,
+}
+
+impl Drop for Waiter {
+    fn drop(&mut self) {
+        let mut in_flight = self.in_flight.lock().unwrap();
+        let Some(request) = in_flight.get_mut(&self.key).filter(|r| r.id == self.id) else {
+            return;
+        };
+        request.waiters -= 1;
+        if request.waiters == 0 {
+            debug!(
+                "Aborting request nobody waits for: {} {}",
+                self.key.method, self.key.url
+            );
+            request.task.abort();
+            in_flight.remove(&self.key);
+        }
+    }
+}
+
+/// Spaces requests out to at most `max_per_second`, handing out the next free slot to each caller
+struct RateLimiter {
+    interval: Duration,
+    next: tokio::sync::Mutex<Instant>,
+}
+
+impl RateLimiter {
+    fn new(max_per_second: f64) -> Self {
+        Self {
+            interval: Duration::from_secs_f64(1.0 / max_per_second),
+            next: tokio::sync::Mutex::new(Instant::now()),
+        }
+    }
+
+    async fn wait(&self) {
+        let slot = {
+            let mut next = self.next.lock().await;
+            let slot = (*next).max(Instant::now());
+            *next = slot + self.interval;
+            slot
+        };
+        tokio::time::sleep_until(slot).await;
+    }
+}
+
+/// Coordinates the requests of all clones of a client: identical GET requests in flight at the
+/// same time share one response, and all requests are rate limited if a limit is configured
+#[derive(Clone, Default)]
+pub struct RequestCoordinator {
+    limiter: Option<Arc<RateLimiter>>,
+    in_flight: InFlightRequests,
+    next_request: Arc<AtomicU64>,
+    // called for every response the server rejected the credentials with, once the app listens
+    auth_failures: Arc<Mutex<Option<AuthFailureReport>>>,
+}
+
+impl RequestCoordinator {
+    pub fn new(max_requests_per_second: Option<f64>) -> Self {
+        Self {
+            limiter: max_requests_per_second
+                .filter(|max| *max > 0.0)
+                .map(|max| Arc::new(RateLimiter::new(max))),
+            ..Self::default()
+        }
+    }
+
+    /// Calls `report` with the status of every 401 response from now on, in the task that sent
+    /// the request
+    pub fn report_auth_failures(&self, report: impl Fn(StatusCode) + Send + Sync + 'static) {
+        *self.auth_failures.lock().unwrap() = Some(Arc::new(report));
+    }
+
+    // a 403 is a missing permission or CSRF token rather than a token the server doesn't accept,
+    // a new token wouldn't help
+    fn check_auth(&self, status: StatusCode) {
+        if status != StatusCode::UNAUTHORIZED {
+            return;
+        }
+        let report = self.auth_failures.lock().unwrap().clone();
+        if let Some(report) = report {
+            report(status);
+        }
+    }
+
+    async fn wait_turn(&self) {
+        if let Some(limiter) = &self.limiter {
+            limiter.wait().await;
+        }
+    }
+
+    /// Sends `request` once it is its turn
+    pub async fn send(&self, request: RequestBuilder) -> Result<Response> {
+        self.wait_turn().await;
+        let response = request.send().await?;
+        self.check_auth(response.status());
+        Ok(response)
+    }
+
+    /// Body of a successful response to the GET `request`, shared with callers sending the same
+    /// request while it is in flight
+    pub async fn get_text(
+        &self,
+        client: &reqwest::Client,
+        request: RequestBuilder,
+    ) -> Result<Arc<String>> {
+        let request = request.build()?;
+        if request.method() != Method::GET {
+            return Err(TeamCityError::Invalid(
+                "Only GET requests can be shared".to_string(),
+            ));
+        }
+        let key = RequestKey::of(&request);
+
+        let (body, _waiter) = {
+            let mut in_flight = self.in_flight.lock().unwrap();
+            let (id, body) = match in_flight.get_mut(&key) {
+                Some(request) => {
+                    debug!("Joining request in flight: {} {}", key.method, key.url);
+                    request.waiters += 1;
+                    (request.id, request.body.clone())
+                }
+                None => {
+                    let id = self.next_request.fetch_add(1, Ordering::Relaxed);
+                    let coordinator = self.clone();
+                    let client = client.clone();
+                    let task_key = key.clone();
+                    // the request runs on its own and leaves `in_flight` when it completes, or
+                    // when the last caller waiting for it is dropped and aborts it
+                    let task = tokio::spawn(async move {
+                        let result = async {
+                            coordinator.wait_turn().await;
+                            let response = client.execute(request).await?;
+                            if !response.status().is_success() {
+                                return Err(TeamCityError::from_response(&response));
+                            }
+                            Ok(Arc::new(response.text().await?))
+                        }
+                        .await;
+                        let mut in_flight = coordinator.in_flight.lock().unwrap();
+                        // a later request for the same key may already be in flight again
+                        if in_flight.get(&task_key).is_some_and(|r| r.id == id) {
+                            in_flight.remove(&task_key);
+                        }
+                        result
+                    });
+                    let abort = task.abort_handle();
+                    let body = async move { task.await.map_err(std::io::Error::other)? }
+                        .boxed()
+                        .shared();
+                    in_flight.insert(
+                        key.clone(),
+                        InFlight {
+                            id,
+                            body: body.clone(),
+                            waiters: 1,
+                            task: abort,
+                        },
+                    );
+                    (id, body)
+                }
+            };
+            let waiter = Waiter {
+                in_flight: self.in_flight.clone(),
+                key,
+                id,
+            };
+            (body, waiter)
+        };
+
+        let result = body.await;
+        if let Err(e) = &result
+            && let Some(status) = e.status()
+        {
+            self.check_auth(status);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::method;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    async fn slow_server() -> MockServer {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string("body")
+                    .set_delay(Duration::from_millis(200)),
+            )
+            .mount(&server)
+            .await;
+        server
+    }
+
+    #[tokio::test]
+    async fn identical_requests_in_flight_share_a_response() {
+        let server = slow_server().await;
+        let client = reqwest::Client::builder().no_proxy().build().unwrap();
+        let coordinator = RequestCoordinator::default();
+        let url = format!("{}/app/rest/projects", server.uri());
+        let request = |token: &str| client.get(&url).bearer_auth(token);
+
+        let (a, b, c) = tokio::join!(
+            coordinator.get_text(&client, request("alice")),
+            coordinator.get_text(&client, request("alice")),
+            coordinator.get_text(&client, request("bob")),
+        );
+
+        assert_eq!(a.unwrap().as_str(), "body");
+        assert_eq!(b.unwrap().as_str(), "body");
+        assert_eq!(c.unwrap().as_str(), "body");
+        // the request with other credentials isn't shared
+        assert_eq!(server.received_requests().await.unwrap().len(), 2);
+        assert!(coordinator.in_flight.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn requests_are_aborted_once_every_caller_is_cancelled() {
+        let server = slow_server().await;
+        let client = reqwest::Client::builder().no_proxy().build().unwrap();
+        let coordinator = RequestCoordinator::default();
+        let url = format!("{}/app/rest/projects", server.uri());
+
+        let (cancelled, waiting) = tokio::join!(
+            tokio::time::timeout(
+                Duration::from_millis(20),
+                coordinator.get_text(&client, client.get(&url)),
+            ),
+            coordinator.get_text(&client, client.get(&url)),
+        );
+        // one caller still waiting keeps the request going
+        assert!(cancelled.is_err());
+        assert_eq!(waiting.unwrap().as_str(), "body");
+
+        let cancelled = tokio::time::timeout(
+            Duration::from_millis(20),
+            coordinator.get_text(&client, client.get(&url)),
+        )
+        .await;
+        assert!(cancelled.is_err());
+        assert!(coordinator.in_flight.lock().unwrap().is_empty());
+    }
+}
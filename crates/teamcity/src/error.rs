@@ -0,0 +1,201 @@
+use reqwest::header::RETRY_AFTER;
+use reqwest::{Response, StatusCode};
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+// characters of a response body kept in a decode error
+const BODY_EXCERPT_LEN: usize = 200;
+
+/// Result of a request to TeamCity
+pub type Result<T, E = TeamCityError> = std::result::Result<T, E>;
+
+/// Why a request to TeamCity failed, to tell apart what the user can fix
+#[derive(Debug, Clone)]
+pub enum TeamCityError {
+    /// The server rejected the credentials (401)
+    Unauthorized,
+    /// The user lacks a permission the request needs (403)
+    Forbidden,
+    /// Nothing at the requested URL, an unknown ID or no REST API under the base URL (404)
+    NotFound,
+    /// Too many requests (429), with the wait the server asked for
+    RateLimited { retry_after: Option<Duration> },
+    /// Any other error status
+    Status(StatusCode),
+    /// No response: the server can't be reached, timed out or failed the TLS handshake. Shared by
+    /// every caller of a request in flight.
+    Network(Arc<reqwest::Error>),
+    /// The response isn't what the request expects, with the start of its body
+    Decode { message: String, body: String },
+    /// Settings or arguments the request can't be sent with, like a malformed server URL
+    Invalid(String),
+    /// The server's answer lacks something the request needs, like the id of a build
+    Missing(String),
+    /// The cache file couldn't be read or written
+    Io(Arc<std::io::Error>),
+    /// The server at the base URL can't be used, with what to check, caused by `cause`
+    Connection {
+        message: String,
+        cause: Box<TeamCityError>,
+    },
+}
+
+impl TeamCityError {
+    /// Error of a response with an error status
+    pub(crate) fn from_response(response: &Response) -> Self {
+        let retry_after = response
+            .headers()
+            .get(RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse().ok())
+            .map(Duration::from_secs);
+        Self::from_status(response.status(), retry_after)
+    }
+
+    pub(crate) fn from_status(status: StatusCode, retry_after: Option<Duration>) -> Self {
+        match status {
+            StatusCode::UNAUTHORIZED => Self::Unauthorized,
+            StatusCode::FORBIDDEN => Self::Forbidden,
+            StatusCode::NOT_FOUND => Self::NotFound,
+            StatusCode::TOO_MANY_REQUESTS => Self::RateLimited { retry_after },
+            status => Self::Status(status),
+        }
+    }
+
+    /// Error of a `body` that couldn't be parsed, keeping an excerpt of it
+    pub(crate) fn decode(error: serde_json::Error, body: &str) -> Self {
+        Self::Decode {
+            message: error.to_string(),
+            body: excerpt(body),
+        }
+    }
+
+    /// Status the server responded with, None if it didn't respond with an error status
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            Self::Unauthorized => Some(StatusCode::UNAUTHORIZED),
+            Self::Forbidden => Some(StatusCode::FORBIDDEN),
+            Self::NotFound => Some(StatusCode::NOT_FOUND),
+            Self::RateLimited { .. } => Some(StatusCode::TOO_MANY_REQUESTS),
+            Self::Status(status) => Some(*status),
+            Self::Connection { cause, .. } => cause.status(),
+            _ => None,
+        }
+    }
+
+    /// The server didn't respond in time
+    pub fn is_timeout(&self) -> bool {
+        match self {
+            Self::Network(e) => e.is_timeout(),
+            Self::Connection { cause, .. } => cause.is_timeout(),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for TeamCityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unauthorized => write!(f, "Unauthorized (401), check the token or credentials"),
+            Self::Forbidden => write!(f, "Access denied (403)"),
+            Self::NotFound => write!(f, "Not found (404)"),
+            Self::RateLimited {
+                retry_after: Some(wait),
+            } => write!(f, "Too many requests (429), retry in {}s", wait.as_secs()),
+            Self::RateLimited { retry_after: None } => write!(f, "Too many requests (429)"),
+            Self::Status(status) => write!(f, "Request failed with status: {}", status),
+            Self::Network(e) => write!(f, "{}", e),
+            Self::Decode { message, body } if body.is_empty() => {
+                write!(f, "Unexpected response: {}", message)
+            }
+            Self::Decode { message, body } => {
+                write!(f, "Unexpected response: {}, got: {}", message, body)
+            }
+            Self::Invalid(message) | Self::Missing(message) | Self::Connection { message, .. } => {
+                write!(f, "{}", message)
+            }
+            Self::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for TeamCityError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Network(e) => Some(e.as_ref()),
+            Self::Io(e) => Some(e.as_ref()),
+            Self::Connection { cause, .. } => Some(cause.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for TeamCityError {
+    fn from(error: reqwest::Error) -> Self {
+        Self::Network(Arc::new(error))
+    }
+}
+
+impl From<std::io::Error> for TeamCityError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(Arc::new(error))
+    }
+}
+
+// the cache file is the only JSON parsed without its body at hand
+impl From<serde_json::Error> for TeamCityError {
+    fn from(error: serde_json::Error) -> Self {
+        Self::Decode {
+            message: error.to_string(),
+            body: String::new(),
+        }
+    }
+}
+
+/// First characters of `body` on a single line
+fn excerpt(body: &str) -> String {
+    let line = body.split_whitespace().collect::<Vec<_>>().join(" ");
+    match line.char_indices().nth(BODY_EXCERPT_LEN) {
+        Some((end, _)) => format!("{}…", &line[..end]),
+        None => line,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn statuses_map_to_kinds() {
+        assert!(matches!(
+            TeamCityError::from_status(StatusCode::UNAUTHORIZED, None),
+            TeamCityError::Unauthorized
+        ));
+        assert!(matches!(
+            TeamCityError::from_status(StatusCode::TOO_MANY_REQUESTS, Some(Duration::from_secs(5))),
+            TeamCityError::RateLimited {
+                retry_after: Some(_)
+            }
+        ));
+        let error = TeamCityError::from_status(StatusCode::BAD_GATEWAY, None);
+        assert_eq!(error.status(), Some(StatusCode::BAD_GATEWAY));
+        assert_eq!(
+            error.to_string(),
+            "Request failed with status: 502 Bad Gateway"
+        );
+    }
+
+    #[test]
+    fn decode_errors_keep_an_excerpt_of_the_body() {
+        let body = format!("<html>\n  <body>{}</body>\n</html>", "x".repeat(300));
+        let json_error = serde_json::from_str::<u32>(&body).unwrap_err();
+
+        let TeamCityError::Decode { body: excerpt, .. } = TeamCityError::decode(json_error, &body)
+        else {
+            panic!("not a decode error");
+        };
+        assert!(excerpt.starts_with("<html> <body>xxx"));
+        assert_eq!(excerpt.chars().count(), BODY_EXCERPT_LEN + 1);
+    }
+}
@@ -0,0 +1,1983 @@
+//! Client for the [TeamCity REST API](https://www.jetbrains.com/help/teamcity/rest/teamcity-rest-api-documentation.html)
+//! behind t9s, usable by other tools.
+//!
+//! [`TeamCityClient`] talks to one server: it limits concurrent and per-second requests, probes
+//! the server version and CSRF protection with [`TeamCityClient::probe_server`], and caches build
+//! configurations on disk. It never prints, failures are returned as errors and diagnostics are
+//! logged with `tracing`.
+//!
+//! ```no_run
+//! # async fn example() -> teamcity::Result<()> {
+//! use teamcity::{Auth, HttpConfig, TeamCityClient};
+//!
+//! let client = TeamCityClient::new(
+//!     "https://teamcity.example.com".to_string(),
+//!     Auth::Token("token".to_string()),
+//!     &HttpConfig::default(),
+//! )?
+//! .probe_server()
+//! .await;
+//! let build = client.get_build(42).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use base64::Engine;
+use base64::engine::general_purpose::{STANDARD as BASE64, URL_SAFE as BASE64_URL_SAFE};
+use directories::ProjectDirs;
+use futures::AsyncWriteExt;
+use futures::stream::{self, StreamExt};
+use reqwest::header::{COOKIE, HeaderMap, SET_COOKIE};
+use reqwest::{IntoUrl, Method, RequestBuilder, Response, StatusCode, Url};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use time::OffsetDateTime;
+use time::format_description::FormatItem;
+use time::macros::format_description;
+use tracing::{info, warn};
+
+pub use cache::{DEFAULT_CACHE_MAX_SIZE, cache_stats, clear_cache, prune_cache};
+use cache::{PersistentCache, PersistentCacheEntry, cache_file_path};
+use coordinator::RequestCoordinator;
+
+mod cache;
+mod config;
+mod coordinator;
+mod error;
+mod server;
+pub mod types;
+pub use config::{BuildsConfig, HttpConfig};
+pub use error::{Result, TeamCityError};
+use server::{CsrfToken, normalize_server_url, server_name};
+pub use server::{LicensingData, ServerInfo};
+use types::{
+    Agent, AgentPool, AgentPools, AgentRequirement, AgentRequirements, Agents, ArtifactFiles,
+    Branches, Build, BuildType, BuildTypes, Builds, Change, Changes, Mute, MuteScope, Mutes,
+    Parameter, Parameters, Project, ProjectFeatures, Projects, Properties, TestOccurrence,
+    TestOccurrences, VcsRoot,
+};
+
+// Authentication docs:
+// https://www.jetbrains.com/help/teamcity/rest/teamcity-rest-api-documentation.html#REST+Authentication
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Auth {
+    /// Personal access token sent as a bearer token
+    Token(String),
+    /// Username and password sent with HTTP basic auth under the `/httpAuth/` prefix
+    Basic { username: String, password: String },
+    /// Anonymous access under the `/guestAuth/` prefix, requires guest login enabled on the server
+    Guest,
+}
+
+impl Auth {
+    fn url_prefix(&self) -> &'static str {
+        match self {
+            Auth::Token(_) => "",
+            Auth::Basic { .. } => "/httpAuth",
+            Auth::Guest => "/guestAuth",
+        }
+    }
+
+    fn headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        match self {
+            Auth::Token(token) => {
+                headers.insert("Authorization", format!("Bearer {token}").parse().unwrap());
+            }
+            Auth::Basic { username, password } => {
+                let credentials = BASE64.encode(format!("{username}:{password}"));
+                headers.insert(
+                    "Authorization",
+                    format!("Basic {credentials}").parse().unwrap(),
+                );
+            }
+            Auth::Guest => {}
+        }
+        headers
+    }
+}
+
+/// A project whose build configurations could not be fetched
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProjectFailure {
+    pub project_id: String,
+    pub error: String,
+}
+
+/// Build configurations of several projects, including the projects that failed to load
+#[derive(Debug, Default)]
+pub struct ProjectsBuildTypes {
+    pub build_types: Vec<BuildType>,
+    pub failed: Vec<ProjectFailure>,
+}
+
+impl ProjectsBuildTypes {
+    /// Which of the `requested` projects failed to load and why, None if all of them loaded
+    pub fn failure_summary(&self, requested: usize) -> Option<String> {
+        if self.failed.is_empty() {
+            return None;
+        }
+        Some(format!(
+            "Failed to fetch build types of {} of {} projects: {}",
+            self.failed.len(),
+            requested,
+            self.failed
+                .iter()
+                .map(|f| format!("{} ({})", f.project_id, f.error))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))
+    }
+}
+
+/// Artifacts of a build that are about to be deleted
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildArtifacts {
+    pub build_id: i64,
+    pub build_number: Option<String>,
+    /// Top-level artifact paths, deleting them removes everything underneath
+    pub paths: Vec<String>,
+    pub total_size: u64,
+}
+
+/// Where the versioned settings of a project are stored
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionedSettings {
+    /// `kotlin` or `xml`
+    pub format: String,
+    /// Clone URL of the VCS root holding the `.teamcity` directory
+    pub vcs_url: String,
+    /// Branch of the VCS root, like `refs/heads/main`
+    pub branch: Option<String>,
+}
+
+/// What a configuration asks of the agents it runs on, and which agents can run it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AgentCompatibility {
+    pub requirements: Vec<AgentRequirement>,
+    pub compatible: Vec<Agent>,
+    /// Authorized agents failing a requirement, or outside the pools of the project
+    pub incompatible: Vec<Agent>,
+}
+
+/// Builds including a change, with those that first included it
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChangeBuilds {
+    /// Builds of any configuration and branch, newest first
+    pub builds: Vec<Build>,
+    /// IDs of the build of each configuration the change was first built in, as the server
+    /// reports them
+    pub first_built_in: Vec<i64>,
+}
+
+/// Snapshot dependency chain around a build
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DependencyChain {
+    /// Builds the build depends on
+    pub upstream: Vec<Build>,
+    /// Builds depending on the build
+    pub downstream: Vec<Build>,
+}
+
+/// State of the server shown in the About server view
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServerOverview {
+    pub info: ServerInfo,
+    /// None if the user isn't allowed to read the license
+    pub licensing: Option<LicensingData>,
+    /// Authorized and unauthorized agents, with their pool and running build
+    pub agents: Vec<Agent>,
+    /// Builds waiting in the whole build queue
+    pub queue_length: u32,
+}
+
+/// Which branches to include when listing builds
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum BranchFilter {
+    /// Only the configuration's default branch, TeamCity's behaviour without a branch locator
+    #[default]
+    Default,
+    /// A single branch by name
+    Named(String),
+    /// Builds from every branch
+    Any,
+}
+
+impl BranchFilter {
+    /// Branch typed in a branch prompt: nothing for the default branch, `*` for every branch
+    pub fn parse(input: &str) -> Self {
+        match input.trim() {
+            "" => BranchFilter::Default,
+            "*" => BranchFilter::Any,
+            name => BranchFilter::Named(name.to_string()),
+        }
+    }
+
+    /// Short description for view titles
+    pub fn label(&self) -> String {
+        match self {
+            BranchFilter::Default => "default branch".to_string(),
+            BranchFilter::Named(name) => format!("branch {}", name),
+            BranchFilter::Any => "all branches".to_string(),
+        }
+    }
+
+    fn locator(&self) -> Option<String> {
+        match self {
+            BranchFilter::Default => None,
+            BranchFilter::Named(name) => Some(format!("branch:(name:{})", locator_value(name))),
+            BranchFilter::Any => Some("branch:default:any".to_string()),
+        }
+    }
+}
+
+/// Start dates of the builds to list, instead of the latest ones whenever they started
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DateRange {
+    /// Builds started within this time before the request
+    Last(Duration),
+    /// Builds started between two dates, without a bound on the side that is None
+    Between {
+        #[serde(default, with = "types::teamcity_datetime")]
+        since: Option<OffsetDateTime>,
+        #[serde(default, with = "types::teamcity_datetime")]
+        until: Option<OffsetDateTime>,
+    },
+}
+
+impl DateRange {
+    /// Short description for view titles, like "last 7 days" or "since 2025-01-31 00:00"
+    pub fn label(&self) -> String {
+        let date = |date: &OffsetDateTime| date.format(LABEL_DATE_FORMAT).unwrap_or_default();
+        match self {
+            DateRange::Last(duration) => {
+                let hours = duration.as_secs() / 3600;
+                if hours >= 48 && hours % 24 == 0 {
+                    format!("last {} days", hours / 24)
+                } else {
+                    format!("last {}h", hours)
+                }
+            }
+            DateRange::Between {
+                since: Some(since),
+                until: Some(until),
+            } => format!("{} – {}", date(since), date(until)),
+            DateRange::Between {
+                since: Some(since),
+                until: None,
+            } => format!("since {}", date(since)),
+            DateRange::Between {
+                since: None,
+                until: Some(until),
+            } => format!("until {}", date(until)),
+            DateRange::Between {
+                since: None,
+                until: None,
+            } => "any date".to_string(),
+        }
+    }
+
+    /// Locator dimensions of the range, ranges ending in the past are taken back from `now`
+    fn locator(&self, now: OffsetDateTime) -> String {
+        let (since, until) = match *self {
+            DateRange::Last(duration) => (Some(now - duration), None),
+            DateRange::Between { since, until } => (since, until),
+        };
+        let date = |date: OffsetDateTime| date.format(LOCATOR_DATE_FORMAT).unwrap_or_default();
+        let mut dimensions = Vec::new();
+        if let Some(since) = since {
+            dimensions.push(format!("sinceDate:{}", date(since)));
+        }
+        if let Some(until) = until {
+            dimensions.push(format!("untilDate:{}", date(until)));
+        }
+        dimensions.join(",")
+    }
+}
+
+// dates in locators are in the format of dates in responses, without fractions of a second
+const LOCATOR_DATE_FORMAT: &[FormatItem<'static>] = format_description!(
+    "[year][month][day]T[hour][minute][second][offset_hour sign:mandatory][offset_minute]"
+);
+const LABEL_DATE_FORMAT: &[FormatItem<'static>] =
+    format_description!("[year]-[month]-[day] [hour]:[minute]");
+// characters ending or nesting a locator value, names with them are sent base64-encoded
+const LOCATOR_SPECIAL_CHARS: [char; 3] = [',', '(', ')'];
+
+/// Whether `locator` has the top-level dimension `name`, outside of nested locators like
+/// `buildType:(count:1)`
+fn has_dimension(locator: &str, name: &str) -> bool {
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in locator.char_indices().chain([(locator.len(), ',')]) {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                let dimension = locator[start..i].trim();
+                if dimension.split(':').next() == Some(name) {
+                    return true;
+                }
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    false
+}
+
+/// `value` as a locator dimension value, in the `$base64:` form if it has characters of the locator
+/// syntax, like a branch named `fix(parser),v2`
+fn locator_value(value: &str) -> String {
+    if value.contains(LOCATOR_SPECIAL_CHARS) {
+        format!("$base64:{}", BASE64_URL_SAFE.encode(value))
+    } else {
+        value.to_string()
+    }
+}
+
+const PROJECT_FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+// logs of long builds take a while to download
+const LOG_DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(600);
+const ARTIFACTS_DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(3600);
+// how often the progress of a download is reported
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+pub const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(3600);
+pub const DEFAULT_MAX_CONCURRENT_REQUESTS: usize = 4;
+pub const DEFAULT_BUILD_COUNT: u32 = 100;
+// builds fetched at most in a date range, which lists the builds in it rather than the latest ones
+const MAX_RANGE_BUILD_COUNT: u32 = 1000;
+// fields of the builds shown in the Builds table
+// servers that don't estimate the start of a build leave `startEstimate` out
+const QUEUED_BUILD_FIELDS: &str = "count,build(id,number,buildTypeId,state,branchName,webUrl,queuedDate,waitReason,startEstimate,personal,triggered(type,details,user(username,name),build(id,number,buildTypeId)),changes(change(id,comment,username)))";
+const TABLE_BUILD_FIELDS: &str = "count,build(id,number,branchName,statusText,status,state,webUrl,buildTypeId,startDate,finishDate,running-info(elapsedSeconds,estimatedTotalSeconds),pinned,personal,tags(tag(name)),triggered(type,details,user(username,name),build(id,number,buildTypeId)),revisions(revision(version,vcsBranchName,vcs-root-instance(name,vcs-root-id))),changes(change(id,version,comment,username)))";
+
+/// Client of a TeamCity server. Clones share the limits on requests to the server.
+#[derive(Clone)]
+pub struct TeamCityClient {
+    base_url: String,
+    auth: Auth,
+    client: reqwest::Client,
+    // shared by all clones so limits hold across the whole app
+    coordinator: RequestCoordinator,
+    cache_file: PathBuf,
+    cache_max_size: u64,
+    default_ttl: Duration,
+    project_ttls: HashMap<String, Duration>,
+    max_concurrent_requests: usize,
+    builds: BuildsConfig,
+    // shared by all clones, so a token renewed by one is sent by all
+    csrf: Arc<Mutex<Option<CsrfToken>>>,
+}
+
+impl TeamCityClient {
+    /// Client of the server at `base_url`, with or without a context path like
+    /// `https://example.com/teamcity`. Fails on a malformed URL or unusable `http` settings, the
+    /// server itself is only contacted by requests.
+    pub fn new(base_url: String, auth: Auth, http: &HttpConfig) -> Result<Self> {
+        Ok(Self {
+            coordinator: RequestCoordinator::new(http.max_requests_per_second),
+            ..Self::with_http_client(base_url, auth, http_client(http)?)?
+        })
+    }
+
+    /// Client of the server at `base_url` sending its requests with `client`, like one with
+    /// settings [`HttpConfig`] lacks or one talking to a mock server in tests. The credentials are
+    /// added to every request, and requests aren't rate limited.
+    pub fn with_http_client(base_url: String, auth: Auth, client: reqwest::Client) -> Result<Self> {
+        let base_url = normalize_server_url(&base_url)?;
+        let cache_file = cache_file_path(&default_cache_dir(), &base_url);
+
+        Ok(Self {
+            base_url,
+            auth,
+            client,
+            coordinator: RequestCoordinator::default(),
+            cache_file,
+            cache_max_size: DEFAULT_CACHE_MAX_SIZE,
+            default_ttl: DEFAULT_CACHE_TTL,
+            project_ttls: HashMap::new(),
+            max_concurrent_requests: DEFAULT_MAX_CONCURRENT_REQUESTS,
+            builds: BuildsConfig::default(),
+            csrf: Arc::default(),
+        })
+    }
+
+    /// Same client sending `token` instead of the credentials it was created with, sharing the
+    /// request coordination and what was probed from the server
+    pub fn with_token(&self, token: String) -> Self {
+        Self {
+            auth: Auth::Token(token),
+            // tied to the session of the previous credentials
+            csrf: Arc::default(),
+            ..self.clone()
+        }
+    }
+
+    /// Calls `report` with the status of every response rejecting the credentials (401), from
+    /// this client and all its clones. It is called in the task that sent the request, so it can
+    /// tell which work failed.
+    pub fn report_auth_failures(&self, report: impl Fn(StatusCode) + Send + Sync + 'static) {
+        self.coordinator.report_auth_failures(report);
+    }
+
+    /// Checks that a TeamCity server answers at the base URL and accepts the credentials, failing
+    /// with what to fix otherwise
+    pub async fn check_connection(&self) -> Result<()> {
+        self.get_json::<ServerInfo>(
+            self.read_request(self.url("/app/rest/server"))
+                .query(&[("fields", "version")])
+                .header("Accept", "application/json"),
+        )
+        .await
+        .map(|_| ())
+        .map_err(|e| TeamCityError::Connection {
+            message: describe_connection_error(&self.base_url, &e),
+            cause: Box::new(e),
+        })
+    }
+
+    /// Logs the server version, and reads the CSRF token of a session on servers that require one
+    pub async fn probe_server(self) -> Self {
+        let url = self.url("/app/rest/server");
+        let probe = self
+            .get_json::<ServerInfo>(
+                self.read_request(&url)
+                    .query(&[("fields", "version")])
+                    .header("Accept", "application/json"),
+            )
+            .await;
+        match probe {
+            Ok(info) => info!(
+                "Connected to TeamCity {}",
+                info.version.as_deref().unwrap_or("of unknown version")
+            ),
+            Err(e) => warn!("Failed to read the server version: {}", e),
+        }
+
+        // tokens and guest access don't open a session, so they need no CSRF token. Whether the
+        // server wants one is up to its settings rather than its version, so it is asked for one.
+        if matches!(self.auth, Auth::Basic { .. }) {
+            match self.get_csrf_token().await {
+                Ok(csrf) => *self.csrf.lock().unwrap() = Some(csrf),
+                Err(e) => info!("No CSRF token from the server: {}", e),
+            }
+        }
+        self
+    }
+
+    fn has_csrf_token(&self) -> bool {
+        self.csrf.lock().unwrap().is_some()
+    }
+
+    /// Sends the request changing data built by `request`, and once more with a new CSRF token if
+    /// the server rejects the one of the session, which expires with it
+    async fn send_write(&self, request: impl Fn() -> RequestBuilder) -> Result<Response> {
+        let response = self.coordinator.send(request()).await?;
+        if response.status() != StatusCode::FORBIDDEN || !self.has_csrf_token() {
+            return Ok(response);
+        }
+        // TeamCity answers "CSRF Header X-TC-CSRF-Token does not match CSRF session value", other
+        // 403s are missing permissions
+        let body = response.text().await?;
+        if !body.contains("CSRF") {
+            return Err(TeamCityError::Forbidden);
+        }
+        info!("The CSRF token was rejected, getting a new one");
+        let csrf = self.get_csrf_token().await?;
+        *self.csrf.lock().unwrap() = Some(csrf);
+        self.coordinator.send(request()).await
+    }
+
+    async fn get_csrf_token(&self) -> Result<CsrfToken> {
+        let response = self
+            .coordinator
+            .send(
+                self.read_request(self.url("/authenticationTest.html"))
+                    .query(&[("csrf", "")]),
+            )
+            .await?;
+        if !response.status().is_success() {
+            return Err(TeamCityError::from_response(&response));
+        }
+
+        let session_cookie = response
+            .headers()
+            .get_all(SET_COOKIE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .find(|cookie| cookie.starts_with("TCSESSIONID="))
+            .and_then(|cookie| cookie.split(';').next())
+            .map(str::to_string);
+        let token = response.text().await?.trim().to_string();
+        Ok(CsrfToken {
+            token,
+            session_cookie,
+        })
+    }
+
+    /// GET request sending the credentials
+    fn read_request(&self, url: impl IntoUrl) -> RequestBuilder {
+        self.client.get(url).headers(self.auth.headers())
+    }
+
+    /// Request changing data on the server, sent with the CSRF token of the session if there is one
+    fn write_request(&self, method: Method, url: &str) -> RequestBuilder {
+        let mut request = self
+            .client
+            .request(method, url)
+            .headers(self.auth.headers());
+        if let Some(csrf) = &*self.csrf.lock().unwrap() {
+            request = request.header("X-TC-CSRF-Token", &csrf.token);
+            if let Some(cookie) = &csrf.session_cookie {
+                request = request.header(COOKIE, cookie);
+            }
+        }
+        request
+    }
+
+    /// Keeps the cache file in `dir` instead of the platform cache directory
+    pub fn cache_dir(mut self, dir: &Path) -> Self {
+        self.cache_file = cache_file_path(dir, &self.base_url);
+        self
+    }
+
+    /// Evicts the least recently used projects from the cache file once it is larger than `max`
+    /// bytes
+    pub fn cache_max_size(mut self, max: u64) -> Self {
+        self.cache_max_size = max;
+        self
+    }
+
+    /// Time the cached build configurations of a project are used for, unless the project has its
+    /// own in [`Self::project_ttls`]
+    pub fn default_ttl(mut self, ttl: Duration) -> Self {
+        self.default_ttl = ttl;
+        self
+    }
+
+    /// Requests sent at the same time at most when fetching several projects
+    pub fn max_concurrent_requests(mut self, max: usize) -> Self {
+        self.max_concurrent_requests = max;
+        self
+    }
+
+    /// Count, fields and extra locator dimensions of the builds fetched for the Builds view
+    pub fn builds(mut self, builds: BuildsConfig) -> Self {
+        self.builds = builds;
+        self
+    }
+
+    /// Cache times overriding the default one, keyed by project ID
+    pub fn project_ttls(mut self, ttls: HashMap<String, Duration>) -> Self {
+        self.project_ttls = ttls;
+        self
+    }
+
+    fn ttl_for(&self, project_id: &str) -> Duration {
+        self.project_ttls
+            .get(project_id)
+            .copied()
+            .unwrap_or(self.default_ttl)
+    }
+
+    /// Sends a GET request and parses its JSON body, shared with identical requests in flight
+    async fn get_json<T: DeserializeOwned>(&self, request: reqwest::RequestBuilder) -> Result<T> {
+        let body = self.coordinator.get_text(&self.client, request).await?;
+        serde_json::from_str(&body).map_err(|e| TeamCityError::decode(e, &body))
+    }
+
+    /// Host of the server, telling it apart from the other configured servers
+    pub fn server_name(&self) -> String {
+        server_name(&self.base_url)
+    }
+
+    /// Builds a full server URL for `path`, prefixed according to the auth mode
+    fn url(&self, path: &str) -> String {
+        format!("{}{}{}", self.base_url, self.auth.url_prefix(), path)
+    }
+
+    async fn load_cache(&self) -> PersistentCache {
+        PersistentCache::load(&self.cache_file).await
+    }
+
+    /// Removes the cache file of the server, the build configurations of every project are fetched
+    /// again
+    pub async fn clear_cache(&self) -> Result<()> {
+        if self.cache_file.exists() {
+            async_fs::remove_file(&self.cache_file).await?;
+        }
+        Ok(())
+    }
+
+    /// Drops the cached build configurations of `project_ids` so the next fetch hits the server
+    pub async fn invalidate_projects(&self, project_ids: &[String]) -> Result<()> {
+        let keys: Vec<String> = project_ids
+            .iter()
+            .map(|project_id| format!("project_{}", project_id))
+            .collect();
+        PersistentCache::update(&self.cache_file, self.cache_max_size, move |cache| {
+            for key in keys {
+                cache.entries.remove(&key);
+            }
+        })
+        .await
+    }
+
+    /// Projects in the cache file of the server and the size of the file in bytes
+    pub async fn get_cache_info(&self) -> (usize, u64) {
+        let cache = self.load_cache().await;
+        let total_entries = cache.entries.len();
+        let cache_size = if self.cache_file.exists() {
+            async_fs::metadata(&self.cache_file)
+                .await
+                .map(|m| m.len())
+                .unwrap_or(0)
+        } else {
+            0
+        };
+        (total_entries, cache_size)
+    }
+
+    /// Build configurations of all `project_ids`, fetching up to `max_concurrent_requests` projects
+    /// at a time. Projects that fail are reported in [`ProjectsBuildTypes::failed`].
+    pub async fn get_build_configurations_by_projects(
+        &self,
+        project_ids: &[String],
+    ) -> Result<ProjectsBuildTypes> {
+        if project_ids.is_empty() {
+            return Err(TeamCityError::Invalid(
+                "You need to specify at least one project ID".to_string(),
+            ));
+        }
+
+        let cache = self.load_cache().await;
+        let mut requests = Vec::new();
+        for project_id in project_ids {
+            let cached = cache
+                .entries
+                .get(&format!("project_{}", project_id))
+                .filter(|entry| !entry.is_older_than(self.ttl_for(project_id)))
+                .map(|entry| entry.data.clone());
+            requests.push(self.cached_or_fetch(project_id, cached));
+        }
+        let fetched: Vec<_> = stream::iter(requests)
+            .buffered(self.max_concurrent_requests.max(1))
+            .collect()
+            .await;
+
+        // only the entries read or fetched here are merged into the cache file, which may have
+        // been updated by another instance in the meantime
+        let mut result = ProjectsBuildTypes::default();
+        let mut used = Vec::new();
+        let mut fresh = Vec::new();
+        for (project_id, fetched, from_cache) in fetched {
+            match fetched {
+                Ok(mut build_types) => {
+                    let key = format!("project_{}", project_id);
+                    if from_cache {
+                        used.push(key);
+                    } else {
+                        let ttl = self.ttl_for(project_id);
+                        fresh.push((key, PersistentCacheEntry::new(build_types.clone(), ttl)));
+                    }
+                    result.build_types.append(&mut build_types);
+                }
+                Err(e) => result.failed.push(ProjectFailure {
+                    project_id: project_id.clone(),
+                    error: describe_error(&e),
+                }),
+            }
+        }
+
+        if !(used.is_empty() && fresh.is_empty()) {
+            let saved = PersistentCache::update(&self.cache_file, self.cache_max_size, |cache| {
+                for key in used {
+                    if let Some(entry) = cache.entries.get_mut(&key) {
+                        entry.touch();
+                    }
+                }
+                cache.entries.extend(fresh);
+            })
+            .await;
+            if let Err(e) = saved {
+                warn!("Failed to save cache: {}", e);
+            }
+        }
+        if let Some(summary) = result.failure_summary(project_ids.len()) {
+            warn!("{}", summary);
+        }
+
+        Ok(result)
+    }
+
+    /// `cached` build configurations of a project if there are any, fetched ones otherwise, with
+    /// whether they came from the cache
+    async fn cached_or_fetch<'a>(
+        &self,
+        project_id: &'a String,
+        cached: Option<Vec<BuildType>>,
+    ) -> (&'a String, Result<Vec<BuildType>>, bool) {
+        match cached {
+            Some(build_types) => {
+                info!(
+                    "Using cached build configurations for project {}",
+                    project_id
+                );
+                (project_id, Ok(build_types), true)
+            }
+            None => {
+                let result = self.fetch_build_configurations_by_project(project_id).await;
+                (project_id, result, false)
+            }
+        }
+    }
+
+    async fn fetch_build_configurations_by_project(
+        &self,
+        project_id: &str,
+    ) -> Result<Vec<BuildType>> {
+        let url = self.url("/app/rest/buildTypes");
+        let fields = "count,href,buildType(id,name,type,paused,description,projectName,projectId,href,links,webUrl)";
+
+        let request = self
+            .read_request(&url)
+            .query(&[
+                ("locator", format!("affectedProject:(id:{})", project_id)),
+                ("fields", fields.to_string()),
+            ])
+            .header("Accept", "application/json")
+            .timeout(PROJECT_FETCH_TIMEOUT);
+
+        let build_types: BuildTypes = self.get_json(request).await?;
+        Ok(build_types.build_type)
+    }
+
+    /// Build configuration by ID, with everything the server returns for it
+    pub async fn get_build_configuration_details(&self, build_type_id: &str) -> Result<BuildType> {
+        let url = self.url(&format!("/app/rest/buildTypes/id:{}", build_type_id));
+
+        let build_type: BuildType = self
+            .get_json(self.read_request(&url).header("Accept", "application/json"))
+            .await?;
+        Ok(build_type)
+    }
+
+    /// Recent builds of the configuration `project_id` on `branch`, personal ones included, with
+    /// the count, fields and locator dimensions of [`Self::builds`]. Builds started in `range` are
+    /// all listed up to a thousand, whatever the configured count.
+    pub async fn get_builds_by_project(
+        &self,
+        project_id: &str,
+        branch: &BranchFilter,
+        range: Option<&DateRange>,
+    ) -> Result<Vec<Build>> {
+        // personal builds are only listed on request, they are narrowed down to our own in the view
+        let mut locator = format!("buildType:{},personal:any", project_id);
+        if let Some(branch_locator) = branch.locator() {
+            locator.push(',');
+            locator.push_str(&branch_locator);
+        }
+        for dimension in &self.builds.locator {
+            locator.push(',');
+            locator.push_str(dimension);
+        }
+        match range {
+            Some(range) => {
+                let dimensions = range.locator(OffsetDateTime::now_utc());
+                if !dimensions.is_empty() {
+                    locator.push(',');
+                    locator.push_str(&dimensions);
+                }
+                let count = self.builds.count.max(MAX_RANGE_BUILD_COUNT);
+                self.get_table_builds_up_to(locator, count).await
+            }
+            None => self.get_table_builds(locator).await,
+        }
+    }
+
+    /// Builds of a configuration on any branch matching `query`, a locator like `number:123`
+    pub async fn search_builds(&self, build_type_id: &str, query: &str) -> Result<Vec<Build>> {
+        let locator = format!(
+            "buildType:{},personal:any,branch:default:any,{}",
+            build_type_id, query
+        );
+        self.get_table_builds(locator).await
+    }
+
+    /// Builds including the changes matching `change_locator`, like `version:3f2a9c1d`, found
+    /// through `/app/rest/changes` with the builds the server reports each change was first built
+    /// in. None if no change matches.
+    pub async fn get_change_builds(&self, change_locator: &str) -> Result<Option<ChangeBuilds>> {
+        let changes: Changes = match self
+            .get_json(
+                self.read_request(self.url("/app/rest/changes"))
+                    .query(&[
+                        ("locator", change_locator),
+                        (
+                            "fields",
+                            "change(id,version,firstBuilds(build(id,buildTypeId)))",
+                        ),
+                    ])
+                    .header("Accept", "application/json"),
+            )
+            .await
+        {
+            Ok(changes) => changes,
+            // an unknown change ID
+            Err(TeamCityError::NotFound) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        let changes = changes.change.unwrap_or_default();
+        if changes.is_empty() {
+            return Ok(None);
+        }
+
+        let mut result = ChangeBuilds::default();
+        for change in &changes {
+            let Some(change_id) = change.id else {
+                continue;
+            };
+            let builds = self
+                .get_table_builds(format!(
+                    "change:(id:{}),personal:any,branch:default:any,defaultFilter:false",
+                    change_id
+                ))
+                .await?;
+            // a commit in several VCS roots is a change of each
+            for build in builds {
+                if !result.builds.iter().any(|b| b.id == build.id) {
+                    result.builds.push(build);
+                }
+            }
+            let first = change.first_builds.iter().flat_map(|builds| &builds.build);
+            result
+                .first_built_in
+                .extend(first.filter_map(|build| build.id));
+        }
+        result.builds.sort_by(|a, b| b.id.cmp(&a.id));
+        Ok(Some(result))
+    }
+
+    /// Builds a composite build aggregated: the builds of its direct snapshot dependencies for
+    /// that run, with the fields shown in the Builds table
+    pub async fn get_composite_builds(&self, build_id: i64) -> Result<Vec<Build>> {
+        let locator = format!(
+            "snapshotDependency:(to:(id:{}),includeInitial:false,recursive:false),defaultFilter:false",
+            build_id
+        );
+        self.get_table_builds(locator).await
+    }
+
+    /// Builds matching a raw build locator as typed by the user, passed through as is. A `count`
+    /// in the locator replaces the configured one.
+    pub async fn get_builds_by_raw_locator(&self, locator: &str) -> Result<Vec<Build>> {
+        if has_dimension(locator, "count") {
+            self.get_builds_with_fields(locator.to_string(), None).await
+        } else {
+            self.get_table_builds(locator.to_string()).await
+        }
+    }
+
+    /// Builds matching `locator` with the fields shown in the Builds table
+    async fn get_table_builds(&self, locator: String) -> Result<Vec<Build>> {
+        self.get_table_builds_up_to(locator, self.builds.count)
+            .await
+    }
+
+    async fn get_table_builds_up_to(&self, locator: String, count: u32) -> Result<Vec<Build>> {
+        self.get_builds_with_fields(locator, Some(count)).await
+    }
+
+    // builds with the fields of the Builds table, `count` of them unless the locator limits them
+    async fn get_builds_with_fields(
+        &self,
+        locator: String,
+        count: Option<u32>,
+    ) -> Result<Vec<Build>> {
+        let url = self.url("/app/rest/builds");
+
+        let fields = self.builds.fields.as_deref().unwrap_or(TABLE_BUILD_FIELDS);
+
+        let mut params = vec![("locator", locator)];
+        if let Some(count) = count {
+            params.push(("count", count.to_string()));
+        }
+        params.push(("fields", fields.to_string()));
+
+        let builds: Builds = self
+            .get_json(
+                self.read_request(&url)
+                    .query(&params)
+                    .header("Accept", "application/json"),
+            )
+            .await?;
+        Ok(builds.build)
+    }
+
+    /// Queued builds of a configuration, next to start first, with their position in the whole
+    /// build queue
+    pub async fn get_queued_builds(&self, build_type_id: &str) -> Result<Vec<Build>> {
+        let url = self.url("/app/rest/buildQueue");
+
+        let queue: Builds = self
+            .get_json(
+                self.read_request(&url)
+                    .query(&[("fields", "count,build(id)")])
+                    .header("Accept", "application/json"),
+            )
+            .await?;
+        let mut builds: Builds = self
+            .get_json(
+                self.read_request(&url)
+                    .query(&[
+                        ("locator", format!("buildType:(id:{})", build_type_id)),
+                        ("fields", QUEUED_BUILD_FIELDS.to_string()),
+                    ])
+                    .header("Accept", "application/json"),
+            )
+            .await?;
+
+        for build in &mut builds.build {
+            build.queue_position = queue
+                .build
+                .iter()
+                .position(|queued| queued.id == build.id)
+                .map(|i| i + 1);
+        }
+        Ok(builds.build)
+    }
+
+    /// Queued builds of all configurations, next to start first, with their position
+    pub async fn get_build_queue(&self) -> Result<Vec<Build>> {
+        let url = self.url("/app/rest/buildQueue");
+        let mut queue: Builds = self
+            .get_json(
+                self.read_request(&url)
+                    .query(&[("fields", QUEUED_BUILD_FIELDS)])
+                    .header("Accept", "application/json"),
+            )
+            .await?;
+        for (i, build) in queue.build.iter_mut().enumerate() {
+            build.queue_position = Some(i + 1);
+        }
+        Ok(queue.build)
+    }
+
+    /// The last `count` finished builds of a configuration, newest first
+    pub async fn get_finished_builds(
+        &self,
+        build_type_id: &str,
+        branch: &BranchFilter,
+        count: u32,
+    ) -> Result<Vec<Build>> {
+        let url = self.url("/app/rest/builds");
+        let fields = "count,build(id,number,branchName,status,state,webUrl,buildTypeId,startDate,finishDate)";
+
+        let mut locator = format!("buildType:{},state:finished,count:{}", build_type_id, count);
+        if let Some(branch_locator) = branch.locator() {
+            locator.push(',');
+            locator.push_str(&branch_locator);
+        }
+
+        let builds: Builds = self
+            .get_json(
+                self.read_request(&url)
+                    .query(&[("locator", locator), ("fields", fields.to_string())])
+                    .header("Accept", "application/json"),
+            )
+            .await?;
+        Ok(builds.build)
+    }
+
+    /// Builds the build `build_id` depends on and the builds depending on it, through snapshot
+    /// dependencies
+    pub async fn get_build_chain(&self, build_id: i64) -> Result<DependencyChain> {
+        let upstream = self
+            .get_builds_by_locator(&format!(
+                "snapshotDependency:(to:(id:{}),includeInitial:false),defaultFilter:false",
+                build_id
+            ))
+            .await?;
+        let downstream = self
+            .get_builds_by_locator(&format!(
+                "snapshotDependency:(from:(id:{}),includeInitial:false),defaultFilter:false",
+                build_id
+            ))
+            .await?;
+        Ok(DependencyChain {
+            upstream,
+            downstream,
+        })
+    }
+
+    async fn get_builds_by_locator(&self, locator: &str) -> Result<Vec<Build>> {
+        let url = self.url("/app/rest/builds");
+        let fields = "count,build(id,number,branchName,statusText,status,state,webUrl,buildTypeId,startDate,finishDate)";
+
+        let builds: Builds = self
+            .get_json(
+                self.read_request(&url)
+                    .query(&[("locator", locator), ("fields", fields)])
+                    .header("Accept", "application/json"),
+            )
+            .await?;
+        Ok(builds.build)
+    }
+
+    /// Current state of the build `build_id`, whether it is still running or has finished
+    pub async fn get_build(&self, build_id: i64) -> Result<Build> {
+        self.get_builds_by_locator(&format!("id:{}", build_id))
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| TeamCityError::Missing(format!("Build {} was not found", build_id)))
+    }
+
+    /// Latest queued, running or finished build on the default branch of each of
+    /// `build_type_ids`, fetching up to `max_concurrent_requests` configurations at once. A
+    /// configuration that can't be read, like a deleted one, is skipped with a warning; it fails
+    /// only if none can be.
+    pub async fn get_latest_builds(&self, build_type_ids: &[String]) -> Result<Vec<Build>> {
+        self.latest_builds(build_type_ids, "state:any").await
+    }
+
+    /// Like [`Self::get_latest_builds`], leaving out queued builds
+    pub async fn get_latest_started_builds(&self, build_type_ids: &[String]) -> Result<Vec<Build>> {
+        self.latest_builds(build_type_ids, "running:any").await
+    }
+
+    async fn latest_builds(&self, build_type_ids: &[String], states: &str) -> Result<Vec<Build>> {
+        let mut requests = Vec::new();
+        for build_type_id in build_type_ids {
+            requests.push(self.latest_builds_of(build_type_id, states));
+        }
+        let mut fetched = self.builds_by_build_type(requests).await?;
+        Ok(build_type_ids
+            .iter()
+            .filter_map(|build_type_id| fetched.remove(build_type_id))
+            .flatten()
+            .collect())
+    }
+
+    async fn latest_builds_of<'a>(
+        &self,
+        build_type_id: &'a String,
+        states: &str,
+    ) -> (&'a String, Result<Vec<Build>>) {
+        let locator = format!("buildType:(id:{}),{},count:1", build_type_id, states);
+        (build_type_id, self.get_builds_by_locator(&locator).await)
+    }
+
+    /// Queued and running builds on the default branch of each of `build_type_ids`, with the
+    /// ones that finished after `finished_since`, by configuration ID. A configuration that
+    /// can't be read is left out with a warning; it fails only if none can be.
+    pub async fn get_active_builds(
+        &self,
+        build_type_ids: &[String],
+        finished_since: OffsetDateTime,
+    ) -> Result<HashMap<String, Vec<Build>>> {
+        let finished_since = finished_since
+            .format(LOCATOR_DATE_FORMAT)
+            .unwrap_or_default();
+        let mut requests = Vec::new();
+        for build_type_id in build_type_ids {
+            requests.push(self.active_builds_of(build_type_id, &finished_since));
+        }
+        self.builds_by_build_type(requests).await
+    }
+
+    async fn active_builds_of<'a>(
+        &self,
+        build_type_id: &'a String,
+        finished_since: &str,
+    ) -> (&'a String, Result<Vec<Build>>) {
+        let states = [
+            "state:queued".to_string(),
+            "state:running".to_string(),
+            format!(
+                "state:finished,finishDate:(date:{},condition:after)",
+                finished_since
+            ),
+        ];
+        let mut builds = Vec::new();
+        for states in states {
+            let locator = format!("buildType:(id:{}),{}", build_type_id, states);
+            match self.get_builds_by_locator(&locator).await {
+                Ok(mut fetched) => builds.append(&mut fetched),
+                Err(e) => return (build_type_id, Err(e)),
+            }
+        }
+        (build_type_id, Ok(builds))
+    }
+
+    // builds of each configuration fetched by `requests`, up to `max_concurrent_requests` at
+    // once, skipping the configurations that fail unless all of them do
+    async fn builds_by_build_type<'a>(
+        &self,
+        requests: Vec<impl Future<Output = (&'a String, Result<Vec<Build>>)>>,
+    ) -> Result<HashMap<String, Vec<Build>>> {
+        let fetched: Vec<_> = stream::iter(requests)
+            .buffered(self.max_concurrent_requests.max(1))
+            .collect()
+            .await;
+
+        let mut builds_by_build_type = HashMap::new();
+        let mut first_error = None;
+        for (build_type_id, builds) in fetched {
+            match builds {
+                Ok(builds) => {
+                    builds_by_build_type.insert(build_type_id.clone(), builds);
+                }
+                Err(e) => {
+                    warn!("Failed to fetch the builds of {}: {}", build_type_id, e);
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        match first_error {
+            Some(e) if builds_by_build_type.is_empty() => Err(e),
+            _ => Ok(builds_by_build_type),
+        }
+    }
+
+    /// Changes of a configuration that no build has run on yet, newest first
+    pub async fn get_pending_changes(&self, build_type_id: &str) -> Result<Vec<Change>> {
+        let url = self.url("/app/rest/changes");
+        let fields = "change(id,version,username,date,comment,webUrl)";
+
+        let changes: Changes = self
+            .get_json(
+                self.read_request(&url)
+                    .query(&[
+                        (
+                            "locator",
+                            format!("buildType:(id:{}),pending:true", build_type_id),
+                        ),
+                        ("fields", fields.to_string()),
+                    ])
+                    .header("Accept", "application/json"),
+            )
+            .await?;
+        Ok(changes.change.unwrap_or_default())
+    }
+
+    /// Versioned settings of `project_id`, enabled on it or inherited from its closest parent
+    pub async fn get_versioned_settings(&self, project_id: &str) -> Result<VersionedSettings> {
+        let mut current = Some(project_id.to_string());
+        while let Some(id) = current {
+            let features: ProjectFeatures = self
+                .get_json(
+                    self.read_request(
+                        self.url(&format!("/app/rest/projects/id:{}/projectFeatures", id)),
+                    )
+                    .query(&[
+                        ("locator", "type:versionedSettings"),
+                        (
+                            "fields",
+                            "count,projectFeature(id,type,properties(property(name,value)))",
+                        ),
+                    ])
+                    .header("Accept", "application/json"),
+                )
+                .await?;
+            if let Some(feature) = features.project_feature.first() {
+                let properties = &feature.properties;
+                // a subproject can turn off the settings inherited from its parent
+                if properties.get("enabled") == Some("false") {
+                    break;
+                }
+                let root_id = properties.get("rootId").ok_or_else(|| {
+                    TeamCityError::Missing(format!("Versioned settings of {} have no VCS root", id))
+                })?;
+                let root: VcsRoot = self
+                    .get_json(
+                        self.read_request(self.url(&format!("/app/rest/vcs-roots/id:{}", root_id)))
+                            .query(&[("fields", "id,name,properties(property(name,value))")])
+                            .header("Accept", "application/json"),
+                    )
+                    .await?;
+                let vcs_url = root.properties.get("url").ok_or_else(|| {
+                    TeamCityError::Missing(format!("VCS root {} has no URL", root_id))
+                })?;
+                return Ok(VersionedSettings {
+                    format: properties.get("format").unwrap_or("xml").to_string(),
+                    vcs_url: vcs_url.to_string(),
+                    branch: root.properties.get("branch").map(str::to_string),
+                });
+            }
+
+            let project: Project = self
+                .get_json(
+                    self.read_request(self.url(&format!("/app/rest/projects/id:{}", id)))
+                        .query(&[("fields", "id,name,parentProjectId")])
+                        .header("Accept", "application/json"),
+                )
+                .await?;
+            current = project.parent_project_id;
+        }
+        Err(TeamCityError::Missing(format!(
+            "Versioned settings are not enabled for {}",
+            project_id
+        )))
+    }
+
+    /// Agent requirements of a configuration with the agents meeting them and the ones that don't
+    pub async fn get_agent_compatibility(&self, build_type_id: &str) -> Result<AgentCompatibility> {
+        let requirements: AgentRequirements = self
+            .get_json(
+                self.read_request(self.url(&format!(
+                    "/app/rest/buildTypes/id:{}/agent-requirements",
+                    build_type_id
+                )))
+                .query(&[(
+                    "fields",
+                    "count,agent-requirement(id,type,disabled,properties(property(name,value)))",
+                )])
+                .header("Accept", "application/json"),
+            )
+            .await?;
+        let agents = |compatibility: &str| {
+            self.get_json::<Agents>(
+                self.read_request(self.url("/app/rest/agents"))
+                    .query(&[
+                        (
+                            "locator",
+                            format!("{}:(buildType:(id:{}))", compatibility, build_type_id),
+                        ),
+                        (
+                            "fields",
+                            "count,agent(id,name,connected,enabled,authorized,build(id),pool(id,name))"
+                                .to_string(),
+                        ),
+                    ])
+                    .header("Accept", "application/json"),
+            )
+        };
+        let compatible = agents("compatible").await?;
+        let incompatible = agents("incompatible").await?;
+        Ok(AgentCompatibility {
+            requirements: requirements.agent_requirement,
+            compatible: compatible.agent,
+            incompatible: incompatible.agent,
+        })
+    }
+
+    /// Parameters of a configuration, its own and the inherited ones, ordered by name
+    pub async fn get_parameters(&self, build_type_id: &str) -> Result<Vec<Parameter>> {
+        let parameters: Parameters = self
+            .get_json(
+                self.read_request(self.url(&format!(
+                    "/app/rest/buildTypes/id:{}/parameters",
+                    build_type_id
+                )))
+                .query(&[(
+                    "fields",
+                    "count,property(name,value,inherited,type(rawValue))",
+                )])
+                .header("Accept", "application/json"),
+            )
+            .await?;
+        let mut parameters = parameters.property;
+        parameters.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(parameters)
+    }
+
+    /// Sets the value of a configuration parameter, overriding it in the configuration if it is
+    /// inherited
+    pub async fn set_parameter(&self, build_type_id: &str, name: &str, value: &str) -> Result<()> {
+        let response = self
+            .send_write(|| {
+                self.write_request(
+                    Method::PUT,
+                    &self.url(&format!(
+                        "/app/rest/buildTypes/id:{}/parameters/{}/value",
+                        build_type_id, name
+                    )),
+                )
+                .header("Content-Type", "text/plain")
+                .header("Accept", "text/plain")
+                .body(value.to_string())
+            })
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(TeamCityError::from_response(&response));
+        }
+
+        Ok(())
+    }
+
+    /// Pauses or resumes a configuration, which takes the right to edit its settings
+    pub async fn set_paused(&self, build_type_id: &str, paused: bool) -> Result<()> {
+        let response = self
+            .send_write(|| {
+                self.write_request(
+                    Method::PUT,
+                    &self.url(&format!("/app/rest/buildTypes/id:{}/paused", build_type_id)),
+                )
+                .header("Content-Type", "text/plain")
+                .header("Accept", "text/plain")
+                .body(paused.to_string())
+            })
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(TeamCityError::from_response(&response));
+        }
+
+        Ok(())
+    }
+
+    /// All projects visible to the user, parents before their subprojects
+    pub async fn get_projects(&self) -> Result<Vec<Project>> {
+        let url = self.url("/app/rest/projects");
+
+        let projects: Projects = self
+            .get_json(
+                self.read_request(&url)
+                    .query(&[("fields", "count,project(id,name,parentProjectId)")])
+                    .header("Accept", "application/json"),
+            )
+            .await?;
+        Ok(projects.project)
+    }
+
+    /// Top-level projects, the direct children of the root project
+    pub async fn get_root_projects(&self) -> Result<Vec<Project>> {
+        let projects: Projects = self
+            .get_json(
+                self.read_request(self.url("/app/rest/projects"))
+                    .query(&[
+                        ("locator", "parentProject:(id:_Root)"),
+                        ("fields", "count,project(id,name,parentProjectId)"),
+                    ])
+                    .header("Accept", "application/json"),
+            )
+            .await?;
+        Ok(projects.project)
+    }
+
+    /// Agent pools with their authorized agents and the projects assigned to them, ordered by name
+    pub async fn get_agent_pools(&self) -> Result<Vec<AgentPool>> {
+        let pools: AgentPools = self
+            .get_json(
+                self.read_request(self.url("/app/rest/agentPools"))
+                    .query(&[(
+                        "fields",
+                        "count,agentPool(id,name,\
+                         agents(count,agent(id,name,connected,enabled,authorized,build(id,buildTypeId,number))),\
+                         projects(count,project(id,name)))",
+                    )])
+                    .header("Accept", "application/json"),
+            )
+            .await?;
+        let mut pools = pools.agent_pool;
+        pools.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(pools)
+    }
+
+    /// Moves an agent to the pool `pool_id`, which needs the right to manage agent pools
+    pub async fn move_agent_to_pool(&self, agent_id: i64, pool_id: i64) -> Result<()> {
+        let response = self
+            .send_write(|| {
+                self.write_request(
+                    Method::POST,
+                    &self.url(&format!("/app/rest/agentPools/id:{}/agents", pool_id)),
+                )
+                .header("Accept", "application/json")
+                .json(&serde_json::json!({ "id": agent_id }))
+            })
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(TeamCityError::from_response(&response));
+        }
+
+        Ok(())
+    }
+
+    /// Version, uptime, license, agents and build queue length of the server
+    pub async fn get_server_overview(&self) -> Result<ServerOverview> {
+        let info: ServerInfo = self
+            .get_json(
+                self.read_request(self.url("/app/rest/server"))
+                    .query(&[(
+                        "fields",
+                        "version,versionMajor,versionMinor,buildNumber,startTime,currentTime",
+                    )])
+                    .header("Accept", "application/json"),
+            )
+            .await?;
+        let licensing = self
+            .get_json::<LicensingData>(
+                self.read_request(self.url("/app/rest/server/licensingData"))
+                    .query(&[(
+                        "fields",
+                        "serverLicenseType,maxAgents,agentsLeft,unlimitedAgents",
+                    )])
+                    .header("Accept", "application/json"),
+            )
+            .await
+            .inspect_err(|e| warn!("Failed to read the license: {}", e))
+            .ok();
+        let agents: Agents = self
+            .get_json(
+                self.read_request(self.url("/app/rest/agents"))
+                    .query(&[
+                        ("locator", "defaultFilter:false"),
+                        (
+                            "fields",
+                            "count,agent(id,name,connected,enabled,authorized,build(id),pool(id,name))",
+                        ),
+                    ])
+                    .header("Accept", "application/json"),
+            )
+            .await?;
+        let queue: Builds = self
+            .get_json(
+                self.read_request(self.url("/app/rest/buildQueue"))
+                    .query(&[("fields", "count")])
+                    .header("Accept", "application/json"),
+            )
+            .await?;
+        Ok(ServerOverview {
+            info,
+            licensing,
+            agents: agents.agent,
+            queue_length: queue.count.unwrap_or_default(),
+        })
+    }
+
+    /// Names of the branches of a configuration, the default branch first
+    pub async fn get_branches(&self, build_type_id: &str) -> Result<Vec<String>> {
+        let url = self.url(&format!(
+            "/app/rest/buildTypes/id:{}/branches",
+            build_type_id
+        ));
+
+        let mut branches: Branches = self
+            .get_json(
+                self.read_request(&url)
+                    .query(&[
+                        ("locator", "policy:ALL_BRANCHES"),
+                        ("fields", "count,branch(name,default)"),
+                    ])
+                    .header("Accept", "application/json"),
+            )
+            .await?;
+        branches.branch.sort_by_key(|branch| !branch.default);
+        Ok(branches
+            .branch
+            .into_iter()
+            .map(|branch| branch.name)
+            .collect())
+    }
+
+    /// Statistic values of a build, like code coverage, artifacts size or test count, by name
+    pub async fn get_build_statistics(&self, build_id: i64) -> Result<Properties> {
+        self.get_json(
+            self.read_request(self.url(&format!("/app/rest/builds/id:{}/statistics", build_id)))
+                .header("Accept", "application/json"),
+        )
+        .await
+    }
+
+    /// Failed tests of a build, muted ones included
+    pub async fn get_failed_tests(&self, build_id: i64) -> Result<Vec<TestOccurrence>> {
+        let url = self.url("/app/rest/testOccurrences");
+        let fields = "count,testOccurrence(id,name,status,muted,details,test(id,name))";
+
+        let tests: TestOccurrences = self
+            .get_json(
+                self.read_request(&url)
+                    .query(&[
+                        (
+                            "locator",
+                            format!("build:(id:{}),status:FAILURE,count:1000", build_id),
+                        ),
+                        ("fields", fields.to_string()),
+                    ])
+                    .header("Accept", "application/json"),
+            )
+            .await?;
+        Ok(tests.test_occurrence)
+    }
+
+    /// Latest `count` occurrences of a test in builds of a configuration, newest first
+    pub async fn get_test_history(
+        &self,
+        test_id: &str,
+        build_type_id: &str,
+        count: u32,
+    ) -> Result<Vec<TestOccurrence>> {
+        let url = self.url("/app/rest/testOccurrences");
+        let fields = "count,testOccurrence(id,name,status,muted,details,build(id,number,status,branchName,startDate,webUrl))";
+
+        let tests: TestOccurrences = self
+            .get_json(
+                self.read_request(&url)
+                    .query(&[
+                        (
+                            "locator",
+                            format!(
+                                "test:(id:{}),buildType:(id:{}),count:{}",
+                                test_id, build_type_id, count
+                            ),
+                        ),
+                        ("fields", fields.to_string()),
+                    ])
+                    .header("Accept", "application/json"),
+            )
+            .await?;
+        Ok(tests.test_occurrence)
+    }
+
+    /// Tests muted in a project and its subprojects
+    pub async fn get_mutes(&self, project_id: &str) -> Result<Vec<Mute>> {
+        let url = self.url("/app/rest/mutes");
+        let fields = "count,mute(id,assignment(text,user(username,name)),scope(project(id,name),buildTypes(buildType(id,name))),target(tests(test(id,name))))";
+
+        let mutes: Mutes = self
+            .get_json(
+                self.read_request(&url)
+                    .query(&[
+                        ("locator", format!("project:(id:{})", project_id)),
+                        ("fields", fields.to_string()),
+                    ])
+                    .header("Accept", "application/json"),
+            )
+            .await?;
+        Ok(mutes.mute)
+    }
+
+    /// Mutes a test in `scope` until it is unmuted manually
+    pub async fn mute_test(&self, test_id: &str, scope: &MuteScope, comment: &str) -> Result<()> {
+        let body = serde_json::json!({
+            "scope": scope,
+            "target": { "tests": { "test": [{ "id": test_id }] } },
+            "assignment": { "text": comment },
+            "resolution": { "type": "manually" },
+        });
+
+        let response = self
+            .send_write(|| {
+                self.write_request(Method::POST, &self.url("/app/rest/mutes"))
+                    .header("Accept", "application/json")
+                    .json(&body)
+            })
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(TeamCityError::from_response(&response));
+        }
+
+        Ok(())
+    }
+
+    /// Removes the mute `mute_id`, unmuting all its tests and problems
+    pub async fn unmute(&self, mute_id: i64) -> Result<()> {
+        let response = self
+            .send_write(|| {
+                self.write_request(
+                    Method::DELETE,
+                    &self.url(&format!("/app/rest/mutes/id:{}", mute_id)),
+                )
+            })
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(TeamCityError::from_response(&response));
+        }
+
+        Ok(())
+    }
+
+    /// Recent running and finished builds of all branches under `project_ids`, newest first
+    pub async fn get_recent_builds_by_projects(
+        &self,
+        project_ids: &[String],
+        count: u32,
+    ) -> Result<Vec<Build>> {
+        let url = self.url("/app/rest/builds");
+        let fields = "count,build(id,number,branchName,status,state,webUrl,buildTypeId,startDate,finishDate)";
+
+        let mut all_builds = Vec::new();
+        for project_id in project_ids {
+            let mut builds: Builds = self
+                .get_json(self.read_request(&url)
+                .query(&[
+                    (
+                        "locator",
+                        format!(
+                            "affectedProject:(id:{}),branch:default:any,running:any,count:{}",
+                            project_id, count
+                        ),
+                    ),
+                    ("fields", fields.to_string()),
+                ])
+                .header("Accept", "application/json"))
+                .await?;
+            all_builds.append(&mut builds.build);
+        }
+        Ok(all_builds)
+    }
+
+    /// Lists the artifacts of a build with their top-level paths and total size
+    pub async fn get_build_artifacts(&self, build: &Build) -> Result<BuildArtifacts> {
+        let build_id = build
+            .id
+            .ok_or_else(|| TeamCityError::Missing("Build has no id".to_string()))?;
+        let url = self.url(&format!("/app/rest/builds/id:{}/artifacts", build_id));
+
+        let files: ArtifactFiles = self
+            .get_json(
+                self.read_request(&url)
+                    .query(&[
+                        ("locator", "recursive:true"),
+                        ("fields", "count,file(name,fullName,size)"),
+                    ])
+                    .header("Accept", "application/json"),
+            )
+            .await?;
+
+        let mut paths: Vec<String> = Vec::new();
+        let mut total_size = 0;
+        for file in &files.file {
+            total_size += file.size.unwrap_or(0);
+            let full_name = file.full_name.as_deref().unwrap_or(&file.name);
+            let top_level = full_name.split('/').next().unwrap_or(full_name);
+            if !paths.iter().any(|p| p == top_level) {
+                paths.push(top_level.to_string());
+            }
+        }
+
+        Ok(BuildArtifacts {
+            build_id,
+            build_number: build.build_number.clone(),
+            paths,
+            total_size,
+        })
+    }
+
+    /// Streams the zip archive of all artifacts of a build to `path`, reporting the bytes written
+    /// so far and the archive size if the server sent it. The file is removed if the download fails.
+    pub async fn download_artifacts_archive(
+        &self,
+        build_id: i64,
+        path: &std::path::Path,
+        mut progress: impl FnMut(u64, Option<u64>),
+    ) -> Result<()> {
+        let url = self.url(&format!(
+            "/app/rest/builds/id:{}/artifacts/archived",
+            build_id
+        ));
+        let mut response = self
+            .coordinator
+            .send(self.read_request(&url).timeout(ARTIFACTS_DOWNLOAD_TIMEOUT))
+            .await?;
+        if !response.status().is_success() {
+            return Err(TeamCityError::from_response(&response));
+        }
+
+        let total = response.content_length();
+        let mut file = async_fs::File::create(path).await?;
+        let result: Result<()> = async {
+            let mut downloaded = 0;
+            let mut reported = Instant::now();
+            while let Some(chunk) = response.chunk().await? {
+                file.write_all(&chunk).await?;
+                downloaded += chunk.len() as u64;
+                if reported.elapsed() >= PROGRESS_INTERVAL {
+                    progress(downloaded, total);
+                    reported = Instant::now();
+                }
+            }
+            file.flush().await?;
+            progress(downloaded, total);
+            Ok(())
+        }
+        .await;
+        if result.is_err() {
+            let _ = async_fs::remove_file(path).await;
+        }
+        result
+    }
+
+    /// Deletes the artifact at `path` of a build, with everything underneath if it is a directory
+    pub async fn delete_build_artifact(&self, build_id: i64, path: &str) -> Result<()> {
+        let mut url =
+            Url::parse(&self.url(&format!("/app/rest/builds/id:{}/artifacts/files", build_id)))
+                .map_err(|e| TeamCityError::Invalid(format!("Invalid artifact URL: {}", e)))?;
+        // each segment is percent-encoded, so paths may have spaces, `#` or `?`
+        url.path_segments_mut()
+            .map_err(|_| TeamCityError::Invalid("Invalid artifact URL".to_string()))?
+            .extend(path.split('/'));
+
+        let response = self
+            .send_write(|| self.write_request(Method::DELETE, url.as_str()))
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(TeamCityError::from_response(&response));
+        }
+
+        Ok(())
+    }
+
+    /// Stops a running build, or removes it from the queue if it hasn't started yet
+    pub async fn cancel_build(&self, build: &Build, comment: &str) -> Result<()> {
+        let build_id = build
+            .id
+            .ok_or_else(|| TeamCityError::Missing("Build has no id".to_string()))?;
+        let path = if build.state.as_deref() == Some("queued") {
+            format!("/app/rest/buildQueue/id:{}", build_id)
+        } else {
+            format!("/app/rest/builds/id:{}", build_id)
+        };
+
+        let response = self
+            .send_write(|| {
+                self.write_request(Method::POST, &self.url(&path))
+                    .header("Accept", "application/json")
+                    .json(&serde_json::json!({
+                        "comment": comment,
+                        "readdIntoQueue": false,
+                    }))
+            })
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(TeamCityError::from_response(&response));
+        }
+
+        Ok(())
+    }
+
+    /// Queues a new build of the same configuration and branch as `build`
+    pub async fn rerun_build(&self, build: &Build) -> Result<()> {
+        let body = Self::rerun_body(build)?;
+        self.queue_build(&body).await
+    }
+
+    /// Queues `build` again with its snapshot dependencies rebuilt: all of them if
+    /// `all_dependencies`, otherwise only those that failed or didn't finish, reusing the others
+    pub async fn rebuild_chain(&self, build: &Build, all_dependencies: bool) -> Result<()> {
+        let mut body = Self::rerun_body(build)?;
+        body["triggeringOptions"] = serde_json::json!({
+            "rebuildAllDependencies": all_dependencies,
+            "rebuildFailedOrIncompleteDependencies": !all_dependencies,
+        });
+        self.queue_build(&body).await
+    }
+
+    // body queueing a build of the same configuration and branch as `build`
+    fn rerun_body(build: &Build) -> Result<serde_json::Value> {
+        let build_type_id = build.build_type_id.as_deref().ok_or_else(|| {
+            TeamCityError::Missing("Build has no build configuration".to_string())
+        })?;
+
+        let mut body = serde_json::json!({ "buildType": { "id": build_type_id } });
+        if let Some(branch_name) = &build.branch_name {
+            body["branchName"] = serde_json::Value::from(branch_name.as_str());
+        }
+        Ok(body)
+    }
+
+    async fn queue_build(&self, body: &serde_json::Value) -> Result<()> {
+        let response = self
+            .send_write(|| {
+                self.write_request(Method::POST, &self.url("/app/rest/buildQueue"))
+                    .header("Accept", "application/json")
+                    .json(body)
+            })
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(TeamCityError::from_response(&response));
+        }
+
+        Ok(())
+    }
+
+    /// Queues a build of a configuration on `branch`, or on its default branch
+    pub async fn trigger_build(&self, build_type_id: &str, branch: Option<&str>) -> Result<()> {
+        self.queue_build(&Self::trigger_body(build_type_id, branch))
+            .await
+    }
+
+    /// Queues a build of a configuration on `branch`, or on its default branch, pinned to the
+    /// change `change_id` so that it runs on that commit even if newer ones were pushed since
+    pub async fn trigger_build_on_change(
+        &self,
+        build_type_id: &str,
+        branch: Option<&str>,
+        change_id: i64,
+    ) -> Result<()> {
+        let mut body = Self::trigger_body(build_type_id, branch);
+        body["lastChanges"] = serde_json::json!({ "change": [{ "id": change_id }] });
+        self.queue_build(&body).await
+    }
+
+    fn trigger_body(build_type_id: &str, branch: Option<&str>) -> serde_json::Value {
+        let mut body = serde_json::json!({ "buildType": { "id": build_type_id } });
+        if let Some(branch) = branch {
+            body["branchName"] = serde_json::Value::from(branch);
+        }
+        body
+    }
+
+    /// Whole log of a build as plain text
+    // TODO: test if downloading and unpacking zip archive will be more efficient
+    pub async fn get_build_log_text(&self, build_id: &i64) -> Result<String> {
+        let text = self.fetch_build_log(*build_id).await?;
+        Ok(Arc::unwrap_or_clone(text))
+    }
+
+    async fn fetch_build_log(&self, build_id: i64) -> Result<Arc<String>> {
+        let url = self.url("/downloadBuildLog.html");
+        let request = self
+            .read_request(&url)
+            .query(&[
+                ("buildId", build_id.to_string()),
+                ("plain", "true".to_string()),
+            ])
+            .timeout(LOG_DOWNLOAD_TIMEOUT);
+
+        self.coordinator.get_text(&self.client, request).await
+    }
+
+    /// Logs of `builds` downloaded in parallel and handed to `process` as each arrives, in the
+    /// order of `builds`. Only what `process` returns is kept, not the whole logs.
+    pub async fn get_build_logs<T>(
+        &self,
+        builds: Vec<Build>,
+        process: impl Fn(&Build, &str) -> T,
+    ) -> Vec<(Build, Result<T>)> {
+        let process = &process;
+        let requests = builds.into_iter().map(|build| async move {
+            let result = match build.id {
+                Some(build_id) => self
+                    .fetch_build_log(build_id)
+                    .await
+                    .map(|log| process(&build, &log)),
+                None => Err(TeamCityError::Missing("Build has no id".to_string())),
+            };
+            (build, result)
+        });
+        stream::iter(requests)
+            .buffered(self.max_concurrent_requests.max(1))
+            .collect()
+            .await
+    }
+
+    /// Writes the log of a build to the file at `path`
+    pub async fn download_build_log_to<P: AsRef<std::path::Path>>(
+        &self,
+        build_id: &i64,
+        path: P,
+    ) -> Result<()> {
+        let text = self.get_build_log_text(build_id).await?;
+        async_fs::write(path.as_ref(), text).await?;
+        Ok(())
+    }
+}
+
+/// Directory of the cache files of clients that aren't given one with
+/// [`TeamCityClient::cache_dir`], the one t9s uses
+pub fn default_cache_dir() -> PathBuf {
+    match ProjectDirs::from("io", "snpefk", "t9s") {
+        Some(dirs) => dirs.cache_dir().to_path_buf(),
+        None => PathBuf::from(".").join(".cache"),
+    }
+}
+
+/// HTTP client with the proxy, certificates and timeouts of `http`
+fn http_client(http: &HttpConfig) -> Result<reqwest::Client> {
+    let mut builder =
+        reqwest::Client::builder().danger_accept_invalid_certs(http.danger_accept_invalid_certs);
+    if let Some(proxy) = &http.proxy {
+        builder = builder.proxy(
+            reqwest::Proxy::all(proxy)
+                .map_err(|e| TeamCityError::Invalid(format!("Invalid proxy {}: {}", proxy, e)))?,
+        );
+    }
+    if let Some(path) = &http.ca_bundle {
+        let pem = std::fs::read(path).map_err(|e| {
+            TeamCityError::Invalid(format!(
+                "Failed to read CA bundle {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        for certificate in reqwest::Certificate::from_pem_bundle(&pem).map_err(|e| {
+            TeamCityError::Invalid(format!("Invalid CA bundle {}: {}", path.display(), e))
+        })? {
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+    if let Some(secs) = http.connect_timeout_secs {
+        builder = builder.connect_timeout(Duration::from_secs(secs));
+    }
+    let request_timeout = http
+        .request_timeout_secs
+        .map_or(DEFAULT_REQUEST_TIMEOUT, Duration::from_secs);
+    Ok(builder.timeout(request_timeout).build()?)
+}
+
+/// Why the server at `base_url` couldn't be reached, with what to check
+fn describe_connection_error(base_url: &str, error: &TeamCityError) -> String {
+    match error {
+        TeamCityError::Unauthorized => format!(
+            "{} rejected the credentials (401), check the token, username and password, or that \
+             guest login is enabled",
+            base_url
+        ),
+        TeamCityError::Forbidden => format!("{} denied access to its REST API (403)", base_url),
+        TeamCityError::NotFound => format!(
+            "No TeamCity REST API at {} (404), check the URL and the context path the server runs \
+             under, like https://host/teamcity",
+            base_url
+        ),
+        TeamCityError::Decode { .. } => format!(
+            "{} doesn't look like a TeamCity server, check the URL and its context path",
+            base_url
+        ),
+        TeamCityError::Network(e) if e.is_timeout() => {
+            format!("{} didn't respond in time", base_url)
+        }
+        TeamCityError::Network(e) if e.is_connect() => {
+            format!("Can't connect to {}: {}", base_url, e)
+        }
+        TeamCityError::Network(e) => format!("Can't reach {}: {}", base_url, e),
+        error => match error.status() {
+            Some(status) => format!("{} responded {}", base_url, status),
+            None => format!("Can't reach {}: {}", base_url, error),
+        },
+    }
+}
+
+/// Short, user-facing description of a request error, telling apart bad credentials, missing
+/// permissions, unknown IDs and network trouble
+fn describe_error(error: &TeamCityError) -> String {
+    match error {
+        TeamCityError::Unauthorized => {
+            "unauthorized (401), check the token or credentials".to_string()
+        }
+        TeamCityError::Forbidden => {
+            "access denied (403), the user can't view this project".to_string()
+        }
+        TeamCityError::NotFound => "not found (404), check the project ID".to_string(),
+        TeamCityError::Network(e) if e.is_timeout() => "timed out".to_string(),
+        TeamCityError::Network(e) if e.is_connect() => "connection failed".to_string(),
+        error => match error.status() {
+            Some(status) => format!("server responded {}", status),
+            None => error.to_string(),
+        },
+    }
+}
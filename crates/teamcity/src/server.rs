@@ -0,0 +1,164 @@
+use crate::error::{Result, TeamCityError};
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+// first path segments of pages and APIs of the server, anything before them is the context path
+const SERVER_PATH_SEGMENTS: [&str; 12] = [
+    "app",
+    "httpAuth",
+    "guestAuth",
+    "favorite",
+    "project",
+    "buildConfiguration",
+    "admin",
+    "agents",
+    "queue",
+    "overview.html",
+    "login.html",
+    "viewLog.html",
+];
+
+/// Response of `/app/rest/server`
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct ServerInfo {
+    pub version: Option<String>,
+    #[serde(rename = "versionMajor")]
+    pub version_major: Option<u32>,
+    #[serde(rename = "versionMinor")]
+    pub version_minor: Option<u32>,
+    #[serde(rename = "buildNumber")]
+    pub build_number: Option<String>,
+    #[serde(
+        rename = "startTime",
+        default,
+        with = "super::types::teamcity_datetime"
+    )]
+    pub start_time: Option<OffsetDateTime>,
+    #[serde(
+        rename = "currentTime",
+        default,
+        with = "super::types::teamcity_datetime"
+    )]
+    pub current_time: Option<OffsetDateTime>,
+}
+
+/// Response of `/app/rest/server/licensingData`, readable by administrators only
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct LicensingData {
+    #[serde(rename = "serverLicenseType")]
+    pub server_license_type: Option<String>,
+    #[serde(rename = "maxAgents")]
+    pub max_agents: Option<i64>,
+    #[serde(rename = "agentsLeft")]
+    pub agents_left: Option<i64>,
+    #[serde(rename = "unlimitedAgents")]
+    pub unlimited_agents: Option<bool>,
+}
+
+/// Base URL of the server from a URL as users paste it: an `http` or `https` URL with the context
+/// path the server runs under, if any, but without trailing slashes, query, or the path of a page
+/// like `/favorite/projects` copied from the browser
+pub fn normalize_server_url(input: &str) -> Result<String> {
+    let input = input.trim();
+    if !input.contains("://") {
+        return Err(TeamCityError::Invalid(format!(
+            "Server URL '{}' has no scheme, use https://{} or http://{}",
+            input, input, input
+        )));
+    }
+    let url = Url::parse(input)
+        .map_err(|e| TeamCityError::Invalid(format!("Invalid server URL '{}': {}", input, e)))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(TeamCityError::Invalid(format!(
+            "Server URL '{}' must start with http:// or https://",
+            input
+        )));
+    }
+    let Some(host) = url.host_str() else {
+        return Err(TeamCityError::Invalid(format!(
+            "Server URL '{}' has no host",
+            input
+        )));
+    };
+
+    let context: Vec<&str> = url
+        .path_segments()
+        .into_iter()
+        .flatten()
+        .take_while(|segment| !SERVER_PATH_SEGMENTS.contains(segment))
+        .filter(|segment| !segment.is_empty())
+        .collect();
+    let mut base = format!("{}://{}", url.scheme(), host);
+    if let Some(port) = url.port() {
+        base.push_str(&format!(":{}", port));
+    }
+    for segment in context {
+        base.push('/');
+        base.push_str(segment);
+    }
+    Ok(base)
+}
+
+/// Short name of the server at `base_url` for telling servers apart: its host, with the port if
+/// it isn't the default one
+pub fn server_name(base_url: &str) -> String {
+    match Url::parse(base_url) {
+        Ok(url) => match (url.host_str(), url.port()) {
+            (Some(host), Some(port)) => format!("{}:{}", host, port),
+            (Some(host), None) => host.to_string(),
+            (None, _) => base_url.to_string(),
+        },
+        Err(_) => base_url.to_string(),
+    }
+}
+
+/// CSRF token of a session, sent with its session cookie in requests changing data
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsrfToken {
+    pub token: String,
+    pub session_cookie: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn server_is_named_after_its_host() {
+        assert_eq!(server_name("https://tc.example.com/tc"), "tc.example.com");
+        assert_eq!(server_name("http://localhost:8111"), "localhost:8111");
+    }
+
+    #[test]
+    fn server_url_keeps_the_context_path_only() {
+        let normalize = |input| normalize_server_url(input).unwrap();
+        assert_eq!(
+            normalize(" https://tc.example.com/ "),
+            "https://tc.example.com"
+        );
+        assert_eq!(
+            normalize("https://tc.example.com/tc//"),
+            "https://tc.example.com/tc"
+        );
+        assert_eq!(
+            normalize("http://ci.local:8111/favorite/projects?mode=builds"),
+            "http://ci.local:8111"
+        );
+        assert_eq!(
+            normalize("https://tc.example.com/tc/buildConfiguration/App_Build/123"),
+            "https://tc.example.com/tc"
+        );
+        assert_eq!(
+            normalize("https://tc.example.com/httpAuth/app/rest/server"),
+            "https://tc.example.com"
+        );
+    }
+
+    #[test]
+    fn server_url_needs_an_http_scheme_and_host() {
+        assert!(normalize_server_url("tc.example.com").is_err());
+        assert!(normalize_server_url("ftp://tc.example.com").is_err());
+        assert!(normalize_server_url("https://").is_err());
+    }
+}
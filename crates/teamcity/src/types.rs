@@ -0,0 +1,633 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+// Look here for docs
+// https://www.jetbrains.com/help/teamcity/rest/buildtype.html
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct BuildType {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(rename = "projectName")]
+    pub project_name: Option<String>,
+    #[serde(rename = "projectId")]
+    pub project_id: Option<String>,
+    pub href: Option<String>,
+    #[serde(rename = "webUrl")]
+    pub web_url: Option<String>,
+    pub links: Option<Links>,
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
+    // paused configurations are not triggered automatically
+    pub paused: Option<bool>,
+    // name of the server the configuration is on, only set when several servers are configured
+    #[serde(skip)]
+    pub server: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct Links {
+    pub count: u32,
+    #[serde(rename = "link")]
+    pub links: Vec<Link>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct Link {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub url: String,
+    #[serde(rename = "relativeUrl")]
+    pub relative_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BuildTypes {
+    pub count: u32,
+    pub href: Option<String>,
+    #[serde(rename = "buildType")]
+    pub build_type: Vec<BuildType>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct Change {
+    pub id: Option<i64>,
+    pub username: Option<String>,
+    pub comment: Option<String>,
+    pub version: Option<String>,
+    #[serde(default, with = "teamcity_datetime")]
+    pub date: Option<OffsetDateTime>,
+    #[serde(rename = "webUrl")]
+    pub web_url: Option<String>,
+    // builds of each configuration that first included the change
+    #[serde(rename = "firstBuilds")]
+    pub first_builds: Option<Builds>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct Changes {
+    #[serde(rename = "change")]
+    pub change: Option<Vec<Change>>,
+}
+
+// Build entity docs:
+// https://www.jetbrains.com/help/teamcity/rest/build.html
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct Build {
+    pub id: Option<i64>,
+    #[serde(rename = "buildTypeId")]
+    pub build_type_id: Option<String>,
+    #[serde(rename = "number")]
+    pub build_number: Option<String>,
+    pub status: Option<String>, // SUCCESS, FAILURE, etc.
+    pub state: Option<String>,  // queued, running, finished
+    pub href: Option<String>,
+    #[serde(rename = "webUrl")]
+    pub web_url: Option<String>,
+    #[serde(rename = "branchName")]
+    pub branch_name: Option<String>,
+    #[serde(rename = "statusText")]
+    pub status_text: Option<String>,
+    #[serde(rename = "queuedDate", default, with = "teamcity_datetime")]
+    pub queued_date: Option<OffsetDateTime>,
+    #[serde(rename = "startDate", default, with = "teamcity_datetime")]
+    pub start_date: Option<OffsetDateTime>,
+    #[serde(rename = "finishDate", default, with = "teamcity_datetime")]
+    pub finish_date: Option<OffsetDateTime>,
+    pub changes: Option<Changes>,
+    pub pinned: Option<bool>,
+    pub personal: Option<bool>,
+    pub triggered: Option<Triggered>,
+    pub revisions: Option<Revisions>,
+    pub tags: Option<Tags>,
+    // why a queued build hasn't started yet
+    #[serde(rename = "waitReason")]
+    pub wait_reason: Option<String>,
+    #[serde(rename = "startEstimate", default, with = "teamcity_datetime")]
+    pub start_estimate: Option<OffsetDateTime>,
+    // progress of a running build as estimated by the server
+    #[serde(rename = "running-info")]
+    pub running_info: Option<RunningInfo>,
+    /// Position of a queued build in the whole build queue, starting at 1; not part of the API
+    #[serde(skip)]
+    pub queue_position: Option<usize>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct RunningInfo {
+    #[serde(rename = "elapsedSeconds")]
+    pub elapsed_seconds: Option<i64>,
+    // from the durations of previous builds, missing for a configuration that never finished one
+    #[serde(rename = "estimatedTotalSeconds")]
+    pub estimated_total_seconds: Option<i64>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct Revisions {
+    #[serde(default)]
+    pub revision: Vec<Revision>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct Tags {
+    #[serde(default)]
+    pub tag: Vec<Tag>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct Tag {
+    pub name: String,
+}
+
+/// VCS revision a build was run against
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct Revision {
+    pub version: Option<String>,
+    #[serde(rename = "vcsBranchName")]
+    pub vcs_branch_name: Option<String>,
+    #[serde(rename = "vcs-root-instance")]
+    pub vcs_root_instance: Option<VcsRootInstance>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct VcsRootInstance {
+    pub name: Option<String>,
+    #[serde(rename = "vcs-root-id")]
+    pub vcs_root_id: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct Triggered {
+    #[serde(rename = "type")]
+    pub kind: Option<String>, // user, vcs, schedule, snapshotDependency, buildType, ...
+    // trigger specific description, e.g. the VCS root of a VCS trigger
+    pub details: Option<String>,
+    pub user: Option<User>,
+    // build whose finish triggered this one
+    pub build: Option<Box<Build>>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct User {
+    pub username: Option<String>,
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct Builds {
+    pub count: Option<u32>,
+    pub href: Option<String>,
+    // missing when no build matches, e.g. in an empty build queue
+    #[serde(rename = "build", default)]
+    pub build: Vec<Build>,
+}
+
+// Artifact docs:
+// https://www.jetbrains.com/help/teamcity/rest/manage-finished-builds.html#Get+Build+Artifacts
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct ArtifactFile {
+    pub name: String,
+    #[serde(rename = "fullName")]
+    pub full_name: Option<String>,
+    // directories have no size
+    pub size: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct ArtifactFiles {
+    pub count: Option<u32>,
+    #[serde(default)]
+    pub file: Vec<ArtifactFile>,
+}
+
+// Test docs:
+// https://www.jetbrains.com/help/teamcity/rest/manage-tests.html
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct Test {
+    pub id: Option<String>,
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct TestOccurrence {
+    pub id: Option<String>,
+    pub name: Option<String>,
+    pub status: Option<String>,
+    pub muted: Option<bool>,
+    pub details: Option<String>,
+    pub test: Option<Test>,
+    /// Build the test ran in, only fetched for the history of a test
+    pub build: Option<Build>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct TestOccurrences {
+    pub count: Option<u32>,
+    #[serde(rename = "testOccurrence", default)]
+    pub test_occurrence: Vec<TestOccurrence>,
+}
+
+/// ID and name of a project or build configuration referenced by another entity
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct EntityRef {
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct BuildTypeRefs {
+    #[serde(rename = "buildType", default)]
+    pub build_type: Vec<EntityRef>,
+}
+
+/// Project or build configurations a test is muted in
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct MuteScope {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project: Option<EntityRef>,
+    #[serde(rename = "buildTypes", skip_serializing_if = "Option::is_none")]
+    pub build_types: Option<BuildTypeRefs>,
+}
+
+impl MuteScope {
+    pub fn project(project_id: &str) -> Self {
+        Self {
+            project: Some(EntityRef {
+                id: Some(project_id.to_string()),
+                name: None,
+            }),
+            build_types: None,
+        }
+    }
+
+    pub fn build_type(build_type_id: &str) -> Self {
+        Self {
+            project: None,
+            build_types: Some(BuildTypeRefs {
+                build_type: vec![EntityRef {
+                    id: Some(build_type_id.to_string()),
+                    name: None,
+                }],
+            }),
+        }
+    }
+
+    /// Names of the project or build configurations, falling back to their IDs
+    pub fn label(&self) -> String {
+        let name = |entity: &EntityRef| {
+            entity
+                .name
+                .clone()
+                .or_else(|| entity.id.clone())
+                .unwrap_or_default()
+        };
+        match (&self.project, &self.build_types) {
+            (Some(project), _) => format!("project {}", name(project)),
+            (None, Some(build_types)) => build_types
+                .build_type
+                .iter()
+                .map(name)
+                .collect::<Vec<_>>()
+                .join(", "),
+            (None, None) => String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct MuteAssignment {
+    pub text: Option<String>,
+    pub user: Option<User>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct Tests {
+    #[serde(default)]
+    pub test: Vec<Test>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct MuteTarget {
+    pub tests: Option<Tests>,
+}
+
+// Mute docs:
+// https://www.jetbrains.com/help/teamcity/rest/manage-tests.html#Mute+Tests
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct Mute {
+    pub id: Option<i64>,
+    pub assignment: Option<MuteAssignment>,
+    pub scope: Option<MuteScope>,
+    pub target: Option<MuteTarget>,
+}
+
+impl Mute {
+    /// Names of the muted tests
+    pub fn test_names(&self) -> Vec<&str> {
+        self.target
+            .iter()
+            .flat_map(|target| target.tests.iter())
+            .flat_map(|tests| tests.test.iter())
+            .filter_map(|test| test.name.as_deref())
+            .collect()
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct Mutes {
+    pub count: Option<u32>,
+    #[serde(default)]
+    pub mute: Vec<Mute>,
+}
+
+// Project docs:
+// https://www.jetbrains.com/help/teamcity/rest/manage-projects.html
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct Project {
+    pub id: String,
+    pub name: String,
+    #[serde(rename = "parentProjectId")]
+    pub parent_project_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct Projects {
+    pub count: Option<u32>,
+    #[serde(default)]
+    pub project: Vec<Project>,
+}
+
+// Branch docs:
+// https://www.jetbrains.com/help/teamcity/rest/get-build-details.html#Get+Branches
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct Branch {
+    pub name: String,
+    #[serde(default)]
+    pub default: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct Branches {
+    pub count: Option<u32>,
+    #[serde(default)]
+    pub branch: Vec<Branch>,
+}
+
+// Agent docs:
+// https://www.jetbrains.com/help/teamcity/rest/manage-agents.html
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct Agent {
+    pub id: Option<i64>,
+    pub name: Option<String>,
+    pub connected: Option<bool>,
+    pub enabled: Option<bool>,
+    pub authorized: Option<bool>,
+    // build the agent is running, missing when it is idle
+    pub build: Option<Build>,
+    pub pool: Option<EntityRef>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct Agents {
+    pub count: Option<u32>,
+    #[serde(default)]
+    pub agent: Vec<Agent>,
+}
+
+// Agent pool docs:
+// https://www.jetbrains.com/help/teamcity/rest/manage-agent-pools.html
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct AgentPool {
+    pub id: Option<i64>,
+    pub name: Option<String>,
+    pub agents: Option<Agents>,
+    // projects whose builds may run on the agents of the pool
+    pub projects: Option<Projects>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct AgentPools {
+    pub count: Option<u32>,
+    #[serde(rename = "agentPool", default)]
+    pub agent_pool: Vec<AgentPool>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct Property {
+    pub name: String,
+    pub value: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct Properties {
+    pub count: Option<u32>,
+    #[serde(default)]
+    pub property: Vec<Property>,
+}
+
+impl Properties {
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.property
+            .iter()
+            .find(|property| property.name == name)
+            .and_then(|property| property.value.as_deref())
+    }
+}
+
+// Project feature docs:
+// https://www.jetbrains.com/help/teamcity/rest/projectfeature.html
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct ProjectFeature {
+    pub id: Option<String>,
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
+    #[serde(default)]
+    pub properties: Properties,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct ProjectFeatures {
+    pub count: Option<u32>,
+    #[serde(rename = "projectFeature", default)]
+    pub project_feature: Vec<ProjectFeature>,
+}
+
+// Parameter docs:
+// https://www.jetbrains.com/help/teamcity/rest/manage-typed-parameters.html
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct Parameter {
+    pub name: String,
+    pub value: Option<String>,
+    // defined in a parent project or template rather than the configuration itself
+    pub inherited: Option<bool>,
+    #[serde(rename = "type")]
+    pub kind: Option<ParameterType>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct ParameterType {
+    // spec like `password display='hidden'` or `checkbox checkedValue='true'`
+    #[serde(rename = "rawValue")]
+    pub raw_value: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct Parameters {
+    pub count: Option<u32>,
+    #[serde(default)]
+    pub property: Vec<Parameter>,
+}
+
+// Agent requirement docs:
+// https://www.jetbrains.com/help/teamcity/rest/buildtypeapi.html#getAllAgentRequirements
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct AgentRequirement {
+    pub id: Option<String>,
+    // condition like "exists", "equals" or "ver-no-less-than"
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
+    pub disabled: Option<bool>,
+    // "property-name" and "property-value"
+    #[serde(default)]
+    pub properties: Properties,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct AgentRequirements {
+    pub count: Option<u32>,
+    #[serde(rename = "agent-requirement", default)]
+    pub agent_requirement: Vec<AgentRequirement>,
+}
+
+// VCS root docs:
+// https://www.jetbrains.com/help/teamcity/rest/vcsroot.html
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct VcsRoot {
+    pub id: Option<String>,
+    pub name: Option<String>,
+    #[serde(default)]
+    pub properties: Properties,
+}
+
+// TeamCity serializes dates like "20250131T235959+0000", optionally with fractional seconds
+pub(super) mod teamcity_datetime {
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+    use time::OffsetDateTime;
+    use time::format_description::FormatItem;
+    use time::macros::format_description;
+
+    const TEAMCITY_DATETIME_FORMAT: &[FormatItem<'static>] = format_description!(
+        "[year][month][day]T[hour][minute][second][optional [.[subsecond]]][offset_hour sign:mandatory][offset_minute]"
+    );
+
+    pub fn serialize<S>(value: &Option<OffsetDateTime>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match value {
+            Some(datetime) => {
+                let formatted = datetime
+                    .format(&TEAMCITY_DATETIME_FORMAT)
+                    .map_err(serde::ser::Error::custom)?;
+                serializer.serialize_some(&formatted)
+            }
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<OffsetDateTime>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Option::<String>::deserialize(deserializer)?
+            .map(|raw| {
+                OffsetDateTime::parse(&raw, &TEAMCITY_DATETIME_FORMAT).map_err(D::Error::custom)
+            })
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use time::macros::datetime;
+
+    use super::*;
+
+    #[test]
+    fn test_build_dates_are_parsed() {
+        let build: Build = serde_json::from_str(
+            r#"{"id": 1, "startDate": "20250131T235959+0300", "finishDate": "20250201T000102.123+0000"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(build.queued_date, None);
+        assert_eq!(
+            build.start_date,
+            Some(datetime!(2025-01-31 23:59:59 +03:00))
+        );
+        assert_eq!(
+            build.finish_date,
+            Some(datetime!(2025-02-01 00:01:02.123 +00:00))
+        );
+    }
+
+    #[test]
+    fn test_build_dates_round_trip() {
+        let build: Build =
+            serde_json::from_str(r#"{"startDate": "20250131T235959+0300"}"#).unwrap();
+        let json = serde_json::to_string(&build).unwrap();
+        let parsed: Build = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(parsed, build);
+    }
+
+    #[test]
+    fn test_invalid_build_date_is_rejected() {
+        assert!(serde_json::from_str::<Build>(r#"{"startDate": "yesterday"}"#).is_err());
+    }
+
+    #[test]
+    fn test_mutes_are_parsed() {
+        let mutes: Mutes = serde_json::from_str(
+            r#"{"count": 1, "mute": [{
+                "id": 42,
+                "assignment": {"text": "Flaky on agents with little memory", "user": {"username": "jdoe"}},
+                "scope": {"buildTypes": {"buildType": [{"id": "Web_Build", "name": "Build"}]}},
+                "target": {"tests": {"test": [{"id": "-1234", "name": "LoginTest.timeout"}]}}
+            }]}"#,
+        )
+        .unwrap();
+
+        let mute = &mutes.mute[0];
+        assert_eq!(mute.id, Some(42));
+        assert_eq!(mute.test_names(), ["LoginTest.timeout"]);
+        assert_eq!(mute.scope.as_ref().unwrap().label(), "Build");
+    }
+
+    #[test]
+    fn test_mute_scope_serializes_only_its_target() {
+        assert_eq!(
+            serde_json::to_value(MuteScope::project("Web")).unwrap(),
+            serde_json::json!({ "project": { "id": "Web" } })
+        );
+        assert_eq!(
+            serde_json::to_value(MuteScope::build_type("Web_Build")).unwrap(),
+            serde_json::json!({ "buildTypes": { "buildType": [{ "id": "Web_Build" }] } })
+        );
+        assert_eq!(MuteScope::project("Web").label(), "project Web");
+    }
+
+    #[test]
+    fn test_pending_changes_are_parsed() {
+        let changes: Changes = serde_json::from_str(
+            r#"{"change": [{"id": 7, "version": "3f2a9c1d", "username": "jdoe", "date": "20250131T120000+0000"}]}"#,
+        )
+        .unwrap();
+
+        let change = &changes.change.unwrap()[0];
+        assert_eq!(change.version.as_deref(), Some("3f2a9c1d"));
+        assert_eq!(change.date, Some(datetime!(2025-01-31 12:00:00 +00:00)));
+    }
+}
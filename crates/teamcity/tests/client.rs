@@ -0,0 +1,777 @@
+//! Requests of the client against a mock server answering with recorded TeamCity responses
+
+mod support;
+
+use std::time::Duration;
+use support::{cache_dir, fixture, header, query, query_contains};
+use teamcity::types::Build;
+use teamcity::{Auth, BranchFilter, DateRange, TeamCityClient, TeamCityError};
+use time::macros::datetime;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn client(server: &MockServer, auth: Auth) -> TeamCityClient {
+    // requests to the local server must not go through a proxy set in the environment
+    let http = reqwest::Client::builder().no_proxy().build().unwrap();
+    TeamCityClient::with_http_client(server.uri(), auth, http).unwrap()
+}
+
+fn json(body: String) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_raw(body, "application/json")
+}
+
+fn text(body: String) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_raw(body, "text/plain")
+}
+
+/// Answers `method_name` requests to `url_path` with `response`
+async fn mock(server: &MockServer, method_name: &str, url_path: &str, response: ResponseTemplate) {
+    Mock::given(method(method_name))
+        .and(path(url_path))
+        .respond_with(response)
+        .mount(server)
+        .await;
+}
+
+/// Answers `method_name` requests to `url_path` whose decoded query contains `part` with
+/// `response`, before the mocks mounted after it
+async fn mock_query(
+    server: &MockServer,
+    method_name: &str,
+    url_path: &str,
+    part: &str,
+    response: ResponseTemplate,
+) {
+    Mock::given(method(method_name))
+        .and(path(url_path))
+        .and(query_contains(part))
+        .respond_with(response)
+        .mount(server)
+        .await;
+}
+
+fn token() -> Auth {
+    Auth::Token("secret".to_string())
+}
+
+#[tokio::test]
+async fn build_types_are_fetched_once_and_cached() {
+    let server = MockServer::start().await;
+    mock(
+        &server,
+        "GET",
+        "/app/rest/buildTypes",
+        json(fixture("build_types.json")),
+    )
+    .await;
+    let dir = cache_dir("build_types");
+    let client = client(&server, token()).cache_dir(&dir);
+    let projects = vec!["Backend".to_string()];
+
+    let fetched = client
+        .get_build_configurations_by_projects(&projects)
+        .await
+        .unwrap();
+    let cached = client
+        .get_build_configurations_by_projects(&projects)
+        .await
+        .unwrap();
+
+    let names: Vec<&str> = fetched
+        .build_types
+        .iter()
+        .map(|bt| bt.name.as_str())
+        .collect();
+    assert_eq!(names, ["Build", "Deploy"]);
+    assert_eq!(fetched.build_types[1].paused, Some(true));
+    assert!(fetched.failed.is_empty());
+    assert_eq!(cached.build_types, fetched.build_types);
+
+    let received = server.received_requests().await.unwrap();
+    assert_eq!(received.len(), 1);
+    assert!(query(&received[0]).contains("locator=affectedProject:(id:Backend)"));
+    assert_eq!(header(&received[0], "Authorization"), Some("Bearer secret"));
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[tokio::test]
+async fn failed_projects_are_reported_with_the_loaded_ones() {
+    let server = MockServer::start().await;
+    mock_query(
+        &server,
+        "GET",
+        "/app/rest/buildTypes",
+        "affectedProject:(id:Backend)",
+        json(fixture("build_types.json")),
+    )
+    .await;
+    mock_query(
+        &server,
+        "GET",
+        "/app/rest/buildTypes",
+        "affectedProject:(id:Secret)",
+        ResponseTemplate::new(403),
+    )
+    .await;
+    let dir = cache_dir("failed_projects");
+    let client = client(&server, token()).cache_dir(&dir);
+
+    let result = client
+        .get_build_configurations_by_projects(&["Backend".to_string(), "Secret".to_string()])
+        .await
+        .unwrap();
+
+    assert_eq!(result.build_types.len(), 2);
+    assert_eq!(result.failed.len(), 1);
+    assert_eq!(result.failed[0].project_id, "Secret");
+    assert!(result.failed[0].error.contains("403"));
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[tokio::test]
+async fn builds_of_a_configuration_are_parsed() {
+    let server = MockServer::start().await;
+    mock(
+        &server,
+        "GET",
+        "/app/rest/builds",
+        json(fixture("builds.json")),
+    )
+    .await;
+    let client = client(&server, token());
+
+    let builds = client
+        .get_builds_by_project("Backend_Build", &BranchFilter::Any, None)
+        .await
+        .unwrap();
+
+    let numbers: Vec<&str> = builds
+        .iter()
+        .filter_map(|b| b.build_number.as_deref())
+        .collect();
+    assert_eq!(numbers, ["318", "317"]);
+    let running = &builds[0];
+    assert_eq!(running.state.as_deref(), Some("running"));
+    assert_eq!(
+        running
+            .running_info
+            .as_ref()
+            .unwrap()
+            .estimated_total_seconds,
+        Some(300)
+    );
+    let finished = &builds[1];
+    assert_eq!(
+        finished.finish_date,
+        Some(datetime!(2025-01-31 09:05:12 +00:00))
+    );
+    assert_eq!(finished.pinned, Some(true));
+    assert_eq!(finished.tags.as_ref().unwrap().tag[0].name, "release");
+
+    let query = query(&server.received_requests().await.unwrap()[0]);
+    assert!(query.contains("locator=buildType:Backend_Build,personal:any,branch:default:any"));
+    assert!(query.contains("count=100"));
+}
+
+#[tokio::test]
+async fn branch_names_with_locator_characters_are_encoded() {
+    let server = MockServer::start().await;
+    mock(
+        &server,
+        "GET",
+        "/app/rest/builds",
+        json(fixture("builds.json")),
+    )
+    .await;
+    let client = client(&server, token());
+
+    for branch in ["main", "fix(parser),v2"] {
+        client
+            .get_builds_by_project(
+                "Backend_Build",
+                &BranchFilter::Named(branch.to_string()),
+                None,
+            )
+            .await
+            .unwrap();
+    }
+
+    let received = server.received_requests().await.unwrap();
+    assert!(query(&received[0]).contains(",branch:(name:main)&"));
+    assert!(query(&received[1]).contains(",branch:(name:$base64:Zml4KHBhcnNlciksdjI=)&"));
+}
+
+#[tokio::test]
+async fn builds_in_a_date_range_are_not_limited_to_the_latest() {
+    let server = MockServer::start().await;
+    mock(
+        &server,
+        "GET",
+        "/app/rest/builds",
+        json(fixture("builds.json")),
+    )
+    .await;
+    let client = client(&server, token());
+    let range = DateRange::Between {
+        since: Some(datetime!(2025-01-01 00:00 +01:00)),
+        until: Some(datetime!(2025-02-01 00:00 +01:00)),
+    };
+
+    client
+        .get_builds_by_project("Backend_Build", &BranchFilter::Default, Some(&range))
+        .await
+        .unwrap();
+    client
+        .get_builds_by_project(
+            "Backend_Build",
+            &BranchFilter::Default,
+            Some(&DateRange::Last(Duration::from_secs(3600))),
+        )
+        .await
+        .unwrap();
+
+    let received = server.received_requests().await.unwrap();
+    assert!(query(&received[0]).contains(
+        "locator=buildType:Backend_Build,personal:any,sinceDate:20250101T000000+0100,untilDate:20250201T000000+0100"
+    ));
+    assert!(query(&received[0]).contains("count=1000"));
+    assert!(query(&received[1]).contains(",sinceDate:"));
+    assert!(!query(&received[1]).contains("untilDate"));
+}
+
+#[tokio::test]
+async fn raw_locators_keep_their_own_count() {
+    let server = MockServer::start().await;
+    mock(
+        &server,
+        "GET",
+        "/app/rest/builds",
+        json(fixture("builds.json")),
+    )
+    .await;
+    let client = client(&server, token());
+
+    client
+        .get_builds_by_raw_locator("status:FAILURE,count:5")
+        .await
+        .unwrap();
+    client
+        .get_builds_by_raw_locator("buildType:(count:1),status:FAILURE")
+        .await
+        .unwrap();
+
+    let received = server.received_requests().await.unwrap();
+    assert!(query(&received[0]).contains("locator=status:FAILURE,count:5&"));
+    assert!(!query(&received[0]).contains("count=100"));
+    assert!(query(&received[1]).contains("count=100"));
+}
+
+#[tokio::test]
+async fn latest_builds_skip_configurations_that_fail() {
+    let server = MockServer::start().await;
+    mock_query(
+        &server,
+        "GET",
+        "/app/rest/builds",
+        "buildType:(id:Deleted)",
+        ResponseTemplate::new(404),
+    )
+    .await;
+    mock(
+        &server,
+        "GET",
+        "/app/rest/builds",
+        json(fixture("builds.json")),
+    )
+    .await;
+    let client = client(&server, token());
+
+    let latest = client
+        .get_latest_builds(&["Deleted".to_string(), "Backend_Build".to_string()])
+        .await
+        .unwrap();
+    let all_deleted = client.get_latest_builds(&["Deleted".to_string()]).await;
+
+    assert!(!latest.is_empty());
+    assert!(matches!(all_deleted, Err(TeamCityError::NotFound)));
+}
+
+#[tokio::test]
+async fn latest_builds_include_queued_ones_unless_only_started_ones_are_asked_for() {
+    let server = MockServer::start().await;
+    mock(
+        &server,
+        "GET",
+        "/app/rest/builds",
+        json(fixture("builds.json")),
+    )
+    .await;
+    let client = client(&server, token());
+    let ids = ["Backend_Build".to_string()];
+
+    client.get_latest_builds(&ids).await.unwrap();
+    client.get_latest_started_builds(&ids).await.unwrap();
+
+    let received = server.received_requests().await.unwrap();
+    assert!(query(&received[0]).contains("locator=buildType:(id:Backend_Build),state:any,count:1"));
+    assert!(
+        query(&received[1]).contains("locator=buildType:(id:Backend_Build),running:any,count:1")
+    );
+}
+
+#[tokio::test]
+async fn active_builds_include_the_ones_finished_since_a_date() {
+    let server = MockServer::start().await;
+    mock(
+        &server,
+        "GET",
+        "/app/rest/builds",
+        json(fixture("builds.json")),
+    )
+    .await;
+    let client = client(&server, token());
+
+    let active = client
+        .get_active_builds(
+            &["Backend_Build".to_string()],
+            datetime!(2025-01-31 09:05:12 UTC),
+        )
+        .await
+        .unwrap();
+
+    assert!(!active["Backend_Build"].is_empty());
+    let received = server.received_requests().await.unwrap();
+    assert!(query(&received[0]).contains("locator=buildType:(id:Backend_Build),state:queued&"));
+    assert!(query(&received[1]).contains("locator=buildType:(id:Backend_Build),state:running&"));
+    assert!(query(&received[2]).contains(
+        "locator=buildType:(id:Backend_Build),state:finished,finishDate:(date:20250131T090512+0000,condition:after)&"
+    ));
+}
+
+#[tokio::test]
+async fn composite_builds_list_their_direct_dependencies() {
+    let server = MockServer::start().await;
+    mock(
+        &server,
+        "GET",
+        "/app/rest/builds",
+        json(fixture("builds.json")),
+    )
+    .await;
+    let client = client(&server, token());
+
+    let builds = client.get_composite_builds(2001).await.unwrap();
+
+    assert_eq!(builds.len(), 2);
+    let query = query(&server.received_requests().await.unwrap()[0]);
+    assert!(query.contains(
+        "locator=snapshotDependency:(to:(id:2001),includeInitial:false,recursive:false)"
+    ));
+    assert!(query.contains("changes(change("));
+}
+
+#[tokio::test]
+async fn queued_builds_get_their_position_in_the_whole_queue() {
+    let server = MockServer::start().await;
+    mock_query(
+        &server,
+        "GET",
+        "/app/rest/buildQueue",
+        "fields=count,build(id)",
+        json(fixture("build_queue.json")),
+    )
+    .await;
+    mock_query(
+        &server,
+        "GET",
+        "/app/rest/buildQueue",
+        "locator=buildType:(id:Backend_Build)",
+        json(fixture("build_queue_backend.json")),
+    )
+    .await;
+    let client = client(&server, token());
+
+    let queued = client.get_queued_builds("Backend_Build").await.unwrap();
+
+    assert_eq!(queued.len(), 1);
+    assert_eq!(queued[0].queue_position, Some(3));
+    assert_eq!(
+        queued[0].wait_reason.as_deref(),
+        Some("Build is waiting for a compatible agent")
+    );
+    assert_eq!(
+        queued[0].start_estimate,
+        Some(datetime!(2025-01-31 10:45:00 +00:00))
+    );
+}
+
+#[tokio::test]
+async fn the_whole_queue_is_fetched_at_once() {
+    let server = MockServer::start().await;
+    mock(
+        &server,
+        "GET",
+        "/app/rest/buildQueue",
+        json(fixture("build_queue.json")),
+    )
+    .await;
+    let client = client(&server, token());
+
+    let queue = client.get_build_queue().await.unwrap();
+
+    assert_eq!(queue.len(), 3);
+    assert_eq!(queue[2].queue_position, Some(3));
+    let received = server.received_requests().await.unwrap();
+    assert_eq!(received.len(), 1);
+    assert!(!query(&received[0]).contains("locator="));
+    assert!(query(&received[0]).contains("queuedDate,waitReason"));
+}
+
+#[tokio::test]
+async fn builds_are_queued_on_the_chosen_change() {
+    let server = MockServer::start().await;
+    mock(
+        &server,
+        "POST",
+        "/app/rest/buildQueue",
+        ResponseTemplate::new(200),
+    )
+    .await;
+    let client = client(&server, token());
+
+    client
+        .trigger_build_on_change("Backend_Build", Some("main"), 4211)
+        .await
+        .unwrap();
+
+    let body: serde_json::Value =
+        serde_json::from_slice(&server.received_requests().await.unwrap()[0].body).unwrap();
+    assert_eq!(
+        body,
+        serde_json::json!({
+            "buildType": { "id": "Backend_Build" },
+            "branchName": "main",
+            "lastChanges": { "change": [{ "id": 4211 }] },
+        })
+    );
+}
+
+#[tokio::test]
+async fn changes_are_looked_up_with_the_builds_they_were_first_built_in() {
+    let server = MockServer::start().await;
+    mock_query(
+        &server,
+        "GET",
+        "/app/rest/changes",
+        "locator=id:404",
+        ResponseTemplate::new(404),
+    )
+    .await;
+    mock(
+        &server,
+        "GET",
+        "/app/rest/changes",
+        json(fixture("changes.json")),
+    )
+    .await;
+    mock(
+        &server,
+        "GET",
+        "/app/rest/builds",
+        json(fixture("builds.json")),
+    )
+    .await;
+    let client = client(&server, token());
+
+    let found = client
+        .get_change_builds("version:3f2a9c1d0b7e")
+        .await
+        .unwrap()
+        .unwrap();
+    let unknown = client.get_change_builds("id:404").await.unwrap();
+
+    // both VCS root changes of the commit are in the same builds
+    let ids: Vec<_> = found.builds.iter().map(|b| b.id).collect();
+    assert_eq!(ids, vec![Some(1042), Some(1041)]);
+    assert_eq!(found.first_built_in, vec![1041]);
+    assert_eq!(unknown, None);
+    let received = server.received_requests().await.unwrap();
+    assert!(query(&received[0]).contains("locator=version:3f2a9c1d0b7e"));
+    assert!(query(&received[0]).contains("firstBuilds(build(id,buildTypeId))"));
+    assert!(query(&received[1]).contains("change:(id:5120)"));
+    assert!(query(&received[2]).contains("change:(id:5121)"));
+}
+
+#[tokio::test]
+async fn build_logs_are_fetched_as_plain_text() {
+    let server = MockServer::start().await;
+    mock(
+        &server,
+        "GET",
+        "/guestAuth/downloadBuildLog.html",
+        text(fixture("build_log.txt")),
+    )
+    .await;
+    let client = client(&server, Auth::Guest);
+
+    let log = client.get_build_log_text(&1042).await.unwrap();
+    let logs = client
+        .get_build_logs(
+            vec![
+                Build {
+                    id: Some(1042),
+                    ..Build::default()
+                },
+                Build::default(),
+            ],
+            |_, log| log.lines().filter(|line| line.contains("failed")).count(),
+        )
+        .await;
+
+    assert_eq!(log, fixture("build_log.txt"));
+    assert!(log.lines().nth(2).unwrap().contains("Compilation failed"));
+    assert_eq!(logs[0].1.as_ref().ok(), Some(&1));
+    assert!(matches!(logs[1].1, Err(TeamCityError::Missing(_))));
+    let received = server.received_requests().await.unwrap();
+    assert!(query(&received[0]).contains("buildId=1042"));
+    assert!(query(&received[0]).contains("plain=true"));
+    assert_eq!(header(&received[0], "Authorization"), None);
+}
+
+#[tokio::test]
+async fn agent_pools_list_their_agents_and_projects() {
+    let server = MockServer::start().await;
+    mock(
+        &server,
+        "GET",
+        "/app/rest/agentPools",
+        json(fixture("agent_pools.json")),
+    )
+    .await;
+    mock(
+        &server,
+        "POST",
+        "/app/rest/agentPools/id:0/agents",
+        json("{\"id\":12}".to_string()),
+    )
+    .await;
+    let client = client(&server, token());
+
+    let pools = client.get_agent_pools().await.unwrap();
+    client.move_agent_to_pool(12, 0).await.unwrap();
+
+    let names: Vec<Option<&str>> = pools.iter().map(|pool| pool.name.as_deref()).collect();
+    assert_eq!(names, [Some("Default"), Some("Linux")]);
+    let agents = &pools[1].agents.as_ref().unwrap().agent;
+    assert_eq!(
+        agents[0].build.as_ref().unwrap().build_number.as_deref(),
+        Some("142")
+    );
+    assert_eq!(agents[1].connected, Some(false));
+    assert!(pools[0].agents.as_ref().unwrap().agent.is_empty());
+    assert_eq!(pools[0].projects.as_ref().unwrap().project.len(), 2);
+    let received = server.received_requests().await.unwrap();
+    assert!(query(&received[0]).contains("agentPool(id,name,"));
+    assert_eq!(received[1].method, "POST");
+}
+
+#[tokio::test]
+async fn artifact_paths_are_percent_encoded() {
+    let server = MockServer::start().await;
+    mock(
+        &server,
+        "DELETE",
+        "/app/rest/builds/id:1042/artifacts/files/test%20reports/run%231.zip",
+        ResponseTemplate::new(204),
+    )
+    .await;
+    let client = client(&server, token());
+
+    client
+        .delete_build_artifact(1042, "test reports/run#1.zip")
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn error_responses_map_to_error_kinds() {
+    let server = MockServer::start().await;
+    mock_query(
+        &server,
+        "GET",
+        "/app/rest/builds",
+        "id:1",
+        ResponseTemplate::new(401),
+    )
+    .await;
+    mock_query(
+        &server,
+        "GET",
+        "/app/rest/builds",
+        "id:2",
+        ResponseTemplate::new(429).insert_header("Retry-After", "7"),
+    )
+    .await;
+    mock_query(
+        &server,
+        "GET",
+        "/app/rest/builds",
+        "id:3",
+        text("<html><body>Log in to TeamCity</body></html>".to_string()),
+    )
+    .await;
+    mock_query(
+        &server,
+        "GET",
+        "/app/rest/builds",
+        "id:4",
+        json(r#"{"count": 0}"#.to_string()),
+    )
+    .await;
+    let client = client(&server, token());
+
+    assert!(matches!(
+        client.get_build(1).await,
+        Err(TeamCityError::Unauthorized)
+    ));
+    assert!(matches!(
+        client.get_build(2).await,
+        Err(TeamCityError::RateLimited { retry_after: Some(wait) }) if wait == Duration::from_secs(7)
+    ));
+    match client.get_build(3).await {
+        Err(TeamCityError::Decode { body, .. }) => {
+            assert_eq!(body, "<html><body>Log in to TeamCity</body></html>")
+        }
+        other => panic!("expected a decode error, got {:?}", other),
+    }
+    match client.get_build(4).await {
+        Err(TeamCityError::Missing(message)) => assert_eq!(message, "Build 4 was not found"),
+        other => panic!("expected a missing build, got {:?}", other),
+    }
+    assert!(matches!(
+        client.get_build_configuration_details("Unknown").await,
+        Err(TeamCityError::NotFound)
+    ));
+}
+
+#[tokio::test]
+async fn a_new_token_is_sent_by_the_swapped_client() {
+    let server = MockServer::start().await;
+    mock(
+        &server,
+        "GET",
+        "/app/rest/builds",
+        json(fixture("builds.json")),
+    )
+    .await;
+    let client = client(&server, token());
+
+    client.get_build(1042).await.unwrap();
+    client
+        .with_token("renewed".to_string())
+        .get_build(1042)
+        .await
+        .unwrap();
+
+    let received = server.received_requests().await.unwrap();
+    assert_eq!(header(&received[0], "Authorization"), Some("Bearer secret"));
+    assert_eq!(
+        header(&received[1], "Authorization"),
+        Some("Bearer renewed")
+    );
+}
+
+#[tokio::test]
+async fn an_expired_csrf_token_is_renewed_and_the_request_sent_again() {
+    let server = MockServer::start().await;
+    mock(
+        &server,
+        "GET",
+        "/httpAuth/app/rest/server",
+        json(r#"{"version": "2024.12", "versionMajor": 2024, "versionMinor": 12}"#.to_string()),
+    )
+    .await;
+    Mock::given(method("GET"))
+        .and(path("/httpAuth/authenticationTest.html"))
+        .respond_with(text("expired".to_string()))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    mock(
+        &server,
+        "GET",
+        "/httpAuth/authenticationTest.html",
+        text("renewed".to_string()),
+    )
+    .await;
+    Mock::given(method("PUT"))
+        .and(path(
+            "/httpAuth/app/rest/buildTypes/id:Backend_Build/paused",
+        ))
+        .and(wiremock::matchers::header("X-TC-CSRF-Token", "expired"))
+        .respond_with(
+            ResponseTemplate::new(403)
+                .set_body_string("CSRF Header X-TC-CSRF-Token does not match CSRF session value"),
+        )
+        .mount(&server)
+        .await;
+    mock(
+        &server,
+        "PUT",
+        "/httpAuth/app/rest/buildTypes/id:Backend_Build/paused",
+        text("true".to_string()),
+    )
+    .await;
+    let basic = Auth::Basic {
+        username: "jane".to_string(),
+        password: "secret".to_string(),
+    };
+    let client = client(&server, basic).probe_server().await;
+
+    client.set_paused("Backend_Build", true).await.unwrap();
+
+    let received = server.received_requests().await.unwrap();
+    let tokens: Vec<Option<&str>> = received
+        .iter()
+        .filter(|request| request.method.as_str() == "PUT")
+        .map(|request| header(request, "X-TC-CSRF-Token"))
+        .collect();
+    assert_eq!(tokens, vec![Some("expired"), Some("renewed")]);
+}
+
+#[tokio::test]
+async fn a_missing_permission_is_not_retried() {
+    let server = MockServer::start().await;
+    mock(
+        &server,
+        "GET",
+        "/httpAuth/authenticationTest.html",
+        text("token".to_string()),
+    )
+    .await;
+    mock(
+        &server,
+        "PUT",
+        "/httpAuth/app/rest/buildTypes/id:Backend_Build/paused",
+        ResponseTemplate::new(403).set_body_string("You do not have enough permissions"),
+    )
+    .await;
+    let basic = Auth::Basic {
+        username: "jane".to_string(),
+        password: "secret".to_string(),
+    };
+    let client = client(&server, basic).probe_server().await;
+
+    assert!(matches!(
+        client.set_paused("Backend_Build", true).await,
+        Err(TeamCityError::Forbidden)
+    ));
+    let received = server.received_requests().await.unwrap();
+    assert_eq!(
+        received
+            .iter()
+            .filter(|request| request.method.as_str() == "PUT")
+            .count(),
+        1
+    );
+}
@@ -0,0 +1,51 @@
+//! Fixtures and matchers shared by the tests against a `wiremock` server
+
+use std::path::PathBuf;
+use wiremock::{Match, Request};
+
+/// Response of a recorded fixture in `tests/fixtures`
+pub fn fixture(name: &str) -> String {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name);
+    std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("Failed to read fixture {}: {}", path.display(), e))
+}
+
+/// Directory of its own under the temp directory for the cache file of a test
+pub fn cache_dir(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("t9s-teamcity-{}-{}", std::process::id(), test));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+/// Decoded query of `request`, like `locator=buildType:(id:Backend)&count=100`
+pub fn query(request: &Request) -> String {
+    request
+        .url
+        .query_pairs()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// Value of the header `name` of `request`
+pub fn header<'a>(request: &'a Request, name: &str) -> Option<&'a str> {
+    request
+        .headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+}
+
+/// Matches requests whose decoded query contains `part`, like a dimension of a locator
+pub fn query_contains(part: &str) -> QueryContains {
+    QueryContains(part.to_string())
+}
+
+pub struct QueryContains(String);
+
+impl Match for QueryContains {
+    fn matches(&self, request: &Request) -> bool {
+        query(request).contains(&self.0)
+    }
+}
@@ -1,8 +1,16 @@
 use serde::{Deserialize, Serialize};
-use strum::Display;
+use std::collections::BTreeMap;
 use std::path::PathBuf;
+use strum::Display;
 
-use crate::teamcity::types::Build;
+use crate::view::log_grep::BuildMatches;
+use teamcity::types::{
+    AgentPool, Build, BuildType, Change, Mute, MuteScope, Parameter, Project, Test, TestOccurrence,
+};
+use teamcity::{
+    AgentCompatibility, BranchFilter, BuildArtifacts, DateRange, DependencyChain, ProjectFailure,
+    ServerOverview,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq, Display, Serialize, Deserialize)]
 pub enum Action {
@@ -14,17 +22,466 @@ pub enum Action {
     Resume,
     Quit,
     ClearScreen,
+    ConfigReloaded,
+    // Latest builds of the favorite configurations, sent by the background poller, with the
+    // average duration of the configurations that have running builds
+    FavoriteBuildsPolled {
+        builds: Vec<Build>,
+        baselines: BTreeMap<String, i64>,
+    },
+    // Latest build of each favorite configuration in a view of its own
+    ShowDashboard,
+    // Apply the saved filter with this name to the Projects and Builds views, or clear it
+    ApplyFilter {
+        name: Option<String>,
+    },
+    // First-run setup wizard: connect with the entered credentials, then save the configuration
+    SetupConnect,
+    SetupProjectsFetched {
+        projects: Vec<Project>,
+    },
+    SetupFinished,
+    // Top-level projects offered when no project is configured, and the ones picked to monitor
+    LoadRootProjects,
+    ShowRootProjects {
+        projects: Vec<Project>,
+    },
+    ProjectsPicked {
+        projects: Vec<String>,
+        save: bool,
+    },
+    // The server rejected the credentials (401), of the main server if `server` is None, while
+    // handling `retry`, and the new token entered for it, retrying that action
+    AuthFailed {
+        server: Option<String>,
+        status: u16,
+        retry: Option<Box<Action>>,
+    },
+    Reauthenticate {
+        server: Option<String>,
+        token: String,
+        retry: Option<Box<Action>>,
+    },
     // General UI actions
     Error(String),
+    Toast(String),
+    ToggleLogPanel,
+    OpenCommandPalette,
+    // External command of a custom action from the config, program first
+    RunExternal {
+        name: String,
+        command: Vec<String>,
+    },
+    // Command typed in the command palette, mapped to the action it stands for
+    RunCommand {
+        command: String,
+    },
+    // Tabs
+    NewTab,
+    CloseTab,
+    NextTab,
+    PreviousTab,
+    // Result of a load started in a tab, handled in that tab even if another one is shown by then
+    TabLoaded {
+        tab: usize,
+        action: Box<Action>,
+    },
+    // Action about a configuration of the server `server`, handled with the tab talking to it
+    OnServer {
+        server: String,
+        action: Box<Action>,
+    },
+    CopyToClipboard {
+        label: String,
+        text: String,
+    },
     Help,
     // External actions
-    Fzf { options: Vec<String> },
-    FzfSelected { selected: String },
-    Pager { file: PathBuf },
+    Fzf {
+        options: Vec<String>,
+    },
+    FzfSelected {
+        selected: String,
+    },
+    Pager {
+        file: PathBuf,
+    },
+    // opens config.toml in $VISUAL or $EDITOR, then reloads the projects
+    EditConfig,
     // Builds
-    LoadBuilds { project_id: String, title: String },
-    ShowBuilds { title: String, items: Vec<Build> },
-    LoadBuildLog { build_id: i64 },
+    LoadBuilds {
+        project_id: String,
+        title: String,
+    },
+    // Builds of a configuration on a branch picked in the Builds view
+    LoadBuildsOnBranch {
+        project_id: String,
+        title: String,
+        branch: BranchFilter,
+    },
+    // Builds of a configuration started in a date range picked in the Builds view, on the branch
+    // picked there if any
+    LoadBuildsInRange {
+        project_id: String,
+        title: String,
+        branch: Option<BranchFilter>,
+        range: DateRange,
+    },
+    // Reopen the most recently viewed configuration other than `current`
+    ShowPreviousBuilds {
+        current: Option<String>,
+    },
+    // Pick one of the recently viewed configurations, the first other than `current` selected
+    OpenRecent {
+        current: Option<String>,
+    },
+    ShowBuilds {
+        project_id: String,
+        title: String,
+        items: Vec<Build>,
+        // branch picked in the Builds view, None for the configured one
+        branch: Option<BranchFilter>,
+        // start dates picked in the Builds view, None for the latest builds
+        range: Option<DateRange>,
+    },
+    // Query the server for builds of a configuration instead of filtering the loaded ones
+    SearchBuilds {
+        project_id: String,
+        query: String,
+        locator: String,
+    },
+    SearchResults {
+        project_id: String,
+        query: String,
+        items: Vec<Build>,
+    },
+    // Branches offered as completions when queueing a build from the Projects view
+    LoadBranches {
+        build_type_id: String,
+    },
+    ShowBranches {
+        build_type_id: String,
+        branches: Vec<String>,
+    },
+    // Queue a build of a configuration, on its default branch if `branch` is None
+    TriggerBuild {
+        build_type_id: String,
+        title: String,
+        branch: Option<String>,
+    },
+    // Queue a build pinned to the change `change_id`, from the changes of a build or the pending
+    // ones; `revision` names the change in messages
+    TriggerBuildOnChange {
+        build_type_id: String,
+        title: String,
+        branch: Option<String>,
+        change_id: i64,
+        revision: String,
+    },
+    // Column widths of the Builds table adjusted in the view, keyed by column name
+    SaveColumnWidths {
+        widths: BTreeMap<String, u16>,
+    },
+    // Line wrapping of the build log viewer toggled in the view
+    SaveLogWrap {
+        wrap: bool,
+    },
+    LoadBuildLog {
+        build_id: i64,
+    },
+    // Build log in the in-app viewer, `project_id` and `title` are of the Builds view to go back to
+    LoadLogViewer {
+        project_id: String,
+        title: String,
+        build: Build,
+    },
+    ShowLogViewer {
+        project_id: String,
+        title: String,
+        build: Build,
+        log: String,
+    },
+    // Log tail or failure summary of a build, for the preview pane of the Builds view
+    LoadBuildPreview {
+        build: Build,
+    },
+    ShowBuildPreview {
+        build_id: i64,
+        lines: Vec<String>,
+        // configured statistic values of the build, e.g. `Tests 1234`
+        statistics: Vec<String>,
+    },
+    // Builds a composite build aggregated, listed under it in the Builds view
+    LoadCompositeBuilds {
+        build_id: i64,
+    },
+    ShowCompositeBuilds {
+        build_id: i64,
+        // why they couldn't be fetched
+        builds: Result<Vec<Build>, String>,
+    },
+    // Bulk operations on the marked builds
+    CancelBuilds {
+        builds: Vec<Build>,
+    },
+    RerunBuilds {
+        builds: Vec<Build>,
+    },
+    DownloadBuildLogs {
+        builds: Vec<Build>,
+    },
+    // Notify when a running build finishes, whatever view is open by then
+    ToggleWatchBuild {
+        build: Build,
+    },
+    WatchedBuildFinished {
+        build: Build,
+    },
+    // A queued build of a favorite configuration waited past `queue_watchdog.threshold_minutes`
+    QueuedBuildStuck {
+        build: Build,
+        causes: Vec<String>,
+    },
+    LoadBuildChain {
+        project_id: String,
+        title: String,
+        build: Build,
+    },
+    ShowBuildChain {
+        project_id: String,
+        title: String,
+        build: Build,
+        chain: DependencyChain,
+    },
+    // Builds of any configuration including a commit, `revision` is its SHA or change ID
+    LoadChangeBuilds {
+        revision: String,
+    },
+    ShowChangeBuilds {
+        revision: String,
+        builds: Vec<Build>,
+        // IDs of the builds the change was first built in
+        first_built_in: Vec<i64>,
+    },
+    // Builds view of a configuration showing only the builds including `revision`
+    ShowBuildsWithChange {
+        project_id: String,
+        title: String,
+        revision: String,
+        items: Vec<Build>,
+        selected: Option<i64>,
+    },
+    // Builds of any configuration matching a raw build locator
+    LoadLocatorBuilds {
+        locator: String,
+    },
+    ShowLocatorBuilds {
+        locator: String,
+        builds: Vec<Build>,
+    },
+    // Agent requirements of a configuration and the agents compatible with it
+    LoadAgentCompatibility {
+        build_type_id: String,
+        title: String,
+    },
+    ShowAgentCompatibility {
+        build_type_id: String,
+        title: String,
+        compatibility: AgentCompatibility,
+    },
+    // Parameters of a configuration and setting the value of one
+    LoadParameters {
+        build_type_id: String,
+        title: String,
+    },
+    ShowParameters {
+        build_type_id: String,
+        title: String,
+        parameters: Vec<Parameter>,
+    },
+    SetParameter {
+        build_type_id: String,
+        name: String,
+        value: String,
+    },
+    ParameterUpdated {
+        build_type_id: String,
+        name: String,
+        value: String,
+    },
+    // Pause or resume a configuration
+    SetPaused {
+        build_type_id: String,
+        paused: bool,
+    },
+    PausedChanged {
+        build_type_id: String,
+        paused: bool,
+    },
+    // Open the versioned settings file defining the configuration in the browser
+    OpenSettingsFile {
+        build_type: BuildType,
+    },
+    // Queue the build again with all of its snapshot dependencies rebuilt, or only the failed ones
+    RebuildChain {
+        build: Build,
+        all_dependencies: bool,
+    },
+    PlanArtifactCleanup {
+        builds: Vec<Build>,
+    },
+    ShowArtifactCleanup {
+        plan: Vec<BuildArtifacts>,
+    },
+    DeleteArtifacts {
+        plan: Vec<BuildArtifacts>,
+    },
+    ArtifactsDeleted {
+        deleted: usize,
+        failed: Vec<String>,
+    },
+    // Zip archive of all artifacts of a build, saved to `dir`
+    DownloadArtifacts {
+        build: Build,
+        dir: PathBuf,
+    },
+    ArtifactsDownloadProgress {
+        build_id: i64,
+        downloaded: u64,
+        total: Option<u64>,
+    },
+    ArtifactsDownloadFinished {
+        build_id: i64,
+    },
+    // Changes no build has run on yet
+    LoadPendingChanges {
+        project_id: String,
+        title: String,
+    },
+    ShowPendingChanges {
+        project_id: String,
+        title: String,
+        changes: Vec<Change>,
+    },
+    // Tests
+    LoadTestFailures {
+        build_type_id: String,
+        title: String,
+        build: Build,
+    },
+    ShowTestFailures {
+        build_type_id: String,
+        title: String,
+        build: Build,
+        tests: Vec<TestOccurrence>,
+    },
+    // failures view of `build` is returned to from the history of `test`
+    LoadTestHistory {
+        build_type_id: String,
+        title: String,
+        build: Build,
+        test: Test,
+    },
+    ShowTestHistory {
+        build_type_id: String,
+        title: String,
+        build: Build,
+        test: Test,
+        history: Vec<TestOccurrence>,
+    },
+    MuteTest {
+        test_id: String,
+        scope: MuteScope,
+        comment: String,
+    },
+    TestMuted {
+        test_id: String,
+    },
+    // Mutes of a project, `back` reopens the view they were opened from
+    LoadMutes {
+        project_id: String,
+        back: Box<Action>,
+    },
+    ShowMutes {
+        project_id: String,
+        mutes: Vec<Mute>,
+        back: Box<Action>,
+    },
+    UnmuteTest {
+        mute_id: i64,
+    },
+    TestUnmuted {
+        mute_id: i64,
+    },
+    // Statistics
+    LoadStatistics {
+        project_id: String,
+        title: String,
+    },
+    ShowStatistics {
+        project_id: String,
+        title: String,
+        builds: Vec<Build>,
+    },
+    // Logs of the recent finished builds of a configuration searched for `query`
+    GrepBuildLogs {
+        project_id: String,
+        title: String,
+        query: String,
+    },
+    ShowLogGrep {
+        project_id: String,
+        title: String,
+        query: String,
+        results: Vec<BuildMatches>,
+    },
+    // Timeline
+    LoadTimeline,
+    ShowTimeline {
+        builds: Vec<Build>,
+    },
+    // About server
+    LoadServerOverview,
+    ShowServerOverview {
+        overview: ServerOverview,
+    },
+    // Agent pools with their agents and assigned projects, and moving an agent to another pool
+    LoadAgentPools,
+    ShowAgentPools {
+        pools: Vec<AgentPool>,
+    },
+    MoveAgent {
+        agent_id: i64,
+        agent: String,
+        pool_id: i64,
+        pool: String,
+    },
     // Projects
     ShowProjects,
+    LoadProjects {
+        bypass_cache: bool,
+    },
+    ProjectsFetched {
+        build_types: Vec<BuildType>,
+        failed: Vec<ProjectFailure>,
+    },
+    // Fetch only the projects that failed to load, and add the ones that loaded this time
+    RetryFailedProjects,
+    ProjectsRetried {
+        build_types: Vec<BuildType>,
+        failed: Vec<ProjectFailure>,
+    },
+}
+
+impl Action {
+    /// This action sent to the server `build_type` is on, when several servers are configured
+    pub fn on_server_of(self, build_type: &BuildType) -> Action {
+        match &build_type.server {
+            Some(server) => Action::OnServer {
+                server: server.clone(),
+                action: Box::new(self),
+            },
+            None => self,
+        }
+    }
 }
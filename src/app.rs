@@ -1,32 +1,171 @@
 use color_eyre::Result;
 use crossterm::event::KeyEvent;
+use futures::future::join_all;
+use ratatui::layout::{Constraint, Layout};
 use ratatui::prelude::Rect;
+use ratatui::style::{Color, Style, Stylize};
+use ratatui::widgets::Tabs;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::Write;
+use std::time::Duration;
+use time::OffsetDateTime;
 use tokio::sync::mpsc;
-use tracing::{debug, info};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, info, warn};
 
+use crate::components::about_server::AboutServer;
+use crate::components::agent_pools::AgentPools;
+use crate::components::build_chain::BuildChain;
+use crate::components::build_log::BuildLog;
 use crate::components::builds::Builds;
+use crate::components::change_builds::ChangeBuilds;
+use crate::components::command_palette::CommandPalette;
+use crate::components::compatible_agents::CompatibleAgents;
+use crate::components::dashboard::Dashboard;
+use crate::components::locator_builds::LocatorBuilds;
+use crate::components::log_grep::LogGrep;
+use crate::components::log_panel::LogPanel;
+use crate::components::mutes::Mutes;
+use crate::components::parameters::Parameters;
+use crate::components::pending_changes::PendingChanges;
+use crate::components::project_picker::ProjectPicker;
 use crate::components::projects::Projects;
-use crate::teamcity::TeamCityClient;
-use crate::teamcity::types::{Build, BuildType};
+use crate::components::reauth::Reauth;
+use crate::components::recent_picker::{RecentEntry, RecentPicker};
+use crate::components::spinner::Spinner;
+use crate::components::statistics::Statistics;
+use crate::components::status_bar::StatusBar;
+use crate::components::test_failures::TestFailures;
+use crate::components::test_history::TestHistory;
+use crate::components::timeline::Timeline;
+use crate::components::toast::Toast;
+use crate::session::{self, Session};
+use crate::view::build_log::log_file_name;
+use crate::view::builds::{
+    duration_baseline, failure_summary, is_failed, is_queued, log_tail, merge_queued,
+    statistics_summary,
+};
+use crate::view::change_builds::{change_locators, first_built_in};
+use crate::view::command::parse_command;
+use crate::view::log_grep::{BuildMatches, grep_log};
+use crate::view::notification::notification_sequence;
+use crate::view::queue_watchdog::{forget_dequeued, newly_stuck, stuck_causes, stuck_message};
+use crate::view::versioned_settings::settings_file_url;
 use crate::{
     action::Action,
-    components::{Component, fps::FpsCounter, home::Home},
-    config::Config,
+    components::Component,
+    config::{self, Config},
     tui::{Event, Tui},
 };
+use teamcity::types::{Build, BuildType};
+use teamcity::{
+    BranchFilter, DateRange, ProjectFailure, ProjectsBuildTypes, TeamCityClient, TeamCityError,
+};
+
+const BULK_CANCEL_COMMENT: &str = "Canceled from t9s";
+
+// builds fetched per monitored project for the timeline view
+const TIMELINE_BUILD_COUNT: u32 = 200;
+
+// finished builds the usual duration of a favorite configuration is averaged over
+const BASELINE_BUILD_COUNT: u32 = 20;
+
+// recent builds of a configuration the pass/fail history of a test is shown for
+const TEST_HISTORY_BUILD_COUNT: u32 = 30;
+
+// log lines shown in the preview pane of the Builds view
+const PREVIEW_LOG_LINES: usize = 100;
+
+// how often a watched build is checked for having finished
+const WATCH_INTERVAL: Duration = Duration::from_secs(10);
+
+tokio::task_local! {
+    // action whose requests a task sends, retried once a rejected token is replaced
+    static ORIGIN: Option<Action>;
+}
 
 pub struct App {
     config: Config,
-    components: Vec<Box<dyn Component>>,
+    tabs: Vec<Tab>,
+    active_tab: usize,
+    next_tab_id: usize,
+    // tab a load result is handled in while it isn't the shown one, see `Action::TabLoaded`
+    routed_tab: Option<usize>,
+    // drawn on top of the current view and kept across view switches
+    toast: Toast,
+    spinner: Spinner,
+    status_bar: StatusBar,
+    log_panel: LogPanel,
+    command_palette: CommandPalette,
+    recent_picker: RecentPicker,
+    // asks for a new token when a server rejects the credentials
+    reauth: Reauth,
+    // polls the latest builds of the favorite configurations, restarted when the config changes
+    status_poller: Option<JoinHandle<()>>,
+    // latest build of each favorite configuration and the usual durations, from the last poll
+    favorite_builds: HashMap<String, Build>,
+    favorite_baselines: BTreeMap<String, i64>,
+    favorites_polled_at: Option<OffsetDateTime>,
+    // pollers of the watched builds by build ID, until they finish
+    watched_builds: HashMap<i64, (Option<String>, JoinHandle<()>)>,
+    // kept alive so copied text stays available on X11/Wayland
+    clipboard: Option<arboard::Clipboard>,
     should_quit: bool,
     should_suspend: bool,
     mode: Mode,
     last_tick_key_events: Vec<KeyEvent>,
     action_tx: mpsc::UnboundedSender<Action>,
     action_rx: mpsc::UnboundedReceiver<Action>,
+    // client of the main server, the one tabs talk to until a configuration of another is opened
     client: TeamCityClient,
+    projects: Vec<String>,
+    // all servers with the main one first, empty when only the main one is configured
+    servers: Vec<Server>,
     build_types: Vec<BuildType>,
+    failed_projects: Vec<ProjectFailure>,
+    // None until the build configurations are fetched
+    projects_fetched_at: Option<OffsetDateTime>,
+    // build configuration IDs, most recently opened first
+    recent_build_types: Vec<String>,
+    // view of the previous run, reopened once the app has started
+    session: Option<Session>,
+    // configuration and build of the previous run to select once its builds are shown
+    restored_build: Option<(String, i64)>,
+    // view opened on startup instead of the one of the previous run
+    startup: Option<Action>,
+    // action being handled, the origin of the requests its tasks send
+    handling: Option<Action>,
+}
+
+/// A TeamCity server whose build configurations are listed in the Projects view
+pub struct Server {
+    pub name: String,
+    pub client: TeamCityClient,
+    pub projects: Vec<String>,
+}
+
+/// A view opened in its own tab, kept with its state while other tabs are shown
+struct Tab {
+    // stays the same when tabs before it are closed, unlike its index
+    id: usize,
+    component: Box<dyn Component>,
+    // cancels loads of the tab's view when it switches to another one or is closed
+    load: CancellationToken,
+    // server the tab's views talk to, the main one if None
+    server: Option<String>,
+}
+
+impl Tab {
+    fn new(id: usize, component: Box<dyn Component>) -> Self {
+        Self {
+            id,
+            component,
+            load: CancellationToken::new(),
+            server: None,
+        }
+    }
 }
 
 #[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -36,37 +175,105 @@ pub enum Mode {
 }
 
 impl App {
-    pub fn new(client: TeamCityClient, build_types: Vec<BuildType>) -> Result<Self> {
+    pub fn new(
+        config: Config,
+        client: TeamCityClient,
+        projects: Vec<String>,
+        mut servers: Vec<Server>,
+        session: Option<Session>,
+    ) -> Result<Self> {
         let (action_tx, action_rx) = mpsc::unbounded_channel();
+        let recent_build_types = session::load_recent(&config.config.data_dir);
+        if !servers.is_empty() {
+            servers.insert(
+                0,
+                Server {
+                    name: config
+                        .server_name
+                        .clone()
+                        .unwrap_or_else(|| client.server_name()),
+                    client: client.clone(),
+                    projects: projects.clone(),
+                },
+            );
+        }
         Ok(Self {
-            components: vec![Box::new(Projects::new(build_types.clone()))],
+            tabs: vec![Tab::new(
+                0,
+                Box::new(Projects::new(vec![], vec![], vec![], None)),
+            )],
+            active_tab: 0,
+            next_tab_id: 1,
+            routed_tab: None,
+            toast: Toast::new(),
+            spinner: Spinner::new(),
+            status_bar: StatusBar::new(),
+            log_panel: LogPanel::new(),
+            command_palette: CommandPalette::new(),
+            recent_picker: RecentPicker::new(),
+            reauth: Reauth::new(),
+            status_poller: None,
+            favorite_builds: HashMap::new(),
+            favorite_baselines: BTreeMap::new(),
+            favorites_polled_at: None,
+            watched_builds: HashMap::new(),
+            clipboard: None,
             should_quit: false,
             should_suspend: false,
-            config: Config::new()?,
+            config,
             mode: Mode::Home,
             last_tick_key_events: Vec::new(),
             action_tx,
             action_rx,
             client,
-            build_types: build_types.clone(),
+            projects,
+            servers,
+            build_types: Vec::new(),
+            failed_projects: Vec::new(),
+            projects_fetched_at: None,
+            recent_build_types,
+            session,
+            restored_build: None,
+            startup: None,
+            handling: None,
         })
     }
 
+    /// Opens the view of `action` on startup instead of reopening the view of the previous run
+    pub fn open(mut self, action: Action) -> Self {
+        self.startup = Some(action);
+        self
+    }
+
     pub async fn run(&mut self) -> Result<()> {
         let mut tui = Tui::new()?
             // .mouse(true) // uncomment this line to enable mouse support
             .tick_rate(4.0)
             .frame_rate(1.0);
         tui.enter()?;
+        config::watch(self.action_tx.clone());
+        self.status_bar
+            .register_config_handler(self.config.clone())?;
+        self.start_status_polling();
+        self.report_auth_failures();
 
-        for component in self.components.iter_mut() {
-            component.register_action_handler(self.action_tx.clone())?;
+        for tab in self.tabs.iter_mut() {
+            tab.component
+                .register_action_handler(self.action_tx.clone())?;
+            tab.component.register_config_handler(self.config.clone())?;
+            tab.component.init(tui.size()?)?;
         }
-        for component in self.components.iter_mut() {
-            component.register_config_handler(self.config.clone())?;
-        }
-        for component in self.components.iter_mut() {
-            component.init(tui.size()?)?;
+        if self.projects.is_empty() && self.servers.is_empty() {
+            self.action_tx.send(Action::LoadRootProjects)?;
+        } else {
+            // fetched in the background so the UI shows up right away, even on slow servers
+            self.action_tx.send(Action::LoadProjects {
+                bypass_cache: false,
+            })?;
+            match self.startup.take() {
+                Some(action) => self.action_tx.send(action)?,
+                None => self.restore_session()?,
+            }
         }
 
         loop {
@@ -93,18 +300,57 @@ impl App {
             return Ok(());
         };
         let action_tx = self.action_tx.clone();
+        // the token prompt takes the keys while it is open, before any keybinding
+        if self.reauth.is_open()
+            && let Event::Key(key) = event
+        {
+            if let Some(action) = self.reauth.handle_key_event(key)? {
+                action_tx.send(action)?;
+            }
+            return Ok(());
+        }
+        // the command palette takes the keys while it is open, before any keybinding
+        if self.command_palette.is_open()
+            && let Event::Key(key) = event
+        {
+            if let Some(action) = self.command_palette.handle_key_event(key)? {
+                action_tx.send(action)?;
+            }
+            return Ok(());
+        }
+        if self.recent_picker.is_open()
+            && let Event::Key(key) = event
+        {
+            if let Some(action) = self.recent_picker.handle_key_event(key)? {
+                action_tx.send(action)?;
+            }
+            return Ok(());
+        }
         match event {
             Event::Quit => action_tx.send(Action::Quit)?,
             Event::Tick => action_tx.send(Action::Tick)?,
             Event::Render => action_tx.send(Action::Render)?,
             Event::Resize(x, y) => action_tx.send(Action::Resize(x, y))?,
-            Event::Key(key) => self.handle_key_event(key)?,
+            // typed text goes to the view's prompt only, `:` included
+            Event::Key(key) if !self.tabs[self.active_tab].component.is_typing() => {
+                self.handle_key_event(key)?
+            }
             _ => {}
         }
-        for component in self.components.iter_mut() {
-            if let Some(action) = component.handle_events(Some(event.clone()))? {
+        // the log panel takes the keys while it is open
+        if self.log_panel.is_visible()
+            && let Event::Key(key) = event
+        {
+            if let Some(action) = self.log_panel.handle_key_event(key)? {
                 action_tx.send(action)?;
             }
+            return Ok(());
+        }
+        if let Some(action) = self.tabs[self.active_tab]
+            .component
+            .handle_events(Some(event.clone()))?
+        {
+            action_tx.send(action)?;
         }
         Ok(())
     }
@@ -136,119 +382,2306 @@ impl App {
 
     fn handle_actions(&mut self, tui: &mut Tui) -> Result<()> {
         while let Ok(action) = self.action_rx.try_recv() {
-            if action != Action::Tick && action != Action::Render {
-                debug!("{action:?}");
+            self.handle_action(tui, action)?;
+        }
+        Ok(())
+    }
+
+    fn handle_action(&mut self, tui: &mut Tui, action: Action) -> Result<()> {
+        // the token isn't logged
+        if action != Action::Tick
+            && action != Action::Render
+            && !matches!(action, Action::Reauthenticate { .. })
+        {
+            debug!("{action:?}");
+        }
+        if let Some(build_type_id) = configuration_of(&action) {
+            self.follow_configuration(build_type_id);
+        }
+        self.handling =
+            (action != Action::Tick && action != Action::Render).then(|| action.clone());
+        match action {
+            Action::Tick => {
+                self.last_tick_key_events.drain(..);
+            }
+            Action::Quit => {
+                self.save_session();
+                self.should_quit = true;
             }
-            match action {
-                Action::Tick => {
-                    self.last_tick_key_events.drain(..);
+            Action::Suspend => self.should_suspend = true,
+            Action::Resume => self.should_suspend = false,
+            Action::ClearScreen => tui.terminal.clear()?,
+            Action::ConfigReloaded => self.reload_config(tui)?,
+            Action::ApplyFilter { ref name } => self.apply_filter(tui, name.clone())?,
+            Action::Resize(w, h) => self.handle_resize(tui, w, h)?,
+            Action::Render => self.render(tui)?,
+            Action::TabLoaded { tab, ref action } => {
+                match self.tabs.iter().position(|t| t.id == tab) {
+                    Some(index) => {
+                        self.routed_tab = Some(index);
+                        let result = self.handle_action(tui, (**action).clone());
+                        self.routed_tab = None;
+                        result?;
+                    }
+                    None => debug!("Dropped {} of a closed tab", action),
+                }
+                return Ok(());
+            }
+            Action::OnServer {
+                ref server,
+                ref action,
+            } => {
+                if self.servers.iter().any(|s| &s.name == server) {
+                    self.tab().server = Some(server.clone());
+                }
+                return self.handle_action(tui, (**action).clone());
+            }
+            Action::RunExternal {
+                ref name,
+                ref command,
+            } => self.run_external(name.clone(), command.clone()),
+            Action::RunCommand { ref command } => match parse_command(command, &self.build_types) {
+                Ok(action) => self.action_tx.send(action)?,
+                Err(e) => self.action_tx.send(Action::Error(e))?,
+            },
+            Action::NewTab => {
+                let mut component = Box::new(Projects::new(
+                    self.build_types.clone(),
+                    self.failed_projects.clone(),
+                    self.recent_build_types.clone(),
+                    self.projects_fetched_at,
+                ));
+                component.register_action_handler(self.action_tx.clone())?;
+                component.register_config_handler(self.config.clone())?;
+                component.init(tui.size()?)?;
+                self.tabs.push(Tab::new(self.next_tab_id, component));
+                self.next_tab_id += 1;
+                self.active_tab = self.tabs.len() - 1;
+                self.render(tui)?;
+            }
+            Action::CloseTab => {
+                if self.tabs.len() == 1 {
+                    self.action_tx
+                        .send(Action::Toast("The last tab can't be closed".to_string()))?;
+                } else {
+                    self.tabs.remove(self.active_tab).load.cancel();
+                    self.active_tab = self.active_tab.min(self.tabs.len() - 1);
+                    self.render(tui)?;
                 }
-                Action::Quit => self.should_quit = true,
-                Action::Suspend => self.should_suspend = true,
-                Action::Resume => self.should_suspend = false,
-                Action::ClearScreen => tui.terminal.clear()?,
-                Action::Resize(w, h) => self.handle_resize(tui, w, h)?,
-                Action::Render => self.render(tui)?,
-                Action::Fzf { ref options } => {
-                    let selected: String = tui.run_fzf(&options)?;
-                    self.action_tx.send(Action::FzfSelected { selected })?;
-                    ()
+            }
+            Action::NextTab => {
+                self.active_tab = (self.active_tab + 1) % self.tabs.len();
+                self.render(tui)?;
+            }
+            Action::PreviousTab => {
+                self.active_tab = (self.active_tab + self.tabs.len() - 1) % self.tabs.len();
+                self.render(tui)?;
+            }
+            Action::Fzf { ref options } => {
+                let selected: String = tui.run_fzf(options)?;
+                self.action_tx.send(Action::FzfSelected { selected })?;
+            }
+            Action::Pager { ref file } => {
+                tui.run_pager(file)?;
+                self.action_tx.send(Action::Render)?;
+            }
+            Action::EditConfig => {
+                if let Err(e) = tui.run_editor(&config::get_config_dir().join("config.toml")) {
+                    self.action_tx.send(Action::Error(e.to_string()))?;
+                }
+                match config::load_projects() {
+                    Ok(projects) => self.projects = projects,
+                    Err(e) => self.action_tx.send(Action::Error(format!(
+                        "Failed to read the projects from config.toml: {}",
+                        e
+                    )))?,
+                }
+                self.action_tx
+                    .send(Action::LoadProjects { bypass_cache: true })?;
+            }
+            Action::LoadBuilds {
+                ref project_id,
+                ref title,
+            } => self.load_builds(tui, project_id, title, None, None)?,
+            Action::LoadBuildsOnBranch {
+                ref project_id,
+                ref title,
+                ref branch,
+            } => self.load_builds(tui, project_id, title, Some(branch.clone()), None)?,
+            Action::LoadBuildsInRange {
+                ref project_id,
+                ref title,
+                ref branch,
+                range,
+            } => self.load_builds(tui, project_id, title, branch.clone(), Some(range))?,
+            Action::SearchBuilds {
+                ref project_id,
+                ref query,
+                ref locator,
+            } => {
+                let client = self.tab_client();
+                let tx = self.tab_tx();
+                let project_id = project_id.clone();
+                let query = query.clone();
+                let locator = locator.clone();
+
+                self.spawn_view_load(async move {
+                    match client.search_builds(&project_id, &locator).await {
+                        Ok(items) => {
+                            let _ = tx.send(Action::SearchResults {
+                                project_id,
+                                query,
+                                items,
+                            });
+                        }
+                        Err(e) => {
+                            let error_msg = format!("Failed to search builds: {}", e);
+                            let _ = tx.send(Action::Error(error_msg));
+                        }
+                    }
+                });
+            }
+            Action::ShowPreviousBuilds { ref current } => {
+                let previous = self
+                    .recent_build_types
+                    .iter()
+                    .find(|id| Some(*id) != current.as_ref());
+                match previous {
+                    Some(project_id) => {
+                        let title = self
+                            .build_types
+                            .iter()
+                            .find(|bt| bt.id == *project_id)
+                            .map(|bt| bt.name.clone())
+                            .unwrap_or_else(|| project_id.clone());
+                        self.action_tx.send(Action::LoadBuilds {
+                            project_id: project_id.clone(),
+                            title,
+                        })?;
+                    }
+                    None => self.action_tx.send(Action::Toast(
+                        "No previously viewed configuration".to_string(),
+                    ))?,
                 }
-                Action::Pager { ref file } => {
-                    let _ = tui.run_pager(file)?;
-                    self.action_tx.send(Action::Render)?;
-                    ()
+            }
+            Action::OpenRecent { ref current } => {
+                self.recent_picker.open(
+                    RecentEntry::list(&self.recent_build_types, &self.build_types),
+                    current.as_deref(),
+                );
+                self.render(tui)?;
+            }
+            Action::ShowBuilds {
+                ref project_id,
+                ref title,
+                ref items,
+                ref branch,
+                range,
+            } => {
+                let restored = self
+                    .restored_build
+                    .take_if(|(id, _)| id == project_id)
+                    .map(|(_, build_id)| build_id);
+                self.switch_to(
+                    tui,
+                    Box::new(
+                        Builds::new(project_id.clone(), title.clone(), items.clone())
+                            .composite(self.is_composite(project_id))
+                            .on_branch(branch.clone())
+                            .in_range(range)
+                            .restore_selection(restored),
+                    ),
+                )?;
+            }
+            Action::LoadStatistics {
+                ref project_id,
+                ref title,
+            } => {
+                self.switch_to(
+                    tui,
+                    Box::new(Statistics::loading(project_id.clone(), title.clone())),
+                )?;
+
+                let client = self.tab_client();
+                let tx = self.tab_tx();
+                let project_id = project_id.clone();
+                let title = title.clone();
+                let branch = self
+                    .config
+                    .default_branches
+                    .get(&project_id)
+                    .map(|policy| policy.branch_filter())
+                    .unwrap_or_default();
+                let count = self.config.statistics.build_count;
+
+                self.spawn_view_load(async move {
+                    match client
+                        .get_finished_builds(&project_id, &branch, count)
+                        .await
+                    {
+                        Ok(builds) => {
+                            let _ = tx.send(Action::ShowStatistics {
+                                project_id,
+                                title,
+                                builds,
+                            });
+                        }
+                        Err(e) => {
+                            let error_msg = format!("Failed to fetch build history: {}", e);
+                            let _ = tx.send(Action::Error(error_msg));
+                        }
+                    }
+                });
+            }
+            Action::ShowStatistics {
+                ref project_id,
+                ref title,
+                ref builds,
+            } => {
+                self.switch_to(
+                    tui,
+                    Box::new(Statistics::new(
+                        project_id.clone(),
+                        title.clone(),
+                        builds.clone(),
+                    )),
+                )?;
+            }
+            Action::GrepBuildLogs {
+                ref project_id,
+                ref title,
+                ref query,
+            } => {
+                self.switch_to(
+                    tui,
+                    Box::new(LogGrep::loading(
+                        project_id.clone(),
+                        title.clone(),
+                        query.clone(),
+                    )),
+                )?;
+
+                let client = self.tab_client();
+                let tx = self.tab_tx();
+                let project_id = project_id.clone();
+                let title = title.clone();
+                let query = query.clone();
+                let branch = self
+                    .config
+                    .default_branches
+                    .get(&project_id)
+                    .map(|policy| policy.branch_filter())
+                    .unwrap_or_default();
+                let count = self.config.log_grep.build_count;
+
+                self.spawn_view_load(async move {
+                    let builds = match client
+                        .get_finished_builds(&project_id, &branch, count)
+                        .await
+                    {
+                        Ok(builds) => builds,
+                        Err(e) => {
+                            let error_msg = format!("Failed to fetch build history: {}", e);
+                            let _ = tx.send(Action::Error(error_msg));
+                            return;
+                        }
+                    };
+                    // each log is grepped as it arrives, only its matching lines are kept
+                    let results = client
+                        .get_build_logs(builds, |build, log| grep_log(build.clone(), log, &query))
+                        .await
+                        .into_iter()
+                        .map(|(build, matches)| match matches {
+                            Ok(matches) => matches,
+                            Err(e) => {
+                                warn!("Failed to download log of {:?}: {}", build.id, e);
+                                BuildMatches::failed(build, e.to_string())
+                            }
+                        })
+                        .collect();
+                    let _ = tx.send(Action::ShowLogGrep {
+                        project_id,
+                        title,
+                        query,
+                        results,
+                    });
+                });
+            }
+            Action::ShowLogGrep {
+                ref project_id,
+                ref title,
+                ref query,
+                ref results,
+            } => {
+                self.switch_to(
+                    tui,
+                    Box::new(LogGrep::new(
+                        project_id.clone(),
+                        title.clone(),
+                        query.clone(),
+                        results.clone(),
+                    )),
+                )?;
+            }
+            Action::LoadTimeline => {
+                self.switch_to(tui, Box::new(Timeline::loading(self.build_types.clone())))?;
+
+                let client = self.tab_client();
+                let tx = self.tab_tx();
+                let project_ids = self.projects.clone();
+
+                self.spawn_view_load(async move {
+                    match client
+                        .get_recent_builds_by_projects(&project_ids, TIMELINE_BUILD_COUNT)
+                        .await
+                    {
+                        Ok(builds) => {
+                            let _ = tx.send(Action::ShowTimeline { builds });
+                        }
+                        Err(e) => {
+                            let error_msg = format!("Failed to fetch recent builds: {}", e);
+                            let _ = tx.send(Action::Error(error_msg));
+                        }
+                    }
+                });
+            }
+            Action::ShowTimeline { ref builds } => {
+                self.switch_to(
+                    tui,
+                    Box::new(Timeline::new(self.build_types.clone(), builds.clone())),
+                )?;
+            }
+            Action::LoadServerOverview => {
+                self.switch_to(tui, Box::new(AboutServer::new(None)))?;
+
+                let client = self.tab_client();
+                let tx = self.tab_tx();
+
+                self.spawn_view_load(async move {
+                    match client.get_server_overview().await {
+                        Ok(overview) => {
+                            let _ = tx.send(Action::ShowServerOverview { overview });
+                        }
+                        Err(e) => {
+                            let error_msg = format!("Failed to fetch server info: {}", e);
+                            let _ = tx.send(Action::Error(error_msg));
+                        }
+                    }
+                });
+            }
+            Action::ShowServerOverview { ref overview } => {
+                self.switch_to(tui, Box::new(AboutServer::new(Some(overview.clone()))))?;
+            }
+            Action::LoadAgentPools => {
+                self.switch_to(tui, Box::new(AgentPools::new(None)))?;
+
+                let client = self.tab_client();
+                let tx = self.tab_tx();
+
+                self.spawn_view_load(async move {
+                    match client.get_agent_pools().await {
+                        Ok(pools) => {
+                            let _ = tx.send(Action::ShowAgentPools { pools });
+                        }
+                        Err(e) => {
+                            let error_msg = format!("Failed to fetch agent pools: {}", e);
+                            let _ = tx.send(Action::Error(error_msg));
+                        }
+                    }
+                });
+            }
+            Action::ShowAgentPools { ref pools } => {
+                self.switch_to(tui, Box::new(AgentPools::new(Some(pools.clone()))))?;
+            }
+            Action::MoveAgent {
+                agent_id,
+                ref agent,
+                pool_id,
+                ref pool,
+            } => {
+                let client = self.tab_client();
+                let tx = self.action_tx.clone();
+                let message = format!("Moved {} to {}", agent, pool);
+
+                self.spawn_request(async move {
+                    match client.move_agent_to_pool(agent_id, pool_id).await {
+                        Ok(()) => {
+                            let _ = tx.send(Action::Toast(message));
+                            let _ = tx.send(Action::LoadAgentPools);
+                        }
+                        Err(e) => {
+                            let error_msg = format!("Failed to move the agent: {}", e);
+                            let _ = tx.send(Action::Error(error_msg));
+                        }
+                    }
+                });
+            }
+            Action::FavoriteBuildsPolled {
+                ref builds,
+                ref baselines,
+            } => {
+                for build in builds {
+                    if let Some(build_type_id) = &build.build_type_id {
+                        self.favorite_builds
+                            .insert(build_type_id.clone(), build.clone());
+                    }
                 }
-                Action::LoadBuilds {
-                    ref project_id,
-                    ref title,
-                } => {
-                    self.components = vec![Box::new(Builds::new(title.clone(), vec![]))];
+                self.favorite_baselines = baselines.clone();
+                self.favorites_polled_at = Some(OffsetDateTime::now_utc());
+            }
+            Action::ShowDashboard => {
+                self.switch_to(
+                    tui,
+                    Box::new(Dashboard::new(
+                        &self.build_types,
+                        self.favorite_builds.clone(),
+                        self.favorite_baselines.clone(),
+                        self.favorites_polled_at,
+                    )),
+                )?;
+            }
+            Action::ShowProjects => {
+                self.switch_to(
+                    tui,
+                    Box::new(Projects::new(
+                        self.build_types.clone(),
+                        self.failed_projects.clone(),
+                        self.recent_build_types.clone(),
+                        self.projects_fetched_at,
+                    )),
+                )?;
+            }
+            Action::LoadProjects { bypass_cache } => {
+                let tx = self.action_tx.clone();
+                let servers = self.project_servers(|_, projects| projects.to_vec());
 
-                    for component in self.components.iter_mut() {
-                        component.register_action_handler(self.action_tx.clone())?;
-                        component.register_config_handler(self.config.clone())?;
-                        component.init(tui.size()?)?;
+                self.spawn_request(async move {
+                    let Some((fetched, requested)) =
+                        fetch_projects(servers, bypass_cache, &tx).await
+                    else {
+                        return;
+                    };
+                    // the chips above the Projects table are cut off when many projects fail, so
+                    // the full list is shown once as an error too
+                    if let Some(summary) = fetched.failure_summary(requested) {
+                        let _ = tx.send(Action::Error(format!(
+                            "{} — R in the Projects view retries",
+                            summary
+                        )));
                     }
-                    self.render(tui)?;
+                    let _ = tx.send(Action::ProjectsFetched {
+                        build_types: fetched.build_types,
+                        failed: fetched.failed,
+                    });
+                });
+            }
+            Action::RetryFailedProjects => {
+                let tx = self.action_tx.clone();
+                let failed = self.failed_projects.clone();
+                let servers = self.project_servers(|name, _| {
+                    failed
+                        .iter()
+                        .filter_map(|failure| match name {
+                            Some(name) => failure
+                                .project_id
+                                .strip_prefix(name)
+                                .and_then(|id| id.strip_prefix('/')),
+                            None => Some(failure.project_id.as_str()),
+                        })
+                        .map(str::to_string)
+                        .collect()
+                });
+                let servers: Vec<_> = servers
+                    .into_iter()
+                    .filter(|(_, _, projects)| !projects.is_empty())
+                    .collect();
+
+                self.spawn_request(async move {
+                    if let Some((fetched, _)) = fetch_projects(servers, false, &tx).await {
+                        let _ = tx.send(Action::ProjectsRetried {
+                            build_types: fetched.build_types,
+                            failed: fetched.failed,
+                        });
+                    }
+                });
+            }
+            Action::ProjectsRetried {
+                ref build_types,
+                ref failed,
+            } => {
+                // the projects that loaded join the ones already listed, once if retried twice
+                let mut all = self.build_types.clone();
+                all.extend(
+                    build_types
+                        .iter()
+                        .filter(|retried| {
+                            !self.build_types.iter().any(|listed| {
+                                listed.id == retried.id && listed.server == retried.server
+                            })
+                        })
+                        .cloned(),
+                );
+                self.action_tx.send(Action::ProjectsFetched {
+                    build_types: all,
+                    failed: failed.clone(),
+                })?;
+            }
+            Action::LoadRootProjects => {
+                self.switch_to(tui, Box::new(ProjectPicker::new(None)))?;
+
+                let client = self.tab_client();
+                let tx = self.tab_tx();
+
+                self.spawn_view_load(async move {
+                    match client.get_root_projects().await {
+                        Ok(projects) => {
+                            let _ = tx.send(Action::ShowRootProjects { projects });
+                        }
+                        Err(e) => {
+                            let error_msg = format!("Failed to fetch projects: {}", e);
+                            let _ = tx.send(Action::Error(error_msg));
+                        }
+                    }
+                });
+            }
+            Action::ShowRootProjects { ref projects } => {
+                self.switch_to(tui, Box::new(ProjectPicker::new(Some(projects.clone()))))?;
+            }
+            Action::ProjectsPicked { ref projects, save } => {
+                self.projects = projects.clone();
+                if save {
+                    match config::save_projects(projects) {
+                        Ok(()) => self.action_tx.send(Action::Toast(
+                            "Saved the projects to config.toml".to_string(),
+                        ))?,
+                        Err(e) => self
+                            .action_tx
+                            .send(Action::Error(format!("Failed to save the projects: {}", e)))?,
+                    }
+                }
+                self.action_tx.send(Action::LoadProjects {
+                    bypass_cache: false,
+                })?;
+                self.action_tx.send(Action::ShowProjects)?;
+            }
+            Action::ProjectsFetched {
+                ref build_types,
+                ref failed,
+            } => {
+                self.build_types = build_types.clone();
+                self.failed_projects = failed.clone();
+                self.projects_fetched_at = Some(OffsetDateTime::now_utc());
+                // favorites are polled on the servers listing them, known by now
+                if !self.servers.is_empty() {
+                    self.start_status_polling();
+                }
+            }
+            Action::SetPaused {
+                ref build_type_id,
+                paused,
+            } => {
+                let client = self.tab_client();
+                let tx = self.action_tx.clone();
+                let build_type_id = build_type_id.clone();
+
+                self.spawn_request(async move {
+                    match client.set_paused(&build_type_id, paused).await {
+                        Ok(_) => {
+                            let verb = if paused { "Paused" } else { "Resumed" };
+                            let _ = tx.send(Action::Toast(format!("{} {}", verb, build_type_id)));
+                            let _ = tx.send(Action::PausedChanged {
+                                build_type_id,
+                                paused,
+                            });
+                        }
+                        Err(e) => {
+                            let error_msg = format!("Failed to pause or resume: {}", e);
+                            let _ = tx.send(Action::Error(error_msg));
+                        }
+                    }
+                });
+            }
+            Action::PausedChanged {
+                ref build_type_id,
+                paused,
+            } => {
+                if let Some(build_type) = self
+                    .build_types
+                    .iter_mut()
+                    .find(|build_type| &build_type.id == build_type_id)
+                {
+                    build_type.paused = Some(paused);
+                }
+            }
+            Action::SaveColumnWidths { ref widths } => {
+                self.config.column_widths = widths.clone();
+                if let Err(e) = config::save_column_widths(widths) {
+                    self.action_tx.send(Action::Error(format!(
+                        "Failed to save column widths: {}",
+                        e
+                    )))?;
+                }
+                self.render(tui)?;
+            }
+            Action::SaveLogWrap { wrap } => {
+                self.config.log_wrap = wrap;
+                if let Err(e) = config::save_log_wrap(wrap) {
+                    self.action_tx.send(Action::Error(format!(
+                        "Failed to save the log wrapping: {}",
+                        e
+                    )))?;
+                }
+                self.render(tui)?;
+            }
+            Action::LoadBuildLog { ref build_id } => {
+                let client = self.tab_client();
+                let tx = self.action_tx.clone();
+
+                let build_id = *build_id;
+                let tmp_dir = std::env::temp_dir();
+                let build_log = tmp_dir.join(format!("build_log_{}.txt", build_id));
+
+                self.spawn_request(async move {
+                    match client.download_build_log_to(&build_id, &build_log).await {
+                        Ok(_) => {
+                            let _ = tx.send(Action::Pager { file: build_log });
+                        }
+                        Err(e) => {
+                            let error_msg = format!("Failed to download build log: {}", e);
+                            let _ = tx.send(Action::Error(error_msg));
+                        }
+                    }
+                });
+            }
+            Action::LoadLogViewer {
+                ref project_id,
+                ref title,
+                ref build,
+            } => {
+                self.switch_to(
+                    tui,
+                    Box::new(BuildLog::new(
+                        project_id.clone(),
+                        title.clone(),
+                        build.clone(),
+                        None,
+                    )),
+                )?;
+
+                let Some(build_id) = build.id else {
+                    self.action_tx.send(Action::Error(
+                        "No id was found for selected build".to_string(),
+                    ))?;
+                    return Ok(());
+                };
+                let client = self.tab_client();
+                let tx = self.tab_tx();
+                let project_id = project_id.clone();
+                let title = title.clone();
+                let build = build.clone();
+
+                self.spawn_view_load(async move {
+                    match client.get_build_log_text(&build_id).await {
+                        Ok(log) => {
+                            let _ = tx.send(Action::ShowLogViewer {
+                                project_id,
+                                title,
+                                build,
+                                log,
+                            });
+                        }
+                        Err(e) => {
+                            let error_msg = format!("Failed to download build log: {}", e);
+                            let _ = tx.send(Action::Error(error_msg));
+                        }
+                    }
+                });
+            }
+            Action::ShowLogViewer {
+                ref project_id,
+                ref title,
+                ref build,
+                ref log,
+            } => {
+                self.switch_to(
+                    tui,
+                    Box::new(BuildLog::new(
+                        project_id.clone(),
+                        title.clone(),
+                        build.clone(),
+                        Some(log.clone()),
+                    )),
+                )?;
+            }
+            Action::LoadCompositeBuilds { build_id } => {
+                let client = self.tab_client();
+                let tx = self.tab_tx();
+                self.spawn_view_load(async move {
+                    let builds = client
+                        .get_composite_builds(build_id)
+                        .await
+                        .map_err(|e| e.to_string());
+                    let _ = tx.send(Action::ShowCompositeBuilds { build_id, builds });
+                });
+            }
+            Action::LoadBuildPreview { ref build } => {
+                let Some(build_id) = build.id else {
+                    return Ok(());
+                };
+                let client = self.tab_client();
+                let tx = self.tab_tx();
+                let build = build.clone();
+                let keys = self.config.preview.statistics.clone();
+
+                self.spawn_view_load(async move {
+                    let statistics = if keys.is_empty() {
+                        Vec::new()
+                    } else {
+                        match client.get_build_statistics(build_id).await {
+                            Ok(statistics) => statistics_summary(&statistics, &keys),
+                            Err(e) => {
+                                warn!("Failed to fetch statistics of {}: {}", build_id, e);
+                                Vec::new()
+                            }
+                        }
+                    };
+                    let mut lines = Vec::new();
+                    if is_failed(&build) {
+                        match client.get_failed_tests(build_id).await {
+                            Ok(tests) => lines = failure_summary(&build, &tests),
+                            Err(e) => warn!("Failed to fetch failed tests of {}: {}", build_id, e),
+                        }
+                    }
+                    // failures without failed tests are usually explained at the end of the log
+                    if lines.len() <= 1 {
+                        if !lines.is_empty() {
+                            lines.push(String::new());
+                        }
+                        match client.get_build_log_text(&build_id).await {
+                            Ok(log) => lines.extend(log_tail(&log, PREVIEW_LOG_LINES)),
+                            Err(e) => {
+                                warn!("Failed to download log of {}: {}", build_id, e);
+                                lines.push(format!("Failed to download build log: {}", e));
+                            }
+                        }
+                    }
+                    let _ = tx.send(Action::ShowBuildPreview {
+                        build_id,
+                        lines,
+                        statistics,
+                    });
+                });
+            }
+            Action::LoadBuildChain {
+                ref project_id,
+                ref title,
+                ref build,
+            } => {
+                self.switch_to(
+                    tui,
+                    Box::new(BuildChain::new(
+                        project_id.clone(),
+                        title.clone(),
+                        build.clone(),
+                        None,
+                        &self.build_types,
+                    )),
+                )?;
+
+                let Some(build_id) = build.id else {
+                    self.action_tx.send(Action::Error(
+                        "No id was found for selected build".to_string(),
+                    ))?;
+                    return Ok(());
+                };
+                let client = self.tab_client();
+                let tx = self.tab_tx();
+                let project_id = project_id.clone();
+                let title = title.clone();
+                let build = build.clone();
+
+                self.spawn_view_load(async move {
+                    match client.get_build_chain(build_id).await {
+                        Ok(chain) => {
+                            let _ = tx.send(Action::ShowBuildChain {
+                                project_id,
+                                title,
+                                build,
+                                chain,
+                            });
+                        }
+                        Err(e) => {
+                            let error_msg = format!("Failed to fetch build chain: {}", e);
+                            let _ = tx.send(Action::Error(error_msg));
+                        }
+                    }
+                });
+            }
+            Action::ShowBuildChain {
+                ref project_id,
+                ref title,
+                ref build,
+                ref chain,
+            } => {
+                self.switch_to(
+                    tui,
+                    Box::new(BuildChain::new(
+                        project_id.clone(),
+                        title.clone(),
+                        build.clone(),
+                        Some(chain.clone()),
+                        &self.build_types,
+                    )),
+                )?;
+            }
+            Action::LoadAgentCompatibility {
+                ref build_type_id,
+                ref title,
+            } => {
+                self.switch_to(
+                    tui,
+                    Box::new(CompatibleAgents::new(
+                        build_type_id.clone(),
+                        title.clone(),
+                        None,
+                    )),
+                )?;
+
+                let client = self.tab_client();
+                let tx = self.tab_tx();
+                let build_type_id = build_type_id.clone();
+                let title = title.clone();
+
+                self.spawn_view_load(async move {
+                    match client.get_agent_compatibility(&build_type_id).await {
+                        Ok(compatibility) => {
+                            let _ = tx.send(Action::ShowAgentCompatibility {
+                                build_type_id,
+                                title,
+                                compatibility,
+                            });
+                        }
+                        Err(e) => {
+                            let error_msg = format!("Failed to fetch agent requirements: {}", e);
+                            let _ = tx.send(Action::Error(error_msg));
+                        }
+                    }
+                });
+            }
+            Action::ShowAgentCompatibility {
+                ref build_type_id,
+                ref title,
+                ref compatibility,
+            } => {
+                self.switch_to(
+                    tui,
+                    Box::new(CompatibleAgents::new(
+                        build_type_id.clone(),
+                        title.clone(),
+                        Some(compatibility.clone()),
+                    )),
+                )?;
+            }
+            Action::LoadParameters {
+                ref build_type_id,
+                ref title,
+            } => {
+                self.switch_to(
+                    tui,
+                    Box::new(Parameters::new(build_type_id.clone(), title.clone(), None)),
+                )?;
+
+                let client = self.tab_client();
+                let tx = self.tab_tx();
+                let build_type_id = build_type_id.clone();
+                let title = title.clone();
+
+                self.spawn_view_load(async move {
+                    match client.get_parameters(&build_type_id).await {
+                        Ok(parameters) => {
+                            let _ = tx.send(Action::ShowParameters {
+                                build_type_id,
+                                title,
+                                parameters,
+                            });
+                        }
+                        Err(e) => {
+                            let error_msg = format!("Failed to fetch parameters: {}", e);
+                            let _ = tx.send(Action::Error(error_msg));
+                        }
+                    }
+                });
+            }
+            Action::ShowParameters {
+                ref build_type_id,
+                ref title,
+                ref parameters,
+            } => {
+                self.switch_to(
+                    tui,
+                    Box::new(Parameters::new(
+                        build_type_id.clone(),
+                        title.clone(),
+                        Some(parameters.clone()),
+                    )),
+                )?;
+            }
+            Action::SetParameter {
+                ref build_type_id,
+                ref name,
+                ref value,
+            } => {
+                let client = self.tab_client();
+                let tx = self.action_tx.clone();
+                let build_type_id = build_type_id.clone();
+                let name = name.clone();
+                let value = value.clone();
+
+                self.spawn_request(async move {
+                    match client.set_parameter(&build_type_id, &name, &value).await {
+                        Ok(_) => {
+                            let _ = tx.send(Action::Toast(format!("Set {}", name)));
+                            let _ = tx.send(Action::ParameterUpdated {
+                                build_type_id,
+                                name,
+                                value,
+                            });
+                        }
+                        Err(e) => {
+                            let error_msg = format!("Failed to set {}: {}", name, e);
+                            let _ = tx.send(Action::Error(error_msg));
+                        }
+                    }
+                });
+            }
+            Action::LoadLocatorBuilds { ref locator } => {
+                self.switch_to(
+                    tui,
+                    Box::new(LocatorBuilds::loading(locator.clone(), &self.build_types)),
+                )?;
+
+                let client = self.tab_client();
+                let tx = self.tab_tx();
+                let locator = locator.clone();
+
+                self.spawn_view_load(async move {
+                    match client.get_builds_by_raw_locator(&locator).await {
+                        Ok(builds) => {
+                            let _ = tx.send(Action::ShowLocatorBuilds { locator, builds });
+                        }
+                        Err(e) => {
+                            let error_msg = format!("Failed to fetch builds: {}", e);
+                            let _ = tx.send(Action::Error(error_msg));
+                        }
+                    }
+                });
+            }
+            Action::ShowLocatorBuilds {
+                ref locator,
+                ref builds,
+            } => {
+                self.switch_to(
+                    tui,
+                    Box::new(LocatorBuilds::new(
+                        locator.clone(),
+                        builds.clone(),
+                        &self.build_types,
+                    )),
+                )?;
+            }
+            Action::LoadChangeBuilds { ref revision } => {
+                let locators = match change_locators(revision) {
+                    Ok(locators) => locators,
+                    Err(e) => {
+                        self.action_tx.send(Action::Error(e))?;
+                        return Ok(());
+                    }
+                };
+                self.switch_to(
+                    tui,
+                    Box::new(ChangeBuilds::loading(revision.clone(), &self.build_types)),
+                )?;
+
+                let client = self.tab_client();
+                let tx = self.tab_tx();
+                let revision = revision.clone();
 
-                    let client = self.client.clone();
-                    let tx = self.action_tx.clone();
-                    let title = title.clone(); // Clone title here to create an owned value for the closure
-                    let project_id = project_id.clone();
-
-                    tokio::spawn(async move {
-                        match client.get_builds_by_project(&project_id).await {
-                            Ok(items) => {
-                                let _ = tx.send(Action::ShowBuilds {
-                                    title: title.clone(),
-                                    items,
+                self.spawn_view_load(async move {
+                    // a number is a change ID or a numeric revision, whichever matches first
+                    for locator in locators {
+                        match client.get_change_builds(&locator).await {
+                            Ok(Some(found)) => {
+                                let _ = tx.send(Action::ShowChangeBuilds {
+                                    revision,
+                                    builds: found.builds,
+                                    first_built_in: found.first_built_in,
                                 });
+                                return;
                             }
+                            Ok(None) => {}
+                            Err(e) => {
+                                let error_msg =
+                                    format!("Failed to find builds of {}: {}", revision, e);
+                                let _ = tx.send(Action::Error(error_msg));
+                                return;
+                            }
+                        }
+                    }
+                    let _ = tx.send(Action::Error(format!("No change {} was found", revision)));
+                });
+            }
+            Action::ShowChangeBuilds {
+                ref revision,
+                ref builds,
+                first_built_in: ref first_ids,
+            } => {
+                // built by a single configuration, there is nothing to pick from
+                let first = first_built_in(builds, first_ids);
+                if first.len() == 1
+                    && let Some((build_type_id, build_id)) = first.into_iter().next()
+                {
+                    let title = self
+                        .build_types
+                        .iter()
+                        .find(|bt| bt.id == build_type_id)
+                        .map(|bt| bt.name.clone())
+                        .unwrap_or_else(|| build_type_id.clone());
+                    self.action_tx.send(Action::ShowBuildsWithChange {
+                        project_id: build_type_id,
+                        title,
+                        revision: revision.clone(),
+                        items: builds.clone(),
+                        selected: Some(build_id),
+                    })?;
+                    return Ok(());
+                }
+                self.switch_to(
+                    tui,
+                    Box::new(ChangeBuilds::new(
+                        revision.clone(),
+                        builds.clone(),
+                        first_ids,
+                        &self.build_types,
+                    )),
+                )?;
+            }
+            Action::ShowBuildsWithChange {
+                ref project_id,
+                ref title,
+                ref revision,
+                ref items,
+                selected,
+            } => {
+                self.switch_to(
+                    tui,
+                    Box::new(
+                        Builds::new(project_id.clone(), title.clone(), items.clone())
+                            .composite(self.is_composite(project_id))
+                            .searched(format!("change {}", revision))
+                            .restore_selection(selected),
+                    ),
+                )?;
+            }
+            Action::OpenSettingsFile { ref build_type } => {
+                let Some(project_id) = build_type.project_id.clone() else {
+                    self.action_tx.send(Action::Error(
+                        "No project was found for selected configuration".to_string(),
+                    ))?;
+                    return Ok(());
+                };
+                let client = self.tab_client();
+                let tx = self.action_tx.clone();
+                let build_type = build_type.clone();
+
+                self.spawn_request(async move {
+                    let url = match client.get_versioned_settings(&project_id).await {
+                        Ok(settings) => settings_file_url(&settings, &build_type),
+                        Err(e) => Err(format!("Failed to fetch versioned settings: {}", e)),
+                    };
+                    let action = match url {
+                        Ok(url) => match open::that(&url) {
+                            Ok(()) => Action::Toast(format!("Opened {}", url)),
+                            Err(e) => Action::Error(format!("Failed to open {}: {}", url, e)),
+                        },
+                        Err(error_msg) => Action::Error(error_msg),
+                    };
+                    let _ = tx.send(action);
+                });
+            }
+            Action::RebuildChain {
+                ref build,
+                all_dependencies,
+            } => {
+                let client = self.tab_client();
+                let tx = self.action_tx.clone();
+                let build = build.clone();
+
+                self.spawn_request(async move {
+                    let number = build.build_number.clone().unwrap_or_default();
+                    let action = match client.rebuild_chain(&build, all_dependencies).await {
+                        Ok(()) if all_dependencies => {
+                            Action::Toast(format!("Queued #{} with the whole chain", number))
+                        }
+                        Ok(()) => Action::Toast(format!(
+                            "Queued #{} rebuilding its failed dependencies",
+                            number
+                        )),
+                        Err(e) => Action::Error(format!("Failed to queue #{}: {}", number, e)),
+                    };
+                    let _ = tx.send(action);
+                });
+            }
+            Action::LoadPendingChanges {
+                ref project_id,
+                ref title,
+            } => {
+                self.switch_to(
+                    tui,
+                    Box::new(PendingChanges::new(project_id.clone(), title.clone(), None)),
+                )?;
+
+                let client = self.tab_client();
+                let tx = self.tab_tx();
+                let project_id = project_id.clone();
+                let title = title.clone();
+
+                self.spawn_view_load(async move {
+                    match client.get_pending_changes(&project_id).await {
+                        Ok(changes) => {
+                            let _ = tx.send(Action::ShowPendingChanges {
+                                project_id,
+                                title,
+                                changes,
+                            });
+                        }
+                        Err(e) => {
+                            let error_msg = format!("Failed to fetch pending changes: {}", e);
+                            let _ = tx.send(Action::Error(error_msg));
+                        }
+                    }
+                });
+            }
+            Action::ShowPendingChanges {
+                ref project_id,
+                ref title,
+                ref changes,
+            } => {
+                self.switch_to(
+                    tui,
+                    Box::new(PendingChanges::new(
+                        project_id.clone(),
+                        title.clone(),
+                        Some(changes.clone()),
+                    )),
+                )?;
+            }
+            Action::LoadTestFailures {
+                ref build_type_id,
+                ref title,
+                ref build,
+            } => {
+                let parent_project_id = self
+                    .build_types
+                    .iter()
+                    .find(|bt| bt.id == *build_type_id)
+                    .and_then(|bt| bt.project_id.clone());
+                self.switch_to(
+                    tui,
+                    Box::new(TestFailures::new(
+                        build_type_id.clone(),
+                        parent_project_id,
+                        title.clone(),
+                        build.clone(),
+                        None,
+                    )),
+                )?;
+
+                let Some(build_id) = build.id else {
+                    self.action_tx.send(Action::Error(
+                        "No id was found for selected build".to_string(),
+                    ))?;
+                    return Ok(());
+                };
+                let client = self.tab_client();
+                let tx = self.tab_tx();
+                let build_type_id = build_type_id.clone();
+                let title = title.clone();
+                let build = build.clone();
+
+                self.spawn_view_load(async move {
+                    match client.get_failed_tests(build_id).await {
+                        Ok(tests) => {
+                            let _ = tx.send(Action::ShowTestFailures {
+                                build_type_id,
+                                title,
+                                build,
+                                tests,
+                            });
+                        }
+                        Err(e) => {
+                            let error_msg = format!("Failed to fetch failed tests: {}", e);
+                            let _ = tx.send(Action::Error(error_msg));
+                        }
+                    }
+                });
+            }
+            Action::ShowTestFailures {
+                ref build_type_id,
+                ref title,
+                ref build,
+                ref tests,
+            } => {
+                let parent_project_id = self
+                    .build_types
+                    .iter()
+                    .find(|bt| bt.id == *build_type_id)
+                    .and_then(|bt| bt.project_id.clone());
+                self.switch_to(
+                    tui,
+                    Box::new(TestFailures::new(
+                        build_type_id.clone(),
+                        parent_project_id,
+                        title.clone(),
+                        build.clone(),
+                        Some(tests.clone()),
+                    )),
+                )?;
+            }
+            Action::LoadTestHistory {
+                ref build_type_id,
+                ref title,
+                ref build,
+                ref test,
+            } => {
+                self.switch_to(
+                    tui,
+                    Box::new(TestHistory::new(
+                        build_type_id.clone(),
+                        title.clone(),
+                        build.clone(),
+                        test.clone(),
+                        None,
+                    )),
+                )?;
+
+                let Some(test_id) = test.id.clone() else {
+                    self.action_tx.send(Action::Error(
+                        "No id was found for selected test".to_string(),
+                    ))?;
+                    return Ok(());
+                };
+                let client = self.tab_client();
+                let tx = self.tab_tx();
+                let build_type_id = build_type_id.clone();
+                let title = title.clone();
+                let build = build.clone();
+                let test = test.clone();
+
+                self.spawn_view_load(async move {
+                    match client
+                        .get_test_history(&test_id, &build_type_id, TEST_HISTORY_BUILD_COUNT)
+                        .await
+                    {
+                        Ok(history) => {
+                            let _ = tx.send(Action::ShowTestHistory {
+                                build_type_id,
+                                title,
+                                build,
+                                test,
+                                history,
+                            });
+                        }
+                        Err(e) => {
+                            let error_msg = format!("Failed to fetch test history: {}", e);
+                            let _ = tx.send(Action::Error(error_msg));
+                        }
+                    }
+                });
+            }
+            Action::ShowTestHistory {
+                ref build_type_id,
+                ref title,
+                ref build,
+                ref test,
+                ref history,
+            } => {
+                self.switch_to(
+                    tui,
+                    Box::new(TestHistory::new(
+                        build_type_id.clone(),
+                        title.clone(),
+                        build.clone(),
+                        test.clone(),
+                        Some(history.clone()),
+                    )),
+                )?;
+            }
+            Action::MuteTest {
+                ref test_id,
+                ref scope,
+                ref comment,
+            } => {
+                let client = self.tab_client();
+                let tx = self.action_tx.clone();
+                let test_id = test_id.clone();
+                let scope = scope.clone();
+                let comment = comment.clone();
+
+                self.spawn_request(async move {
+                    match client.mute_test(&test_id, &scope, &comment).await {
+                        Ok(_) => {
+                            let _ = tx.send(Action::TestMuted { test_id });
+                            let _ =
+                                tx.send(Action::Toast(format!("Muted test in {}", scope.label())));
+                        }
+                        Err(e) => {
+                            let error_msg = format!("Failed to mute test: {}", e);
+                            let _ = tx.send(Action::Error(error_msg));
+                        }
+                    }
+                });
+            }
+            Action::LoadMutes {
+                ref project_id,
+                ref back,
+            } => {
+                self.switch_to(
+                    tui,
+                    Box::new(Mutes::new(project_id.clone(), None, (**back).clone())),
+                )?;
+
+                let client = self.tab_client();
+                let tx = self.tab_tx();
+                let project_id = project_id.clone();
+                let back = back.clone();
+
+                self.spawn_view_load(async move {
+                    match client.get_mutes(&project_id).await {
+                        Ok(mutes) => {
+                            let _ = tx.send(Action::ShowMutes {
+                                project_id,
+                                mutes,
+                                back,
+                            });
+                        }
+                        Err(e) => {
+                            let error_msg = format!("Failed to fetch muted tests: {}", e);
+                            let _ = tx.send(Action::Error(error_msg));
+                        }
+                    }
+                });
+            }
+            Action::ShowMutes {
+                ref project_id,
+                ref mutes,
+                ref back,
+            } => {
+                self.switch_to(
+                    tui,
+                    Box::new(Mutes::new(
+                        project_id.clone(),
+                        Some(mutes.clone()),
+                        (**back).clone(),
+                    )),
+                )?;
+            }
+            Action::LoadBranches { ref build_type_id } => {
+                let client = self.tab_client();
+                let tx = self.tab_tx();
+                let build_type_id = build_type_id.clone();
+
+                self.spawn_view_load(async move {
+                    match client.get_branches(&build_type_id).await {
+                        Ok(branches) => {
+                            let _ = tx.send(Action::ShowBranches {
+                                build_type_id,
+                                branches,
+                            });
+                        }
+                        Err(e) => {
+                            let error_msg =
+                                format!("Failed to fetch branches of {}: {}", build_type_id, e);
+                            let _ = tx.send(Action::Error(error_msg));
+                        }
+                    }
+                });
+            }
+            Action::TriggerBuild {
+                ref build_type_id,
+                ref title,
+                ref branch,
+            } => {
+                let client = self.tab_client();
+                let tx = self.action_tx.clone();
+                let build_type_id = build_type_id.clone();
+                let title = title.clone();
+                let branch = branch.clone();
+
+                self.spawn_request(async move {
+                    match client
+                        .trigger_build(&build_type_id, branch.as_deref())
+                        .await
+                    {
+                        Ok(_) => {
+                            let message = match branch {
+                                Some(branch) => format!("Queued {} on {}", title, branch),
+                                None => format!("Queued {}", title),
+                            };
+                            let _ = tx.send(Action::Toast(message));
+                        }
+                        Err(e) => {
+                            let error_msg = format!("Failed to queue {}: {}", title, e);
+                            let _ = tx.send(Action::Error(error_msg));
+                        }
+                    }
+                });
+            }
+            Action::TriggerBuildOnChange {
+                ref build_type_id,
+                ref title,
+                ref branch,
+                change_id,
+                ref revision,
+            } => {
+                let client = self.tab_client();
+                let tx = self.action_tx.clone();
+                let build_type_id = build_type_id.clone();
+                let branch = branch.clone();
+                let message = format!("{} on {}", title, revision);
+
+                self.spawn_request(async move {
+                    match client
+                        .trigger_build_on_change(&build_type_id, branch.as_deref(), change_id)
+                        .await
+                    {
+                        Ok(_) => {
+                            let _ = tx.send(Action::Toast(format!("Queued {}", message)));
+                        }
+                        Err(e) => {
+                            let error_msg = format!("Failed to queue {}: {}", message, e);
+                            let _ = tx.send(Action::Error(error_msg));
+                        }
+                    }
+                });
+            }
+            Action::UnmuteTest { mute_id } => {
+                let client = self.tab_client();
+                let tx = self.action_tx.clone();
+
+                self.spawn_request(async move {
+                    match client.unmute(mute_id).await {
+                        Ok(_) => {
+                            let _ = tx.send(Action::TestUnmuted { mute_id });
+                            let _ = tx.send(Action::Toast("Unmuted test".to_string()));
+                        }
+                        Err(e) => {
+                            let error_msg = format!("Failed to unmute test: {}", e);
+                            let _ = tx.send(Action::Error(error_msg));
+                        }
+                    }
+                });
+            }
+            Action::CancelBuilds { ref builds } => {
+                let client = self.tab_client();
+                let tx = self.action_tx.clone();
+                let builds = builds.clone();
+
+                self.spawn_request(async move {
+                    let mut failed = Vec::new();
+                    for build in &builds {
+                        if let Err(e) = client.cancel_build(build, BULK_CANCEL_COMMENT).await {
+                            failed.push(bulk_failure(build, &e));
+                        }
+                    }
+                    let _ = tx.send(bulk_result("Canceled", builds.len(), failed));
+                });
+            }
+            Action::ToggleWatchBuild { ref build } => self.toggle_watch(build)?,
+            Action::WatchedBuildFinished { ref build } => {
+                if let Some(id) = build.id {
+                    self.watched_builds.remove(&id);
+                }
+                let message = format!(
+                    "{} #{} finished: {}",
+                    self.build_type_name(build),
+                    build.build_number.as_deref().unwrap_or_default(),
+                    build.status.as_deref().unwrap_or("UNKNOWN")
+                );
+                let mut stdout = std::io::stdout();
+                stdout.write_all(notification_sequence(&message).as_bytes())?;
+                stdout.flush()?;
+                self.action_tx.send(Action::Toast(message))?;
+            }
+            Action::QueuedBuildStuck {
+                ref build,
+                ref causes,
+            } => {
+                let message = stuck_message(
+                    &self.build_type_name(build),
+                    build,
+                    causes,
+                    OffsetDateTime::now_utc(),
+                );
+                let mut stdout = std::io::stdout();
+                stdout.write_all(notification_sequence(&message).as_bytes())?;
+                stdout.flush()?;
+                self.action_tx.send(Action::Toast(message))?;
+            }
+            Action::RerunBuilds { ref builds } => {
+                let client = self.tab_client();
+                let tx = self.action_tx.clone();
+                let builds = builds.clone();
+
+                self.spawn_request(async move {
+                    let mut failed = Vec::new();
+                    for build in &builds {
+                        if let Err(e) = client.rerun_build(build).await {
+                            failed.push(bulk_failure(build, &e));
+                        }
+                    }
+                    let _ = tx.send(bulk_result("Re-triggered", builds.len(), failed));
+                });
+            }
+            Action::DownloadBuildLogs { ref builds } => {
+                let client = self.tab_client();
+                let tx = self.action_tx.clone();
+                let builds = builds.clone();
+                let dir = self.config.log_download_dir();
+
+                self.spawn_request(async move {
+                    if let Err(e) = async_fs::create_dir_all(&dir).await {
+                        let error_msg = format!("Failed to create {}: {}", dir.display(), e);
+                        let _ = tx.send(Action::Error(error_msg));
+                        return;
+                    }
+
+                    let mut failed = Vec::new();
+                    for build in &builds {
+                        let Some(build_id) = build.id else {
+                            continue;
+                        };
+                        let file = dir.join(log_file_name(build));
+                        if let Err(e) = client.download_build_log_to(&build_id, &file).await {
+                            failed.push(bulk_failure(build, &e));
+                        }
+                    }
+                    let _ = tx.send(if failed.is_empty() {
+                        Action::Toast(format!(
+                            "Saved logs of {} builds to {}",
+                            builds.len(),
+                            dir.display()
+                        ))
+                    } else {
+                        bulk_result("Saved logs of", builds.len(), failed)
+                    });
+                });
+            }
+            Action::PlanArtifactCleanup { ref builds } => {
+                let client = self.tab_client();
+                let tx = self.action_tx.clone();
+                let builds = builds.clone();
+
+                self.spawn_request(async move {
+                    let mut plan = Vec::new();
+                    for build in &builds {
+                        match client.get_build_artifacts(build).await {
+                            Ok(artifacts) if !artifacts.paths.is_empty() => plan.push(artifacts),
+                            Ok(_) => {}
                             Err(e) => {
                                 let error_msg = format!(
-                                    "Failed to fetch builds for project {}: {}",
-                                    project_id, e
+                                    "Failed to list artifacts of build {:?}: {}",
+                                    build.id, e
                                 );
                                 let _ = tx.send(Action::Error(error_msg));
                             }
                         }
+                    }
+                    let _ = tx.send(Action::ShowArtifactCleanup { plan });
+                });
+            }
+            Action::DeleteArtifacts { ref plan } => {
+                let client = self.tab_client();
+                let tx = self.action_tx.clone();
+                let plan = plan.clone();
+
+                self.spawn_request(async move {
+                    let mut deleted = 0;
+                    let mut failed = Vec::new();
+                    for artifacts in &plan {
+                        for path in &artifacts.paths {
+                            match client.delete_build_artifact(artifacts.build_id, path).await {
+                                Ok(_) => deleted += 1,
+                                Err(e) => failed.push(format!(
+                                    "#{} {}: {}",
+                                    artifacts.build_number.as_deref().unwrap_or_default(),
+                                    path,
+                                    e
+                                )),
+                            }
+                        }
+                    }
+                    let _ = tx.send(Action::ArtifactsDeleted { deleted, failed });
+                });
+            }
+            Action::DownloadArtifacts { ref build, ref dir } => {
+                let Some(build_id) = build.id else {
+                    self.action_tx.send(Action::Error(
+                        "No id was found for selected build".to_string(),
+                    ))?;
+                    return Ok(());
+                };
+                let client = self.tab_client();
+                let tx = self.action_tx.clone();
+                let number = build
+                    .build_number
+                    .clone()
+                    .unwrap_or_else(|| build_id.to_string());
+                let file = dir.join(format!(
+                    "{}_{}_artifacts.zip",
+                    build.build_type_id.as_deref().unwrap_or("build"),
+                    number
+                ));
+                let dir = dir.clone();
+
+                self.spawn_request(async move {
+                    let result = match async_fs::create_dir_all(&dir).await {
+                        Ok(_) => {
+                            let progress_tx = tx.clone();
+                            client
+                                .download_artifacts_archive(build_id, &file, |downloaded, total| {
+                                    let _ = progress_tx.send(Action::ArtifactsDownloadProgress {
+                                        build_id,
+                                        downloaded,
+                                        total,
+                                    });
+                                })
+                                .await
+                        }
+                        Err(e) => Err(e.into()),
+                    };
+                    let _ = tx.send(Action::ArtifactsDownloadFinished { build_id });
+                    let _ = tx.send(match result {
+                        Ok(_) => Action::Toast(format!(
+                            "Saved artifacts of #{} to {}",
+                            number,
+                            file.display()
+                        )),
+                        Err(e) => Action::Error(format!(
+                            "Failed to download artifacts of #{}: {}",
+                            number, e
+                        )),
                     });
+                });
+            }
+            Action::CopyToClipboard {
+                ref label,
+                ref text,
+            } => match self.copy_to_clipboard(text) {
+                Ok(_) => self
+                    .action_tx
+                    .send(Action::Toast(format!("Copied {}: {}", label, text)))?,
+                Err(e) => self
+                    .action_tx
+                    .send(Action::Error(format!("Failed to copy {}: {}", label, e)))?,
+            },
+            Action::Reauthenticate {
+                ref server,
+                ref token,
+                ref retry,
+            } => self.reauthenticate(server.clone(), token.clone(), retry.as_deref().cloned())?,
+            Action::Error(ref msg) => {
+                error!("{msg}");
+            }
+            _ => {}
+        }
+
+        if let Some(action) = self.tab().component.update(action.clone())? {
+            self.action_tx.send(action)?
+        };
+        if let Some(action) = self.toast.update(action.clone())? {
+            self.action_tx.send(action)?
+        }
+        if let Some(action) = self.spinner.update(action.clone())? {
+            self.action_tx.send(action)?
+        }
+        if let Some(action) = self.status_bar.update(action.clone())? {
+            self.action_tx.send(action)?
+        }
+        if let Some(action) = self.log_panel.update(action.clone())? {
+            self.action_tx.send(action)?
+        }
+        if let Some(action) = self.command_palette.update(action.clone())? {
+            self.action_tx.send(action)?
+        }
+        if let Some(action) = self.reauth.update(action.clone())? {
+            self.action_tx.send(action)?
+        }
+        Ok(())
+    }
+
+    /// Runs the command of a custom action in the background and reports how it ended
+    fn run_external(&self, name: String, command: Vec<String>) {
+        let tx = self.action_tx.clone();
+        tokio::spawn(async move {
+            let Some((program, args)) = command.split_first() else {
+                let _ = tx.send(Action::Error(format!("{}: the command is empty", name)));
+                return;
+            };
+            info!("Running custom action {}: {:?}", name, command);
+            let output = tokio::process::Command::new(program)
+                .args(args)
+                .stdin(std::process::Stdio::null())
+                .output()
+                .await;
+            let action = match output {
+                Ok(output) if output.status.success() => Action::Toast(format!("{} done", name)),
+                Ok(output) => {
+                    let stderr = String::from_utf8_lossy(&output.stderr);
+                    match stderr.lines().rfind(|line| !line.trim().is_empty()) {
+                        Some(line) => Action::Error(format!("{} failed: {}", name, line)),
+                        None => Action::Error(format!("{} failed with {}", name, output.status)),
+                    }
                 }
-                Action::ShowBuilds {
-                    ref title,
-                    ref items,
-                } => {
-                    self.components = vec![Box::new(Builds::new(title.clone(), items.clone()))];
+                Err(e) => Action::Error(format!("Failed to run {}: {}", name, e)),
+            };
+            let _ = tx.send(action);
+        });
+    }
+
+    /// Starts polling `build` until it finishes, or stops if it is already watched
+    fn toggle_watch(&mut self, build: &Build) -> Result<()> {
+        let Some(build_id) = build.id else {
+            self.action_tx
+                .send(Action::Error("The build has no ID to watch".to_string()))?;
+            return Ok(());
+        };
+        let number = build.build_number.as_deref().unwrap_or_default();
+        if let Some((_, poller)) = self.watched_builds.remove(&build_id) {
+            poller.abort();
+            self.action_tx
+                .send(Action::Toast(format!("Stopped watching #{}", number)))?;
+            return Ok(());
+        }
+
+        let server = self.tabs[self.routed_tab.unwrap_or(self.active_tab)]
+            .server
+            .clone();
+        let poller = self.spawn_watch(self.tab_client(), build_id);
+        self.watched_builds.insert(build_id, (server, poller));
+        self.action_tx.send(Action::Toast(format!(
+            "Watching #{}, notifying when it finishes",
+            number
+        )))?;
+        Ok(())
+    }
 
-                    for component in self.components.iter_mut() {
-                        component.register_action_handler(self.action_tx.clone())?;
-                        component.register_config_handler(self.config.clone())?;
-                        component.init(tui.size()?)?;
+    /// Polls the build `build_id` with `client` until it finishes
+    fn spawn_watch(&self, client: TeamCityClient, build_id: i64) -> JoinHandle<()> {
+        let tx = self.action_tx.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(WATCH_INTERVAL);
+            loop {
+                interval.tick().await;
+                match client.get_build(build_id).await {
+                    Ok(build) if build.state.as_deref() == Some("finished") => {
+                        let _ = tx.send(Action::WatchedBuildFinished { build });
+                        break;
                     }
-                    self.render(tui)?;
+                    Ok(_) => {}
+                    // checked again on the next tick
+                    Err(e) => warn!("Failed to check watched build {}: {}", build_id, e),
+                }
+            }
+        })
+    }
+
+    /// Name of the configuration of `build`, its ID if it isn't one of the listed ones
+    fn build_type_name(&self, build: &Build) -> String {
+        let id = build.build_type_id.as_deref().unwrap_or_default();
+        self.build_types
+            .iter()
+            .find(|bt| bt.id == id)
+            .map(|bt| bt.name.clone())
+            .unwrap_or_else(|| id.to_string())
+    }
+
+    /// Whether the listed configuration `build_type_id` is a composite one
+    fn is_composite(&self, build_type_id: &str) -> bool {
+        self.build_types.iter().any(|bt| {
+            bt.id == build_type_id
+                && bt
+                    .kind
+                    .as_deref()
+                    .is_some_and(|kind| kind.eq_ignore_ascii_case("composite"))
+        })
+    }
+
+    /// Opens the token prompt whenever a server rejects the credentials, naming the server unless
+    /// it is the main one
+    fn report_auth_failures(&self) {
+        let clients = std::iter::once((None, &self.client)).chain(
+            self.servers
+                .iter()
+                .skip(1)
+                .map(|server| (Some(server.name.clone()), &server.client)),
+        );
+        for (server, client) in clients {
+            let action_tx = self.action_tx.clone();
+            client.report_auth_failures(move |status| {
+                // requests of the pollers have no action to retry
+                let retry = ORIGIN.try_with(|origin| origin.clone()).ok().flatten();
+                let _ = action_tx.send(Action::AuthFailed {
+                    server: server.clone(),
+                    status: status.as_u16(),
+                    retry: retry.map(Box::new),
+                });
+            });
+        }
+    }
+
+    /// Each server with the projects `projects` picks out of the ones it monitors, tagged with
+    /// its name when there are several
+    fn project_servers(
+        &self,
+        projects: impl Fn(Option<&str>, &[String]) -> Vec<String>,
+    ) -> Vec<(Option<String>, TeamCityClient, Vec<String>)> {
+        if self.servers.is_empty() {
+            vec![(None, self.client.clone(), projects(None, &self.projects))]
+        } else {
+            self.servers
+                .iter()
+                .map(|server| {
+                    (
+                        Some(server.name.clone()),
+                        server.client.clone(),
+                        projects(Some(&server.name), &server.projects),
+                    )
+                })
+                .collect()
+        }
+    }
+
+    /// Sends `token` to the main server, or the one named `server`, from now on, saves it in
+    /// `config.toml` and retries the action whose request was rejected
+    fn reauthenticate(
+        &mut self,
+        server: Option<String>,
+        token: String,
+        retry: Option<Action>,
+    ) -> Result<()> {
+        let index = match &server {
+            Some(name) => self.servers.iter().position(|s| &s.name == name),
+            None => (!self.servers.is_empty()).then_some(0),
+        };
+        let current = match index {
+            Some(i) => &self.servers[i].client,
+            None => &self.client,
+        };
+        let client = current.with_token(token.clone());
+        match index {
+            Some(i) => {
+                if i == 0 {
+                    self.client = client.clone();
+                }
+                self.servers[i].client = client;
+            }
+            None => self.client = client,
+        }
+        self.start_status_polling();
+        // the watched builds would keep polling with the rejected token otherwise
+        let watched: Vec<(i64, Option<String>)> = self
+            .watched_builds
+            .iter()
+            .map(|(id, (server, _))| (*id, server.clone()))
+            .collect();
+        for (build_id, server) in watched {
+            let poller = self.spawn_watch(self.server_client(server.as_deref()), build_id);
+            if let Some((_, previous)) = self.watched_builds.insert(build_id, (server, poller)) {
+                previous.abort();
+            }
+        }
+
+        match config::save_token(server.as_deref(), &token) {
+            Ok(()) if retry.is_some() => self
+                .action_tx
+                .send(Action::Toast("Token saved, retrying".to_string()))?,
+            Ok(()) => self
+                .action_tx
+                .send(Action::Toast("Token saved".to_string()))?,
+            Err(e) => self
+                .action_tx
+                .send(Action::Error(format!("Failed to save the token: {}", e)))?,
+        }
+        if let Some(retry) = retry {
+            self.action_tx.send(retry)?;
+        }
+        Ok(())
+    }
+
+    /// Client of the server the tab actions are handled in talks to
+    fn tab_client(&self) -> TeamCityClient {
+        let index = self.routed_tab.unwrap_or(self.active_tab);
+        self.server_client(self.tabs[index].server.as_deref())
+    }
+
+    /// Client of the server named `server`, the main one if None or it isn't configured
+    fn server_client(&self, server: Option<&str>) -> TeamCityClient {
+        server
+            .and_then(|name| self.servers.iter().find(|s| s.name == name))
+            .map_or_else(|| self.client.clone(), |s| s.client.clone())
+    }
+
+    /// Points the tab to a server listing the configuration `build_type_id`, keeping its own if it
+    /// lists one by that ID. Views opened from the Projects view name the server with
+    /// `Action::OnServer` instead, as several servers may use the same ID.
+    fn follow_configuration(&mut self, build_type_id: &str) {
+        let servers: Vec<Option<String>> = self
+            .build_types
+            .iter()
+            .filter(|build_type| build_type.id == build_type_id)
+            .map(|build_type| build_type.server.clone())
+            .collect();
+        let tab = self.tab();
+        if let Some(server) = servers.first()
+            && !servers.contains(&tab.server)
+        {
+            tab.server = server.clone();
+        }
+    }
+
+    /// Reopens the Builds view of the previous run
+    fn restore_session(&mut self) -> Result<()> {
+        let Some(Session {
+            build_type_id: Some(build_type_id),
+            title,
+            selected_build,
+            ..
+        }) = self.session.take()
+        else {
+            return Ok(());
+        };
+        self.restored_build = selected_build.map(|build_id| (build_type_id.clone(), build_id));
+        self.action_tx.send(Action::LoadBuilds {
+            title: title.unwrap_or_else(|| build_type_id.clone()),
+            project_id: build_type_id,
+        })?;
+        Ok(())
+    }
+
+    /// Saves the current view to be reopened on the next launch. Views that can't be reopened
+    /// are saved as the configuration opened last.
+    fn save_session(&self) {
+        let mut session = self.tabs[self.active_tab]
+            .component
+            .session()
+            .unwrap_or_else(|| Session {
+                build_type_id: self.recent_build_types.first().cloned(),
+                title: self.recent_build_types.first().map(|id| {
+                    self.build_types
+                        .iter()
+                        .find(|bt| bt.id == *id)
+                        .map(|bt| bt.name.clone())
+                        .unwrap_or_else(|| id.clone())
+                }),
+                ..Session::default()
+            });
+        session.active_filter = self.config.active_filter.clone();
+        if let Err(e) = session::save(&self.config.config.data_dir, &session) {
+            warn!("Failed to save the session: {}", e);
+        }
+    }
+
+    fn reload_config(&mut self, tui: &mut Tui) -> Result<()> {
+        let mut config = match Config::new() {
+            Ok(config) => config,
+            Err(e) => {
+                let error_msg = format!("Failed to reload configuration: {}", e);
+                self.action_tx.send(Action::Error(error_msg))?;
+                return Ok(());
+            }
+        };
+        config.login_username = self.config.login_username.clone();
+        config.active_filter = self.config.active_filter.clone();
+
+        let configure = |client: TeamCityClient| {
+            client
+                .default_ttl(config.cache.default_ttl())
+                .project_ttls(config.cache.project_ttls())
+                .max_concurrent_requests(config.max_concurrent_requests)
+                .builds(config.builds.clone())
+        };
+        self.client = configure(self.client.clone());
+        for server in self.servers.iter_mut() {
+            server.client = configure(server.client.clone());
+        }
+        for tab in self.tabs.iter_mut() {
+            tab.component.register_config_handler(config.clone())?;
+        }
+        self.status_bar.register_config_handler(config.clone())?;
+        self.config = config;
+        self.start_status_polling();
+
+        info!("Configuration reloaded");
+        self.action_tx
+            .send(Action::Toast("Configuration reloaded".to_string()))?;
+        self.render(tui)
+    }
+
+    /// The tab actions are handled in: the one a load result was routed to, or the shown one
+    fn tab(&mut self) -> &mut Tab {
+        let index = self.routed_tab.unwrap_or(self.active_tab);
+        &mut self.tabs[index]
+    }
+
+    /// Sender for results of a load of the current tab, handled in that tab even after another one
+    /// is shown
+    fn tab_tx(&mut self) -> mpsc::UnboundedSender<Action> {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let action_tx = self.action_tx.clone();
+        let tab = self.tab().id;
+        tokio::spawn(async move {
+            while let Some(action) = rx.recv().await {
+                let action = Box::new(action);
+                if action_tx.send(Action::TabLoaded { tab, action }).is_err() {
+                    break;
                 }
-                Action::ShowProjects => {
-                    self.components = vec![Box::new(Projects::new(self.build_types.clone()))];
-                    for component in self.components.iter_mut() {
-                        component.register_action_handler(self.action_tx.clone())?;
-                        component.register_config_handler(self.config.clone())?;
-                        component.init(tui.size()?)?;
+            }
+        });
+        tx
+    }
+
+    /// Opens the builds of a configuration, on `branch` or else the branch configured in
+    /// `default_branches`, the ones started in `range` or else the latest ones
+    fn load_builds(
+        &mut self,
+        tui: &mut Tui,
+        project_id: &str,
+        title: &str,
+        branch: Option<BranchFilter>,
+        range: Option<DateRange>,
+    ) -> Result<()> {
+        self.recent_build_types.retain(|id| id != project_id);
+        self.recent_build_types.insert(0, project_id.to_string());
+        self.recent_build_types.truncate(session::MAX_RECENT);
+        if let Err(e) = session::save_recent(&self.config.config.data_dir, &self.recent_build_types)
+        {
+            warn!("Failed to save the recently viewed configurations: {}", e);
+        }
+
+        self.switch_to(
+            tui,
+            Box::new(
+                Builds::loading(project_id.to_string(), title.to_string())
+                    .composite(self.is_composite(project_id))
+                    .on_branch(branch.clone())
+                    .in_range(range),
+            ),
+        )?;
+
+        let client = self.tab_client();
+        let tx = self.tab_tx();
+        let title = title.to_string();
+        let project_id = project_id.to_string();
+
+        // a branch picked in the view replaces the configured one and its preferred branches
+        let (server_branch, prefer) = match &branch {
+            Some(branch) => (branch.clone(), None),
+            None => {
+                let policy = self
+                    .config
+                    .default_branches
+                    .get(&project_id)
+                    .cloned()
+                    .unwrap_or_default();
+                let prefer = policy.prefer_regex().unwrap_or_else(|e| {
+                    let _ = tx.send(Action::Error(format!(
+                        "Invalid preferred branch pattern for {}: {}",
+                        project_id, e
+                    )));
+                    None
+                });
+                (policy.branch_filter(), prefer)
+            }
+        };
+
+        self.spawn_view_load(async move {
+            match client
+                .get_builds_by_project(&project_id, &server_branch, range.as_ref())
+                .await
+            {
+                Ok(items) => {
+                    // queued builds haven't started in any range yet, and the builds are still
+                    // worth showing if the queue can't be read
+                    let queued = if range.is_some() {
+                        vec![]
+                    } else {
+                        client
+                            .get_queued_builds(&project_id)
+                            .await
+                            .unwrap_or_else(|e| {
+                                warn!("Failed to fetch queued builds of {}: {}", project_id, e);
+                                vec![]
+                            })
+                    };
+                    let mut items = merge_queued(queued, items);
+                    // Only narrow down to preferred branches if any build matches
+                    if let Some(prefer) = prefer
+                        && items
+                            .iter()
+                            .any(|b| prefer.is_match(b.branch_name.as_deref().unwrap_or_default()))
+                    {
+                        items.retain(|b| {
+                            prefer.is_match(b.branch_name.as_deref().unwrap_or_default())
+                        });
                     }
-                    self.render(tui)?;
+                    let _ = tx.send(Action::ShowBuilds {
+                        project_id,
+                        title,
+                        items,
+                        branch,
+                        range,
+                    });
                 }
-                Action::LoadBuildLog { ref build_id } => {
-                    let client = self.client.clone();
-                    let tx = self.action_tx.clone();
-
-                    let build_id = build_id.clone();
-                    let tmp_dir = std::env::temp_dir();
-                    let build_log = tmp_dir.join(format!("build_log_{}.txt", build_id));
-
-                    tokio::spawn(async move {
-                        match client.download_build_log_to(&build_id, &build_log).await {
-                            Ok(_) => {
-                                let _ = tx.send(Action::Pager { file: build_log });
-                            }
-                            Err(e) => {
-                                let error_msg = format!("Failed to download build log: {}", e);
-                                let _ = tx.send(Action::Error(error_msg));
+                Err(e) => {
+                    let error_msg =
+                        format!("Failed to fetch builds for project {}: {}", project_id, e);
+                    let _ = tx.send(Action::Error(error_msg));
+                }
+            }
+        });
+        Ok(())
+    }
+
+    /// Runs a load for the current view in the background until it finishes or the view is left
+    fn spawn_view_load(&mut self, load: impl Future<Output = ()> + Send + 'static) {
+        let cancelled = self.tab().load.clone();
+        self.spawn_request(async move {
+            tokio::select! {
+                _ = cancelled.cancelled() => debug!("Load canceled after leaving the view"),
+                _ = load => {}
+            }
+        });
+    }
+
+    /// Runs the requests of the action being handled in the background, so the action is retried
+    /// if the server rejects the token
+    fn spawn_request(&self, requests: impl Future<Output = ()> + Send + 'static) {
+        tokio::spawn(ORIGIN.scope(self.handling.clone(), requests));
+    }
+
+    /// Polls the latest builds of the favorite configurations in the background, replacing the
+    /// poller started for the previous configuration
+    fn start_status_polling(&mut self) {
+        if let Some(poller) = self.status_poller.take() {
+            poller.abort();
+        }
+        if self.config.favorites.is_empty() {
+            return;
+        }
+
+        let servers = self.favorite_servers();
+        let tx = self.action_tx.clone();
+        let period = self.config.status_polling.interval();
+        let stuck_after = self.config.queue_watchdog.threshold();
+        self.status_poller = Some(tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            let mut baselines = BTreeMap::new();
+            // queued builds of each server already reported as stuck
+            let mut reported = vec![HashSet::new(); servers.len()];
+            loop {
+                interval.tick().await;
+                let mut builds = Vec::new();
+                let mut any_polled = false;
+                for ((client, favorites), reported) in servers.iter().zip(&mut reported) {
+                    match poll_favorites(client, favorites, &mut baselines).await {
+                        Ok(mut polled) => {
+                            if let Some(threshold) = stuck_after {
+                                for (build, causes) in
+                                    stuck_builds(client, &polled, threshold, reported).await
+                                {
+                                    let _ = tx.send(Action::QueuedBuildStuck { build, causes });
+                                }
                             }
+                            builds.append(&mut polled);
+                            any_polled = true;
                         }
-                    });
+                        // the server asked to slow down, skip the polls until it is ready again
+                        Err(TeamCityError::RateLimited {
+                            retry_after: Some(wait),
+                        }) => {
+                            warn!("Polling is rate limited, pausing for {}s", wait.as_secs());
+                            tokio::time::sleep(wait).await;
+                            interval.reset();
+                        }
+                        // a transient failure every interval would bury the UI in error toasts
+                        Err(e) => warn!("Failed to poll favorite configurations: {}", e),
+                    }
                 }
-                Action::Error(ref msg) => {
-                    // TODO: show error msg
+                let baselines = baselines.clone();
+                if any_polled
+                    && tx
+                        .send(Action::FavoriteBuildsPolled { builds, baselines })
+                        .is_err()
+                {
+                    break;
                 }
-                _ => {}
             }
+        }));
+    }
 
-            for component in self.components.iter_mut() {
-                if let Some(action) = component.update(action.clone())? {
-                    self.action_tx.send(action)?
-                };
+    /// Favorite configurations grouped by the client of the first server listing them, the main
+    /// one for those that aren't listed
+    fn favorite_servers(&self) -> Vec<(TeamCityClient, Vec<String>)> {
+        let mut servers: Vec<(Option<String>, Vec<String>)> = Vec::new();
+        for favorite in &self.config.favorites {
+            let server = self
+                .build_types
+                .iter()
+                .find(|build_type| &build_type.id == favorite)
+                .and_then(|build_type| build_type.server.clone());
+            match servers.iter_mut().find(|(name, _)| *name == server) {
+                Some((_, favorites)) => favorites.push(favorite.clone()),
+                None => servers.push((server, vec![favorite.clone()])),
             }
         }
+        servers
+            .into_iter()
+            .map(|(server, favorites)| (self.server_client(server.as_deref()), favorites))
+            .collect()
+    }
+
+    fn apply_filter(&mut self, tui: &mut Tui, name: Option<String>) -> Result<()> {
+        let message = match &name {
+            Some(name) => format!("Filter: {}", name),
+            None => "Filter cleared".to_string(),
+        };
+        self.config.active_filter = name;
+        for tab in self.tabs.iter_mut() {
+            tab.component.register_config_handler(self.config.clone())?;
+        }
+        self.action_tx.send(Action::Toast(message))?;
+        self.render(tui)
+    }
+
+    /// Replaces the view of the current tab with `component` and renders it, aborting loads of the
+    /// previous view so their results don't replace this one later
+    fn switch_to(&mut self, tui: &mut Tui, mut component: Box<dyn Component>) -> Result<()> {
+        component.register_action_handler(self.action_tx.clone())?;
+        component.register_config_handler(self.config.clone())?;
+        component.init(tui.size()?)?;
+        let tab = self.tab();
+        tab.load.cancel();
+        tab.load = CancellationToken::new();
+        tab.component = component;
+        self.render(tui)
+    }
+
+    fn copy_to_clipboard(&mut self, text: &str) -> Result<()> {
+        let clipboard = match &mut self.clipboard {
+            Some(clipboard) => clipboard,
+            None => self.clipboard.insert(arboard::Clipboard::new()?),
+        };
+        clipboard.set_text(text)?;
         Ok(())
     }
 
@@ -260,14 +2693,222 @@ impl App {
 
     fn render(&mut self, tui: &mut Tui) -> Result<()> {
         tui.draw(|frame| {
-            for component in self.components.iter_mut() {
-                if let Err(err) = component.draw(frame, frame.area()) {
-                    let _ = self
-                        .action_tx
-                        .send(Action::Error(format!("Failed to draw: {:?}", err)));
+            let mut area = frame.area();
+            if self.status_bar.is_visible() {
+                let [view_area, status_area] =
+                    Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(area);
+                area = view_area;
+                if let Err(err) = self.status_bar.draw(frame, status_area) {
+                    error!("Failed to draw status bar: {:?}", err);
                 }
             }
+            if self.tabs.len() > 1 {
+                let [tabs_area, view_area] =
+                    Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(area);
+                area = view_area;
+                let titles = self.tabs.iter().map(|tab| tab.component.tab_title());
+                frame.render_widget(
+                    Tabs::new(titles)
+                        .select(self.active_tab)
+                        .highlight_style(Style::default().fg(Color::Yellow).bold()),
+                    tabs_area,
+                );
+            }
+            if let Err(err) = self.tabs[self.active_tab].component.draw(frame, area) {
+                let _ = self
+                    .action_tx
+                    .send(Action::Error(format!("Failed to draw: {:?}", err)));
+            }
+            if let Err(err) = self.log_panel.draw(frame, frame.area()) {
+                error!("Failed to draw log panel: {:?}", err);
+            }
+            if let Err(err) = self.command_palette.draw(frame, frame.area()) {
+                error!("Failed to draw command palette: {:?}", err);
+            }
+            if let Err(err) = self.recent_picker.draw(frame, frame.area()) {
+                error!("Failed to draw recent configurations: {:?}", err);
+            }
+            if let Err(err) = self.reauth.draw(frame, frame.area()) {
+                error!("Failed to draw token prompt: {:?}", err);
+            }
+            if let Err(err) = self.spinner.draw(frame, frame.area()) {
+                error!("Failed to draw spinner: {:?}", err);
+            }
+            if let Err(err) = self.toast.draw(frame, frame.area()) {
+                error!("Failed to draw toast: {:?}", err);
+            }
         })?;
         Ok(())
     }
 }
+
+/// Build configurations of the projects of every server, fetched from all servers at once, with
+/// the number of projects requested; None if no server could be reached
+async fn fetch_projects(
+    servers: Vec<(Option<String>, TeamCityClient, Vec<String>)>,
+    bypass_cache: bool,
+    tx: &mpsc::UnboundedSender<Action>,
+) -> Option<(ProjectsBuildTypes, usize)> {
+    let fetched = join_all(servers.into_iter().map(|(name, client, project_ids)| {
+        let tx = tx.clone();
+        async move {
+            if bypass_cache && let Err(e) = client.invalidate_projects(&project_ids).await {
+                let _ = tx.send(Action::Error(format!("Failed to invalidate cache: {}", e)));
+            }
+            let fetched = client
+                .get_build_configurations_by_projects(&project_ids)
+                .await;
+            (name, project_ids.len(), fetched)
+        }
+    }))
+    .await;
+
+    let mut all = ProjectsBuildTypes::default();
+    let mut requested = 0;
+    let mut any_fetched = false;
+    for (name, count, fetched) in fetched {
+        match fetched {
+            Ok(mut fetched) => {
+                if let Some(name) = &name {
+                    for build_type in &mut fetched.build_types {
+                        build_type.server = Some(name.clone());
+                    }
+                    for failure in &mut fetched.failed {
+                        failure.project_id = format!("{}/{}", name, failure.project_id);
+                    }
+                }
+                all.build_types.append(&mut fetched.build_types);
+                all.failed.append(&mut fetched.failed);
+                requested += count;
+                any_fetched = true;
+            }
+            Err(e) => {
+                let error_msg = match name {
+                    Some(name) => format!("Failed to fetch projects of {}: {}", name, e),
+                    None => format!("Failed to fetch projects: {}", e),
+                };
+                let _ = tx.send(Action::Error(error_msg));
+            }
+        }
+    }
+    any_fetched.then_some((all, requested))
+}
+
+/// Latest builds of `favorites`, with the usual duration of each configuration added to
+/// `baselines` when it first has a running build
+async fn poll_favorites(
+    client: &TeamCityClient,
+    favorites: &[String],
+    baselines: &mut BTreeMap<String, i64>,
+) -> teamcity::Result<Vec<Build>> {
+    let builds = client.get_latest_builds(favorites).await?;
+    for build in builds
+        .iter()
+        .filter(|b| b.state.as_deref() == Some("running"))
+    {
+        let Some(build_type_id) = &build.build_type_id else {
+            continue;
+        };
+        if baselines.contains_key(build_type_id) {
+            continue;
+        }
+        match client
+            .get_finished_builds(build_type_id, &BranchFilter::Default, BASELINE_BUILD_COUNT)
+            .await
+        {
+            Ok(finished) => {
+                if let Some(baseline) = duration_baseline(&finished) {
+                    baselines.insert(build_type_id.clone(), baseline);
+                }
+            }
+            Err(e) => warn!("Failed to fetch build history of {}: {}", build_type_id, e),
+        }
+    }
+    Ok(builds)
+}
+
+/// Queued builds of the configurations whose latest build among `latest` is queued that have
+/// waited longer than `threshold`, with their likely causes. Each build is returned once and kept
+/// in `reported` until it leaves the queue. The queue is fetched once for all configurations.
+async fn stuck_builds(
+    client: &TeamCityClient,
+    latest: &[Build],
+    threshold: time::Duration,
+    reported: &mut HashSet<i64>,
+) -> Vec<(Build, Vec<String>)> {
+    let queued: Vec<&str> = latest
+        .iter()
+        .filter(|build| is_queued(build))
+        .filter_map(|build| build.build_type_id.as_deref())
+        .collect();
+    if queued.is_empty() && reported.is_empty() {
+        return Vec::new();
+    }
+    // the latest builds don't tell how long they have been queued, the queue does
+    let queue = match client.get_build_queue().await {
+        Ok(queue) => queue,
+        Err(e) => {
+            warn!("Failed to fetch the build queue: {}", e);
+            return Vec::new();
+        }
+    };
+    forget_dequeued(reported, &queue);
+
+    let now = OffsetDateTime::now_utc();
+    let mut stuck = Vec::new();
+    for build_type_id in queued {
+        let waiting = newly_stuck(&queue, build_type_id, threshold, now, reported);
+        if waiting.is_empty() {
+            continue;
+        }
+        let compatibility = client
+            .get_agent_compatibility(build_type_id)
+            .await
+            .inspect_err(|e| warn!("Failed to fetch the agents of {}: {}", build_type_id, e))
+            .ok();
+        for build in waiting {
+            reported.extend(build.id);
+            let causes = stuck_causes(&build, compatibility.as_ref());
+            stuck.push((build, causes));
+        }
+    }
+    stuck
+}
+
+fn bulk_failure(build: &Build, error: &TeamCityError) -> String {
+    format!(
+        "#{}: {}",
+        build.build_number.as_deref().unwrap_or_default(),
+        error
+    )
+}
+
+/// Toast summarizing a bulk operation, or an error listing the builds it failed for
+fn bulk_result(verb: &str, total: usize, failed: Vec<String>) -> Action {
+    if failed.is_empty() {
+        Action::Toast(format!("{} {} builds", verb, total))
+    } else {
+        Action::Error(format!(
+            "{} {} of {} builds, failed: {}",
+            verb,
+            total - failed.len(),
+            total,
+            failed.join(", ")
+        ))
+    }
+}
+
+/// Configuration an action opened from the Projects view is about, whose server handles it
+fn configuration_of(action: &Action) -> Option<&str> {
+    match action {
+        Action::LoadBuilds { project_id, .. } => Some(project_id),
+        Action::LoadBranches { build_type_id }
+        | Action::TriggerBuild { build_type_id, .. }
+        | Action::TriggerBuildOnChange { build_type_id, .. }
+        | Action::LoadAgentCompatibility { build_type_id, .. }
+        | Action::LoadParameters { build_type_id, .. }
+        | Action::SetPaused { build_type_id, .. } => Some(build_type_id),
+        Action::OpenSettingsFile { build_type } => Some(&build_type.id),
+        _ => None,
+    }
+}
@@ -1,11 +1,20 @@
-use crate::config::{get_config_dir, get_data_dir};
-use clap::Parser;
+use crate::components::setup_wizard::SetupWizard;
+use crate::config::{get_cache_dir, get_config_dir, get_data_dir};
+use crate::export::{self, ExportFormat};
+use crate::setup;
+use crate::utils::format_size;
+use crate::view::builds::{EXPORT_COLUMNS, export_row};
+use clap::{CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
 use color_eyre::Result;
 use color_eyre::eyre::eyre;
 use serde::{Deserialize, Serialize};
 use std::fs::{File, create_dir_all, read_to_string};
-use std::io;
 use std::io::Write;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use teamcity::{Auth, TeamCityClient, cache_stats, clear_cache, prune_cache};
+use time::OffsetDateTime;
 
 #[derive(Parser, Debug, Serialize, Deserialize)]
 #[command(author, version = version(), about)]
@@ -18,12 +27,221 @@ pub struct Cli {
     #[arg(long, env = "T9S_TEAMCITY_TOKEN")]
     pub token: Option<String>,
 
+    /// Username for basic authentication, used when no token is set
+    #[arg(long, env = "T9S_TEAMCITY_USERNAME", requires = "password")]
+    pub username: Option<String>,
+
+    /// Password for basic authentication
+    #[arg(long, env = "T9S_TEAMCITY_PASSWORD", requires = "username")]
+    pub password: Option<String>,
+
+    /// Connect anonymously through the guest account
+    #[arg(long, env = "T9S_TEAMCITY_GUEST")]
+    #[serde(default)]
+    pub guest: bool,
+
     /// List of projects to monitor
     #[arg(short, long, env = "T9S_TEAMCITY_PROJECTS", value_delimiter = ',')]
     pub projects: Option<Vec<String>>,
+
+    /// Directory of the cache files, overrides `cache.dir` of the configuration
+    #[arg(long, env = "T9S_CACHE_DIR")]
+    #[serde(skip)]
+    pub cache_dir: Option<PathBuf>,
+
+    /// Start on the Projects view instead of reopening the view of the last run
+    #[arg(long)]
+    #[serde(skip)]
+    pub fresh: bool,
+
+    #[command(subcommand)]
+    #[serde(skip)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// Manage the monitored projects
+    Projects {
+        #[command(subcommand)]
+        action: ProjectsCommand,
+    },
+    /// List the builds matching a raw build locator of the TeamCity REST API
+    Builds {
+        /// Passed as is to `/app/rest/builds`, e.g. `buildType:App_Build,status:FAILURE,count:10`
+        #[arg(long)]
+        locator: String,
+        /// Print JSON instead of CSV
+        #[arg(long, conflicts_with = "tui")]
+        json: bool,
+        /// Open the builds in the TUI instead of printing them
+        #[arg(long)]
+        tui: bool,
+    },
+    /// Serve the state of the monitored configurations, the build queue and the agents as
+    /// Prometheus metrics, without the TUI
+    ExportMetrics {
+        /// Address to serve `/metrics` on
+        #[arg(long, default_value = "127.0.0.1:9464")]
+        listen: SocketAddr,
+        /// Seconds between two polls of the server, scrapes get the result of the last one
+        #[arg(long, default_value_t = 60, value_parser = clap::value_parser!(u64).range(1..))]
+        interval: u64,
+    },
+    /// Print a line for each build of the watched configurations that is queued, starts or
+    /// finishes, without the TUI
+    Watch {
+        /// ID of a configuration to watch, repeatable; the configurations of the monitored
+        /// projects otherwise
+        #[arg(long = "build-type", value_name = "ID")]
+        build_types: Vec<String>,
+        /// Print newline-delimited JSON events instead of text
+        #[arg(long)]
+        json: bool,
+        /// Seconds between two polls of the server
+        #[arg(long, default_value_t = 30, value_parser = clap::value_parser!(u64).range(1..))]
+        interval: u64,
+    },
+    /// Inspect and clean up the cache of build configurations
+    Cache {
+        #[command(subcommand)]
+        action: CacheCommand,
+    },
+    /// Print the completion script of a shell, e.g. `t9s completions zsh > ~/.zfunc/_t9s`
+    Completions { shell: Shell },
+    /// Print the man page, or write the pages of t9s and each subcommand to a directory
+    Man {
+        /// Directory to write `t9s.1` and pages like `t9s-watch.1` to
+        #[arg(long)]
+        dir: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone, PartialEq, Eq)]
+pub enum ProjectsCommand {
+    /// Pick the monitored projects from the ones on the server
+    Edit,
+}
+
+#[derive(Subcommand, Debug, Clone, PartialEq, Eq)]
+pub enum CacheCommand {
+    /// Show the size and entries of the cache of each server
+    Stats,
+    /// Remove the cache of all servers
+    Clear,
+    /// Drop expired entries and shrink each cache to the configured maximum size
+    Prune,
+}
+
+impl CacheCommand {
+    pub async fn run(&self, dir: &Path, max_size: Option<u64>) -> Result<()> {
+        let stats = match self {
+            CacheCommand::Stats => cache_stats(dir).await?,
+            CacheCommand::Clear => {
+                let removed = clear_cache(dir).await?;
+                println!("Removed {} cache files from {}", removed, dir.display());
+                return Ok(());
+            }
+            CacheCommand::Prune => prune_cache(dir, max_size.unwrap_or(u64::MAX)).await?,
+        };
+
+        if stats.is_empty() {
+            println!("No cache files in {}", dir.display());
+        }
+        for file in &stats {
+            println!(
+                "{}: {}, {} projects ({} expired)",
+                file.path.display(),
+                format_size(file.size),
+                file.entries,
+                file.expired
+            );
+        }
+        let total: u64 = stats.iter().map(|file| file.size).sum();
+        println!("{}", cache_total(total, max_size));
+        Ok(())
+    }
+}
+
+/// Summary line of `t9s cache` with the `total` size of the cache files and the maximum size of
+/// each, None if unlimited
+fn cache_total(total: u64, max_size: Option<u64>) -> String {
+    match max_size {
+        Some(max_size) => format!(
+            "Total: {} of at most {} per server",
+            format_size(total),
+            format_size(max_size)
+        ),
+        None => format!("Total: {}, no limit per server", format_size(total)),
+    }
+}
+
+/// Prints the builds matching `locator` as CSV or JSON, with the configuration of each build
+pub async fn print_builds(client: &TeamCityClient, locator: &str, json: bool) -> Result<()> {
+    let builds = client.get_builds_by_raw_locator(locator).await?;
+    let now = OffsetDateTime::now_utc();
+    let headers: Vec<&str> = std::iter::once("build_type_id")
+        .chain(EXPORT_COLUMNS)
+        .collect();
+    let rows: Vec<Vec<String>> = builds
+        .iter()
+        .map(|build| {
+            std::iter::once(build.build_type_id.clone().unwrap_or_default())
+                .chain(export_row(build, now))
+                .collect()
+        })
+        .collect();
+    let format = if json {
+        ExportFormat::Json
+    } else {
+        ExportFormat::Csv
+    };
+    print!("{}", export::render(format, &headers, &rows)?);
+    Ok(())
+}
+
+/// Writes the completion script of `shell` to `out`
+pub fn write_completions(shell: Shell, out: &mut dyn Write) -> Result<()> {
+    let mut command = Cli::command();
+    let name = command.get_name().to_string();
+    // generated in memory, the generator panics on write errors like a closed pipe
+    let mut script = Vec::new();
+    clap_complete::generate(shell, &mut command, name, &mut script);
+    out.write_all(&script)?;
+    Ok(())
+}
+
+/// Writes the man page of t9s to `out`, or the pages of t9s and all its subcommands to `dir`
+pub fn write_man_pages(dir: Option<&Path>, out: &mut dyn Write) -> Result<()> {
+    // the full version lists the directories of the machine generating the pages
+    let command = Cli::command().version(VERSION_MESSAGE);
+    match dir {
+        Some(dir) => {
+            create_dir_all(dir)?;
+            clap_mangen::generate_to(command, dir)?;
+        }
+        None => clap_mangen::Man::new(command).render(out)?,
+    }
+    Ok(())
 }
 
 impl Cli {
+    /// Picks the auth mode from the configured credentials: token, then username/password, then guest
+    pub fn auth(&self) -> Option<Auth> {
+        if let Some(token) = &self.token {
+            Some(Auth::Token(token.clone()))
+        } else if let (Some(username), Some(password)) = (&self.username, &self.password) {
+            Some(Auth::Basic {
+                username: username.clone(),
+                password: password.clone(),
+            })
+        } else if self.guest {
+            Some(Auth::Guest)
+        } else {
+            None
+        }
+    }
+
     pub fn load_cli_config() -> Result<Cli> {
         let cfg_dir = get_config_dir();
         let app_cfg = cfg_dir.join("config.toml");
@@ -33,8 +251,7 @@ impl Cli {
 
             let content = read_to_string(app_cfg)?;
 
-            toml::from_str::<Cli>(&content)
-                .map_err(|e| eyre!("Failed to parse config: {}", e))
+            toml::from_str::<Cli>(&content).map_err(|e| eyre!("Failed to parse config: {}", e))
         } else {
             Err(eyre!("{:?} config file does not exists", app_cfg))
         }
@@ -53,65 +270,19 @@ impl Cli {
         Ok(())
     }
 
-    pub fn init_config(projects: &Option<Vec<String>>) -> Result<Cli> {
-        println!(
-            "Initial setup: let's configure your TeamCity connection. Press Enter to accept defaults where shown."
-        );
-
-        let teamcity_url = Cli::prompt(
-            "TeamCity server URL",
-            Some("https://teamcity.example.com".to_string()),
-        );
-        let token = rpassword::prompt_password("Personal access token")?;
-        let projects_input: Option<String> = match projects.clone() {
-            Some(v) if !v.is_empty() => Some(v.join(",")),
-            _ => None,
-        };
-        let projects_str = Cli::prompt("Projects (comma-separated, optional)", projects_input);
-        let projects = {
-            let s = projects_str.trim();
-            if s.is_empty() {
-                None
-            } else {
-                let list: Vec<String> = s
-                    .split(',')
-                    .map(|p| p.trim().to_string())
-                    .filter(|p| !p.is_empty())
-                    .collect();
-                if list.is_empty() { None } else { Some(list) }
-            }
-        };
-
-        let args = Self {
-            teamcity_url: Some(teamcity_url),
-            token: Some(token),
-            projects,
-        };
-
+    /// Asks for the connection settings in the setup wizard and saves them
+    pub async fn init_config(projects: &Option<Vec<String>>) -> Result<Cli> {
+        let args = setup::run(SetupWizard::new(projects.clone())).await?;
         Cli::save_cli_config(&args)?;
-
         Ok(args)
     }
 
-    fn prompt(label: &str, default: Option<String>) -> String {
-        let mut stdout = io::stdout();
-
-        match default {
-            Some(ref val) => print!("{label} [{val}]: "),
-            None => print!("{label}: "),
-        }
-
-        stdout.flush().expect("Can't flush stdout");
-
-        let mut input = String::new();
-        io::stdin().read_line(&mut input).expect("read_line failed");
-        let trimmed = input.trim();
-
-        if trimmed.is_empty() {
-            default.unwrap_or_default()
-        } else {
-            trimmed.to_string()
-        }
+    /// Picks the monitored projects of the saved configuration in the setup wizard and saves them
+    pub async fn edit_projects() -> Result<Cli> {
+        let saved = Cli::load_cli_config()?;
+        let args = setup::run(SetupWizard::editing(&saved)).await?;
+        Cli::save_cli_config(&args)?;
+        Ok(args)
     }
 }
 
@@ -130,6 +301,7 @@ pub fn version() -> String {
     // let current_exe_path = PathBuf::from(clap::crate_name!()).display().to_string();
     let config_dir_path = get_config_dir().display().to_string();
     let data_dir_path = get_data_dir().display().to_string();
+    let cache_dir_path = get_cache_dir().display().to_string();
 
     format!(
         "\
@@ -138,6 +310,46 @@ pub fn version() -> String {
 Authors: {author}
 
 Config directory: {config_dir_path}
-Data directory: {data_dir_path}"
+Data directory: {data_dir_path}
+Cache directory: {cache_dir_path}"
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn completions_and_man_page_cover_the_subcommands() {
+        Cli::command().debug_assert();
+
+        let mut completions = Vec::new();
+        write_completions(Shell::Bash, &mut completions).unwrap();
+        let completions = String::from_utf8(completions).unwrap();
+        assert!(completions.contains("export-metrics"));
+        assert!(completions.contains("--build-type"));
+
+        let mut page = Vec::new();
+        write_man_pages(None, &mut page).unwrap();
+        let page = String::from_utf8(page).unwrap();
+        assert!(page.starts_with(".ie"), "{}", page);
+        assert!(page.contains("watch"));
+        assert!(!page.contains("Config directory"));
+    }
+
+    #[test]
+    fn cache_total_tells_when_there_is_no_limit() {
+        assert_eq!(
+            cache_total(2048, Some(10 * 1024 * 1024)),
+            format!(
+                "Total: {} of at most {} per server",
+                format_size(2048),
+                format_size(10 * 1024 * 1024)
+            )
+        );
+        assert_eq!(
+            cache_total(2048, None),
+            format!("Total: {}, no limit per server", format_size(2048))
+        );
+    }
+}
@@ -6,12 +6,42 @@ use ratatui::{
 };
 use tokio::sync::mpsc::UnboundedSender;
 
-use crate::{action::Action, config::Config, tui::Event};
+use crate::{action::Action, config::Config, session::Session, tui::Event};
 
-pub mod fps;
-pub mod home;
-pub mod projects;
+pub mod about_server;
+pub mod agent_pools;
+pub mod build_chain;
+pub mod build_log;
 pub mod builds;
+pub mod change_builds;
+pub mod command_palette;
+pub mod compatible_agents;
+pub mod context_menu;
+pub mod dashboard;
+pub mod date_range_picker;
+pub mod empty_state;
+pub mod filter_picker;
+pub mod freshness;
+pub mod locator_builds;
+pub mod log_grep;
+pub mod log_panel;
+pub mod mutes;
+pub mod parameters;
+pub mod pending_changes;
+pub mod project_picker;
+pub mod projects;
+pub mod prompt;
+pub mod reauth;
+pub mod recent_picker;
+pub mod selectable_table;
+pub mod setup_wizard;
+pub mod spinner;
+pub mod statistics;
+pub mod status_bar;
+pub mod test_failures;
+pub mod test_history;
+pub mod timeline;
+pub mod toast;
 
 /// `Component` is a trait that represents a visual and interactive element of the user interface.
 ///
@@ -100,6 +130,30 @@ pub trait Component {
         let _ = mouse; // to appease clippy
         Ok(None)
     }
+    /// Title of the tab showing the component.
+    ///
+    /// # Returns
+    ///
+    /// * `String` - A short title for the tab bar.
+    fn tab_title(&self) -> String {
+        "t9s".to_string()
+    }
+    /// Whether the component is taking text input, so the keybindings of the app don't apply.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True while one of the component's prompts or filters is open.
+    fn is_typing(&self) -> bool {
+        false
+    }
+    /// Where the component is, saved on quit so the next launch can reopen it.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Session>` - The view to reopen, none if the component can't be reopened.
+    fn session(&self) -> Option<Session> {
+        None
+    }
     /// Update the state of the component based on a received action. (REQUIRED)
     ///
     /// # Arguments
@@ -0,0 +1,225 @@
+use super::Component;
+use super::freshness::freshness_line;
+use crate::action::Action;
+use crate::config::Config;
+use crate::time::{TimeDisplay, format_datetime};
+use crate::view::server_info::{PoolSummary, format_uptime, pool_summaries};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Padding, Paragraph, Row, Table};
+use teamcity::ServerOverview;
+use time::{Duration, OffsetDateTime};
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Version, uptime, license, agent pools and build queue of the server, for admins keeping an eye
+/// on the instance
+pub struct AboutServer {
+    // None while loading
+    overview: Option<ServerOverview>,
+    pools: Vec<PoolSummary>,
+    fetched_at: Option<OffsetDateTime>,
+    stale_after: Duration,
+    time_display: TimeDisplay,
+    pub action_tx: Option<UnboundedSender<Action>>,
+}
+
+impl AboutServer {
+    pub fn new(overview: Option<ServerOverview>) -> Self {
+        Self {
+            pools: overview
+                .as_ref()
+                .map(|overview| pool_summaries(&overview.agents))
+                .unwrap_or_default(),
+            fetched_at: overview.is_some().then(OffsetDateTime::now_utc),
+            overview,
+            stale_after: Duration::ZERO,
+            time_display: TimeDisplay::default(),
+            action_tx: None,
+        }
+    }
+
+    fn field<'a>(name: &'a str, value: String) -> Line<'a> {
+        Line::from(vec![
+            Span::styled(format!("{:<14}", name), Style::default().fg(Color::Yellow)),
+            Span::raw(value),
+        ])
+    }
+
+    fn summary_lines(&self) -> Vec<Line<'_>> {
+        let Some(overview) = &self.overview else {
+            return vec![Line::from("Loading server info…")];
+        };
+        let info = &overview.info;
+
+        let version = match (&info.version, &info.build_number) {
+            (Some(version), Some(build)) => format!("{} (build {})", version, build),
+            (Some(version), None) => version.clone(),
+            _ => "unknown".to_string(),
+        };
+        let uptime = match (&info.start_time, &info.current_time) {
+            (Some(start), Some(now)) => format!(
+                "{}, started {}",
+                format_uptime(start, now),
+                format_datetime(start, TimeDisplay::Absolute).unwrap_or_default()
+            ),
+            _ => "unknown".to_string(),
+        };
+        let server_time = info
+            .current_time
+            .and_then(|now| format_datetime(&now, self.time_display).ok())
+            .unwrap_or_else(|| "unknown".to_string());
+        let license = match &overview.licensing {
+            Some(licensing) => {
+                let agents = if licensing.unlimited_agents == Some(true) {
+                    "unlimited agents".to_string()
+                } else {
+                    format!(
+                        "{} agents, {} left",
+                        licensing.max_agents.unwrap_or_default(),
+                        licensing.agents_left.unwrap_or_default()
+                    )
+                };
+                format!(
+                    "{}, {}",
+                    licensing
+                        .server_license_type
+                        .as_deref()
+                        .unwrap_or("unknown type"),
+                    agents
+                )
+            }
+            None => "not readable without administrator rights".to_string(),
+        };
+        let connected: usize = self.pools.iter().map(|pool| pool.connected).sum();
+        let busy: usize = self.pools.iter().map(|pool| pool.busy).sum();
+
+        vec![
+            Self::field("Version", version),
+            Self::field("Uptime", uptime),
+            Self::field("Server time", server_time),
+            Self::field("License", license),
+            Self::field(
+                "Agents",
+                format!(
+                    "{} connected of {}, {} running a build",
+                    connected,
+                    overview.agents.len(),
+                    busy
+                ),
+            ),
+            Self::field("Build queue", format!("{} builds", overview.queue_length)),
+        ]
+    }
+}
+
+impl Component for AboutServer {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> color_eyre::Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> color_eyre::Result<()> {
+        self.time_display = config.time_display;
+        self.stale_after = config.stale_after();
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>> {
+        let action = match key.code {
+            KeyCode::Char('r') => Action::LoadServerOverview,
+            KeyCode::Char('p') => Action::LoadAgentPools,
+            KeyCode::Esc | KeyCode::Char('h') => Action::ShowProjects,
+            _ => Action::Render,
+        };
+        Ok(Some(action))
+    }
+
+    fn tab_title(&self) -> String {
+        "About server".to_string()
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> color_eyre::Result<()> {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(8), // Summary height
+                Constraint::Min(0),    // Pools take remaining space
+                Constraint::Length(2), // Footer height
+            ])
+            .split(area);
+
+        let summary = Paragraph::new(self.summary_lines()).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("About server")
+                .title_top(freshness_line(self.fetched_at, self.stale_after)),
+        );
+        frame.render_widget(summary, chunks[0]);
+
+        let header = Row::new(vec![
+            "Pool",
+            "Agents",
+            "Connected",
+            "Busy",
+            "Idle",
+            "Disabled",
+            "Unauthorized",
+        ])
+        .style(
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )
+        .height(1)
+        .bottom_margin(1);
+
+        let rows: Vec<Row> = self
+            .pools
+            .iter()
+            .map(|pool| {
+                // a pool with agents but none of them connected can't run its builds
+                let style = if pool.agents > 0 && pool.connected == 0 {
+                    Style::default().fg(Color::Red)
+                } else {
+                    Style::default()
+                };
+                Row::new(vec![
+                    pool.name.clone(),
+                    pool.agents.to_string(),
+                    pool.connected.to_string(),
+                    pool.busy.to_string(),
+                    pool.idle().to_string(),
+                    pool.disabled.to_string(),
+                    pool.unauthorized.to_string(),
+                ])
+                .style(style)
+            })
+            .collect();
+
+        let table = Table::new(
+            rows,
+            &[
+                Constraint::Min(20),    // Pool
+                Constraint::Length(8),  // Agents
+                Constraint::Length(10), // Connected
+                Constraint::Length(6),  // Busy
+                Constraint::Length(6),  // Idle
+                Constraint::Length(9),  // Disabled
+                Constraint::Length(13), // Unauthorized
+            ],
+        )
+        .header(header)
+        .block(Block::default().borders(Borders::ALL).title("Agent pools"))
+        .column_spacing(1);
+        frame.render_widget(table, chunks[1]);
+
+        let footer = Paragraph::new("p: Agent pools  r: Refresh  h/Esc: Back")
+            .style(Style::default().fg(Color::DarkGray))
+            .block(Block::default().padding(Padding::horizontal(1)));
+        frame.render_widget(footer, chunks[2]);
+        Ok(())
+    }
+}
@@ -0,0 +1,327 @@
+use super::Component;
+use super::freshness::freshness_line;
+use super::prompt::render_confirm_popup;
+use super::selectable_table::SelectableTable;
+use crate::action::Action;
+use crate::config::Config;
+use crate::view::agent_pools::{
+    PoolRow, move_targets, pool_agents, pool_name, pool_projects, pool_rows,
+};
+use crate::view::server_info::agent_state;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::{Block, Borders, Clear, Padding, Paragraph, Row, Table, Wrap};
+use teamcity::types::{Agent, AgentPool};
+use time::{Duration, OffsetDateTime};
+use tokio::sync::mpsc::UnboundedSender;
+
+const PICKER_WIDTH: u16 = 44;
+
+/// Agent move waiting to be confirmed
+struct PendingMove {
+    prompt: String,
+    agent_id: i64,
+    agent: String,
+    pool_id: i64,
+    pool: String,
+}
+
+/// Agent pools with their agents and the projects assigned to them, where administrators move
+/// agents between pools
+pub struct AgentPools {
+    pools: Vec<AgentPool>,
+    rows: SelectableTable<PoolRow>,
+    // pools the selected agent can be moved to while picking one
+    targets: Option<SelectableTable<usize>>,
+    pending_move: Option<PendingMove>,
+    // None while loading
+    fetched_at: Option<OffsetDateTime>,
+    stale_after: Duration,
+    pub action_tx: Option<UnboundedSender<Action>>,
+}
+
+impl AgentPools {
+    pub fn new(pools: Option<Vec<AgentPool>>) -> Self {
+        let fetched_at = pools.is_some().then(OffsetDateTime::now_utc);
+        let pools = pools.unwrap_or_default();
+        Self {
+            rows: SelectableTable::new(pool_rows(&pools)),
+            pools,
+            targets: None,
+            pending_move: None,
+            fetched_at,
+            stale_after: Duration::ZERO,
+            action_tx: None,
+        }
+    }
+
+    fn selected_agent(&self) -> Option<(usize, &Agent)> {
+        match self.rows.selected()? {
+            PoolRow::Agent(pool, agent) => {
+                Some((*pool, pool_agents(&self.pools[*pool]).get(*agent)?))
+            }
+            PoolRow::Pool(_) => None,
+        }
+    }
+
+    fn start_move(&mut self) -> Action {
+        let Some((pool, _)) = self.selected_agent() else {
+            return Action::Toast("Select an agent to move it to another pool".to_string());
+        };
+        let targets = move_targets(&self.pools, pool);
+        if targets.is_empty() {
+            return Action::Toast("There is no other pool to move the agent to".to_string());
+        }
+        self.targets = Some(SelectableTable::new(targets));
+        Action::Render
+    }
+
+    fn handle_target_key(&mut self, key: KeyEvent) -> Action {
+        let Some(targets) = &mut self.targets else {
+            return Action::Render;
+        };
+        if targets.handle_navigation_key(&key) {
+            return Action::Render;
+        }
+        let target = match key.code {
+            KeyCode::Enter => targets.selected().copied(),
+            KeyCode::Esc | KeyCode::Char('m') => None,
+            _ => return Action::Render,
+        };
+        self.targets = None;
+        let (Some(target), Some((_, agent))) = (target, self.selected_agent()) else {
+            return Action::Render;
+        };
+        let (Some(agent_id), Some(pool_id)) = (agent.id, self.pools[target].id) else {
+            return Action::Error("No id was found for this agent or pool".to_string());
+        };
+        let agent = agent.name.clone().unwrap_or_default();
+        let pool = pool_name(&self.pools[target]).to_string();
+        self.pending_move = Some(PendingMove {
+            prompt: format!("Move {} to {}? y: Yes  n/Esc: No", agent, pool),
+            agent_id,
+            agent,
+            pool_id,
+            pool,
+        });
+        Action::Render
+    }
+
+    fn render_targets(&mut self, frame: &mut Frame, area: Rect) {
+        let Some(targets) = &mut self.targets else {
+            return;
+        };
+        let rows: Vec<Row> = targets
+            .items()
+            .iter()
+            .map(|&i| Row::new(vec![pool_name(&self.pools[i]).to_string()]))
+            .collect();
+
+        let popup_width = PICKER_WIDTH.min(area.width);
+        let popup_height = (rows.len() as u16 + 2).min(area.height);
+        let popup_area = Rect {
+            x: area.x + (area.width.saturating_sub(popup_width)) / 2,
+            y: area.y + (area.height.saturating_sub(popup_height)) / 2,
+            width: popup_width,
+            height: popup_height,
+        };
+        targets.set_viewport(popup_area);
+
+        let table = Table::new(rows, &[Constraint::Min(0)])
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Move to pool")
+                    .title_bottom("Enter: Move  Esc: Close")
+                    .border_style(Style::default().fg(Color::Cyan)),
+            )
+            .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+        frame.render_widget(Clear, popup_area);
+        frame.render_stateful_widget(table, popup_area, &mut targets.state);
+    }
+
+    fn render_confirm(&self, frame: &mut Frame, area: Rect) {
+        let Some(pending) = &self.pending_move else {
+            return;
+        };
+
+        render_confirm_popup(frame, area, &pending.prompt);
+    }
+
+    fn pool_row(pool: &AgentPool) -> Row<'_> {
+        let agents = pool_agents(pool);
+        let connected = agents
+            .iter()
+            .filter(|agent| agent.connected == Some(true))
+            .count();
+        // a pool with agents but none of them connected can't run its builds
+        let color = if !agents.is_empty() && connected == 0 {
+            Color::Red
+        } else {
+            Color::Cyan
+        };
+        Row::new(vec![
+            pool_name(pool).to_string(),
+            format!("{} agents, {} connected", agents.len(), connected),
+            String::new(),
+        ])
+        .style(Style::default().fg(color).add_modifier(Modifier::BOLD))
+    }
+
+    fn agent_row(agent: &Agent) -> Row<'_> {
+        let state = agent_state(agent);
+        let color = match state {
+            "idle" | "busy" => Color::Green,
+            _ => Color::DarkGray,
+        };
+        let build = agent
+            .build
+            .as_ref()
+            .map(|build| {
+                format!(
+                    "{} #{}",
+                    build.build_type_id.as_deref().unwrap_or_default(),
+                    build.build_number.as_deref().unwrap_or_default()
+                )
+            })
+            .unwrap_or_default();
+        Row::new(vec![
+            format!("  {}", agent.name.as_deref().unwrap_or_default()),
+            state.to_string(),
+            build,
+        ])
+        .style(Style::default().fg(color))
+    }
+}
+
+impl Component for AgentPools {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> color_eyre::Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> color_eyre::Result<()> {
+        self.stale_after = config.stale_after();
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>> {
+        if let Some(pending) = self.pending_move.take() {
+            let action = match key.code {
+                KeyCode::Char('y') => Action::MoveAgent {
+                    agent_id: pending.agent_id,
+                    agent: pending.agent,
+                    pool_id: pending.pool_id,
+                    pool: pending.pool,
+                },
+                _ => Action::Render,
+            };
+            return Ok(Some(action));
+        }
+        if self.targets.is_some() {
+            return Ok(Some(self.handle_target_key(key)));
+        }
+        if self.rows.handle_navigation_key(&key) {
+            return Ok(Some(Action::Render));
+        }
+        let action = match key.code {
+            KeyCode::Char('m') => self.start_move(),
+            KeyCode::Char('r') => Action::LoadAgentPools,
+            KeyCode::Esc | KeyCode::Char('h') => Action::ShowProjects,
+            _ => Action::Render,
+        };
+        Ok(Some(action))
+    }
+
+    fn tab_title(&self) -> String {
+        "Agent pools".to_string()
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> color_eyre::Result<()> {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(0),    // Pools take remaining space
+                Constraint::Length(5), // Projects of the selected pool
+                Constraint::Length(2), // Footer height
+            ])
+            .split(area);
+
+        let header = Row::new(vec!["Pool / Agent", "State", "Build"])
+            .style(
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .height(1)
+            .bottom_margin(1);
+
+        let rows: Vec<Row> = self
+            .rows
+            .items()
+            .iter()
+            .map(|row| match *row {
+                PoolRow::Pool(pool) => Self::pool_row(&self.pools[pool]),
+                PoolRow::Agent(pool, agent) => {
+                    Self::agent_row(&pool_agents(&self.pools[pool])[agent])
+                }
+            })
+            .collect();
+
+        let title = if self.fetched_at.is_none() {
+            "Agent pools — loading…".to_string()
+        } else {
+            format!("Agent pools — {}", self.pools.len())
+        };
+        let table = Table::new(
+            rows,
+            &[
+                Constraint::Min(30),    // Pool / Agent
+                Constraint::Length(24), // State
+                Constraint::Min(20),    // Build
+            ],
+        )
+        .header(header)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(title)
+                .title_top(freshness_line(self.fetched_at, self.stale_after)),
+        )
+        .column_spacing(1)
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol(">> ");
+
+        self.rows.set_viewport(chunks[0]);
+        frame.render_stateful_widget(table, chunks[0], &mut self.rows.state);
+
+        let selected_pool = self.rows.selected().map(|row| &self.pools[row.pool()]);
+        let (projects_title, projects) = match selected_pool {
+            Some(pool) => (
+                format!("Projects of {}", pool_name(pool)),
+                match pool_projects(pool) {
+                    projects if projects.is_empty() => "No project is assigned".to_string(),
+                    projects => projects.join(", "),
+                },
+            ),
+            None => ("Projects".to_string(), String::new()),
+        };
+        let projects = Paragraph::new(projects)
+            .wrap(Wrap { trim: true })
+            .block(Block::default().borders(Borders::ALL).title(projects_title));
+        frame.render_widget(projects, chunks[1]);
+
+        let footer =
+            Paragraph::new("j/k: Move  m: Move agent to another pool  r: Refresh  h/Esc: Back")
+                .style(Style::default().fg(Color::DarkGray))
+                .block(Block::default().padding(Padding::horizontal(1)));
+        frame.render_widget(footer, chunks[2]);
+
+        self.render_targets(frame, area);
+        self.render_confirm(frame, area);
+        Ok(())
+    }
+}
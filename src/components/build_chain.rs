@@ -0,0 +1,318 @@
+use super::Component;
+use super::freshness::freshness_line;
+use super::prompt::render_confirm_popup;
+use super::selectable_table::SelectableTable;
+use crate::action::Action;
+use crate::config::Config;
+use crate::icons::IconSet;
+use crate::time::format_duration;
+use crate::view::builds::{duration_secs, is_failed, status_text};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::{Block, Borders, Padding, Paragraph, Row, Table};
+use std::collections::HashMap;
+use teamcity::DependencyChain;
+use teamcity::types::{Build, BuildType};
+use time::{Duration, OffsetDateTime};
+use tokio::sync::mpsc::UnboundedSender;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Section {
+    Upstream,
+    Selected,
+    Downstream,
+}
+
+impl Section {
+    fn label(self, icons: IconSet) -> String {
+        match self {
+            Section::Upstream => format!("{} depends on", icons.arrow_up()),
+            Section::Selected => format!("{} this build", icons.mark()),
+            Section::Downstream => format!("{} depended on by", icons.arrow_down()),
+        }
+    }
+}
+
+/// Snapshot dependency chain of a build, with the builds it depends on above it and the builds
+/// depending on it below
+pub struct BuildChain {
+    // configuration the chain was opened from, to go back to
+    build_type_id: String,
+    title: String,
+    build: Build,
+    rows: SelectableTable<(Section, Build)>,
+    // None while loading
+    fetched_at: Option<OffsetDateTime>,
+    stale_after: Duration,
+    icons: IconSet,
+    build_type_names: HashMap<String, String>,
+    // rebuild waiting for confirmation, with its prompt
+    pending_rebuild: Option<(String, Action)>,
+    pub action_tx: Option<UnboundedSender<Action>>,
+}
+
+impl BuildChain {
+    pub fn new(
+        build_type_id: String,
+        title: String,
+        build: Build,
+        chain: Option<DependencyChain>,
+        build_types: &[BuildType],
+    ) -> Self {
+        let rows = chain.map(|chain| {
+            let mut upstream = chain.upstream;
+            let mut downstream = chain.downstream;
+            // roughly the order the chain runs in
+            upstream.sort_by(|a, b| a.start_date.cmp(&b.start_date));
+            downstream.sort_by(|a, b| a.start_date.cmp(&b.start_date));
+
+            upstream
+                .into_iter()
+                .map(|b| (Section::Upstream, b))
+                .chain(std::iter::once((Section::Selected, build.clone())))
+                .chain(downstream.into_iter().map(|b| (Section::Downstream, b)))
+                .collect()
+        });
+        let fetched_at = rows.is_some().then(OffsetDateTime::now_utc);
+        let mut rows = SelectableTable::new(rows.unwrap_or_default());
+        // start on the build the chain was opened for
+        rows.select_where(|(section, _)| *section == Section::Selected);
+
+        Self {
+            build_type_id,
+            title,
+            build,
+            fetched_at,
+            rows,
+            stale_after: Duration::ZERO,
+            icons: IconSet::default(),
+            build_type_names: build_types
+                .iter()
+                .map(|bt| (bt.id.clone(), bt.name.clone()))
+                .collect(),
+            pending_rebuild: None,
+            action_tx: None,
+        }
+    }
+
+    fn get_selected_build(&self) -> Option<&Build> {
+        self.rows.selected().map(|(_, build)| build)
+    }
+
+    fn confirm_rebuild(&mut self, all_dependencies: bool) -> Action {
+        let Some(build) = self.get_selected_build() else {
+            return Action::Error("No build was selected".to_string());
+        };
+        let what = if all_dependencies {
+            "the whole chain"
+        } else {
+            "its failed dependencies"
+        };
+        let prompt = format!(
+            "Queue #{} again rebuilding {}? y: Yes  n/Esc: No",
+            build.build_number.as_deref().unwrap_or_default(),
+            what
+        );
+        let action = Action::RebuildChain {
+            build: build.clone(),
+            all_dependencies,
+        };
+        self.pending_rebuild = Some((prompt, action));
+        Action::Render
+    }
+
+    fn handle_confirm_key(&mut self, key: KeyEvent) -> Action {
+        match key.code {
+            KeyCode::Char('y') => self
+                .pending_rebuild
+                .take()
+                .map_or(Action::Render, |(_, action)| action),
+            KeyCode::Char('n') | KeyCode::Esc => {
+                self.pending_rebuild = None;
+                Action::Render
+            }
+            _ => Action::Render,
+        }
+    }
+
+    fn render_confirm(&self, frame: &mut Frame, area: Rect) {
+        let Some((prompt, _)) = &self.pending_rebuild else {
+            return;
+        };
+
+        render_confirm_popup(frame, area, prompt);
+    }
+
+    fn configuration_name(&self, build: &Build) -> String {
+        let id = build.build_type_id.as_deref().unwrap_or_default();
+        self.build_type_names
+            .get(id)
+            .cloned()
+            .unwrap_or_else(|| id.to_string())
+    }
+}
+
+impl Component for BuildChain {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> color_eyre::Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> color_eyre::Result<()> {
+        self.stale_after = config.stale_after();
+        self.icons = config.icons;
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>> {
+        if self.pending_rebuild.is_some() {
+            return Ok(Some(self.handle_confirm_key(key)));
+        }
+        if self.rows.handle_navigation_key(&key) {
+            return Ok(Some(Action::Render));
+        }
+        let action = match key.code {
+            KeyCode::Char('o') => {
+                if let Some(url) = self.get_selected_build().and_then(|b| b.web_url.as_ref()) {
+                    let _ = open::that(url);
+                }
+                Action::Render
+            }
+            KeyCode::Char('l') => match self.get_selected_build().and_then(|b| b.id) {
+                Some(build_id) => Action::LoadBuildLog { build_id },
+                None => Action::Error("No build was selected".to_string()),
+            },
+            // follow the chain from another build
+            KeyCode::Enter => match self.get_selected_build() {
+                Some(build) if build.id != self.build.id => Action::LoadBuildChain {
+                    project_id: self.build_type_id.clone(),
+                    title: self.title.clone(),
+                    build: build.clone(),
+                },
+                _ => Action::Render,
+            },
+            // successful dependencies are reused, failed ones run again
+            KeyCode::Char('R') => self.confirm_rebuild(false),
+            KeyCode::Char('A') => self.confirm_rebuild(true),
+            KeyCode::Char('r') => Action::LoadBuildChain {
+                project_id: self.build_type_id.clone(),
+                title: self.title.clone(),
+                build: self.build.clone(),
+            },
+            KeyCode::Esc | KeyCode::Char('h') => Action::LoadBuilds {
+                project_id: self.build_type_id.clone(),
+                title: self.title.clone(),
+            },
+            _ => Action::Render,
+        };
+        Ok(Some(action))
+    }
+
+    fn tab_title(&self) -> String {
+        format!("Chain — {}", self.title)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> color_eyre::Result<()> {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(0),    // Table takes remaining space
+                Constraint::Length(2), // Footer height
+            ])
+            .split(area);
+
+        let header = Row::new(vec![
+            "",
+            "Configuration",
+            "Number",
+            "Branch",
+            "Status",
+            "Duration",
+        ])
+        .style(
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )
+        .height(1)
+        .bottom_margin(1);
+
+        let now = OffsetDateTime::now_utc();
+        let rows: Vec<Row> = match self.rows.items() {
+            _ if self.fetched_at.is_none() => vec![Row::new(vec!["", "Loading build chain…"])],
+            rows => rows
+                .iter()
+                .map(|(section, build)| {
+                    let duration = duration_secs(build, now)
+                        .and_then(|secs| format_duration(secs).ok())
+                        .unwrap_or_default();
+                    let row = Row::new(vec![
+                        section.label(self.icons),
+                        self.configuration_name(build),
+                        build.build_number.clone().unwrap_or_default(),
+                        build.branch_name.clone().unwrap_or_default(),
+                        status_text(build),
+                        duration,
+                    ]);
+
+                    let style = if is_failed(build) {
+                        Style::default().fg(Color::Red)
+                    } else if build.state.as_deref() == Some("running") {
+                        Style::default().fg(Color::Yellow)
+                    } else if build.state.as_deref() == Some("queued") {
+                        Style::default().fg(Color::DarkGray)
+                    } else {
+                        Style::default()
+                    };
+                    if *section == Section::Selected {
+                        row.style(style.add_modifier(Modifier::BOLD))
+                    } else {
+                        row.style(style)
+                    }
+                })
+                .collect(),
+        };
+
+        let table = Table::new(
+            rows,
+            &[
+                Constraint::Length(17), // Section
+                Constraint::Min(20),    // Configuration
+                Constraint::Max(20),    // Number
+                Constraint::Length(30), // Branch
+                Constraint::Min(20),    // Status text
+                Constraint::Length(9),  // Duration
+            ],
+        )
+        .header(header)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(
+                    "Build chain — {} #{}",
+                    self.title,
+                    self.build.build_number.as_deref().unwrap_or_default()
+                ))
+                .title_top(freshness_line(self.fetched_at, self.stale_after)),
+        )
+        .column_spacing(1)
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol(">> ");
+
+        self.rows.set_viewport(chunks[0]);
+        frame.render_stateful_widget(table, chunks[0], &mut self.rows.state);
+
+        let footer = Paragraph::new(
+            "j/k: Move  gg/G: Top/Bottom  Enter: Chain of selected build  l: Log  o: Open URL  R: Rebuild failed  A: Rebuild chain  r: Refresh  h/Esc: Back",
+        )
+        .style(Style::default().fg(Color::DarkGray))
+        .block(Block::default().padding(Padding::horizontal(1)));
+
+        frame.render_widget(footer, chunks[1]);
+
+        self.render_confirm(frame, area);
+        Ok(())
+    }
+}
@@ -0,0 +1,401 @@
+use super::Component;
+use crate::action::Action;
+use crate::config::Config;
+use crate::time::format_duration;
+use crate::view::build_log::{
+    IssueKind, LineKind, LogIssue, LogStep, OutlineItem, find_error, last_page_start, line_kind,
+    log_issues, log_steps, next_mark, outline,
+};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Padding, Paragraph, Wrap};
+use teamcity::types::Build;
+use tokio::sync::mpsc::UnboundedSender;
+
+const MINIMAP_WIDTH: u16 = 36;
+// characters of the duration bars in the minimap
+const BAR_WIDTH: usize = 8;
+// columns scrolled by Left/Right when lines don't wrap
+const HORIZONTAL_STEP: usize = 8;
+
+/// Build log shown in the app, with highlighted errors, warnings and steps
+pub struct BuildLog {
+    // configuration the log was opened from, to go back to
+    build_type_id: String,
+    title: String,
+    build: Build,
+    // None while loading
+    lines: Option<Vec<String>>,
+    kinds: Vec<LineKind>,
+    steps: Vec<LogStep>,
+    issues: Vec<LogIssue>,
+    // characters of the longest line, how far lines scroll horizontally
+    longest_line: usize,
+    // first line in view
+    scroll: usize,
+    // error or issue line jumped to with n/N or i/I
+    current_error: Option<usize>,
+    // first column in view when lines don't wrap
+    column: usize,
+    // wrap long lines instead of scrolling horizontally
+    wrap: bool,
+    // lines that fit in the view and their width, from the last draw
+    height: usize,
+    width: usize,
+    pub action_tx: Option<UnboundedSender<Action>>,
+}
+
+impl BuildLog {
+    pub fn new(build_type_id: String, title: String, build: Build, log: Option<String>) -> Self {
+        let lines: Option<Vec<String>> = log.map(|log| log.lines().map(str::to_string).collect());
+        let lines_ref = lines.as_deref().unwrap_or_default();
+        Self {
+            build_type_id,
+            title,
+            build,
+            kinds: lines_ref.iter().map(|line| line_kind(line)).collect(),
+            steps: log_steps(lines_ref),
+            issues: log_issues(lines_ref),
+            longest_line: lines_ref
+                .iter()
+                .map(|line| line.chars().count())
+                .max()
+                .unwrap_or(0),
+            lines,
+            scroll: 0,
+            current_error: None,
+            column: 0,
+            wrap: false,
+            height: 0,
+            width: 0,
+            action_tx: None,
+        }
+    }
+
+    fn max_scroll(&self) -> usize {
+        match &self.lines {
+            Some(lines) if self.wrap => last_page_start(lines, self.width, self.height),
+            _ => self.kinds.len().saturating_sub(self.height),
+        }
+    }
+
+    fn max_column(&self) -> usize {
+        self.longest_line.saturating_sub(self.width)
+    }
+
+    fn scroll_horizontally(&mut self, delta: isize) -> Action {
+        if self.wrap {
+            return Action::Toast(
+                "Lines are wrapped, press w to scroll them horizontally".to_string(),
+            );
+        }
+        self.column = self
+            .column
+            .saturating_add_signed(delta)
+            .min(self.max_column());
+        Action::Render
+    }
+
+    fn toggle_wrap(&mut self) -> Action {
+        self.wrap = !self.wrap;
+        self.column = 0;
+        Action::SaveLogWrap { wrap: self.wrap }
+    }
+
+    fn scroll_by(&mut self, delta: isize) {
+        self.scroll = self
+            .scroll
+            .saturating_add_signed(delta)
+            .min(self.max_scroll());
+    }
+
+    fn jump_to_error(&mut self, forward: bool) -> Action {
+        let from = self.current_error.unwrap_or(if forward {
+            self.scroll.saturating_sub(1)
+        } else {
+            self.scroll
+        });
+        match find_error(&self.kinds, from, forward) {
+            Some(line) => {
+                self.current_error = Some(line);
+                // keep a few lines of context above the error
+                self.scroll = line.saturating_sub(3).min(self.max_scroll());
+                Action::Render
+            }
+            None => Action::Toast("No errors in this log".to_string()),
+        }
+    }
+
+    fn jump_to_step(&mut self, forward: bool) -> Action {
+        let marks: Vec<usize> = self.steps.iter().map(|step| step.line).collect();
+        match next_mark(&marks, self.scroll, forward) {
+            Some(line) => {
+                self.scroll = line.min(self.max_scroll());
+                Action::Render
+            }
+            None if marks.is_empty() => Action::Toast("No steps in this log".to_string()),
+            None => Action::Render,
+        }
+    }
+
+    fn jump_to_issue(&mut self, forward: bool) -> Action {
+        let marks: Vec<usize> = self.issues.iter().map(|issue| issue.line).collect();
+        let from = self.current_error.unwrap_or(self.scroll);
+        match next_mark(&marks, from, forward) {
+            Some(line) => {
+                self.current_error = Some(line);
+                self.scroll = line.saturating_sub(3).min(self.max_scroll());
+                Action::Render
+            }
+            None if marks.is_empty() => Action::Toast(
+                "No errors, warnings or statuses reported by service messages".to_string(),
+            ),
+            None => Action::Render,
+        }
+    }
+
+    /// Step the first line in view belongs to, the innermost if blocks are nested
+    fn current_step(&self) -> Option<usize> {
+        self.steps.iter().rposition(|step| step.line <= self.scroll)
+    }
+
+    fn line_style(kind: LineKind) -> Style {
+        match kind {
+            LineKind::Plain => Style::default(),
+            LineKind::Error => Style::default().fg(Color::Red),
+            LineKind::Warning => Style::default().fg(Color::Yellow),
+            LineKind::ServiceMessage => Style::default().fg(Color::DarkGray),
+            LineKind::StepBoundary => Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        }
+    }
+
+    fn render_minimap(&self, frame: &mut Frame, area: Rect) {
+        let longest = self
+            .steps
+            .iter()
+            .filter_map(|step| step.duration_secs)
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        let current = self.current_step().map(|i| &self.steps[i]);
+        let mut current_row = None;
+        // issues are indented under the step they were reported in
+        let mut depth = 0;
+        let lines: Vec<Line> = outline(&self.steps, &self.issues)
+            .into_iter()
+            .enumerate()
+            .map(|(row, item)| match item {
+                OutlineItem::Step(step) => {
+                    depth = step.depth + 1;
+                    let (bar, duration) = match step.duration_secs {
+                        Some(secs) => (
+                            "█".repeat((secs as usize * BAR_WIDTH).div_ceil(longest as usize)),
+                            format_duration(secs as i64).unwrap_or_default(),
+                        ),
+                        None => (String::new(), String::new()),
+                    };
+                    let text = format!(
+                        "{:<bar_width$} {:>7} {}{}",
+                        bar,
+                        duration,
+                        "  ".repeat(step.depth),
+                        step.name,
+                        bar_width = BAR_WIDTH
+                    );
+                    let style = if current.is_some_and(|current| std::ptr::eq(current, step)) {
+                        current_row = Some(row);
+                        Style::default().add_modifier(Modifier::REVERSED)
+                    } else if step.depth == 0 {
+                        Style::default().fg(Color::Cyan)
+                    } else {
+                        Style::default().fg(Color::Gray)
+                    };
+                    Line::from(text).style(style)
+                }
+                OutlineItem::Issue(issue) => {
+                    let (letter, color) = match issue.kind {
+                        IssueKind::Error => ('E', Color::Red),
+                        IssueKind::Warning => ('W', Color::Yellow),
+                        IssueKind::Status => ('S', Color::Gray),
+                    };
+                    let text = format!(
+                        "{:width$}{}{}: {}",
+                        "",
+                        "  ".repeat(depth),
+                        letter,
+                        issue.text,
+                        width = BAR_WIDTH + 9
+                    );
+                    let mut style = Style::default().fg(color);
+                    if self.current_error == Some(issue.line) {
+                        current_row = Some(row);
+                        style = style.add_modifier(Modifier::REVERSED);
+                    }
+                    Line::from(text).style(style)
+                }
+            })
+            .collect();
+
+        // keep the current step or issue in view
+        let offset = current_row.map_or(0, |row| {
+            row.saturating_sub(area.height.saturating_sub(3) as usize)
+        });
+        let title = match self.issues.len() {
+            0 => "Steps".to_string(),
+            1 => "Steps — 1 issue".to_string(),
+            issues => format!("Steps — {} issues", issues),
+        };
+        let minimap = Paragraph::new(lines)
+            .scroll((offset as u16, 0))
+            .block(Block::default().borders(Borders::ALL).title(title));
+        frame.render_widget(minimap, area);
+    }
+}
+
+impl Component for BuildLog {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> color_eyre::Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> color_eyre::Result<()> {
+        self.wrap = config.log_wrap;
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>> {
+        let page = self.height.max(1) as isize;
+        let action = match key.code {
+            KeyCode::Char('j') | KeyCode::Down => {
+                self.scroll_by(1);
+                Action::Render
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                self.scroll_by(-1);
+                Action::Render
+            }
+            KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.scroll_by(page / 2);
+                Action::Render
+            }
+            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.scroll_by(-page / 2);
+                Action::Render
+            }
+            KeyCode::Char('f') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.scroll_by(page);
+                Action::Render
+            }
+            KeyCode::Char('b') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.scroll_by(-page);
+                Action::Render
+            }
+            KeyCode::Char('g') => {
+                self.scroll = 0;
+                Action::Render
+            }
+            KeyCode::Char('G') => {
+                self.scroll = self.max_scroll();
+                Action::Render
+            }
+            KeyCode::Left => self.scroll_horizontally(-(HORIZONTAL_STEP as isize)),
+            KeyCode::Right => self.scroll_horizontally(HORIZONTAL_STEP as isize),
+            KeyCode::Char('0') => {
+                self.column = 0;
+                Action::Render
+            }
+            KeyCode::Char('$') => self.scroll_horizontally(isize::MAX),
+            KeyCode::Char('w') => self.toggle_wrap(),
+            KeyCode::Char('n') => self.jump_to_error(true),
+            KeyCode::Char('N') => self.jump_to_error(false),
+            KeyCode::Char(']') => self.jump_to_step(true),
+            KeyCode::Char('[') => self.jump_to_step(false),
+            KeyCode::Char('i') => self.jump_to_issue(true),
+            KeyCode::Char('I') => self.jump_to_issue(false),
+            KeyCode::Char('l') => match self.build.id {
+                Some(build_id) => Action::LoadBuildLog { build_id },
+                None => Action::Error("No id was found for this build".to_string()),
+            },
+            KeyCode::Esc | KeyCode::Char('h') => Action::LoadBuilds {
+                project_id: self.build_type_id.clone(),
+                title: self.title.clone(),
+            },
+            _ => Action::Render,
+        };
+        Ok(Some(action))
+    }
+
+    fn tab_title(&self) -> String {
+        format!("Log — {}", self.title)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> color_eyre::Result<()> {
+        let [main_area, footer_area] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(2)]).areas(area);
+        let log_area = if self.steps.is_empty() && self.issues.is_empty() {
+            main_area
+        } else {
+            let [log_area, minimap_area] =
+                Layout::horizontal([Constraint::Min(0), Constraint::Length(MINIMAP_WIDTH)])
+                    .areas(main_area);
+            self.render_minimap(frame, minimap_area);
+            log_area
+        };
+        self.height = log_area.height.saturating_sub(2) as usize;
+        self.width = log_area.width.saturating_sub(2) as usize;
+        self.scroll = self.scroll.min(self.max_scroll());
+        self.column = self.column.min(self.max_column());
+
+        let text: Vec<Line> = match &self.lines {
+            None => vec![Line::from("Loading build log…")],
+            Some(lines) if lines.is_empty() => vec![Line::from("The build log is empty")],
+            Some(lines) => lines
+                .iter()
+                .zip(&self.kinds)
+                .enumerate()
+                .skip(self.scroll)
+                .take(self.height)
+                .map(|(i, (line, kind))| {
+                    let mut style = Self::line_style(*kind);
+                    if Some(i) == self.current_error {
+                        style = style.add_modifier(Modifier::REVERSED);
+                    }
+                    Line::from(line.as_str()).style(style)
+                })
+                .collect(),
+        };
+
+        let errors = self.kinds.iter().filter(|k| **k == LineKind::Error).count();
+        let mut title = format!(
+            "Log — {} #{} — line {}/{}, {} errors",
+            self.title,
+            self.build.build_number.as_deref().unwrap_or_default(),
+            (self.scroll + 1).min(self.kinds.len()),
+            self.kinds.len(),
+            errors
+        );
+        if self.column > 0 {
+            title.push_str(&format!(", column {}", self.column + 1));
+        }
+        let log = Paragraph::new(text).block(Block::default().borders(Borders::ALL).title(title));
+        let log = if self.wrap {
+            log.wrap(Wrap { trim: false })
+        } else {
+            log.scroll((0, self.column.min(u16::MAX as usize) as u16))
+        };
+        frame.render_widget(log, log_area);
+
+        let footer = Paragraph::new(
+            "j/k: Scroll  Ctrl-d/u/f/b: Half/Full page  g/G: Top/Bottom  Left/Right/0/$: Scroll lines  w: Wrap  n/N: Next/Previous error  ]/[: Next/Previous step  i/I: Next/Previous issue  l: Open in pager  h/Esc: Back",
+        )
+        .style(Style::default().fg(Color::DarkGray))
+        .block(Block::default().padding(Padding::horizontal(1)));
+        frame.render_widget(footer, footer_area);
+        Ok(())
+    }
+}
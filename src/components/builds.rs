@@ -1,123 +1,1366 @@
 use super::Component;
-use crate::teamcity::types::Build;
-use crate::time::{
-    format_datetime_to_human_readable_string, format_duration, parse_tc_datetime_to_epoch,
+use super::context_menu::{ContextMenu, MENU_KEY, MenuEvent, MenuItem, replay_keys};
+use super::date_range_picker::{
+    RangeChoice, handle_date_range_picker_key, render_date_range_picker,
 };
-use crate::{action::Action, config::Config};
-use color_eyre::eyre::anyhow;
-use crossterm::event::{KeyCode, KeyEvent};
+use super::empty_state::render_empty_state;
+use super::filter_picker::{handle_filter_picker_key, render_filter_picker};
+use super::freshness::freshness_line;
+use super::prompt::{BranchPrompt, BranchPromptEvent, render_confirm_popup, render_prompt};
+use super::selectable_table::{FzfLabel, SelectableTable};
+use crate::export::{self, EXPORT_PROMPT_TITLE, expand_path};
+use crate::icons::IconSet;
+use crate::session::Session;
+use crate::time::{TimeDisplay, format_datetime, format_duration, format_remaining, local_offset};
+use crate::utils::{format_size, is_alternate_key};
+use crate::view::builds::{
+    BuildTab, COLUMNS, DEFAULT_COLUMN_WIDTHS, EXPORT_COLUMNS, FILTER_FIELDS, SortColumn,
+    build_tab_url, change_details, change_revision, column_widths, commit_url, duration_baseline,
+    duration_secs, empty_reasons, exceeds_baseline, export_row, is_failed, is_queued, is_running,
+    matches_filter, queue_position, remaining_secs, resize_column, revision, search_locator,
+    short_revision, status_text, summarize_changes, table_rows, tag_names, triggered_text,
+    visible_builds, visible_columns, wait_kind,
+};
+use crate::view::custom_actions::{build_placeholders, command_line};
+use crate::view::date_range::parse_range;
+use crate::view::filter_query::FilterQuery;
+use crate::{
+    action::Action,
+    config::{Config, CustomAction, SavedFilter},
+};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::Frame;
 use ratatui::layout::{Constraint, Direction, Layout, Rect, Size};
 use ratatui::style::{Color, Modifier, Style};
-use ratatui::widgets::{Block, Borders, Row, Table, TableState, Paragraph, Clear, Padding};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Cell, Clear, Gauge, Padding, Paragraph, Row, Table, Wrap};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use teamcity::types::{Build, Change};
+use teamcity::{BranchFilter, BuildArtifacts, DateRange};
+use time::{Duration, OffsetDateTime};
 use tokio::sync::mpsc::UnboundedSender;
 
+const BRANCH_PROMPT_TITLE: &str =
+    "Branch (empty: default, *: all, ↑/↓: Complete, Enter: Show, Esc: Cancel)";
+// wide enough for an author and a commit subject line
+const CHANGES_POPUP_WIDTH: u16 = 90;
+const DETAILS_POPUP_WIDTH: u16 = 70;
+const SEARCH_PROMPT_TITLE: &str =
+    "Search server: 123, tag:<tag>, status:<status> (Enter to search, Esc to cancel)";
+const FILTER_PROMPT_TITLE: &str = "Filter by branch, id:<number>, type:<configuration ID>, tag:<tag>, /regex/ (Enter: Keep, Esc: Cancel)";
+const RANGE_PROMPT_TITLE: &str =
+    "Builds started since..until, e.g. 2025-01-01..2025-01-31 18:00 (Enter to load, Esc to cancel)";
+const GREP_PROMPT_TITLE: &str =
+    "Grep logs of the latest builds for (Enter to search, Esc to cancel)";
+const ARTIFACTS_PROMPT_TITLE: &str =
+    "Download artifacts as zip to directory (Enter to download, Esc to cancel)";
+
+// Dry-run and progress of deleting artifacts of old builds
+enum ArtifactCleanup {
+    Loading,
+    Plan(Vec<BuildArtifacts>),
+    Deleting,
+    Done { deleted: usize, failed: Vec<String> },
+}
+
+// Artifact archive of a build being downloaded
+struct ArtifactsDownload {
+    build_id: i64,
+    number: String,
+    downloaded: u64,
+    total: Option<u64>,
+}
+
 #[derive(Default)]
 pub struct Builds {
+    build_type_id: String,
     title: String,
-    items: Vec<Build>,
-    table_state: TableState,
+    // all fetched builds, `table` holds the visible ones
+    all_items: Vec<Build>,
+    username: Option<String>,
+    commit_url_template: Option<String>,
+    // show only builds with my changes and my personal builds
+    mine_only: bool,
+    filters: Vec<SavedFilter>,
+    custom_actions: Vec<CustomAction>,
+    active_filter: Option<SavedFilter>,
+    picking_filter: bool,
+    context_menu: Option<ContextMenu>,
+    // build to select once the builds are shown, from the previous session
+    restored_build: Option<i64>,
+    // None while the builds are being fetched
+    fetched_at: Option<OffsetDateTime>,
+    stale_after: Duration,
+    // current sort column and whether it is descending; None keeps the server order
+    sort: Option<(SortColumn, bool)>,
+    // width of each of COLUMNS
+    column_widths: Vec<u16>,
+    // first column shown, the ones before it are scrolled out of view to the left
+    first_column: usize,
+    // builds of a composite configuration aggregate the builds of its snapshot dependencies
+    composite: bool,
+    // builds aggregated by the expanded composite builds, by their ID; None while loading
+    expanded: HashMap<i64, Option<Vec<Build>>>,
+    table: SelectableTable<Build>,
     last_events: Vec<KeyEvent>,
+    cleanup_older_than_days: u32,
+    time_display: TimeDisplay,
+    icons: IconSet,
+    // frame of the spinner of running builds, advanced on every tick
+    spinner_frame: usize,
+    artifact_cleanup: Option<ArtifactCleanup>,
+    // IDs of builds marked for bulk operations
+    marked: HashSet<i64>,
+    // row where visual selection started, the range up to the cursor is marked when it ends
+    visual_anchor: Option<usize>,
+    // bulk operation waiting for confirmation, with its prompt
+    pending_bulk: Option<(String, Action)>,
+    // path typed in the export prompt, while it is open
+    export_path: Option<String>,
+    // build whose artifacts are about to be downloaded, with the directory typed in the prompt
+    artifacts_prompt: Option<(Build, String)>,
+    artifacts_download: Option<ArtifactsDownload>,
+    artifact_download_dir: PathBuf,
+    // branch picked with `b`, fetched with a server-side locator; None for the configured one
+    branch: Option<BranchFilter>,
+    branch_prompt: Option<BranchPrompt>,
+    // start dates picked with `d`, fetched with a server-side locator; None for the latest builds
+    range: Option<DateRange>,
+    picking_range: bool,
+    // since..until typed in the custom date range prompt, while it is open
+    range_input: Option<String>,
+    // why the typed date range can't be loaded
+    range_error: Option<String>,
+    // query typed in the search prompt, while it is open
+    search_input: Option<String>,
+    // filter typed in the filter prompt while it is open, with the filter applied before
+    filter_input: Option<(String, Option<(String, FilterQuery)>)>,
+    // why the typed filter can't be applied, the previous one stays applied
+    filter_error: Option<String>,
+    // filter on the branch, number and configuration typed with `&`, with its text
+    filter: Option<(String, FilterQuery)>,
+    // string typed in the prompt for grepping the logs of the latest builds, while it is open
+    grep_input: Option<String>,
+    // query the shown builds were searched on the server with
+    search: Option<String>,
+    // average duration of the successful builds, running builds well over it are flagged
+    baseline: Option<i64>,
+    long_running_percent: u32,
+    // running builds already reported to run longer than usual
+    reported_long_running: HashSet<i64>,
+    // popup listing the author and comment of each change of the selected build
+    show_changes: bool,
+    // change picked in that popup with `J`/`K`, to queue a build on
+    change_index: usize,
+    // popup with every column of the selected build, toggled with `i`
+    show_details: bool,
+    // split pane with the log tail or failure summary of the selected build
+    preview: bool,
+    // previews by build ID with the statistics shown under them, those of running builds are
+    // fetched again when selected
+    previews: HashMap<i64, (Vec<String>, Vec<String>)>,
+    // build the preview pane shows or is loading
+    preview_build: Option<i64>,
+    // selected build waiting for the selection to settle before its preview is loaded
+    preview_candidate: Option<i64>,
     pub action_tx: Option<UnboundedSender<Action>>,
 }
 
 impl Builds {
-    pub fn new(project_title: String, builds: Vec<Build>) -> Self {
+    /// Empty table shown while the builds are being fetched
+    pub fn loading(build_type_id: String, project_title: String) -> Self {
+        let mut builds = Self::new(build_type_id, project_title, vec![]);
+        builds.fetched_at = None;
+        builds
+    }
+
+    pub fn new(build_type_id: String, project_title: String, builds: Vec<Build>) -> Self {
         Self {
+            build_type_id,
             title: project_title,
-            items: builds,
+            all_items: builds,
+            fetched_at: Some(OffsetDateTime::now_utc()),
+            column_widths: DEFAULT_COLUMN_WIDTHS.to_vec(),
             ..Self::default()
         }
     }
 
-    fn get_items(&self) -> &Vec<Build> {
-        self.items.as_ref()
+    /// Command of the custom action bound to `key`, run on the selected build
+    fn custom_action(&self, key: &KeyEvent) -> Option<Action> {
+        let custom = self
+            .custom_actions
+            .iter()
+            .find(|custom| custom.on_build.is_some() && custom.matches(key))?;
+        let Some(build) = self.get_selected_build() else {
+            return Some(Action::Error("No build was selected".to_string()));
+        };
+        Some(Action::RunExternal {
+            name: custom.name.clone(),
+            command: command_line(custom.on_build.as_deref()?, &build_placeholders(build)),
+        })
     }
 
-    fn move_down(&mut self) {
-        let items = self.get_items();
-        if items.is_empty() {
-            self.table_state.select(None);
-            return;
+    /// Builds of a composite configuration, whose aggregated builds can be listed under them
+    pub fn composite(mut self, composite: bool) -> Self {
+        self.composite = composite;
+        self
+    }
+
+    /// Shows that the builds were fetched on `branch` rather than the configured branch
+    pub fn on_branch(mut self, branch: Option<BranchFilter>) -> Self {
+        self.branch = branch;
+        self
+    }
+
+    /// Shows that the builds were fetched for the start dates in `range` rather than the latest
+    pub fn in_range(mut self, range: Option<DateRange>) -> Self {
+        self.range = range;
+        self
+    }
+
+    /// Fetches the builds again, on the branch and in the date range they were fetched for
+    fn reload(&self) -> Action {
+        self.load(self.branch.clone(), self.range)
+    }
+
+    /// Fetches the builds on `branch`, None for the configured one, started in `range`, None for
+    /// the latest builds
+    fn load(&self, branch: Option<BranchFilter>, range: Option<DateRange>) -> Action {
+        let project_id = self.build_type_id.clone();
+        let title = self.title.clone();
+        match (branch, range) {
+            (branch, Some(range)) => Action::LoadBuildsInRange {
+                project_id,
+                title,
+                branch,
+                range,
+            },
+            (Some(branch), None) => Action::LoadBuildsOnBranch {
+                project_id,
+                title,
+                branch,
+            },
+            (None, None) => Action::LoadBuilds { project_id, title },
         }
-        let i = match self.table_state.selected() {
-            Some(i) => {
-                if i >= items.len() - 1 {
-                    0
-                } else {
-                    i + 1
+    }
+
+    fn handle_branch_key(&mut self, key: KeyEvent) -> Action {
+        let Some(prompt) = &mut self.branch_prompt else {
+            return Action::Render;
+        };
+        match prompt.handle_key(key) {
+            BranchPromptEvent::Edited => Action::Render,
+            BranchPromptEvent::Cancelled => {
+                self.branch_prompt = None;
+                Action::Render
+            }
+            BranchPromptEvent::Submitted(branch) => {
+                self.branch_prompt = None;
+                self.load(Some(BranchFilter::parse(&branch)), self.range)
+            }
+        }
+    }
+
+    fn handle_range_picker_key(&mut self, key: KeyEvent) -> Action {
+        self.picking_range = false;
+        match handle_date_range_picker_key(key.code) {
+            Some(RangeChoice::Range(range)) => self.load(self.branch.clone(), range),
+            Some(RangeChoice::Custom) => {
+                self.range_input = Some(String::new());
+                Action::Render
+            }
+            None => Action::Render,
+        }
+    }
+
+    fn handle_range_key(&mut self, key: KeyEvent) -> Action {
+        let Some(input) = &mut self.range_input else {
+            return Action::Render;
+        };
+        match key.code {
+            KeyCode::Esc => {
+                self.range_input = None;
+                self.range_error = None;
+                return Action::Render;
+            }
+            KeyCode::Char(c) => input.push(c),
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Enter => match parse_range(input, local_offset()) {
+                Ok(range) => {
+                    self.range_input = None;
+                    self.range_error = None;
+                    return self.load(self.branch.clone(), Some(range));
                 }
+                Err(e) => {
+                    self.range_error = Some(e);
+                    return Action::Render;
+                }
+            },
+            _ => return Action::Render,
+        }
+        self.range_error = None;
+        Action::Render
+    }
+
+    /// Selects the build with `build_id` once the builds are shown
+    /// Builds already searched on the server with `query`, `Esc` goes back to the latest ones
+    pub fn searched(mut self, query: String) -> Self {
+        self.search = Some(query);
+        self
+    }
+
+    pub fn restore_selection(mut self, build_id: Option<i64>) -> Self {
+        self.restored_build = build_id;
+        self
+    }
+
+    /// Recomputes the visible builds from the fetched ones, keeping the sort order and selection
+    fn refresh_items(&mut self) {
+        let selected_id = self
+            .restored_build
+            .take()
+            .or_else(|| self.get_selected_build().and_then(|b| b.id));
+        self.baseline = duration_baseline(&self.all_items);
+        self.table.set_items(visible_builds(
+            &self.all_items,
+            self.username.as_deref(),
+            self.mine_only,
+        ));
+        if let Some(filter) = &self.active_filter {
+            self.table.retain(|build| filter.matches_build(build));
+        }
+        if let Some((_, filter)) = &self.filter {
+            self.table.retain(|build| matches_filter(build, filter));
+        }
+        let constituents: HashMap<i64, Vec<Build>> = self
+            .expanded
+            .iter()
+            .filter_map(|(id, builds)| Some((*id, builds.clone()?)))
+            .collect();
+        let items = std::mem::take(self.table.items_mut());
+        *self.table.items_mut() =
+            table_rows(items, self.sort, &constituents, OffsetDateTime::now_utc());
+        // rows move, so a visual selection by position no longer makes sense
+        self.visual_anchor = None;
+
+        if !self
+            .table
+            .select_where(|b| selected_id.is_some() && b.id == selected_id)
+        {
+            self.table.move_begin();
+        }
+    }
+
+    fn get_items(&self) -> &[Build] {
+        self.table.items()
+    }
+
+    fn get_selected_build(&self) -> Option<&Build> {
+        self.table.selected()
+    }
+
+    fn changes_of_selected(&self) -> &[Change] {
+        self.get_selected_build()
+            .and_then(|build| build.changes.as_ref())
+            .and_then(|changes| changes.change.as_deref())
+            .unwrap_or_default()
+    }
+
+    /// Change picked in the Changes popup, kept within the changes of the selected build
+    fn selected_change(&self) -> Option<&Change> {
+        let changes = self.changes_of_selected();
+        changes.get(self.change_index.min(changes.len().saturating_sub(1)))
+    }
+
+    fn handle_changes_key(&mut self, key: &KeyEvent) -> Option<Action> {
+        match key.code {
+            KeyCode::Char('J') => {
+                let last = self.changes_of_selected().len().saturating_sub(1);
+                self.change_index = (self.change_index + 1).min(last);
+                Some(Action::Render)
+            }
+            KeyCode::Char('K') => {
+                self.change_index = self.change_index.saturating_sub(1);
+                Some(Action::Render)
             }
-            None => 0,
+            KeyCode::Char('T') => Some(self.trigger_on_selected_change()),
+            _ => None,
+        }
+    }
+
+    fn trigger_on_selected_change(&self) -> Action {
+        let (Some(build), Some(change)) = (self.get_selected_build(), self.selected_change())
+        else {
+            return Action::Error("No change was selected".to_string());
+        };
+        let (Some(build_type_id), Some(change_id)) = (&build.build_type_id, change.id) else {
+            return Action::Error("The change can't be queued, it has no ID".to_string());
+        };
+        Action::TriggerBuildOnChange {
+            build_type_id: build_type_id.clone(),
+            title: self.title.clone(),
+            branch: build.branch_name.clone(),
+            change_id,
+            revision: change_revision(change),
+        }
+    }
+
+    fn open_selected_url(&mut self) {
+        if let Some(url) = self.get_selected_build().and_then(|b| b.web_url.as_ref()) {
+            let _ = open::that(url);
+        }
+    }
+
+    /// Sorts by `column`, flipping the direction if it is already the sort column
+    fn toggle_sort(&mut self, column: SortColumn) {
+        let descending = match self.sort {
+            Some((current, descending)) if current == column => !descending,
+            _ => false,
         };
-        self.table_state.select(Some(i));
+        self.sort = Some((column, descending));
+        self.refresh_items();
+    }
+
+    /// ID of the expanded composite build that aggregated `build`, None for a build of the
+    /// configuration
+    fn composite_of(&self, build: &Build) -> Option<i64> {
+        let id = build.id?;
+        self.expanded.iter().find_map(|(composite, builds)| {
+            builds
+                .as_ref()
+                .is_some_and(|builds| builds.iter().any(|b| b.id == Some(id)))
+                .then_some(*composite)
+        })
     }
 
-    fn move_up(&mut self) {
+    /// Lists the builds the selected composite build aggregated under it, or hides them again,
+    /// also from one of them
+    fn toggle_expanded(&mut self) -> Action {
+        let Some(build) = self.get_selected_build() else {
+            return Action::Render;
+        };
+        if let Some(composite) = self.composite_of(build) {
+            self.expanded.remove(&composite);
+            self.restored_build = Some(composite);
+            self.refresh_items();
+            return Action::Render;
+        }
+        if !self.composite {
+            return Action::Error(
+                "Only builds of composite configurations aggregate other builds".to_string(),
+            );
+        }
+        let Some(build_id) = build.id else {
+            return Action::Error("No id was found for selected build".to_string());
+        };
+        if self.expanded.remove(&build_id).is_some() {
+            self.refresh_items();
+            return Action::Render;
+        }
+        self.expanded.insert(build_id, None);
+        Action::LoadCompositeBuilds { build_id }
+    }
+
+    // Second key of a `y` yank sequence: y for the URL, i for the ID, n for the number
+    fn yank_selected(&self, code: KeyCode) -> Option<Action> {
+        let build = self.get_selected_build()?;
+        let (label, text) = match code {
+            KeyCode::Char('y') => ("URL", build.web_url.clone()?),
+            KeyCode::Char('i') => ("build ID", build.id?.to_string()),
+            KeyCode::Char('n') => ("build number", build.build_number.clone()?),
+            KeyCode::Char('r') => ("revision", revision(build)?.version.clone()?),
+            _ => return None,
+        };
+        Some(Action::CopyToClipboard {
+            label: label.to_string(),
+            text,
+        })
+    }
+
+    // Second key of an `O` sequence: c for the commit, or a tab of the build's web page
+    fn open_selected_page(&self, code: KeyCode) -> Option<Action> {
+        let KeyCode::Char(key) = code else {
+            return None;
+        };
+        if key == 'c' {
+            return Some(self.open_selected_commit());
+        }
+        let tab = BuildTab::from_key(key)?;
+        let Some(url) = self.get_selected_build().and_then(|b| b.web_url.as_deref()) else {
+            return Some(Action::Error(
+                "No URL was found for selected build".to_string(),
+            ));
+        };
+        let _ = open::that(build_tab_url(url, tab));
+        Some(Action::Render)
+    }
+
+    fn open_selected_commit(&self) -> Action {
+        let Some(template) = &self.commit_url_template else {
+            return Action::Error(
+                "Set commit_url_template in config.toml to open commits".to_string(),
+            );
+        };
+        match self
+            .get_selected_build()
+            .and_then(revision)
+            .and_then(|r| commit_url(template, r))
+        {
+            Some(url) => {
+                let _ = open::that(url);
+                Action::Render
+            }
+            None => Action::Error("No revision was found for selected build".to_string()),
+        }
+    }
+
+    /// Loads the preview of the selected build once it stays selected for a tick, so moving
+    /// through the list doesn't download the log of every build on the way
+    fn is_long_running(&self, build: &Build, now: OffsetDateTime) -> bool {
+        self.baseline.is_some_and(|baseline| {
+            exceeds_baseline(build, baseline, self.long_running_percent, now)
+        })
+    }
+
+    /// Toast for running builds that started to take longer than usual since the last check
+    fn report_long_running(&mut self) -> Option<Action> {
+        let now = OffsetDateTime::now_utc();
+        let new: Vec<(i64, String)> = self
+            .all_items
+            .iter()
+            .filter(|build| self.is_long_running(build, now))
+            .filter_map(|build| Some((build.id?, build.build_number.clone().unwrap_or_default())))
+            .filter(|(id, _)| !self.reported_long_running.contains(id))
+            .collect();
+        if new.is_empty() {
+            return None;
+        }
+        let numbers: Vec<String> = new
+            .into_iter()
+            .map(|(id, number)| {
+                self.reported_long_running.insert(id);
+                format!("#{}", number)
+            })
+            .collect();
+        Some(Action::Toast(format!(
+            "{} {} running {}% longer than usual",
+            self.title,
+            numbers.join(", "),
+            self.long_running_percent
+        )))
+    }
+
+    fn update_preview(&mut self) -> Option<Action> {
+        let build = self.get_selected_build()?.clone();
+        let build_id = build.id?;
+        if self.preview_build == Some(build_id) {
+            return None;
+        }
+        if self.previews.contains_key(&build_id) && build.state.as_deref() != Some("running") {
+            self.preview_build = Some(build_id);
+            return Some(Action::Render);
+        }
+        if self.preview_candidate != Some(build_id) {
+            self.preview_candidate = Some(build_id);
+            return None;
+        }
+        self.preview_build = Some(build_id);
+        Some(Action::LoadBuildPreview { build })
+    }
+
+    fn render_preview(&self, frame: &mut Frame, area: Rect) {
+        let selected = self.get_selected_build();
+        let title = match selected.and_then(|build| build.build_number.as_deref()) {
+            Some(number) => format!("Preview — #{}", number),
+            None => "Preview".to_string(),
+        };
+        let preview = self.preview_build.and_then(|id| self.previews.get(&id));
+        let lines: Vec<Line> = match preview {
+            Some((lines, _)) => lines.iter().map(|line| Line::from(line.as_str())).collect(),
+            None if selected.is_some() => vec![Line::from("Loading…")],
+            None => vec![],
+        };
+        // on the border, so it stays in view whatever the log is scrolled to
+        let statistics = preview
+            .map(|(_, statistics)| statistics.join("  "))
+            .unwrap_or_default();
+        // keep the end of the log in view
+        let height = area.height.saturating_sub(2) as usize;
+        let scroll = lines.len().saturating_sub(height) as u16;
+
+        let preview = Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(title)
+                    .title_bottom(statistics),
+            )
+            .scroll((scroll, 0));
+        frame.render_widget(preview, area);
+    }
+
+    fn scroll_columns(&mut self, right: bool) -> Action {
+        self.first_column = if right {
+            (self.first_column + 1).min(COLUMNS.len() - 1)
+        } else {
+            self.first_column.saturating_sub(1)
+        };
+        Action::Render
+    }
+
+    /// Grows or shrinks the first column in view and saves the widths
+    fn resize_first_column(&mut self, delta: i16) -> Action {
+        let width = &mut self.column_widths[self.first_column];
+        *width = resize_column(*width, delta);
+        Action::SaveColumnWidths {
+            widths: COLUMNS
+                .iter()
+                .map(|name| name.to_string())
+                .zip(self.column_widths.iter().copied())
+                .collect(),
+        }
+    }
+
+    /// Why no build is shown and the keys to fix it, in place of the empty table
+    fn render_empty(&self, frame: &mut Frame, area: Rect, block: Block) {
+        let filtered = self.filter.is_some()
+            || self.active_filter.is_some()
+            || self.search.is_some()
+            || self.mine_only;
+        let mut keys = Vec::new();
+        if self.filter.is_some() || self.search.is_some() {
+            keys.push("Esc: Clear filter or search");
+        }
+        if self.active_filter.is_some() {
+            keys.push("F: Saved filters");
+        }
+        if self.mine_only {
+            keys.push("m: All builds");
+        }
+        keys.extend(["b: Other branch", "d: Other dates", "r: Refresh", "h: Back"]);
+        let branch = self
+            .branch
+            .as_ref()
+            .map_or("configured branches".to_string(), |branch| branch.label());
+        let range = self.range.map(|range| range.label());
+        render_empty_state(
+            frame,
+            area,
+            block,
+            "No builds to show",
+            &empty_reasons(self.all_items.len(), filtered, &branch, range.as_deref()),
+            &keys.join("  "),
+        );
+    }
+
+    fn table_title(&self) -> String {
+        let mut title = format!("Builds — {}", self.title);
+        if let Some(branch) = &self.branch {
+            title.push_str(&format!(" — {}", branch.label()));
+        }
+        if let Some(range) = &self.range {
+            title.push_str(&format!(" — {}", range.label()));
+        }
+        if self.mine_only {
+            title.push_str(" — mine");
+        }
+        if let Some(filter) = &self.active_filter {
+            title.push_str(&format!(" — filter: {}", filter.name));
+        }
+        if let Some((text, _)) = &self.filter {
+            title.push_str(&format!(" — matching: {}", text));
+        }
+        if let Some(query) = &self.search {
+            title.push_str(&format!(" — search: {}", query));
+        }
         let items = self.get_items();
-        if items.is_empty() {
-            self.table_state.select(None);
-            return;
+        let marked = (0..items.len())
+            .filter(|i| self.is_marked(*i, &items[*i]))
+            .count();
+        if self.visual_anchor.is_some() {
+            title.push_str(" — VISUAL");
+        }
+        if marked > 0 {
+            title.push_str(&format!(" — {} marked", marked));
         }
-        let i = match self.table_state.selected() {
-            Some(i) => {
-                if i == 0 {
-                    items.len() - 1
+        title
+    }
+
+    fn visual_range(&self) -> Option<std::ops::RangeInclusive<usize>> {
+        let anchor = self.visual_anchor?;
+        let cursor = self.table.selected_index()?;
+        Some(anchor.min(cursor)..=anchor.max(cursor))
+    }
+
+    fn is_marked(&self, index: usize, build: &Build) -> bool {
+        build.id.is_some_and(|id| self.marked.contains(&id))
+            || self
+                .visual_range()
+                .is_some_and(|range| range.contains(&index))
+    }
+
+    fn toggle_mark_selected(&mut self) {
+        if let Some(id) = self.get_selected_build().and_then(|b| b.id)
+            && !self.marked.remove(&id)
+        {
+            self.marked.insert(id);
+        }
+    }
+
+    /// Starts a visual selection, or marks the selected range and ends it
+    fn toggle_visual(&mut self) {
+        match self.visual_range() {
+            Some(range) => {
+                let ids: Vec<i64> = self.get_items()[range]
+                    .iter()
+                    .filter_map(|b| b.id)
+                    .collect();
+                self.marked.extend(ids);
+                self.visual_anchor = None;
+            }
+            None => self.visual_anchor = self.table.selected_index(),
+        }
+    }
+
+    /// Actions on the selected build that apply to its state, then the ones of the whole view
+    fn context_menu(&self) -> ContextMenu {
+        let mut items = Vec::new();
+        let title = match self.get_selected_build() {
+            Some(build) => {
+                let state = build.state.as_deref();
+                items.extend([
+                    MenuItem::new(&[KeyCode::Enter], "Open log"),
+                    MenuItem::new(&[KeyCode::Char('l')], "Open log in pager"),
+                ]);
+                if is_failed(build) {
+                    items.push(MenuItem::new(&[KeyCode::Char('t')], "Failed tests"));
+                }
+                items.extend([
+                    MenuItem::new(&[KeyCode::Char('c')], "Build chain"),
+                    MenuItem::new(&[KeyCode::Char('x')], "Changes"),
+                    MenuItem::new(&[KeyCode::Char('o')], "Open in browser"),
+                    MenuItem::new(&[KeyCode::Char('O'), KeyCode::Char('c')], "Open commit"),
+                    MenuItem::new(
+                        &[KeyCode::Char('O'), KeyCode::Char('x')],
+                        "Open changes page",
+                    ),
+                    MenuItem::new(&[KeyCode::Char('O'), KeyCode::Char('t')], "Open tests page"),
+                    MenuItem::new(
+                        &[KeyCode::Char('O'), KeyCode::Char('d')],
+                        "Open dependencies page",
+                    ),
+                    MenuItem::new(
+                        &[KeyCode::Char('O'), KeyCode::Char('p')],
+                        "Open parameters page",
+                    ),
+                    MenuItem::new(&[KeyCode::Char(' ')], "Mark"),
+                ]);
+                if self.composite || self.composite_of(build).is_some() {
+                    items.push(MenuItem::new(&[KeyCode::Char('e')], "Expand/collapse"));
+                }
+                if state == Some("running") {
+                    items.push(MenuItem::new(&[KeyCode::Char('w')], "Watch"));
+                }
+                if matches!(state, Some("queued" | "running")) {
+                    items.push(MenuItem::new(&[KeyCode::Char('C')], "Cancel"));
                 } else {
-                    i - 1
+                    items.extend([
+                        MenuItem::new(&[KeyCode::Char('R')], "Re-run"),
+                        MenuItem::new(&[KeyCode::Char('A')], "Download artifacts"),
+                    ]);
+                }
+                if state != Some("queued") {
+                    items.push(MenuItem::new(&[KeyCode::Char('L')], "Save log"));
                 }
+                items.extend([
+                    MenuItem::new(&[KeyCode::Char('y'), KeyCode::Char('y')], "Copy URL"),
+                    MenuItem::new(&[KeyCode::Char('y'), KeyCode::Char('n')], "Copy number"),
+                    MenuItem::new(&[KeyCode::Char('y'), KeyCode::Char('r')], "Copy revision"),
+                ]);
+                format!("#{}", build.build_number.as_deref().unwrap_or_default())
             }
-            None => 0,
+            None => self.title.clone(),
         };
-        self.table_state.select(Some(i));
+        items.extend([
+            MenuItem::new(&[KeyCode::Char('p')], "Pending changes"),
+            MenuItem::new(&[KeyCode::Char('b')], "Switch branch"),
+            MenuItem::new(&[KeyCode::Char('d')], "Date range"),
+            MenuItem::new(&[KeyCode::Char('&')], "Filter"),
+            MenuItem::new(&[KeyCode::Char('W')], "Grep logs"),
+            MenuItem::new(&[KeyCode::Char('S')], "Statistics"),
+            MenuItem::new(&[KeyCode::Char('E')], "Export"),
+            MenuItem::new(&[KeyCode::Char('D')], "Clean up artifacts"),
+            MenuItem::new(&[KeyCode::Char('r')], "Refresh"),
+        ]);
+        ContextMenu::new(title, items)
     }
 
-    fn move_begin(&mut self) {
-        if self.get_items().is_empty() {
-            self.table_state.select(None);
+    /// Builds a bulk operation applies to: the marked ones, or the selected one if none are marked
+    fn get_bulk_targets(&self) -> Vec<Build> {
+        let marked: Vec<Build> = self
+            .get_items()
+            .iter()
+            .enumerate()
+            .filter(|(i, build)| self.is_marked(*i, build))
+            .map(|(_, build)| build.clone())
+            .collect();
+        if marked.is_empty() {
+            self.get_selected_build().cloned().into_iter().collect()
         } else {
-            self.table_state.select(Some(0));
+            marked
         }
     }
 
-    fn move_end(&mut self) {
-        let items = self.get_items();
-        if items.is_empty() {
-            self.table_state.select(None);
-        } else {
-            self.table_state.select(Some(items.len() - 1));
+    fn confirm_bulk(&mut self, verb: &str, action: fn(Vec<Build>) -> Action) -> Action {
+        let builds = self.get_bulk_targets();
+        if builds.is_empty() {
+            return Action::Error("No build was selected".to_string());
         }
+        let prompt = format!("{} {} builds? y: Yes  n/Esc: No", verb, builds.len());
+        self.pending_bulk = Some((prompt, action(builds)));
+        Action::Render
     }
 
-    fn get_selected_build(&self) -> Option<&Build> {
-        if let Some(i) = self.table_state.selected() {
-            self.get_items().get(i)
+    fn clear_marks(&mut self) {
+        self.marked.clear();
+        self.visual_anchor = None;
+    }
+
+    fn handle_confirm_key(&mut self, key: KeyEvent) -> Action {
+        match key.code {
+            KeyCode::Char('y') => {
+                self.clear_marks();
+                self.pending_bulk
+                    .take()
+                    .map_or(Action::Render, |(_, action)| action)
+            }
+            KeyCode::Char('n') | KeyCode::Esc => {
+                self.pending_bulk = None;
+                Action::Render
+            }
+            _ => Action::Render,
+        }
+    }
+
+    /// Text of each of COLUMNS for `build`, running builds are measured up to `now`
+    fn cells(&self, build: &Build, marked: bool, now: OffsetDateTime) -> Vec<String> {
+        let mut number = build.build_number.clone().unwrap_or_default();
+        let mut branch = build.branch_name.as_deref().unwrap_or_default();
+        if self.composite_of(build).is_some() {
+            // aggregated builds are of other configurations, which says more than their branch
+            number = format!("└ {}", number);
+            branch = build.build_type_id.as_deref().unwrap_or_default();
+        } else if self.composite {
+            let expanded = build.id.is_some_and(|id| self.expanded.contains_key(&id));
+            let marker = if expanded {
+                self.icons.arrow_down()
+            } else {
+                self.icons.arrow_right()
+            };
+            number = format!("{} {}", marker, number);
+        }
+        let start_datetime = build
+            .start_date
+            .as_ref()
+            .and_then(|start| format_datetime(start, self.time_display).ok())
+            .unwrap_or_default();
+
+        let duration = match remaining_secs(build, now) {
+            Some(secs) => Ok(format_remaining(secs)),
+            None => duration_secs(build, now)
+                .map(format_duration)
+                .unwrap_or_else(|| Ok(String::new())),
+        };
+
+        vec![
+            if marked {
+                format!("{} {}", self.icons.mark(), number)
+            } else {
+                number
+            },
+            branch.to_string(),
+            match wait_kind(build) {
+                Some(kind) => format!("{} {}", kind.tag(), status_text(build)),
+                None => status_text(build),
+            },
+            summarize_changes(build, self.icons),
+            start_datetime,
+            duration.unwrap_or_default(),
+            triggered_text(build),
+            short_revision(build),
+            tag_names(build).join(", "),
+            queue_position(build),
+            build
+                .start_estimate
+                .as_ref()
+                .and_then(|eta| format_datetime(eta, self.time_display).ok())
+                .unwrap_or_default(),
+        ]
+    }
+
+    /// Every column of the selected build, for tables too narrow to show them all
+    fn render_details(&self, frame: &mut Frame, area: Rect) {
+        let Some(build) = self.get_selected_build().filter(|_| self.show_details) else {
+            return;
+        };
+        let width = COLUMNS
+            .iter()
+            .map(|name| name.chars().count())
+            .max()
+            .unwrap_or_default();
+        let lines: Vec<Line> = COLUMNS
+            .iter()
+            .zip(self.cells(build, false, OffsetDateTime::now_utc()))
+            .map(|(name, value)| {
+                Line::from(vec![
+                    Span::styled(
+                        format!("{:<width$}  ", name, width = width),
+                        Style::default().fg(Color::Yellow),
+                    ),
+                    Span::raw(value),
+                ])
+            })
+            .collect();
+
+        let popup_width = DETAILS_POPUP_WIDTH.min(area.width);
+        let popup_height = (lines.len() as u16 + 2).min(area.height);
+        let popup_area = Rect {
+            x: area.x + (area.width.saturating_sub(popup_width)) / 2,
+            y: area.y + (area.height.saturating_sub(popup_height)) / 2,
+            width: popup_width,
+            height: popup_height,
+        };
+
+        let popup = Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(
+                    "#{} (i/Esc: Close)",
+                    build.build_number.as_deref().unwrap_or_default()
+                ))
+                .border_style(Style::default().fg(Color::Cyan)),
+        );
+
+        frame.render_widget(Clear, popup_area);
+        frame.render_widget(popup, popup_area);
+    }
+
+    fn render_changes(&self, frame: &mut Frame, area: Rect) {
+        let Some(build) = self.get_selected_build().filter(|_| self.show_changes) else {
+            return;
+        };
+        let changes = change_details(build);
+        let selected = self.change_index.min(changes.len().saturating_sub(1));
+        let lines: Vec<Line> = if changes.is_empty() {
+            vec![Line::from("No changes")]
         } else {
-            None
+            let width = changes
+                .iter()
+                .map(|(author, _)| author.chars().count())
+                .max()
+                .unwrap_or_default();
+            changes
+                .into_iter()
+                .enumerate()
+                .map(|(i, (author, comment))| {
+                    let line = Line::from(vec![
+                        Span::styled(
+                            format!("{:<width$}  ", author, width = width),
+                            Style::default().fg(Color::Cyan),
+                        ),
+                        Span::raw(comment),
+                    ]);
+                    if i == selected {
+                        line.style(Style::default().add_modifier(Modifier::REVERSED))
+                    } else {
+                        line
+                    }
+                })
+                .collect()
+        };
+
+        let popup_width = CHANGES_POPUP_WIDTH.min(area.width);
+        let popup_height = (lines.len() as u16 + 2).min(area.height);
+        let popup_area = Rect {
+            x: area.x + (area.width.saturating_sub(popup_width)) / 2,
+            y: area.y + (area.height.saturating_sub(popup_height)) / 2,
+            width: popup_width,
+            height: popup_height,
+        };
+
+        let popup = Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(
+                    "Changes in #{} (J/K: Pick, T: Queue on it, x/Esc: Close)",
+                    build.build_number.as_deref().unwrap_or_default()
+                ))
+                .border_style(Style::default().fg(Color::Cyan)),
+        );
+
+        frame.render_widget(Clear, popup_area);
+        frame.render_widget(popup, popup_area);
+    }
+
+    fn render_confirm(&self, frame: &mut Frame, area: Rect) {
+        let Some((prompt, _)) = &self.pending_bulk else {
+            return;
+        };
+
+        render_confirm_popup(frame, area, prompt);
+    }
+
+    fn handle_export_key(&mut self, key: KeyEvent) -> Action {
+        let Some(path) = &mut self.export_path else {
+            return Action::Render;
+        };
+        match key.code {
+            KeyCode::Esc => {
+                self.export_path = None;
+                Action::Render
+            }
+            KeyCode::Char(c) => {
+                path.push(c);
+                Action::Render
+            }
+            KeyCode::Backspace => {
+                path.pop();
+                Action::Render
+            }
+            KeyCode::Enter => {
+                let path = expand_path(path);
+                self.export_path = None;
+                self.export_to(&path)
+            }
+            _ => Action::Render,
         }
     }
 
-    fn open_selected_url(&mut self) {
-        if let Some(i) = self.table_state.selected() {
-            if let Some(bt) = self.get_items().get(i) {
-                if let Some(url) = &bt.web_url {
-                    let _ = open::that(url);
+    fn handle_grep_key(&mut self, key: KeyEvent) -> Action {
+        let Some(query) = &mut self.grep_input else {
+            return Action::Render;
+        };
+        match key.code {
+            KeyCode::Esc => {
+                self.grep_input = None;
+                Action::Render
+            }
+            KeyCode::Char(c) => {
+                query.push(c);
+                Action::Render
+            }
+            KeyCode::Backspace => {
+                query.pop();
+                Action::Render
+            }
+            KeyCode::Enter if query.trim().is_empty() => Action::Render,
+            KeyCode::Enter => {
+                let query = query.trim().to_string();
+                self.grep_input = None;
+                Action::GrepBuildLogs {
+                    project_id: self.build_type_id.clone(),
+                    title: self.title.clone(),
+                    query,
                 }
             }
+            _ => Action::Render,
         }
     }
 
-    fn select_build(&mut self, selected_string: String) {
-        if let Some((i, _)) = self.get_items().iter().enumerate().find(|(_, b)| {
-            let label = format!(
-                "#{} {}",
-                b.id.map(|x| x.to_string()).unwrap_or_default(),
-                b.build_number.clone().unwrap_or_default(),
-            );
-            label == selected_string
-        }) {
-            self.table_state.select(Some(i));
+    fn start_artifacts_download(&mut self) -> Action {
+        let Some(build) = self.get_selected_build() else {
+            return Action::Error("No build was selected".to_string());
+        };
+        if self.artifacts_download.is_some() {
+            return Action::Toast("Artifacts are already being downloaded".to_string());
         }
+        let dir = self.artifact_download_dir.display().to_string();
+        self.artifacts_prompt = Some((build.clone(), dir));
+        Action::Render
+    }
+
+    fn handle_artifacts_key(&mut self, key: KeyEvent) -> Action {
+        let Some((build, dir)) = &mut self.artifacts_prompt else {
+            return Action::Render;
+        };
+        match key.code {
+            KeyCode::Esc => {
+                self.artifacts_prompt = None;
+                Action::Render
+            }
+            KeyCode::Char(c) => {
+                dir.push(c);
+                Action::Render
+            }
+            KeyCode::Backspace => {
+                dir.pop();
+                Action::Render
+            }
+            KeyCode::Enter => {
+                let Some(build_id) = build.id else {
+                    self.artifacts_prompt = None;
+                    return Action::Error("No id was found for selected build".to_string());
+                };
+                self.artifacts_download = Some(ArtifactsDownload {
+                    build_id,
+                    number: build.build_number.clone().unwrap_or_default(),
+                    downloaded: 0,
+                    total: None,
+                });
+                let action = Action::DownloadArtifacts {
+                    build: build.clone(),
+                    dir: expand_path(dir),
+                };
+                self.artifacts_prompt = None;
+                action
+            }
+            _ => Action::Render,
+        }
+    }
+
+    fn render_artifacts_download(&self, frame: &mut Frame, area: Rect) {
+        let Some(download) = &self.artifacts_download else {
+            return;
+        };
+        let popup = Rect {
+            x: area.x + area.width.saturating_sub(60) / 2,
+            y: area.y + area.height.saturating_sub(5),
+            width: 60.min(area.width),
+            height: 3.min(area.height),
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Downloading artifacts of #{}", download.number));
+        frame.render_widget(Clear, popup);
+        match download.total {
+            Some(total) if total > 0 => {
+                let gauge = Gauge::default()
+                    .block(block)
+                    .gauge_style(Style::default().fg(Color::Cyan))
+                    .ratio((download.downloaded as f64 / total as f64).min(1.0))
+                    .label(format!(
+                        "{} / {}",
+                        format_size(download.downloaded),
+                        format_size(total)
+                    ));
+                frame.render_widget(gauge, popup);
+            }
+            _ => {
+                let text =
+                    Paragraph::new(format!("{} downloaded", format_size(download.downloaded)))
+                        .block(block);
+                frame.render_widget(text, popup);
+            }
+        }
+    }
+
+    /// Applies the filter typed so far as it is typed, unless it is invalid
+    fn handle_filter_key(&mut self, key: KeyEvent) -> Action {
+        let Some((text, _)) = &mut self.filter_input else {
+            return Action::Render;
+        };
+        match key.code {
+            KeyCode::Esc => {
+                self.filter_error = None;
+                if let Some((_, previous)) = self.filter_input.take() {
+                    self.filter = previous;
+                    self.refresh_items();
+                }
+                return Action::Render;
+            }
+            KeyCode::Enter if self.filter_error.is_some() => return Action::Render,
+            KeyCode::Enter => {
+                self.filter_input = None;
+                return Action::Render;
+            }
+            KeyCode::Char(c) => text.push(c),
+            KeyCode::Backspace => {
+                text.pop();
+            }
+            _ => return Action::Render,
+        }
+        let text = text.trim().to_string();
+        match FilterQuery::parse(&text, &FILTER_FIELDS) {
+            Ok(filter) => {
+                self.filter_error = None;
+                self.filter = (!text.is_empty()).then_some((text, filter));
+                self.refresh_items();
+            }
+            Err(e) => self.filter_error = Some(e),
+        }
+        Action::Render
+    }
+
+    fn handle_search_key(&mut self, key: KeyEvent) -> Action {
+        let Some(query) = &mut self.search_input else {
+            return Action::Render;
+        };
+        match key.code {
+            KeyCode::Esc => {
+                self.search_input = None;
+                Action::Render
+            }
+            KeyCode::Char(c) => {
+                query.push(c);
+                Action::Render
+            }
+            KeyCode::Backspace => {
+                query.pop();
+                Action::Render
+            }
+            KeyCode::Enter => {
+                let query = query.trim().to_string();
+                self.search_input = None;
+                match search_locator(&query) {
+                    Ok(locator) => Action::SearchBuilds {
+                        project_id: self.build_type_id.clone(),
+                        query,
+                        locator,
+                    },
+                    Err(e) => Action::Error(e),
+                }
+            }
+            _ => Action::Render,
+        }
+    }
+
+    /// Writes the visible builds to `path`
+    fn export_to(&self, path: &Path) -> Action {
+        let now = OffsetDateTime::now_utc();
+        let rows: Vec<Vec<String>> = self
+            .get_items()
+            .iter()
+            .map(|build| export_row(build, now))
+            .collect();
+        match export::write(path, &EXPORT_COLUMNS, &rows) {
+            Ok(_) => Action::Toast(format!(
+                "Exported {} builds to {}",
+                rows.len(),
+                path.display()
+            )),
+            Err(e) => Action::Error(format!("Failed to export to {}: {}", path.display(), e)),
+        }
+    }
+
+    /// Finished, unpinned builds that are old enough to have their artifacts cleaned up
+    fn get_cleanup_candidates(&self) -> Vec<Build> {
+        let cutoff =
+            OffsetDateTime::now_utc() - time::Duration::days(self.cleanup_older_than_days as i64);
+        self.get_items()
+            .iter()
+            .filter(|b| b.state.as_deref() == Some("finished"))
+            .filter(|b| b.pinned != Some(true))
+            .filter(|b| b.finish_date.is_some_and(|finish| finish < cutoff))
+            .cloned()
+            .collect()
+    }
+
+    fn handle_cleanup_key(&mut self, key: KeyEvent) -> Action {
+        match (&self.artifact_cleanup, key.code) {
+            (Some(ArtifactCleanup::Plan(plan)), KeyCode::Char('y')) if !plan.is_empty() => {
+                let plan = plan.clone();
+                self.artifact_cleanup = Some(ArtifactCleanup::Deleting);
+                Action::DeleteArtifacts { plan }
+            }
+            (Some(ArtifactCleanup::Deleting), _) => Action::Render,
+            (Some(ArtifactCleanup::Done { .. }), _) | (_, KeyCode::Esc | KeyCode::Char('n')) => {
+                self.artifact_cleanup = None;
+                Action::Render
+            }
+            _ => Action::Render,
+        }
+    }
+
+    fn render_artifact_cleanup(&self, frame: &mut Frame, area: Rect) {
+        let Some(cleanup) = &self.artifact_cleanup else {
+            return;
+        };
+
+        let mut lines: Vec<Line> = Vec::new();
+        match cleanup {
+            ArtifactCleanup::Loading => lines.push(Line::from("Collecting artifacts…")),
+            ArtifactCleanup::Plan(plan) if plan.is_empty() => {
+                lines.push(Line::from(format!(
+                    "No artifacts found in unpinned builds older than {} days",
+                    self.cleanup_older_than_days
+                )));
+                lines.push(Line::from(""));
+                lines.push(Line::from("Esc: Close").style(Style::default().fg(Color::DarkGray)));
+            }
+            ArtifactCleanup::Plan(plan) => {
+                lines.push(Line::from(
+                    "Dry run — the following artifacts will be deleted:",
+                ));
+                lines.push(Line::from(""));
+                for artifacts in plan {
+                    lines.push(Line::from(format!(
+                        "#{:<12} {:>10}  {}",
+                        artifacts.build_number.as_deref().unwrap_or_default(),
+                        format_size(artifacts.total_size),
+                        artifacts.paths.join(", ")
+                    )));
+                }
+                let total: u64 = plan.iter().map(|a| a.total_size).sum();
+                lines.push(Line::from(""));
+                lines.push(
+                    Line::from(format!(
+                        "{} builds, {} total",
+                        plan.len(),
+                        format_size(total)
+                    ))
+                    .style(Style::default().add_modifier(Modifier::BOLD)),
+                );
+                lines.push(
+                    Line::from("y: Delete  n/Esc: Cancel")
+                        .style(Style::default().fg(Color::DarkGray)),
+                );
+            }
+            ArtifactCleanup::Deleting => lines.push(Line::from("Deleting artifacts…")),
+            ArtifactCleanup::Done { deleted, failed } => {
+                lines.push(Line::from(format!("Deleted {} artifact paths", deleted)));
+                for failure in failed {
+                    lines.push(Line::from(failure.as_str()).style(Style::default().fg(Color::Red)));
+                }
+                lines.push(Line::from(""));
+                lines.push(
+                    Line::from("Press any key to close")
+                        .style(Style::default().fg(Color::DarkGray)),
+                );
+            }
+        }
+
+        let popup_width = 90.min(area.width);
+        let popup_height = (lines.len() as u16 + 2).min(area.height);
+        let popup_area = Rect {
+            x: area.x + (area.width.saturating_sub(popup_width)) / 2,
+            y: area.y + (area.height.saturating_sub(popup_height)) / 2,
+            width: popup_width,
+            height: popup_height,
+        };
+
+        let popup = Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Artifact cleanup")
+                .border_style(Style::default().fg(Color::Red)),
+        );
+
+        frame.render_widget(Clear, popup_area);
+        frame.render_widget(popup, popup_area);
+    }
+}
+
+impl FzfLabel for Build {
+    fn fzf_label(&self) -> Option<String> {
+        Some(format!(
+            "#{} {} [{}]",
+            self.id.unwrap_or_default(),
+            self.build_number.as_deref().unwrap_or_default(),
+            self.build_type_id.as_deref().unwrap_or_default()
+        ))
     }
 }
 
@@ -127,57 +1370,118 @@ impl Component for Builds {
         Ok(())
     }
 
-    fn register_config_handler(&mut self, _config: Config) -> color_eyre::Result<()> {
+    fn register_config_handler(&mut self, config: Config) -> color_eyre::Result<()> {
+        self.cleanup_older_than_days = config.artifact_cleanup.older_than_days;
+        self.time_display = config.time_display;
+        self.icons = config.icons;
+        self.artifact_download_dir = config.artifact_download_dir();
+        self.long_running_percent = config.long_running.threshold_percent;
+        self.stale_after = config.stale_after();
+        self.username = config.teamcity_username().map(str::to_string);
+        self.commit_url_template = config.commit_url_template.clone();
+        self.column_widths = column_widths(&config.column_widths);
+        self.active_filter = config.active_filter().cloned();
+        self.filters = config.filters;
+        self.custom_actions = config.custom_actions;
+        self.refresh_items();
         Ok(())
     }
 
     fn init(&mut self, _area: Size) -> color_eyre::Result<()> {
-        if !self.items.is_empty() {
-            self.table_state.select(Some(0));
-        }
+        self.refresh_items();
         Ok(())
     }
 
     fn handle_key_event(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>> {
+        if let Some(menu) = &mut self.context_menu {
+            return match menu.handle_key(&key) {
+                MenuEvent::Pending => Ok(Some(Action::Render)),
+                MenuEvent::Closed => {
+                    self.context_menu = None;
+                    Ok(Some(Action::Render))
+                }
+                MenuEvent::Picked(keys) => {
+                    self.context_menu = None;
+                    replay_keys(self, keys)
+                }
+            };
+        }
+        if self.artifact_cleanup.is_some() {
+            return Ok(Some(self.handle_cleanup_key(key)));
+        }
+        if self.pending_bulk.is_some() {
+            return Ok(Some(self.handle_confirm_key(key)));
+        }
+        if self.export_path.is_some() {
+            return Ok(Some(self.handle_export_key(key)));
+        }
+        if self.search_input.is_some() {
+            return Ok(Some(self.handle_search_key(key)));
+        }
+        if self.filter_input.is_some() {
+            return Ok(Some(self.handle_filter_key(key)));
+        }
+        if self.grep_input.is_some() {
+            return Ok(Some(self.handle_grep_key(key)));
+        }
+        if self.branch_prompt.is_some() {
+            return Ok(Some(self.handle_branch_key(key)));
+        }
+        if self.range_input.is_some() {
+            return Ok(Some(self.handle_range_key(key)));
+        }
+        if self.picking_range {
+            return Ok(Some(self.handle_range_picker_key(key)));
+        }
+        if self.artifacts_prompt.is_some() {
+            return Ok(Some(self.handle_artifacts_key(key)));
+        }
+        if self.picking_filter {
+            self.picking_filter = false;
+            return Ok(Some(handle_filter_picker_key(key.code, &self.filters)));
+        }
+        if self.show_changes
+            && let Some(action) = self.handle_changes_key(&key)
+        {
+            return Ok(Some(action));
+        }
+        if let Some(action) = self.custom_action(&key) {
+            return Ok(Some(action));
+        }
+
         self.last_events.push(key);
 
+        if let Some(previous_key) = self.last_events.iter().rev().nth(1)
+            && previous_key.code == KeyCode::Char('y')
+            && let Some(action) = self.yank_selected(key.code)
+        {
+            self.last_events.clear();
+            return Ok(Some(action));
+        }
+        if let Some(previous_key) = self.last_events.iter().rev().nth(1)
+            && previous_key.code == KeyCode::Char('O')
+            && let Some(action) = self.open_selected_page(key.code)
+        {
+            self.last_events.clear();
+            return Ok(Some(action));
+        }
+
+        if self.table.handle_navigation_key(&key) {
+            return Ok(Some(Action::Render));
+        }
+
         let action = match key.code {
-            KeyCode::Char('G') => {
-                self.move_end();
-                Action::Render
+            KeyCode::Char('l') | KeyCode::Right if key.modifiers.contains(KeyModifiers::ALT) => {
+                self.scroll_columns(true)
             }
-            KeyCode::Char('g') => {
-                if let Some(previous_key) = self.last_events.iter().rev().nth(1) {
-                    if previous_key.code == KeyCode::Char('g') {
-                        self.move_begin();
-                        self.last_events.clear()
-                    }
-                }
-                Action::Render
-            }
-            KeyCode::Char('j') | KeyCode::Down => {
-                self.move_down();
-                Action::Render
-            }
-            KeyCode::Char('k') | KeyCode::Up => {
-                self.move_up();
-                Action::Render
-            }
-            KeyCode::Char('f') => {
-                let items: Vec<String> = self
-                    .get_items()
-                    .iter()
-                    .map(|build| {
-                        format!(
-                            "#{} {} [{}]",
-                            build.id.unwrap_or_default(),
-                            build.build_number.clone().unwrap_or_default(),
-                            build.build_type_id.clone().unwrap_or_default()
-                        )
-                    })
-                    .collect();
-                Action::Fzf { options: items }
+            KeyCode::Char('h') | KeyCode::Left if key.modifiers.contains(KeyModifiers::ALT) => {
+                self.scroll_columns(false)
             }
+            KeyCode::Char('>') => self.resize_first_column(2),
+            KeyCode::Char('<') => self.resize_first_column(-2),
+            KeyCode::Char('f') => Action::Fzf {
+                options: self.table.fzf_options(),
+            },
             KeyCode::Char('l') => {
                 if let Some(selected_build) = self.get_selected_build() {
                     if let Some(build_id) = selected_build.id {
@@ -189,10 +1493,175 @@ impl Component for Builds {
                     Action::Error("No build was selected".to_string())
                 }
             }
+            KeyCode::Enter => match self.get_selected_build() {
+                Some(build) => Action::LoadLogViewer {
+                    project_id: self.build_type_id.clone(),
+                    title: self.title.clone(),
+                    build: build.clone(),
+                },
+                None => Action::Error("No build was selected".to_string()),
+            },
             KeyCode::Char('o') => {
                 self.open_selected_url();
                 Action::Render
             }
+            // waits for the page to open, see `open_selected_page`
+            KeyCode::Char('O') => Action::Render,
+            KeyCode::Char('r') => self.reload(),
+            KeyCode::Char('b') => {
+                self.branch_prompt = Some(BranchPrompt::default());
+                Action::LoadBranches {
+                    build_type_id: self.build_type_id.clone(),
+                }
+            }
+            KeyCode::Char('d') => {
+                self.picking_range = true;
+                Action::Render
+            }
+            _ if is_alternate_key(&key) => Action::ShowPreviousBuilds {
+                current: Some(self.build_type_id.clone()),
+            },
+            KeyCode::Char('\'') => Action::OpenRecent {
+                current: Some(self.build_type_id.clone()),
+            },
+            KeyCode::Char(c) if SortColumn::from_key(c).is_some() => {
+                if let Some(column) = SortColumn::from_key(c) {
+                    self.toggle_sort(column);
+                }
+                Action::Render
+            }
+            KeyCode::Char('c') => match self.get_selected_build() {
+                Some(build) => Action::LoadBuildChain {
+                    project_id: self.build_type_id.clone(),
+                    title: self.title.clone(),
+                    build: build.clone(),
+                },
+                None => Action::Error("No build was selected".to_string()),
+            },
+            KeyCode::Char('e') => self.toggle_expanded(),
+            KeyCode::Char('t') => match self.get_selected_build() {
+                Some(build) => Action::LoadTestFailures {
+                    build_type_id: self.build_type_id.clone(),
+                    title: self.title.clone(),
+                    build: build.clone(),
+                },
+                None => Action::Error("No build was selected".to_string()),
+            },
+            KeyCode::Char('p') => Action::LoadPendingChanges {
+                project_id: self.build_type_id.clone(),
+                title: self.title.clone(),
+            },
+            KeyCode::Char('/') => {
+                self.search_input = Some(String::new());
+                Action::Render
+            }
+            KeyCode::Char('&') => {
+                let text = self
+                    .filter
+                    .as_ref()
+                    .map(|(text, _)| text.clone())
+                    .unwrap_or_default();
+                self.filter_input = Some((text, self.filter.clone()));
+                Action::Render
+            }
+            KeyCode::Char('W') => {
+                self.grep_input = Some(String::new());
+                Action::Render
+            }
+            KeyCode::Char('w') => match self.get_selected_build() {
+                Some(build) if build.state.as_deref() == Some("running") => {
+                    Action::ToggleWatchBuild {
+                        build: build.clone(),
+                    }
+                }
+                Some(_) => Action::Error("Only running builds can be watched".to_string()),
+                None => Action::Error("No build was selected".to_string()),
+            },
+            KeyCode::Char('E') => {
+                self.export_path = Some(format!("{}-builds.csv", self.build_type_id));
+                Action::Render
+            }
+            KeyCode::Char('S') => Action::LoadStatistics {
+                project_id: self.build_type_id.clone(),
+                title: self.title.clone(),
+            },
+            KeyCode::Char('A') => self.start_artifacts_download(),
+            KeyCode::Char('D') => {
+                self.artifact_cleanup = Some(ArtifactCleanup::Loading);
+                Action::PlanArtifactCleanup {
+                    builds: self.get_cleanup_candidates(),
+                }
+            }
+            KeyCode::Char(' ') => {
+                self.toggle_mark_selected();
+                self.table.move_down();
+                Action::Render
+            }
+            KeyCode::Char('m') if self.username.is_none() => Action::Error(
+                "Set teamcity_username in config.toml to filter my builds".to_string(),
+            ),
+            KeyCode::Char('m') => {
+                self.mine_only = !self.mine_only;
+                self.refresh_items();
+                Action::Render
+            }
+            KeyCode::Char('v') => {
+                self.toggle_visual();
+                Action::Render
+            }
+            KeyCode::Char('P') => {
+                self.preview = !self.preview;
+                self.preview_build = None;
+                self.preview_candidate = None;
+                Action::Render
+            }
+            KeyCode::Char('F') => {
+                self.picking_filter = true;
+                Action::Render
+            }
+            KeyCode::Char('C') => {
+                self.confirm_bulk("Cancel", |builds| Action::CancelBuilds { builds })
+            }
+            KeyCode::Char('R') => {
+                self.confirm_bulk("Re-trigger", |builds| Action::RerunBuilds { builds })
+            }
+            KeyCode::Char('L') => {
+                let builds = self.get_bulk_targets();
+                self.clear_marks();
+                Action::DownloadBuildLogs { builds }
+            }
+            MENU_KEY => {
+                self.context_menu = Some(self.context_menu());
+                Action::Render
+            }
+            KeyCode::Char('x') => {
+                self.show_changes = !self.show_changes;
+                self.change_index = 0;
+                Action::Render
+            }
+            KeyCode::Esc if self.show_changes => {
+                self.show_changes = false;
+                Action::Render
+            }
+            KeyCode::Char('i') => {
+                self.show_details = !self.show_details;
+                Action::Render
+            }
+            KeyCode::Esc if self.show_details => {
+                self.show_details = false;
+                Action::Render
+            }
+            KeyCode::Esc if self.visual_anchor.is_some() || !self.marked.is_empty() => {
+                self.clear_marks();
+                Action::Render
+            }
+            KeyCode::Esc if self.filter.is_some() => {
+                self.filter = None;
+                self.refresh_items();
+                Action::Render
+            }
+            // back to the builds loaded when opening the configuration
+            KeyCode::Esc if self.search.is_some() => self.reload(),
             KeyCode::Esc | KeyCode::Char('h') => Action::ShowProjects,
             _ => Action::Render,
         };
@@ -202,13 +1671,131 @@ impl Component for Builds {
     fn update(&mut self, action: Action) -> color_eyre::Result<Option<Action>> {
         match action {
             Action::FzfSelected { selected } => {
-                self.select_build(selected);
+                self.table.select_fzf(&selected);
+            }
+            Action::SearchResults {
+                project_id,
+                query,
+                items,
+            } if project_id == self.build_type_id => {
+                self.all_items = items;
+                self.fetched_at = Some(OffsetDateTime::now_utc());
+                self.search = Some(query);
+                self.clear_marks();
+                self.refresh_items();
+                return Ok(Some(Action::Render));
+            }
+            Action::Tick => {
+                if let Some(toast) = self.report_long_running() {
+                    return Ok(Some(toast));
+                }
+                if self.preview {
+                    return Ok(self.update_preview());
+                }
+                if self.get_items().iter().any(is_running) {
+                    self.spinner_frame = self.spinner_frame.wrapping_add(1);
+                    return Ok(Some(Action::Render));
+                }
+            }
+            Action::ShowBranches {
+                build_type_id,
+                branches,
+            } if build_type_id == self.build_type_id => {
+                if let Some(prompt) = &mut self.branch_prompt {
+                    prompt.branches = Some(branches);
+                    return Ok(Some(Action::Render));
+                }
+            }
+            Action::ShowBuildPreview {
+                build_id,
+                lines,
+                statistics,
+            } => {
+                self.previews.insert(build_id, (lines, statistics));
+                return Ok(Some(Action::Render));
+            }
+            Action::ShowCompositeBuilds { build_id, builds } => {
+                if !self.expanded.contains_key(&build_id) {
+                    return Ok(None);
+                }
+                return Ok(Some(match builds {
+                    Ok(builds) if builds.is_empty() => {
+                        self.expanded.remove(&build_id);
+                        Action::Toast("The composite build aggregated no builds".to_string())
+                    }
+                    Ok(builds) => {
+                        self.expanded.insert(build_id, Some(builds));
+                        self.refresh_items();
+                        Action::Render
+                    }
+                    Err(e) => {
+                        self.expanded.remove(&build_id);
+                        Action::Error(format!("Failed to fetch aggregated builds: {}", e))
+                    }
+                }));
+            }
+            // a plan arriving after the popup was closed is dropped rather than reopening it
+            Action::ShowArtifactCleanup { plan }
+                if matches!(self.artifact_cleanup, Some(ArtifactCleanup::Loading)) =>
+            {
+                self.artifact_cleanup = Some(ArtifactCleanup::Plan(plan));
+                return Ok(Some(Action::Render));
+            }
+            Action::ArtifactsDownloadProgress {
+                build_id,
+                downloaded,
+                total,
+            } => {
+                if let Some(download) = &mut self.artifacts_download
+                    && download.build_id == build_id
+                {
+                    download.downloaded = downloaded;
+                    download.total = total;
+                    return Ok(Some(Action::Render));
+                }
+            }
+            Action::ArtifactsDownloadFinished { build_id } => {
+                if self
+                    .artifacts_download
+                    .as_ref()
+                    .is_some_and(|download| download.build_id == build_id)
+                {
+                    self.artifacts_download = None;
+                    return Ok(Some(Action::Render));
+                }
+            }
+            Action::ArtifactsDeleted { deleted, failed } => {
+                self.artifact_cleanup = Some(ArtifactCleanup::Done { deleted, failed });
+                return Ok(Some(Action::Render));
             }
             _ => {}
         }
         Ok(None)
     }
 
+    fn tab_title(&self) -> String {
+        self.title.clone()
+    }
+
+    fn is_typing(&self) -> bool {
+        self.export_path.is_some()
+            || self.search_input.is_some()
+            || self.filter_input.is_some()
+            || self.grep_input.is_some()
+            || self.branch_prompt.is_some()
+            || self.artifacts_prompt.is_some()
+            || self.range_input.is_some()
+    }
+
+    fn session(&self) -> Option<Session> {
+        Some(Session {
+            build_type_id: Some(self.build_type_id.clone()),
+            title: Some(self.title.clone()),
+            selected_build: self.get_selected_build().and_then(|b| b.id),
+            ..Session::default()
+        })
+    }
+
     fn draw(&mut self, frame: &mut Frame, area: Rect) -> color_eyre::Result<()> {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
@@ -219,14 +1806,37 @@ impl Component for Builds {
             ])
             .split(area);
 
-        let header = Row::new(vec![
-            "Number",
-            "Branch",
-            "Status",
-            "Last Changes",
-            "Start time",
-            "Duration",
-        ])
+        let table_area = if self.preview {
+            let [table_area, preview_area] =
+                Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)])
+                    .areas(chunks[0]);
+            self.render_preview(frame, preview_area);
+            table_area
+        } else {
+            chunks[0]
+        };
+        let columns = visible_columns(table_area.width, self.first_column);
+
+        // the status icon stays in view when the columns are scrolled
+        let header = Row::new(
+            std::iter::once(String::new()).chain(columns.iter().map(|&i| {
+                let name = COLUMNS[i];
+                match self.sort {
+                    Some((column, descending)) if column.index() == i => {
+                        format!(
+                            "{} {}",
+                            name,
+                            if descending {
+                                self.icons.arrow_down()
+                            } else {
+                                self.icons.arrow_up()
+                            }
+                        )
+                    }
+                    _ => name.to_string(),
+                }
+            })),
+        )
         .style(
             Style::default()
                 .fg(Color::Yellow)
@@ -235,121 +1845,125 @@ impl Component for Builds {
         .height(1)
         .bottom_margin(1);
 
+        let now = OffsetDateTime::now_utc();
         let rows: Vec<Row> = self
             .get_items()
-            .into_iter()
-            .map(|build| {
-                let number = build.build_number.as_deref().unwrap_or_default();
-                let branch = build.branch_name.as_deref().unwrap_or_default();
-                let status_text = build
-                    .status_text
-                    .clone()
-                    .or(build.status.clone())
-                    .unwrap_or_default();
-
-                let changes = build
-                    .changes
-                    .as_ref()
-                    .and_then(|c| c.change.clone())
-                    .unwrap_or_default();
-
-                let last_changes = if !changes.is_empty() {
-                    let users: Vec<&String> =
-                        changes.iter().filter_map(|c| c.username.as_ref()).collect();
-                    if users.len() == 0 {
-                        format!("⚠️ {} Changes from 0 users", changes.len())
-                    } else if users.len() == 1 {
-                        format!("{}: {}", users[0], changes.len())
-                    } else {
-                        format!("{} Changes", changes.len())
-                    }
-                } else {
-                    "No changes".to_string()
-                };
-
-                let start_datetime = build
-                    .start_date
-                    .as_ref()
-                    .and_then(|s| format_datetime_to_human_readable_string(s).ok())
-                    .unwrap_or_default();
-
-                let duration = {
-                    if let Some(ref start) = build.start_date {
-                        let start_epoch = parse_tc_datetime_to_epoch(start);
-                        let end_epoch = if let Some(ref finish) = build.finish_date {
-                            parse_tc_datetime_to_epoch(finish)
-                        } else {
-                            std::time::SystemTime::now()
-                                .duration_since(std::time::UNIX_EPOCH)
-                                .map(|d| d.as_secs() as i64)
-                                .map_err(|e| anyhow!(e))
-                        };
-
-                        match (start_epoch, end_epoch) {
-                            (Ok(s), Ok(e)) if e >= s => format_duration(e - s),
-                            _ => Ok(String::new()),
-                        }
-                    } else {
-                        Ok(String::new())
-                    }
-                };
-
-                let mut row = Row::new(vec![
-                    number.to_string(),
-                    branch.to_string(),
-                    status_text,
-                    last_changes,
-                    start_datetime,
-                    duration.unwrap_or_default(),
-                ]);
-
-                // if build status is None then it's in queue state
-                let is_failed = if let Some(status) = build.status.as_deref() {
-                    match status {
-                        "FAILURE" | "UNKNOWN" => { true }
-                        _ => false,
-                    }
+            .iter()
+            .enumerate()
+            .map(|(i, build)| {
+                let marked = self.is_marked(i, build);
+                let cells = self.cells(build, marked, now);
+                let status_style = if is_queued(build) {
+                    Style::default()
+                        .fg(Color::DarkGray)
+                        .add_modifier(Modifier::ITALIC)
+                } else if is_failed(build) {
+                    Style::default().fg(Color::Red)
+                } else if is_running(build) {
+                    Style::default().fg(Color::Yellow)
+                } else if build.status.as_deref() == Some("SUCCESS") {
+                    Style::default().fg(Color::Green)
                 } else {
-                    false
+                    Style::default()
                 };
-
-                if is_failed {
-                    row = row.style(Style::default().fg(Color::Red));
+                let icon = Cell::from(self.icons.build_status(build, self.spinner_frame))
+                    .style(status_style);
+                // the status color goes first, the highlights of long-running and marked builds
+                // are laid over it
+                let mut row_style = status_style;
+                if self.is_long_running(build, now) {
+                    row_style = row_style.fg(Color::Magenta);
                 }
-                row
+                if marked {
+                    row_style = row_style.fg(Color::Cyan).add_modifier(Modifier::BOLD);
+                }
+                Row::new(
+                    std::iter::once(icon)
+                        .chain(columns.iter().map(|&i| Cell::from(cells[i].clone()))),
+                )
+                .style(row_style)
             })
             .collect();
 
-        let table = Table::new(
-            rows,
-            &[
-                Constraint::Max(20),    // Number
-                Constraint::Length(30), // Branch
-                Constraint::Min(20),    // Status text
-                Constraint::Max(40),    // Last Changes
-                Constraint::Length(13), // Start time (HH:MM)
-                Constraint::Length(9),  // Duration (M:SS or H:MM:SS)
-            ],
-        )
-        .header(header)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title(format!("Builds — {}", self.title)),
-        )
-        .column_spacing(1)
-        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
-        .highlight_symbol(">> ");
+        // the status column takes the space left over
+        let widths = columns.iter().map(|&i| {
+            if i == SortColumn::Status.index() {
+                Constraint::Min(self.column_widths[i])
+            } else {
+                Constraint::Length(self.column_widths[i])
+            }
+        });
+        let widths = std::iter::once(Constraint::Length(2)).chain(widths);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(self.table_title())
+            .title_top(freshness_line(self.fetched_at, self.stale_after));
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(block.clone())
+            .column_spacing(1)
+            .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .highlight_symbol(">> ");
 
-        frame.render_stateful_widget(table, chunks[0], &mut self.table_state);
+        self.table.set_viewport(table_area);
+        if self.fetched_at.is_some() && self.get_items().is_empty() {
+            self.render_empty(frame, table_area, block);
+        } else {
+            frame.render_stateful_widget(table, table_area, &mut self.table.state);
+        }
 
         let footer = Paragraph::new(
-            "j/k: Move  gg/G: Top/Bottom  f: Fuzzy  l: Log  o: Open URL  h/Esc: Back"
+            "j/k: Move  gg/G: Top/Bottom  1-6: Sort  f: Fuzzy  /: Search server  &: Filter  W: Grep logs  w: Watch  .: Menu  Enter/l: Log/Log in pager  t: Failed tests  p: Pending changes  c: Chain  e: Expand composite  o: Open URL  Oo/Ol/Ox/Ot/Od/Op/Oc: Open overview/log/changes/tests/dependencies/parameters/commit  yy/yi/yn/yr: Copy URL/ID/number/revision  x: Changes  i: Details  m: Mine  b: Branch  d: Dates  P: Preview  F: Filters  Space/v: Mark  C/R/L: Cancel/Re-run/Save logs  Alt-h/l: Scroll columns  </>: Resize column  E: Export  S: Statistics  A: Download artifacts  D: Clean up artifacts  r: Refresh  Ctrl-^: Previous config  ': Recent  h/Esc: Back",
         )
-            .style(Style::default().fg(Color::DarkGray))
-            .block(Block::default().padding(Padding::horizontal(1)));
+        .wrap(Wrap { trim: true })
+        .style(Style::default().fg(Color::DarkGray))
+        .block(Block::default().padding(Padding::horizontal(1)));
 
         frame.render_widget(footer, chunks[1]);
+
+        self.render_artifacts_download(frame, chunks[0]);
+        self.render_artifact_cleanup(frame, area);
+        self.render_changes(frame, chunks[0]);
+        self.render_details(frame, chunks[0]);
+        self.render_confirm(frame, area);
+        if let Some(path) = &self.export_path {
+            render_prompt(frame, area, EXPORT_PROMPT_TITLE, path);
+        }
+        if let Some(query) = &self.search_input {
+            render_prompt(frame, area, SEARCH_PROMPT_TITLE, query);
+        }
+        if let Some((text, _)) = &self.filter_input {
+            let title = match &self.filter_error {
+                Some(error) => format!("Filter — {} (Esc: Cancel)", error),
+                None => FILTER_PROMPT_TITLE.to_string(),
+            };
+            render_prompt(frame, area, &title, text);
+        }
+        if let Some(query) = &self.grep_input {
+            render_prompt(frame, area, GREP_PROMPT_TITLE, query);
+        }
+        if let Some(prompt) = &self.branch_prompt {
+            prompt.render(frame, area, BRANCH_PROMPT_TITLE);
+        }
+        if let Some((_, dir)) = &self.artifacts_prompt {
+            render_prompt(frame, area, ARTIFACTS_PROMPT_TITLE, dir);
+        }
+        if self.picking_filter {
+            render_filter_picker(frame, area, &self.filters, self.active_filter.as_ref());
+        }
+        if self.picking_range {
+            render_date_range_picker(frame, area, self.range.as_ref());
+        }
+        if let Some(input) = &self.range_input {
+            let title = match &self.range_error {
+                Some(error) => format!("Date range — {} (Esc: Cancel)", error),
+                None => RANGE_PROMPT_TITLE.to_string(),
+            };
+            render_prompt(frame, area, &title, input);
+        }
+        if let Some(menu) = &mut self.context_menu {
+            menu.render(frame, area);
+        }
         Ok(())
     }
 }
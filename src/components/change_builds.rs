@@ -0,0 +1,260 @@
+use super::Component;
+use super::freshness::freshness_line;
+use super::selectable_table::SelectableTable;
+use crate::action::Action;
+use crate::config::Config;
+use crate::icons::IconSet;
+use crate::time::{TimeDisplay, format_datetime};
+use crate::view::builds::{is_failed, status_text};
+use crate::view::change_builds::first_built_in;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::{Block, Borders, Padding, Paragraph, Row, Table};
+use std::collections::HashMap;
+use teamcity::types::{Build, BuildType};
+use time::{Duration, OffsetDateTime};
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Builds of every configuration including a commit, with the one each configuration first built
+/// it in marked
+pub struct ChangeBuilds {
+    // commit SHA or change ID as it was typed
+    revision: String,
+    // by configuration name, newest first
+    builds: SelectableTable<Build>,
+    // build the change was first built in, by configuration ID
+    first: HashMap<String, i64>,
+    build_type_names: HashMap<String, String>,
+    // None while loading
+    fetched_at: Option<OffsetDateTime>,
+    stale_after: Duration,
+    time_display: TimeDisplay,
+    icons: IconSet,
+    pub action_tx: Option<UnboundedSender<Action>>,
+}
+
+impl ChangeBuilds {
+    /// Empty list shown while the builds are being looked up
+    pub fn loading(revision: String, build_types: &[BuildType]) -> Self {
+        let mut change_builds = Self::new(revision, vec![], &[], build_types);
+        change_builds.fetched_at = None;
+        change_builds
+    }
+
+    /// `first_built_in` are the IDs of the builds the server reports the change was first built in
+    pub fn new(
+        revision: String,
+        mut builds: Vec<Build>,
+        first_built_in_ids: &[i64],
+        build_types: &[BuildType],
+    ) -> Self {
+        let build_type_names: HashMap<String, String> = build_types
+            .iter()
+            .map(|bt| (bt.id.clone(), bt.name.clone()))
+            .collect();
+        let name = |build: &Build| {
+            let id = build.build_type_id.as_deref().unwrap_or_default();
+            build_type_names
+                .get(id)
+                .cloned()
+                .unwrap_or_else(|| id.to_string())
+        };
+        builds.sort_by(|a, b| {
+            name(a)
+                .cmp(&name(b))
+                .then_with(|| b.start_date.cmp(&a.start_date))
+        });
+
+        Self {
+            revision,
+            first: first_built_in(&builds, first_built_in_ids),
+            builds: SelectableTable::new(builds),
+            build_type_names,
+            fetched_at: Some(OffsetDateTime::now_utc()),
+            stale_after: Duration::ZERO,
+            time_display: TimeDisplay::default(),
+            icons: IconSet::default(),
+            action_tx: None,
+        }
+    }
+
+    fn configuration_name(&self, build: &Build) -> String {
+        let id = build.build_type_id.as_deref().unwrap_or_default();
+        self.build_type_names
+            .get(id)
+            .cloned()
+            .unwrap_or_else(|| id.to_string())
+    }
+
+    fn is_first(&self, build: &Build) -> bool {
+        build
+            .build_type_id
+            .as_ref()
+            .and_then(|id| self.first.get(id))
+            .is_some_and(|first| Some(*first) == build.id)
+    }
+
+    /// Builds view of the selected build's configuration, narrowed down to the builds including
+    /// the change
+    fn open_selected(&self) -> Action {
+        let Some(build) = self.builds.selected() else {
+            return Action::Error("No build was selected".to_string());
+        };
+        let Some(build_type_id) = build.build_type_id.clone() else {
+            return Action::Error("No configuration was found for selected build".to_string());
+        };
+        Action::ShowBuildsWithChange {
+            title: self.configuration_name(build),
+            revision: self.revision.clone(),
+            items: self
+                .builds
+                .items()
+                .iter()
+                .filter(|b| b.build_type_id.as_ref() == Some(&build_type_id))
+                .cloned()
+                .collect(),
+            selected: build.id,
+            project_id: build_type_id,
+        }
+    }
+}
+
+impl Component for ChangeBuilds {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> color_eyre::Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> color_eyre::Result<()> {
+        self.time_display = config.time_display;
+        self.stale_after = config.stale_after();
+        self.icons = config.icons;
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>> {
+        if self.builds.handle_navigation_key(&key) {
+            return Ok(Some(Action::Render));
+        }
+        let action = match key.code {
+            KeyCode::Enter | KeyCode::Char('l') => self.open_selected(),
+            KeyCode::Char('o') => {
+                if let Some(url) = self.builds.selected().and_then(|b| b.web_url.as_ref()) {
+                    let _ = open::that(url);
+                }
+                Action::Render
+            }
+            KeyCode::Char('r') => Action::LoadChangeBuilds {
+                revision: self.revision.clone(),
+            },
+            KeyCode::Esc | KeyCode::Char('h') => Action::ShowProjects,
+            _ => Action::Render,
+        };
+        Ok(Some(action))
+    }
+
+    fn tab_title(&self) -> String {
+        format!("Change — {}", self.revision)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> color_eyre::Result<()> {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(0),    // Table takes remaining space
+                Constraint::Length(2), // Footer height
+            ])
+            .split(area);
+
+        let header = Row::new(vec![
+            "",
+            "Configuration",
+            "Number",
+            "Branch",
+            "Status",
+            "Started",
+        ])
+        .style(
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )
+        .height(1)
+        .bottom_margin(1);
+
+        let rows: Vec<Row> = if self.fetched_at.is_none() {
+            vec![Row::new(vec!["", "Looking up builds…"])]
+        } else if self.builds.items().is_empty() {
+            vec![Row::new(vec!["", "No build includes this change"])]
+        } else {
+            self.builds
+                .items()
+                .iter()
+                .map(|build| {
+                    let first = self.is_first(build);
+                    let started = build
+                        .start_date
+                        .and_then(|date| format_datetime(&date, self.time_display).ok())
+                        .unwrap_or_default();
+                    let style = if is_failed(build) {
+                        Style::default().fg(Color::Red)
+                    } else if build.state.as_deref() == Some("running") {
+                        Style::default().fg(Color::Yellow)
+                    } else {
+                        Style::default()
+                    };
+                    Row::new(vec![
+                        if first { self.icons.mark() } else { "" }.to_string(),
+                        self.configuration_name(build),
+                        build.build_number.clone().unwrap_or_default(),
+                        build.branch_name.clone().unwrap_or_default(),
+                        status_text(build),
+                        started,
+                    ])
+                    .style(if first {
+                        style.add_modifier(Modifier::BOLD)
+                    } else {
+                        style
+                    })
+                })
+                .collect()
+        };
+
+        let table = Table::new(
+            rows,
+            &[
+                Constraint::Length(2),  // First built in
+                Constraint::Min(20),    // Configuration
+                Constraint::Max(20),    // Number
+                Constraint::Length(30), // Branch
+                Constraint::Min(20),    // Status text
+                Constraint::Length(20), // Started
+            ],
+        )
+        .header(header)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Builds including {}", self.revision))
+                .title_top(freshness_line(self.fetched_at, self.stale_after)),
+        )
+        .column_spacing(1)
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol(">> ");
+
+        self.builds.set_viewport(chunks[0]);
+        frame.render_stateful_widget(table, chunks[0], &mut self.builds.state);
+
+        let footer = Paragraph::new(format!(
+            "{}: First built in  j/k: Move  Enter/l: Open in Builds  o: Open URL  r: Refresh  h/Esc: Back",
+            self.icons.mark()
+        ))
+        .style(Style::default().fg(Color::DarkGray))
+        .block(Block::default().padding(Padding::horizontal(1)));
+
+        frame.render_widget(footer, chunks[1]);
+        Ok(())
+    }
+}
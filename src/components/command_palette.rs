@@ -0,0 +1,103 @@
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{Frame, layout::Rect};
+
+use super::Component;
+use super::prompt::{render_completions, render_prompt};
+use crate::action::Action;
+use crate::view::command::{COMMANDS, command_completions};
+
+const PALETTE_TITLE: &str = "Command (Tab: Complete, ↑/↓: History, Enter: Run, Esc: Cancel)";
+
+/// Prompt for typed commands like `builds MyConfig`, opened with `:` on top of the current view
+#[derive(Default)]
+pub struct CommandPalette {
+    // None while closed
+    input: Option<String>,
+    // commands run before, oldest first
+    history: Vec<String>,
+    // entry of `history` shown in the prompt while browsing it with ↑/↓
+    history_index: Option<usize>,
+}
+
+impl CommandPalette {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.input.is_some()
+    }
+
+    fn browse_history(&mut self, back: bool) {
+        if self.history.is_empty() {
+            return;
+        }
+        let index = match (self.history_index, back) {
+            (None, true) => Some(self.history.len() - 1),
+            (None, false) => None,
+            (Some(i), true) => Some(i.saturating_sub(1)),
+            (Some(i), false) => (i + 1 < self.history.len()).then_some(i + 1),
+        };
+        self.history_index = index;
+        self.input = Some(index.map(|i| self.history[i].clone()).unwrap_or_default());
+    }
+}
+
+impl Component for CommandPalette {
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        let Some(input) = &mut self.input else {
+            return Ok(None);
+        };
+        match key.code {
+            KeyCode::Esc => self.input = None,
+            KeyCode::Enter => {
+                let command = input.trim().to_string();
+                self.input = None;
+                self.history_index = None;
+                if command.is_empty() {
+                    return Ok(Some(Action::Render));
+                }
+                self.history.retain(|c| *c != command);
+                self.history.push(command.clone());
+                return Ok(Some(Action::RunCommand { command }));
+            }
+            KeyCode::Tab if !input.contains(' ') => {
+                let matching: Vec<&str> = COMMANDS
+                    .iter()
+                    .map(|(name, _)| *name)
+                    .filter(|name| name.starts_with(input.as_str()))
+                    .collect();
+                if let [name] = matching.as_slice() {
+                    *input = format!("{} ", name);
+                }
+            }
+            KeyCode::Up => self.browse_history(true),
+            KeyCode::Down => self.browse_history(false),
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Char(c) => input.push(c),
+            _ => {}
+        }
+        Ok(Some(Action::Render))
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        if action == Action::OpenCommandPalette {
+            self.input = Some(String::new());
+            self.history_index = None;
+            return Ok(Some(Action::Render));
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let Some(input) = &self.input else {
+            return Ok(());
+        };
+        render_prompt(frame, area, PALETTE_TITLE, &format!(":{}", input));
+        render_completions(frame, area, &command_completions(input), None);
+        Ok(())
+    }
+}
@@ -0,0 +1,230 @@
+use super::Component;
+use super::freshness::freshness_line;
+use super::selectable_table::SelectableTable;
+use crate::action::Action;
+use crate::config::Config;
+use crate::view::agent_requirements::{available, describe_requirement};
+use crate::view::server_info::agent_state;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Padding, Paragraph, Row, Table};
+use teamcity::AgentCompatibility;
+use teamcity::types::{Agent, AgentRequirement};
+use time::{Duration, OffsetDateTime};
+use tokio::sync::mpsc::UnboundedSender;
+
+// requirement rows shown before the agents table takes the rest of the screen
+const MAX_REQUIREMENT_ROWS: u16 = 12;
+
+/// Agent requirements of a configuration and the agents that meet them, to find out why its
+/// builds wait for a compatible agent
+pub struct CompatibleAgents {
+    build_type_id: String,
+    title: String,
+    requirements: Vec<AgentRequirement>,
+    // compatible agents first, each with whether it is compatible
+    agents: SelectableTable<(bool, Agent)>,
+    // None while loading
+    fetched_at: Option<OffsetDateTime>,
+    stale_after: Duration,
+    pub action_tx: Option<UnboundedSender<Action>>,
+}
+
+impl CompatibleAgents {
+    pub fn new(
+        build_type_id: String,
+        title: String,
+        compatibility: Option<AgentCompatibility>,
+    ) -> Self {
+        let fetched_at = compatibility.is_some().then(OffsetDateTime::now_utc);
+        let compatibility = compatibility.unwrap_or(AgentCompatibility {
+            requirements: vec![],
+            compatible: vec![],
+            incompatible: vec![],
+        });
+        let agents = compatibility
+            .compatible
+            .into_iter()
+            .map(|agent| (true, agent))
+            .chain(
+                compatibility
+                    .incompatible
+                    .into_iter()
+                    .map(|agent| (false, agent)),
+            )
+            .collect();
+        Self {
+            build_type_id,
+            title,
+            requirements: compatibility.requirements,
+            agents: SelectableTable::new(agents),
+            fetched_at,
+            stale_after: Duration::ZERO,
+            action_tx: None,
+        }
+    }
+
+    fn summary(&self) -> String {
+        let compatible: Vec<Agent> = self
+            .agents
+            .items()
+            .iter()
+            .filter(|(compatible, _)| *compatible)
+            .map(|(_, agent)| agent.clone())
+            .collect();
+        format!(
+            "{} of {} agents compatible, {} of them available",
+            compatible.len(),
+            self.agents.items().len(),
+            available(&compatible)
+        )
+    }
+
+    fn render_requirements(&self, frame: &mut Frame, area: Rect) {
+        let lines: Vec<Line> = if self.fetched_at.is_none() {
+            vec![Line::from("Loading agent requirements…")]
+        } else if self.requirements.is_empty() {
+            vec![Line::from(
+                "No agent requirements, any agent of the project's pools can run it",
+            )]
+        } else {
+            self.requirements
+                .iter()
+                .map(|requirement| {
+                    let line = Line::from(describe_requirement(requirement));
+                    if requirement.disabled == Some(true) {
+                        line.style(
+                            Style::default()
+                                .fg(Color::DarkGray)
+                                .add_modifier(Modifier::CROSSED_OUT),
+                        )
+                    } else {
+                        line
+                    }
+                })
+                .collect()
+        };
+        let requirements = Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Agent requirements — {}", self.title))
+                .title_top(freshness_line(self.fetched_at, self.stale_after)),
+        );
+        frame.render_widget(requirements, area);
+    }
+}
+
+impl Component for CompatibleAgents {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> color_eyre::Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> color_eyre::Result<()> {
+        self.stale_after = config.stale_after();
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>> {
+        if self.agents.handle_navigation_key(&key) {
+            return Ok(Some(Action::Render));
+        }
+        let action = match key.code {
+            KeyCode::Enter | KeyCode::Char('l') => Action::LoadBuilds {
+                project_id: self.build_type_id.clone(),
+                title: self.title.clone(),
+            },
+            KeyCode::Char('r') => Action::LoadAgentCompatibility {
+                build_type_id: self.build_type_id.clone(),
+                title: self.title.clone(),
+            },
+            KeyCode::Esc | KeyCode::Char('h') => Action::ShowProjects,
+            _ => Action::Render,
+        };
+        Ok(Some(action))
+    }
+
+    fn tab_title(&self) -> String {
+        format!("Agents — {}", self.title)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> color_eyre::Result<()> {
+        let requirement_rows = (self.requirements.len() as u16).clamp(1, MAX_REQUIREMENT_ROWS);
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(requirement_rows + 2), // Requirements height
+                Constraint::Min(0),                       // Agents take remaining space
+                Constraint::Length(2),                    // Footer height
+            ])
+            .split(area);
+
+        self.render_requirements(frame, chunks[0]);
+
+        let header = Row::new(vec!["Agent", "Pool", "State", "Compatible"])
+            .style(
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .height(1)
+            .bottom_margin(1);
+
+        let rows: Vec<Row> = self
+            .agents
+            .items()
+            .iter()
+            .map(|(compatible, agent)| {
+                let state = agent_state(agent);
+                let color = match (compatible, state) {
+                    (false, _) => Color::Red,
+                    (true, "idle" | "busy") => Color::Green,
+                    (true, _) => Color::DarkGray,
+                };
+                Row::new(vec![
+                    agent.name.clone().unwrap_or_default(),
+                    agent
+                        .pool
+                        .as_ref()
+                        .and_then(|pool| pool.name.clone())
+                        .unwrap_or_default(),
+                    state.to_string(),
+                    if *compatible { "yes" } else { "no" }.to_string(),
+                ])
+                .style(Style::default().fg(color))
+            })
+            .collect();
+
+        let table = Table::new(
+            rows,
+            &[
+                Constraint::Min(30),    // Agent
+                Constraint::Min(20),    // Pool
+                Constraint::Length(12), // State
+                Constraint::Length(10), // Compatible
+            ],
+        )
+        .header(header)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Agents — {}", self.summary())),
+        )
+        .column_spacing(1)
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol(">> ");
+
+        self.agents.set_viewport(chunks[1]);
+        frame.render_stateful_widget(table, chunks[1], &mut self.agents.state);
+
+        let footer = Paragraph::new("j/k: Move  Enter/l: Builds  r: Refresh  h/Esc: Back")
+            .style(Style::default().fg(Color::DarkGray))
+            .block(Block::default().padding(Padding::horizontal(1)));
+
+        frame.render_widget(footer, chunks[2]);
+        Ok(())
+    }
+}
@@ -0,0 +1,239 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::{Block, Borders, Clear, Row, Table};
+
+use super::Component;
+use super::selectable_table::SelectableTable;
+use crate::action::Action;
+
+const MENU_WIDTH: u16 = 44;
+/// Opens the context menu in every view that has one, and closes it
+pub const MENU_KEY: KeyCode = KeyCode::Char('.');
+
+/// Entry of a context menu: what it does and the keys of the view that do it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MenuItem {
+    pub label: &'static str,
+    keys: Vec<KeyCode>,
+}
+
+impl MenuItem {
+    pub fn new(keys: &[KeyCode], label: &'static str) -> Self {
+        Self {
+            label,
+            keys: keys.to_vec(),
+        }
+    }
+
+    /// Keys as they read in the footers, e.g. "Enter" or "yy"
+    fn key_label(&self) -> String {
+        self.keys
+            .iter()
+            .map(|code| match code {
+                KeyCode::Char(' ') => "Space".to_string(),
+                KeyCode::Char(c) => c.to_string(),
+                other => other.to_string(),
+            })
+            .collect()
+    }
+
+    fn key_events(&self) -> Vec<KeyEvent> {
+        self.keys
+            .iter()
+            .map(|code| KeyEvent::new(*code, KeyModifiers::NONE))
+            .collect()
+    }
+}
+
+/// Popup listing what can be done with the selected row, for picking an action without knowing
+/// its key. Picking an item hands back its keys for the view to handle as if they were pressed.
+#[derive(Debug)]
+pub struct ContextMenu {
+    title: String,
+    items: SelectableTable<MenuItem>,
+    // keys typed so far of an item sharing its first key with others, like the `y` of `yy`
+    typed: Vec<KeyCode>,
+}
+
+/// Outcome of a key pressed in a context menu
+#[derive(Debug, PartialEq, Eq)]
+pub enum MenuEvent {
+    // moved within the menu or ignored the key
+    Pending,
+    Closed,
+    // keys of the picked item, to replay in the view
+    Picked(Vec<KeyEvent>),
+}
+
+impl ContextMenu {
+    pub fn new(title: impl Into<String>, items: Vec<MenuItem>) -> Self {
+        Self {
+            title: title.into(),
+            items: SelectableTable::new(items),
+            typed: Vec::new(),
+        }
+    }
+
+    /// `j`/`k` move, `Enter` picks the selected item, the keys of an item pick it directly once
+    /// no other item starts with them, `Esc` or [`MENU_KEY`] closes the menu
+    pub fn handle_key(&mut self, key: &KeyEvent) -> MenuEvent {
+        if self.items.handle_navigation_key(key) {
+            self.typed.clear();
+            return MenuEvent::Pending;
+        }
+        match key.code {
+            KeyCode::Esc | MENU_KEY => MenuEvent::Closed,
+            KeyCode::Enter => match self.items.selected() {
+                Some(item) => MenuEvent::Picked(item.key_events()),
+                None => MenuEvent::Closed,
+            },
+            code => {
+                self.typed.push(code);
+                let mut matching = self
+                    .items
+                    .items()
+                    .iter()
+                    .filter(|item| item.keys.starts_with(&self.typed));
+                match (matching.next(), matching.next()) {
+                    (Some(item), None) => {
+                        let keys = item.key_events();
+                        self.typed.clear();
+                        MenuEvent::Picked(keys)
+                    }
+                    (Some(_), Some(_)) => MenuEvent::Pending,
+                    (None, _) => {
+                        self.typed.clear();
+                        MenuEvent::Pending
+                    }
+                }
+            }
+        }
+    }
+
+    /// Menu centered over `area`, the key of each item next to its label
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        let rows: Vec<Row> = self
+            .items
+            .items()
+            .iter()
+            .map(|item| {
+                Row::new(vec![item.key_label(), item.label.to_string()])
+                    .style(Style::default().fg(Color::White))
+            })
+            .collect();
+
+        let popup_width = MENU_WIDTH.min(area.width);
+        let popup_height = (rows.len() as u16 + 2).min(area.height);
+        let popup_area = Rect {
+            x: area.x + (area.width.saturating_sub(popup_width)) / 2,
+            y: area.y + (area.height.saturating_sub(popup_height)) / 2,
+            width: popup_width,
+            height: popup_height,
+        };
+
+        let hint = if self.typed.is_empty() {
+            "Enter: Run  Esc: Close".to_string()
+        } else {
+            format!(
+                "{}…  Esc: Close",
+                MenuItem::new(&self.typed, "").key_label()
+            )
+        };
+        let table = Table::new(rows, &[Constraint::Length(6), Constraint::Min(0)])
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(self.title.as_str())
+                    .title_bottom(hint)
+                    .border_style(Style::default().fg(Color::Cyan)),
+            )
+            .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+        frame.render_widget(Clear, popup_area);
+        frame.render_stateful_widget(table, popup_area, &mut self.items.state);
+    }
+}
+
+/// Handles the keys of a picked menu item in `component`, returns the action of the last one
+pub fn replay_keys(
+    component: &mut impl Component,
+    keys: Vec<KeyEvent>,
+) -> color_eyre::Result<Option<Action>> {
+    let mut action = None;
+    for key in keys {
+        action = component.handle_key_event(key)?;
+    }
+    Ok(action)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn picking_an_item_hands_back_its_keys() {
+        let mut menu = ContextMenu::new(
+            "Build",
+            vec![
+                MenuItem::new(&[KeyCode::Enter], "Open log"),
+                MenuItem::new(&[KeyCode::Char('y'), KeyCode::Char('y')], "Copy URL"),
+                MenuItem::new(&[KeyCode::Char('R')], "Re-run"),
+            ],
+        );
+
+        assert_eq!(
+            menu.handle_key(&key(KeyCode::Char('j'))),
+            MenuEvent::Pending
+        );
+        assert_eq!(
+            menu.handle_key(&key(KeyCode::Enter)),
+            MenuEvent::Picked(vec![key(KeyCode::Char('y')), key(KeyCode::Char('y'))])
+        );
+        assert_eq!(
+            menu.handle_key(&key(KeyCode::Char('R'))),
+            MenuEvent::Picked(vec![key(KeyCode::Char('R'))])
+        );
+        assert_eq!(menu.handle_key(&key(MENU_KEY)), MenuEvent::Closed);
+    }
+
+    #[test]
+    fn items_sharing_a_first_key_are_picked_by_the_next_one() {
+        let mut menu = ContextMenu::new(
+            "Build",
+            vec![
+                MenuItem::new(&[KeyCode::Char('y'), KeyCode::Char('y')], "Copy URL"),
+                MenuItem::new(&[KeyCode::Char('y'), KeyCode::Char('n')], "Copy number"),
+                MenuItem::new(&[KeyCode::Char('R')], "Re-run"),
+            ],
+        );
+
+        assert_eq!(
+            menu.handle_key(&key(KeyCode::Char('y'))),
+            MenuEvent::Pending
+        );
+        assert_eq!(
+            menu.handle_key(&key(KeyCode::Char('n'))),
+            MenuEvent::Picked(vec![key(KeyCode::Char('y')), key(KeyCode::Char('n'))])
+        );
+
+        // a key no item continues with starts over
+        assert_eq!(
+            menu.handle_key(&key(KeyCode::Char('y'))),
+            MenuEvent::Pending
+        );
+        assert_eq!(
+            menu.handle_key(&key(KeyCode::Char('x'))),
+            MenuEvent::Pending
+        );
+        assert_eq!(
+            menu.handle_key(&key(KeyCode::Char('R'))),
+            MenuEvent::Picked(vec![key(KeyCode::Char('R'))])
+        );
+    }
+}
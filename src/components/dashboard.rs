@@ -0,0 +1,293 @@
+use super::Component;
+use super::freshness::freshness_line;
+use super::selectable_table::SelectableTable;
+use crate::action::Action;
+use crate::config::Config;
+use crate::icons::IconSet;
+use crate::time::{TimeDisplay, format_datetime, format_duration};
+use crate::view::builds::{duration_secs, exceeds_baseline, is_failed, status_text};
+use crate::view::favorites::{FavoriteRow, favorite_rows};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::{Block, Borders, Padding, Paragraph, Row, Table};
+use std::collections::{BTreeMap, HashMap};
+use teamcity::types::{Build, BuildType};
+use time::{Duration, OffsetDateTime};
+
+/// Latest build of each favorite configuration, kept up to date by the background poller while
+/// the view is open
+pub struct Dashboard {
+    favorites: Vec<String>,
+    rows: SelectableTable<FavoriteRow>,
+    names: HashMap<String, String>,
+    // latest build by build configuration ID
+    latest: HashMap<String, Build>,
+    // average duration by build configuration ID
+    baselines: BTreeMap<String, i64>,
+    // None until the first poll
+    polled_at: Option<OffsetDateTime>,
+    stale_after: Duration,
+    long_running_percent: u32,
+    time_display: TimeDisplay,
+    icons: IconSet,
+}
+
+impl Dashboard {
+    pub fn new(
+        build_types: &[BuildType],
+        latest: HashMap<String, Build>,
+        baselines: BTreeMap<String, i64>,
+        polled_at: Option<OffsetDateTime>,
+    ) -> Self {
+        Self {
+            favorites: Vec::new(),
+            rows: SelectableTable::new(Vec::new()),
+            names: build_types
+                .iter()
+                .map(|bt| (bt.id.clone(), bt.name.clone()))
+                .collect(),
+            latest,
+            baselines,
+            polled_at,
+            stale_after: Duration::ZERO,
+            long_running_percent: 0,
+            time_display: TimeDisplay::default(),
+            icons: IconSet::default(),
+        }
+    }
+
+    fn name<'a>(&'a self, build_type_id: &'a str) -> &'a str {
+        self.names
+            .get(build_type_id)
+            .map_or(build_type_id, String::as_str)
+    }
+
+    fn refresh_rows(&mut self) {
+        self.rows
+            .set_items(favorite_rows(&self.favorites, &self.latest));
+    }
+
+    fn is_long_running(&self, build: &Build, now: OffsetDateTime) -> bool {
+        build
+            .build_type_id
+            .as_ref()
+            .and_then(|id| self.baselines.get(id))
+            .is_some_and(|baseline| {
+                exceeds_baseline(build, *baseline, self.long_running_percent, now)
+            })
+    }
+
+    fn open_builds(&self) -> Action {
+        let Some(row) = self.rows.selected() else {
+            return Action::Error("No configuration was selected".to_string());
+        };
+        Action::LoadBuilds {
+            project_id: row.build_type_id.clone(),
+            title: self.name(&row.build_type_id).to_string(),
+        }
+    }
+
+    fn open_log(&self) -> Action {
+        let Some(row) = self.rows.selected() else {
+            return Action::Error("No configuration was selected".to_string());
+        };
+        let Some(build) = &row.build else {
+            return Action::Error(format!(
+                "{} has no build yet",
+                self.name(&row.build_type_id)
+            ));
+        };
+        Action::LoadLogViewer {
+            title: self.name(&row.build_type_id).to_string(),
+            project_id: row.build_type_id.clone(),
+            build: build.clone(),
+        }
+    }
+
+    fn build_row(&self, row: &FavoriteRow, now: OffsetDateTime) -> Row<'static> {
+        let name = self.name(&row.build_type_id).to_string();
+        let Some(build) = &row.build else {
+            return Row::new(vec![
+                self.icons.empty_mark().to_string(),
+                name,
+                String::new(),
+                String::new(),
+                "No build yet".to_string(),
+            ])
+            .style(Style::default().fg(Color::DarkGray));
+        };
+        let started = build
+            .start_date
+            .and_then(|date| format_datetime(&date, self.time_display).ok())
+            .unwrap_or_default();
+        let duration = duration_secs(build, now)
+            .and_then(|secs| format_duration(secs).ok())
+            .unwrap_or_default();
+        let style = if self.is_long_running(build, now) {
+            Style::default().fg(Color::Magenta)
+        } else if is_failed(build) {
+            Style::default().fg(Color::Red)
+        } else if build.state.as_deref() == Some("running") {
+            Style::default().fg(Color::Yellow)
+        } else {
+            Style::default()
+        };
+        Row::new(vec![
+            self.icons.build_status(build, 0).to_string(),
+            name,
+            build.build_number.clone().unwrap_or_default(),
+            build.branch_name.clone().unwrap_or_default(),
+            status_text(build),
+            started,
+            duration,
+        ])
+        .style(style)
+    }
+}
+
+impl Component for Dashboard {
+    fn register_config_handler(&mut self, config: Config) -> color_eyre::Result<()> {
+        self.favorites = config.favorites.clone();
+        self.stale_after = config.stale_after();
+        self.long_running_percent = config.long_running.threshold_percent;
+        self.time_display = config.time_display;
+        self.icons = config.icons;
+        self.refresh_rows();
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>> {
+        if self.rows.handle_navigation_key(&key) {
+            return Ok(Some(Action::Render));
+        }
+        let action = match key.code {
+            KeyCode::Enter => self.open_builds(),
+            KeyCode::Char('l') => self.open_log(),
+            KeyCode::Char('o') => {
+                if let Some(url) = self
+                    .rows
+                    .selected()
+                    .and_then(|row| row.build.as_ref())
+                    .and_then(|build| build.web_url.as_ref())
+                {
+                    let _ = open::that(url);
+                }
+                Action::Render
+            }
+            KeyCode::Esc | KeyCode::Char('h') => Action::ShowProjects,
+            _ => Action::Render,
+        };
+        Ok(Some(action))
+    }
+
+    fn update(&mut self, action: Action) -> color_eyre::Result<Option<Action>> {
+        match action {
+            Action::ProjectsFetched {
+                ref build_types, ..
+            } => {
+                self.names = build_types
+                    .iter()
+                    .map(|bt| (bt.id.clone(), bt.name.clone()))
+                    .collect();
+                return Ok(Some(Action::Render));
+            }
+            Action::FavoriteBuildsPolled { builds, baselines } => {
+                self.baselines = baselines;
+                for build in builds {
+                    if let Some(build_type_id) = build.build_type_id.clone() {
+                        self.latest.insert(build_type_id, build);
+                    }
+                }
+                self.polled_at = Some(OffsetDateTime::now_utc());
+                self.refresh_rows();
+                return Ok(Some(Action::Render));
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn tab_title(&self) -> String {
+        "Favorites".to_string()
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> color_eyre::Result<()> {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(0),    // Table takes remaining space
+                Constraint::Length(2), // Footer height
+            ])
+            .split(area);
+
+        let header = Row::new(vec![
+            "",
+            "Configuration",
+            "Number",
+            "Branch",
+            "Status",
+            "Started",
+            "Duration",
+        ])
+        .style(
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )
+        .height(1)
+        .bottom_margin(1);
+
+        let now = OffsetDateTime::now_utc();
+        let rows: Vec<Row> = if self.favorites.is_empty() {
+            vec![Row::new(vec![
+                "",
+                "No favorite configurations, list their IDs in `favorites` in config.toml",
+            ])]
+        } else if self.polled_at.is_none() {
+            vec![Row::new(vec!["", "Waiting for the first poll…"])]
+        } else {
+            self.rows
+                .items()
+                .iter()
+                .map(|row| self.build_row(row, now))
+                .collect()
+        };
+
+        let table = Table::new(
+            rows,
+            &[
+                Constraint::Length(2),  // Status icon
+                Constraint::Min(20),    // Configuration
+                Constraint::Max(20),    // Number
+                Constraint::Length(30), // Branch
+                Constraint::Min(20),    // Status text
+                Constraint::Length(20), // Started
+                Constraint::Length(10), // Duration
+            ],
+        )
+        .header(header)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Favorite configurations")
+                .title_top(freshness_line(self.polled_at, self.stale_after)),
+        )
+        .column_spacing(1)
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol(">> ");
+
+        self.rows.set_viewport(chunks[0]);
+        frame.render_stateful_widget(table, chunks[0], &mut self.rows.state);
+
+        let footer = Paragraph::new(
+            "j/k: Move  gg/G: Top/Bottom  Enter: Builds  l: Log of the latest build  o: Open URL  h/Esc: Back",
+        )
+        .style(Style::default().fg(Color::DarkGray))
+        .block(Block::default().padding(Padding::horizontal(1)));
+
+        frame.render_widget(footer, chunks[1]);
+        Ok(())
+    }
+}
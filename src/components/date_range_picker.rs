@@ -0,0 +1,74 @@
+use crossterm::event::KeyCode;
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+use teamcity::DateRange;
+
+use crate::view::date_range::{PRESETS, preset};
+
+const PICKER_WIDTH: u16 = 40;
+
+/// What a key pressed in the date range picker picks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RangeChoice {
+    // None for the latest builds whenever they started
+    Range(Option<DateRange>),
+    // the since..until prompt
+    Custom,
+}
+
+/// Choice for a key pressed in the date range picker: 1-3 pick a preset, c a custom range and 0
+/// the latest builds; None closes the picker
+pub fn handle_date_range_picker_key(code: KeyCode) -> Option<RangeChoice> {
+    match code {
+        KeyCode::Char('0') => Some(RangeChoice::Range(None)),
+        KeyCode::Char('c') => Some(RangeChoice::Custom),
+        KeyCode::Char(c) => preset(c).map(|range| RangeChoice::Range(Some(range))),
+        _ => None,
+    }
+}
+
+/// Presets of the date range picker centered over `area`, with the applied one highlighted
+pub fn render_date_range_picker(frame: &mut Frame, area: Rect, active: Option<&DateRange>) {
+    let mut lines: Vec<Line> = PRESETS
+        .iter()
+        .map(|(key, label, duration)| {
+            let line = Line::from(format!("{}  {}", key, label));
+            if active == Some(&DateRange::Last(*duration)) {
+                line.style(Style::default().add_modifier(Modifier::BOLD))
+            } else {
+                line
+            }
+        })
+        .collect();
+    let custom = match active {
+        Some(range @ DateRange::Between { .. }) => format!("c  Custom range ({})", range.label()),
+        _ => "c  Custom range".to_string(),
+    };
+    lines.push(Line::from(custom));
+    lines.push(Line::from(""));
+    lines.push(
+        Line::from("0: Latest builds  Esc: Close").style(Style::default().fg(Color::DarkGray)),
+    );
+
+    let popup_width = PICKER_WIDTH.min(area.width);
+    let popup_height = (lines.len() as u16 + 2).min(area.height);
+    let popup_area = Rect {
+        x: area.x + (area.width.saturating_sub(popup_width)) / 2,
+        y: area.y + (area.height.saturating_sub(popup_height)) / 2,
+        width: popup_width,
+        height: popup_height,
+    };
+
+    let popup = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Builds started")
+            .border_style(Style::default().fg(Color::Cyan)),
+    );
+
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(popup, popup_area);
+}
@@ -0,0 +1,38 @@
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Padding, Paragraph, Wrap};
+
+/// Panel drawn in `block` instead of an empty table: `message`, the `reasons` the table may be
+/// empty for, most likely first, then the `keys` to do something about it
+pub fn render_empty_state<S: AsRef<str>>(
+    frame: &mut Frame,
+    area: Rect,
+    block: Block,
+    message: &str,
+    reasons: &[S],
+    keys: &str,
+) {
+    let mut lines = vec![
+        Line::styled(message, Style::default().add_modifier(Modifier::BOLD)),
+        Line::default(),
+        Line::styled("This may be because:", Style::default().fg(Color::DarkGray)),
+    ];
+    lines.extend(
+        reasons
+            .iter()
+            .map(|reason| Line::raw(format!("  • {}", reason.as_ref()))),
+    );
+    lines.extend([
+        Line::default(),
+        Line::styled(keys, Style::default().fg(Color::Yellow)),
+    ]);
+
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(block.padding(Padding::uniform(1)))
+            .wrap(Wrap { trim: false }),
+        area,
+    );
+}
@@ -0,0 +1,74 @@
+use crossterm::event::KeyCode;
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+
+use crate::action::Action;
+use crate::config::SavedFilter;
+
+const PICKER_WIDTH: u16 = 50;
+
+/// Action for a key pressed in the filter picker: 1-9 apply a saved filter, 0 clears it
+pub fn handle_filter_picker_key(code: KeyCode, filters: &[SavedFilter]) -> Action {
+    match code {
+        KeyCode::Char('0') => Action::ApplyFilter { name: None },
+        KeyCode::Char(c @ '1'..='9') => {
+            let index = c as usize - '1' as usize;
+            match filters.get(index) {
+                Some(filter) => Action::ApplyFilter {
+                    name: Some(filter.name.clone()),
+                },
+                None => Action::Render,
+            }
+        }
+        _ => Action::Render,
+    }
+}
+
+/// Numbered list of the saved filters centered over `area`, with the active one highlighted
+pub fn render_filter_picker(
+    frame: &mut Frame,
+    area: Rect,
+    filters: &[SavedFilter],
+    active: Option<&SavedFilter>,
+) {
+    let mut lines: Vec<Line> = Vec::new();
+    if filters.is_empty() {
+        lines.push(Line::from(
+            "No saved filters, add [[filters]] to config.toml",
+        ));
+    }
+    for (i, filter) in filters.iter().take(9).enumerate() {
+        let line = Line::from(format!("{}  {}", i + 1, filter.name));
+        if active == Some(filter) {
+            lines.push(line.style(Style::default().add_modifier(Modifier::BOLD)));
+        } else {
+            lines.push(line);
+        }
+    }
+    lines.push(Line::from(""));
+    lines.push(
+        Line::from("0: Clear filter  Esc: Close").style(Style::default().fg(Color::DarkGray)),
+    );
+
+    let popup_width = PICKER_WIDTH.min(area.width);
+    let popup_height = (lines.len() as u16 + 2).min(area.height);
+    let popup_area = Rect {
+        x: area.x + (area.width.saturating_sub(popup_width)) / 2,
+        y: area.y + (area.height.saturating_sub(popup_height)) / 2,
+        width: popup_width,
+        height: popup_height,
+    };
+
+    let popup = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Saved filters")
+            .border_style(Style::default().fg(Color::Cyan)),
+    );
+
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(popup, popup_area);
+}
@@ -0,0 +1,29 @@
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use time::{Duration, OffsetDateTime};
+
+use crate::time::format_relative;
+
+/// Age of the data in a view for the right of its title, e.g. "fetched 45s ago", marked stale
+/// once it is older than `stale_after`. Empty while the data is loading.
+pub fn freshness_line(fetched_at: Option<OffsetDateTime>, stale_after: Duration) -> Line<'static> {
+    let Some(fetched_at) = fetched_at else {
+        return Line::default();
+    };
+    let now = OffsetDateTime::now_utc();
+    let age = format_relative(&fetched_at, &now);
+    let line = if now - fetched_at > stale_after {
+        Line::styled(
+            format!(" stale, fetched {} — r: Refresh ", age),
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )
+    } else {
+        Line::styled(
+            format!(" fetched {} ", age),
+            Style::default().fg(Color::DarkGray),
+        )
+    };
+    line.right_aligned()
+}
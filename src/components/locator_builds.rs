@@ -0,0 +1,223 @@
+use super::Component;
+use super::freshness::freshness_line;
+use super::selectable_table::SelectableTable;
+use crate::action::Action;
+use crate::config::Config;
+use crate::icons::IconSet;
+use crate::time::{TimeDisplay, format_datetime};
+use crate::view::builds::{is_failed, status_text};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::{Block, Borders, Padding, Paragraph, Row, Table};
+use std::collections::HashMap;
+use teamcity::types::{Build, BuildType};
+use time::{Duration, OffsetDateTime};
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Builds matching a raw TeamCity build locator, of any configuration, in the server's order
+pub struct LocatorBuilds {
+    locator: String,
+    builds: SelectableTable<Build>,
+    build_type_names: HashMap<String, String>,
+    // None while loading
+    fetched_at: Option<OffsetDateTime>,
+    stale_after: Duration,
+    time_display: TimeDisplay,
+    icons: IconSet,
+    pub action_tx: Option<UnboundedSender<Action>>,
+}
+
+impl LocatorBuilds {
+    /// Empty list shown while the builds are being fetched
+    pub fn loading(locator: String, build_types: &[BuildType]) -> Self {
+        let mut locator_builds = Self::new(locator, vec![], build_types);
+        locator_builds.fetched_at = None;
+        locator_builds
+    }
+
+    pub fn new(locator: String, builds: Vec<Build>, build_types: &[BuildType]) -> Self {
+        Self {
+            locator,
+            builds: SelectableTable::new(builds),
+            build_type_names: build_types
+                .iter()
+                .map(|bt| (bt.id.clone(), bt.name.clone()))
+                .collect(),
+            fetched_at: Some(OffsetDateTime::now_utc()),
+            stale_after: Duration::ZERO,
+            time_display: TimeDisplay::default(),
+            icons: IconSet::default(),
+            action_tx: None,
+        }
+    }
+
+    fn configuration_name(&self, build: &Build) -> String {
+        let id = build.build_type_id.as_deref().unwrap_or_default();
+        self.build_type_names
+            .get(id)
+            .cloned()
+            .unwrap_or_else(|| id.to_string())
+    }
+
+    fn open_log(&self) -> Action {
+        let Some(build) = self.builds.selected() else {
+            return Action::Error("No build was selected".to_string());
+        };
+        let Some(build_type_id) = build.build_type_id.clone() else {
+            return Action::Error("No configuration was found for selected build".to_string());
+        };
+        Action::LoadLogViewer {
+            title: self.configuration_name(build),
+            project_id: build_type_id,
+            build: build.clone(),
+        }
+    }
+
+    fn open_builds(&self) -> Action {
+        let Some(build) = self.builds.selected() else {
+            return Action::Error("No build was selected".to_string());
+        };
+        let Some(build_type_id) = build.build_type_id.clone() else {
+            return Action::Error("No configuration was found for selected build".to_string());
+        };
+        Action::LoadBuilds {
+            title: self.configuration_name(build),
+            project_id: build_type_id,
+        }
+    }
+}
+
+impl Component for LocatorBuilds {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> color_eyre::Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> color_eyre::Result<()> {
+        self.time_display = config.time_display;
+        self.stale_after = config.stale_after();
+        self.icons = config.icons;
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>> {
+        if self.builds.handle_navigation_key(&key) {
+            return Ok(Some(Action::Render));
+        }
+        let action = match key.code {
+            KeyCode::Enter | KeyCode::Char('l') => self.open_log(),
+            KeyCode::Char('b') => self.open_builds(),
+            KeyCode::Char('o') => {
+                if let Some(url) = self.builds.selected().and_then(|b| b.web_url.as_ref()) {
+                    let _ = open::that(url);
+                }
+                Action::Render
+            }
+            KeyCode::Char('r') => Action::LoadLocatorBuilds {
+                locator: self.locator.clone(),
+            },
+            KeyCode::Esc | KeyCode::Char('h') => Action::ShowProjects,
+            _ => Action::Render,
+        };
+        Ok(Some(action))
+    }
+
+    fn tab_title(&self) -> String {
+        format!("Locator — {}", self.locator)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> color_eyre::Result<()> {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(0),    // Table takes remaining space
+                Constraint::Length(2), // Footer height
+            ])
+            .split(area);
+
+        let header = Row::new(vec![
+            "",
+            "Configuration",
+            "Number",
+            "Branch",
+            "Status",
+            "Started",
+        ])
+        .style(
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )
+        .height(1)
+        .bottom_margin(1);
+
+        let rows: Vec<Row> = if self.fetched_at.is_none() {
+            vec![Row::new(vec!["", "Fetching builds…"])]
+        } else if self.builds.items().is_empty() {
+            vec![Row::new(vec!["", "No build matches the locator"])]
+        } else {
+            self.builds
+                .items()
+                .iter()
+                .map(|build| {
+                    let started = build
+                        .start_date
+                        .and_then(|date| format_datetime(&date, self.time_display).ok())
+                        .unwrap_or_default();
+                    let style = if is_failed(build) {
+                        Style::default().fg(Color::Red)
+                    } else if build.state.as_deref() == Some("running") {
+                        Style::default().fg(Color::Yellow)
+                    } else {
+                        Style::default()
+                    };
+                    Row::new(vec![
+                        self.icons.build_status(build, 0).to_string(),
+                        self.configuration_name(build),
+                        build.build_number.clone().unwrap_or_default(),
+                        build.branch_name.clone().unwrap_or_default(),
+                        status_text(build),
+                        started,
+                    ])
+                    .style(style)
+                })
+                .collect()
+        };
+
+        let table = Table::new(
+            rows,
+            &[
+                Constraint::Length(2),  // Status icon
+                Constraint::Min(20),    // Configuration
+                Constraint::Max(20),    // Number
+                Constraint::Length(30), // Branch
+                Constraint::Min(20),    // Status text
+                Constraint::Length(20), // Started
+            ],
+        )
+        .header(header)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Builds matching {}", self.locator))
+                .title_top(freshness_line(self.fetched_at, self.stale_after)),
+        )
+        .column_spacing(1)
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol(">> ");
+
+        self.builds.set_viewport(chunks[0]);
+        frame.render_stateful_widget(table, chunks[0], &mut self.builds.state);
+
+        let footer = Paragraph::new(
+            "j/k: Move  Enter/l: Log  b: Builds of the configuration  o: Open URL  r: Refresh  h/Esc: Back",
+        )
+        .style(Style::default().fg(Color::DarkGray))
+        .block(Block::default().padding(Padding::horizontal(1)));
+
+        frame.render_widget(footer, chunks[1]);
+        Ok(())
+    }
+}
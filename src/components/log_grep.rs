@@ -0,0 +1,298 @@
+use super::Component;
+use super::freshness::freshness_line;
+use super::selectable_table::SelectableTable;
+use crate::action::Action;
+use crate::config::Config;
+use crate::time::{TimeDisplay, format_datetime};
+use crate::view::log_grep::{BuildMatches, present_since};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Padding, Paragraph, Row, Table};
+use time::{Duration, OffsetDateTime};
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Which of the recent builds of a configuration have a string in their logs, with the matching
+/// lines of the selected one
+pub struct LogGrep {
+    // configuration the search was started from, to go back to
+    build_type_id: String,
+    title: String,
+    query: String,
+    // newest first
+    results: SelectableTable<BuildMatches>,
+    // None while the logs are being searched
+    fetched_at: Option<OffsetDateTime>,
+    stale_after: Duration,
+    time_display: TimeDisplay,
+    pub action_tx: Option<UnboundedSender<Action>>,
+}
+
+impl LogGrep {
+    /// Empty results shown while the logs are being downloaded and searched
+    pub fn loading(build_type_id: String, title: String, query: String) -> Self {
+        let mut grep = Self::new(build_type_id, title, query, vec![]);
+        grep.fetched_at = None;
+        grep
+    }
+
+    pub fn new(
+        build_type_id: String,
+        title: String,
+        query: String,
+        results: Vec<BuildMatches>,
+    ) -> Self {
+        let mut results = SelectableTable::new(results);
+        // start on the first build containing the string
+        results.select_where(|result| result.count > 0);
+        Self {
+            build_type_id,
+            title,
+            query,
+            results,
+            fetched_at: Some(OffsetDateTime::now_utc()),
+            stale_after: Duration::ZERO,
+            time_display: TimeDisplay::default(),
+            action_tx: None,
+        }
+    }
+
+    fn summary_lines(&self) -> Vec<Line<'_>> {
+        if self.fetched_at.is_none() {
+            return vec![Line::from("Searching build logs…")];
+        }
+        let results = self.results.items();
+        if results.is_empty() {
+            return vec![Line::from("No finished builds found")];
+        }
+
+        let matched = results.iter().filter(|result| result.count > 0).count();
+        let failed = results
+            .iter()
+            .filter(|result| result.error.is_some())
+            .count();
+        let mut lines = vec![Line::from(format!(
+            "Found in {} of {} builds{}",
+            matched,
+            results.len(),
+            if failed > 0 {
+                format!(", {} logs couldn't be downloaded", failed)
+            } else {
+                String::new()
+            }
+        ))];
+        match present_since(results) {
+            Some(since) => lines.push(
+                Line::from(format!(
+                    "In every log since #{}",
+                    since.build.build_number.as_deref().unwrap_or_default()
+                ))
+                .style(Style::default().fg(Color::Yellow)),
+            ),
+            None if matched > 0 => {
+                lines.push(Line::from("Not in the log of the latest build"));
+            }
+            None => {}
+        }
+        lines
+    }
+
+    fn render_preview(&self, frame: &mut Frame, area: Rect) {
+        let lines: Vec<Line> = match self.results.selected() {
+            Some(BuildMatches {
+                error: Some(error), ..
+            }) => vec![
+                Line::from(format!("Failed to download build log: {}", error))
+                    .style(Style::default().fg(Color::Red)),
+            ],
+            Some(result) => {
+                let mut lines: Vec<Line> = result
+                    .lines
+                    .iter()
+                    .map(|line| {
+                        Line::from(vec![
+                            Span::styled(
+                                format!("{:>6} ", line.number),
+                                Style::default().fg(Color::DarkGray),
+                            ),
+                            Span::raw(line.text.clone()),
+                        ])
+                    })
+                    .collect();
+                if result.count > result.lines.len() {
+                    lines.push(
+                        Line::from(format!(
+                            "… and {} more lines",
+                            result.count - result.lines.len()
+                        ))
+                        .style(Style::default().fg(Color::DarkGray)),
+                    );
+                }
+                lines
+            }
+            None => vec![],
+        };
+        let title = match self.results.selected() {
+            Some(result) => format!(
+                "Matching lines of #{}",
+                result.build.build_number.as_deref().unwrap_or_default()
+            ),
+            None => "Matching lines".to_string(),
+        };
+        let preview =
+            Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title));
+        frame.render_widget(preview, area);
+    }
+}
+
+impl Component for LogGrep {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> color_eyre::Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> color_eyre::Result<()> {
+        self.time_display = config.time_display;
+        self.stale_after = config.stale_after();
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>> {
+        if self.results.handle_navigation_key(&key) {
+            return Ok(Some(Action::Render));
+        }
+        let action = match key.code {
+            KeyCode::Enter | KeyCode::Char('l') => match self.results.selected() {
+                Some(result) => Action::LoadLogViewer {
+                    project_id: self.build_type_id.clone(),
+                    title: self.title.clone(),
+                    build: result.build.clone(),
+                },
+                None => Action::Error("No build was selected".to_string()),
+            },
+            KeyCode::Char('o') => {
+                if let Some(url) = self
+                    .results
+                    .selected()
+                    .and_then(|result| result.build.web_url.as_ref())
+                {
+                    let _ = open::that(url);
+                }
+                Action::Render
+            }
+            KeyCode::Char('r') => Action::GrepBuildLogs {
+                project_id: self.build_type_id.clone(),
+                title: self.title.clone(),
+                query: self.query.clone(),
+            },
+            KeyCode::Esc | KeyCode::Char('h') => Action::LoadBuilds {
+                project_id: self.build_type_id.clone(),
+                title: self.title.clone(),
+            },
+            _ => Action::Render,
+        };
+        Ok(Some(action))
+    }
+
+    fn tab_title(&self) -> String {
+        format!("Grep — {}", self.title)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> color_eyre::Result<()> {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(4),      // Summary height
+                Constraint::Percentage(45), // Table
+                Constraint::Min(0),         // Matching lines take remaining space
+                Constraint::Length(2),      // Footer height
+            ])
+            .split(area);
+
+        let summary = Paragraph::new(self.summary_lines()).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Logs containing \"{}\"", self.query))
+                .title_top(freshness_line(self.fetched_at, self.stale_after)),
+        );
+        frame.render_widget(summary, chunks[0]);
+
+        let header = Row::new(vec!["Build", "Branch", "Started", "Matches", "First match"])
+            .style(
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .height(1)
+            .bottom_margin(1);
+
+        let rows: Vec<Row> = self
+            .results
+            .items()
+            .iter()
+            .map(|result| {
+                let build = &result.build;
+                let started = build
+                    .start_date
+                    .and_then(|date| format_datetime(&date, self.time_display).ok())
+                    .unwrap_or_default();
+                let (matches, first, color) = match &result.error {
+                    Some(_) => ("?".to_string(), "log unavailable", Color::Red),
+                    None if result.count == 0 => ("0".to_string(), "", Color::DarkGray),
+                    None => (
+                        result.count.to_string(),
+                        result
+                            .lines
+                            .first()
+                            .map(|line| line.text.trim())
+                            .unwrap_or_default(),
+                        Color::Reset,
+                    ),
+                };
+                Row::new(vec![
+                    format!("#{}", build.build_number.as_deref().unwrap_or_default()),
+                    build.branch_name.clone().unwrap_or_default(),
+                    started,
+                    matches,
+                    first.to_string(),
+                ])
+                .style(Style::default().fg(color))
+            })
+            .collect();
+
+        let table = Table::new(
+            rows,
+            &[
+                Constraint::Length(12), // Build
+                Constraint::Length(20), // Branch
+                Constraint::Length(20), // Started
+                Constraint::Length(8),  // Matches
+                Constraint::Min(30),    // First match
+            ],
+        )
+        .header(header)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Recent builds of {}", self.title)),
+        )
+        .column_spacing(1)
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol(">> ");
+
+        self.results.set_viewport(chunks[1]);
+        frame.render_stateful_widget(table, chunks[1], &mut self.results.state);
+        self.render_preview(frame, chunks[2]);
+
+        let footer = Paragraph::new(
+            "j/k: Move  gg/G: Top/Bottom  Enter/l: Open log  o: Open in Browser  r: Search again  h/Esc: Back",
+        )
+        .style(Style::default().fg(Color::DarkGray))
+        .block(Block::default().padding(Padding::horizontal(1)));
+
+        frame.render_widget(footer, chunks[3]);
+        Ok(())
+    }
+}
@@ -0,0 +1,118 @@
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use super::Component;
+use crate::action::Action;
+use crate::logging::LOG_LINES;
+
+/// Recent log lines in the lower half of the screen, on top of the current view
+#[derive(Default)]
+pub struct LogPanel {
+    visible: bool,
+    // lines scrolled up from the newest one
+    scroll: usize,
+    // lines that fit in the panel, from the last draw
+    height: usize,
+}
+
+impl LogPanel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn max_scroll(&self) -> usize {
+        LOG_LINES.lines().len().saturating_sub(self.height)
+    }
+}
+
+impl Component for LogPanel {
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        match key.code {
+            KeyCode::Char('k') | KeyCode::Up => {
+                self.scroll = (self.scroll + 1).min(self.max_scroll());
+            }
+            KeyCode::Char('j') | KeyCode::Down => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::Char('g') => self.scroll = self.max_scroll(),
+            KeyCode::Char('G') => self.scroll = 0,
+            KeyCode::Esc => self.visible = false,
+            _ => {}
+        }
+        Ok(Some(Action::Render))
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::ToggleLogPanel => {
+                self.visible = !self.visible;
+                self.scroll = 0;
+                return Ok(Some(Action::Render));
+            }
+            // follow new lines while at the bottom
+            Action::Tick if self.visible => return Ok(Some(Action::Render)),
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        if !self.visible {
+            return Ok(());
+        }
+
+        let height = area.height / 2;
+        let panel_area = Rect {
+            x: area.x,
+            y: area.y + area.height - height,
+            width: area.width,
+            height,
+        };
+        self.height = height.saturating_sub(2) as usize;
+
+        let lines = LOG_LINES.lines();
+        let end = lines.len().saturating_sub(self.scroll);
+        let start = end.saturating_sub(self.height);
+        let text: Vec<Line> = lines[start..end]
+            .iter()
+            .map(|line| {
+                let color = if line.contains("ERROR") {
+                    Color::Red
+                } else if line.contains("WARN") {
+                    Color::Yellow
+                } else {
+                    Color::Gray
+                };
+                Line::from(line.as_str()).style(Style::default().fg(color))
+            })
+            .collect();
+
+        let title = if self.scroll > 0 {
+            format!(
+                "Log — {} lines up (j/k: Scroll  G: Newest  F12/Esc: Close)",
+                self.scroll
+            )
+        } else {
+            "Log (j/k: Scroll  g: Oldest  F12/Esc: Close)".to_string()
+        };
+        let panel = Paragraph::new(text).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(title)
+                .border_style(Style::default().fg(Color::Cyan)),
+        );
+
+        frame.render_widget(Clear, panel_area);
+        frame.render_widget(panel, panel_area);
+        Ok(())
+    }
+}
@@ -0,0 +1,183 @@
+use super::Component;
+use super::freshness::freshness_line;
+use super::prompt::render_confirm_popup;
+use super::selectable_table::SelectableTable;
+use crate::action::Action;
+use crate::config::Config;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::{Block, Borders, Padding, Paragraph, Row, Table};
+use teamcity::types::Mute;
+use time::{Duration, OffsetDateTime};
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Tests muted in a project, with unmuting of the selected mute
+pub struct Mutes {
+    project_id: String,
+    mutes: SelectableTable<Mute>,
+    // None while loading
+    fetched_at: Option<OffsetDateTime>,
+    stale_after: Duration,
+    // view to return to
+    back: Action,
+    // mute waiting for the unmute to be confirmed, with its prompt
+    pending_unmute: Option<(String, i64)>,
+    pub action_tx: Option<UnboundedSender<Action>>,
+}
+
+impl Mutes {
+    pub fn new(project_id: String, mutes: Option<Vec<Mute>>, back: Action) -> Self {
+        Self {
+            project_id,
+            fetched_at: mutes.is_some().then(OffsetDateTime::now_utc),
+            mutes: SelectableTable::new(mutes.unwrap_or_default()),
+            stale_after: Duration::ZERO,
+            back,
+            pending_unmute: None,
+            action_tx: None,
+        }
+    }
+
+    fn confirm_unmute(&mut self) -> Action {
+        let Some(mute) = self.mutes.selected() else {
+            return Action::Error("No muted test was selected".to_string());
+        };
+        let Some(mute_id) = mute.id else {
+            return Action::Error("No id was found for selected mute".to_string());
+        };
+        let prompt = format!("Unmute {}? y: Yes  n/Esc: No", mute.test_names().join(", "));
+        self.pending_unmute = Some((prompt, mute_id));
+        Action::Render
+    }
+
+    fn render_confirm(&self, frame: &mut Frame, area: Rect) {
+        let Some((prompt, _)) = &self.pending_unmute else {
+            return;
+        };
+
+        render_confirm_popup(frame, area, prompt);
+    }
+}
+
+impl Component for Mutes {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> color_eyre::Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> color_eyre::Result<()> {
+        self.stale_after = config.stale_after();
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>> {
+        if let Some((_, mute_id)) = self.pending_unmute {
+            let action = match key.code {
+                KeyCode::Char('y') => Action::UnmuteTest { mute_id },
+                _ => Action::Render,
+            };
+            self.pending_unmute = None;
+            return Ok(Some(action));
+        }
+
+        if self.mutes.handle_navigation_key(&key) {
+            return Ok(Some(Action::Render));
+        }
+
+        let action = match key.code {
+            KeyCode::Char('u') => self.confirm_unmute(),
+            KeyCode::Char('r') => Action::LoadMutes {
+                project_id: self.project_id.clone(),
+                back: Box::new(self.back.clone()),
+            },
+            KeyCode::Esc | KeyCode::Char('h') => self.back.clone(),
+            _ => Action::Render,
+        };
+        Ok(Some(action))
+    }
+
+    fn update(&mut self, action: Action) -> color_eyre::Result<Option<Action>> {
+        if let Action::TestUnmuted { mute_id } = action {
+            self.mutes.retain(|mute| mute.id != Some(mute_id));
+            return Ok(Some(Action::Render));
+        }
+        Ok(None)
+    }
+
+    fn tab_title(&self) -> String {
+        format!("Mutes — {}", self.project_id)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> color_eyre::Result<()> {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(0),    // Table takes remaining space
+                Constraint::Length(2), // Footer height
+            ])
+            .split(area);
+
+        let header = Row::new(vec!["Test", "Muted in", "Comment", "By"])
+            .style(
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .height(1)
+            .bottom_margin(1);
+
+        let rows: Vec<Row> = match self.mutes.items() {
+            _ if self.fetched_at.is_none() => vec![Row::new(vec!["Loading muted tests…"])],
+            [] => vec![Row::new(vec!["No muted tests"])],
+            mutes => mutes
+                .iter()
+                .map(|mute| {
+                    let assignment = mute.assignment.as_ref();
+                    let user = assignment
+                        .and_then(|a| a.user.as_ref())
+                        .and_then(|u| u.name.clone().or_else(|| u.username.clone()));
+                    Row::new(vec![
+                        mute.test_names().join(", "),
+                        mute.scope.as_ref().map(|s| s.label()).unwrap_or_default(),
+                        assignment.and_then(|a| a.text.clone()).unwrap_or_default(),
+                        user.unwrap_or_default(),
+                    ])
+                })
+                .collect(),
+        };
+
+        let table = Table::new(
+            rows,
+            &[
+                Constraint::Min(40),    // Test
+                Constraint::Length(30), // Muted in
+                Constraint::Min(30),    // Comment
+                Constraint::Length(20), // By
+            ],
+        )
+        .header(header)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Muted tests — {}", self.project_id))
+                .title_top(freshness_line(self.fetched_at, self.stale_after)),
+        )
+        .column_spacing(1)
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol(">> ");
+
+        self.mutes.set_viewport(chunks[0]);
+        frame.render_stateful_widget(table, chunks[0], &mut self.mutes.state);
+
+        let footer =
+            Paragraph::new("j/k: Move  gg/G: Top/Bottom  u: Unmute  r: Refresh  h/Esc: Back")
+                .style(Style::default().fg(Color::DarkGray))
+                .block(Block::default().padding(Padding::horizontal(1)));
+        frame.render_widget(footer, chunks[1]);
+
+        self.render_confirm(frame, area);
+        Ok(())
+    }
+}
@@ -0,0 +1,275 @@
+use super::Component;
+use super::freshness::freshness_line;
+use super::prompt::{render_confirm_popup, render_prompt};
+use super::selectable_table::SelectableTable;
+use crate::action::Action;
+use crate::config::Config;
+use crate::view::parameters::{display_value, is_secret, toggled_value};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::{Block, Borders, Padding, Paragraph, Row, Table};
+use teamcity::types::Parameter;
+use time::{Duration, OffsetDateTime};
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Parameters of a configuration, with editing and toggling of a value after a confirmation
+pub struct Parameters {
+    build_type_id: String,
+    title: String,
+    parameters: SelectableTable<Parameter>,
+    // None while loading
+    fetched_at: Option<OffsetDateTime>,
+    stale_after: Duration,
+    // name of the edited parameter and the value typed so far
+    edit_input: Option<(String, String)>,
+    // new value waiting to be confirmed, with its prompt
+    pending_update: Option<(String, String, String)>,
+    pub action_tx: Option<UnboundedSender<Action>>,
+}
+
+impl Parameters {
+    pub fn new(build_type_id: String, title: String, parameters: Option<Vec<Parameter>>) -> Self {
+        Self {
+            build_type_id,
+            title,
+            fetched_at: parameters.is_some().then(OffsetDateTime::now_utc),
+            parameters: SelectableTable::new(parameters.unwrap_or_default()),
+            stale_after: Duration::ZERO,
+            edit_input: None,
+            pending_update: None,
+            action_tx: None,
+        }
+    }
+
+    fn start_edit(&mut self) -> Action {
+        let Some(parameter) = self.parameters.selected() else {
+            return Action::Error("No parameter was selected".to_string());
+        };
+        // the value of a password parameter is never sent, start from scratch
+        let value = if is_secret(parameter) {
+            String::new()
+        } else {
+            parameter.value.clone().unwrap_or_default()
+        };
+        self.edit_input = Some((parameter.name.clone(), value));
+        Action::Render
+    }
+
+    fn toggle(&mut self) -> Action {
+        let Some(parameter) = self.parameters.selected() else {
+            return Action::Error("No parameter was selected".to_string());
+        };
+        let Some(value) = toggled_value(parameter) else {
+            return Action::Error(format!("{} is not a boolean parameter", parameter.name));
+        };
+        let name = parameter.name.clone();
+        self.confirm_update(name, value)
+    }
+
+    fn confirm_update(&mut self, name: String, value: String) -> Action {
+        let shown = if self
+            .parameters
+            .items()
+            .iter()
+            .any(|p| p.name == name && is_secret(p))
+        {
+            "the new password".to_string()
+        } else {
+            format!("'{}'", value)
+        };
+        let prompt = format!("Set {} to {}? y: Yes  n/Esc: No", name, shown);
+        self.pending_update = Some((prompt, name, value));
+        Action::Render
+    }
+
+    fn handle_edit_key(&mut self, key: KeyEvent) -> Action {
+        let Some((_, value)) = &mut self.edit_input else {
+            return Action::Render;
+        };
+        match key.code {
+            KeyCode::Esc => {
+                self.edit_input = None;
+                Action::Render
+            }
+            KeyCode::Char(c) => {
+                value.push(c);
+                Action::Render
+            }
+            KeyCode::Backspace => {
+                value.pop();
+                Action::Render
+            }
+            KeyCode::Enter => {
+                let Some((name, value)) = self.edit_input.take() else {
+                    return Action::Render;
+                };
+                self.confirm_update(name, value)
+            }
+            _ => Action::Render,
+        }
+    }
+
+    fn render_confirm(&self, frame: &mut Frame, area: Rect) {
+        let Some((prompt, _, _)) = &self.pending_update else {
+            return;
+        };
+
+        render_confirm_popup(frame, area, prompt);
+    }
+}
+
+impl Component for Parameters {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> color_eyre::Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> color_eyre::Result<()> {
+        self.stale_after = config.stale_after();
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>> {
+        if let Some((_, name, value)) = self.pending_update.take() {
+            let action = match key.code {
+                KeyCode::Char('y') => Action::SetParameter {
+                    build_type_id: self.build_type_id.clone(),
+                    name,
+                    value,
+                },
+                _ => Action::Render,
+            };
+            return Ok(Some(action));
+        }
+        if self.edit_input.is_some() {
+            return Ok(Some(self.handle_edit_key(key)));
+        }
+        if self.parameters.handle_navigation_key(&key) {
+            return Ok(Some(Action::Render));
+        }
+
+        let action = match key.code {
+            KeyCode::Enter | KeyCode::Char('e') => self.start_edit(),
+            KeyCode::Char(' ') | KeyCode::Char('t') => self.toggle(),
+            KeyCode::Char('r') => Action::LoadParameters {
+                build_type_id: self.build_type_id.clone(),
+                title: self.title.clone(),
+            },
+            KeyCode::Esc | KeyCode::Char('h') => Action::ShowProjects,
+            _ => Action::Render,
+        };
+        Ok(Some(action))
+    }
+
+    fn update(&mut self, action: Action) -> color_eyre::Result<Option<Action>> {
+        if let Action::ParameterUpdated {
+            build_type_id,
+            name,
+            value,
+        } = action
+            && build_type_id == self.build_type_id
+        {
+            if let Some(parameter) = self
+                .parameters
+                .items_mut()
+                .iter_mut()
+                .find(|p| p.name == name)
+            {
+                if !is_secret(parameter) {
+                    parameter.value = Some(value);
+                }
+                // the value is now set in the configuration itself
+                parameter.inherited = Some(false);
+            }
+            return Ok(Some(Action::Render));
+        }
+        Ok(None)
+    }
+
+    fn tab_title(&self) -> String {
+        format!("Parameters — {}", self.title)
+    }
+
+    fn is_typing(&self) -> bool {
+        self.edit_input.is_some()
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> color_eyre::Result<()> {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(0),    // Table takes remaining space
+                Constraint::Length(2), // Footer height
+            ])
+            .split(area);
+
+        let header = Row::new(vec!["Name", "Value", "Inherited"])
+            .style(
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .height(1)
+            .bottom_margin(1);
+
+        let rows: Vec<Row> = if self.fetched_at.is_none() {
+            vec![Row::new(vec!["Loading parameters…"])]
+        } else if self.parameters.items().is_empty() {
+            vec![Row::new(vec!["No parameters"])]
+        } else {
+            self.parameters
+                .items()
+                .iter()
+                .map(|parameter| {
+                    let inherited = parameter.inherited == Some(true);
+                    Row::new(vec![
+                        parameter.name.clone(),
+                        display_value(parameter),
+                        if inherited { "yes" } else { "" }.to_string(),
+                    ])
+                    .style(if inherited {
+                        Style::default().fg(Color::DarkGray)
+                    } else {
+                        Style::default()
+                    })
+                })
+                .collect()
+        };
+
+        let table = Table::new(
+            rows,
+            &[
+                Constraint::Min(40),    // Name
+                Constraint::Min(30),    // Value
+                Constraint::Length(10), // Inherited
+            ],
+        )
+        .header(header)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Parameters — {}", self.title))
+                .title_top(freshness_line(self.fetched_at, self.stale_after)),
+        )
+        .column_spacing(1)
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol(">> ");
+
+        self.parameters.set_viewport(chunks[0]);
+        frame.render_stateful_widget(table, chunks[0], &mut self.parameters.state);
+
+        let footer =
+            Paragraph::new("j/k: Move  Enter/e: Edit  Space/t: Toggle  r: Refresh  h/Esc: Back")
+                .style(Style::default().fg(Color::DarkGray))
+                .block(Block::default().padding(Padding::horizontal(1)));
+        frame.render_widget(footer, chunks[1]);
+
+        if let Some((name, value)) = &self.edit_input {
+            render_prompt(frame, area, &format!("New value of {}", name), value);
+        }
+        self.render_confirm(frame, area);
+        Ok(())
+    }
+}
@@ -0,0 +1,195 @@
+use super::Component;
+use super::freshness::freshness_line;
+use super::selectable_table::SelectableTable;
+use crate::action::Action;
+use crate::config::Config;
+use crate::time::{TimeDisplay, format_datetime};
+use crate::view::builds::change_revision;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::{Block, Borders, Padding, Paragraph, Row, Table};
+use teamcity::types::Change;
+use time::{Duration, OffsetDateTime};
+use tokio::sync::mpsc::UnboundedSender;
+
+// length of abbreviated commit SHAs, like `git log --oneline`
+const SHORT_VERSION_LEN: usize = 8;
+
+/// Changes of a configuration that are waiting for the next build
+pub struct PendingChanges {
+    // configuration the changes were opened from, to go back to
+    build_type_id: String,
+    title: String,
+    changes: SelectableTable<Change>,
+    // None while loading
+    fetched_at: Option<OffsetDateTime>,
+    stale_after: Duration,
+    time_display: TimeDisplay,
+    pub action_tx: Option<UnboundedSender<Action>>,
+}
+
+impl PendingChanges {
+    pub fn new(build_type_id: String, title: String, changes: Option<Vec<Change>>) -> Self {
+        Self {
+            build_type_id,
+            title,
+            fetched_at: changes.is_some().then(OffsetDateTime::now_utc),
+            changes: SelectableTable::new(changes.unwrap_or_default()),
+            stale_after: Duration::ZERO,
+            time_display: TimeDisplay::default(),
+            action_tx: None,
+        }
+    }
+}
+
+impl Component for PendingChanges {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> color_eyre::Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> color_eyre::Result<()> {
+        self.time_display = config.time_display;
+        self.stale_after = config.stale_after();
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>> {
+        if self.changes.handle_navigation_key(&key) {
+            return Ok(Some(Action::Render));
+        }
+        let action = match key.code {
+            KeyCode::Char('o') => {
+                if let Some(url) = self.changes.selected().and_then(|c| c.web_url.as_ref()) {
+                    let _ = open::that(url);
+                }
+                Action::Render
+            }
+            KeyCode::Char('y') => match self.changes.selected().and_then(|c| c.version.clone()) {
+                Some(version) => Action::CopyToClipboard {
+                    label: "revision".to_string(),
+                    text: version,
+                },
+                None => Action::Error("No revision was found for selected change".to_string()),
+            },
+            KeyCode::Char('T') => match self.changes.selected() {
+                Some(change) => match change.id {
+                    // pending changes are those of the default branch
+                    Some(change_id) => Action::TriggerBuildOnChange {
+                        build_type_id: self.build_type_id.clone(),
+                        title: self.title.clone(),
+                        branch: None,
+                        change_id,
+                        revision: change_revision(change),
+                    },
+                    None => Action::Error("The change can't be queued, it has no ID".to_string()),
+                },
+                None => Action::Error("No change was selected".to_string()),
+            },
+            KeyCode::Char('r') => Action::LoadPendingChanges {
+                project_id: self.build_type_id.clone(),
+                title: self.title.clone(),
+            },
+            KeyCode::Esc | KeyCode::Char('h') => Action::LoadBuilds {
+                project_id: self.build_type_id.clone(),
+                title: self.title.clone(),
+            },
+            _ => Action::Render,
+        };
+        Ok(Some(action))
+    }
+
+    fn tab_title(&self) -> String {
+        format!("Pending — {}", self.title)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> color_eyre::Result<()> {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(0),    // Table takes remaining space
+                Constraint::Length(2), // Footer height
+            ])
+            .split(area);
+
+        let header = Row::new(vec!["Revision", "Author", "Date", "Comment"])
+            .style(
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .height(1)
+            .bottom_margin(1);
+
+        let rows: Vec<Row> = match self.changes.items() {
+            _ if self.fetched_at.is_none() => vec![Row::new(vec!["", "Loading pending changes…"])],
+            [] => {
+                vec![Row::new(vec![
+                    "",
+                    "No pending changes, every change has been built",
+                ])]
+            }
+            changes => changes
+                .iter()
+                .map(|change| {
+                    let version: String = change
+                        .version
+                        .as_deref()
+                        .unwrap_or_default()
+                        .chars()
+                        .take(SHORT_VERSION_LEN)
+                        .collect();
+                    let date = change
+                        .date
+                        .and_then(|date| format_datetime(&date, self.time_display).ok())
+                        .unwrap_or_default();
+                    // only the summary line of the commit message fits
+                    let comment = change
+                        .comment
+                        .as_deref()
+                        .and_then(|c| c.lines().next())
+                        .unwrap_or_default();
+                    Row::new(vec![
+                        version,
+                        change.username.clone().unwrap_or_default(),
+                        date,
+                        comment.to_string(),
+                    ])
+                })
+                .collect(),
+        };
+
+        let table = Table::new(
+            rows,
+            &[
+                Constraint::Length(10), // Revision
+                Constraint::Length(20), // Author
+                Constraint::Length(20), // Date
+                Constraint::Min(30),    // Comment
+            ],
+        )
+        .header(header)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Pending changes — {}", self.title))
+                .title_top(freshness_line(self.fetched_at, self.stale_after)),
+        )
+        .column_spacing(1)
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol(">> ");
+
+        self.changes.set_viewport(chunks[0]);
+        frame.render_stateful_widget(table, chunks[0], &mut self.changes.state);
+
+        let footer = Paragraph::new(
+            "j/k: Move  gg/G: Top/Bottom  o: Open in Browser  y: Copy revision  T: Queue on change  r: Refresh  h/Esc: Back",
+        )
+        .style(Style::default().fg(Color::DarkGray))
+        .block(Block::default().padding(Padding::horizontal(1)));
+        frame.render_widget(footer, chunks[1]);
+        Ok(())
+    }
+}
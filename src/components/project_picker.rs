@@ -0,0 +1,200 @@
+use super::Component;
+use super::prompt::render_confirm_popup;
+use super::selectable_table::SelectableTable;
+use crate::action::Action;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::{Block, Borders, Padding, Paragraph, Row, Table};
+use std::collections::HashSet;
+use teamcity::types::Project;
+use tokio::sync::mpsc::UnboundedSender;
+
+const SAVE_PROMPT: &str = "Save the selection to config.toml? y: Yes  n: No  Esc: Back";
+
+/// Top-level projects of the server to pick the monitored ones from, shown on startup when no
+/// project is configured
+pub struct ProjectPicker {
+    // None while loading
+    projects: Option<SelectableTable<Project>>,
+    // IDs of the picked projects
+    picked: HashSet<String>,
+    // the selection is picked and waits for the answer to saving it
+    confirming: bool,
+    pub action_tx: Option<UnboundedSender<Action>>,
+}
+
+impl ProjectPicker {
+    pub fn new(projects: Option<Vec<Project>>) -> Self {
+        Self {
+            projects: projects.map(SelectableTable::new),
+            picked: HashSet::new(),
+            confirming: false,
+            action_tx: None,
+        }
+    }
+
+    fn toggle_selected(&mut self) {
+        let Some(id) = self
+            .projects
+            .as_ref()
+            .and_then(|projects| projects.selected())
+            .map(|project| project.id.clone())
+        else {
+            return;
+        };
+        if !self.picked.remove(&id) {
+            self.picked.insert(id);
+        }
+    }
+
+    /// Picked project IDs in the server's order rather than the order of the set
+    fn picked_ids(&self) -> Vec<String> {
+        self.projects
+            .iter()
+            .flat_map(|projects| projects.items())
+            .filter(|project| self.picked.contains(&project.id))
+            .map(|project| project.id.clone())
+            .collect()
+    }
+
+    fn render_confirm(&self, frame: &mut Frame, area: Rect) {
+        if !self.confirming {
+            return;
+        }
+
+        render_confirm_popup(frame, area, SAVE_PROMPT);
+    }
+}
+
+impl Component for ProjectPicker {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> color_eyre::Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>> {
+        if self.confirming {
+            let action = match key.code {
+                KeyCode::Char('y') => Action::ProjectsPicked {
+                    projects: self.picked_ids(),
+                    save: true,
+                },
+                KeyCode::Char('n') => Action::ProjectsPicked {
+                    projects: self.picked_ids(),
+                    save: false,
+                },
+                _ => Action::Render,
+            };
+            self.confirming = false;
+            return Ok(Some(action));
+        }
+        if let Some(projects) = &mut self.projects
+            && projects.handle_navigation_key(&key)
+        {
+            return Ok(Some(Action::Render));
+        }
+
+        let action = match key.code {
+            KeyCode::Char(' ') => {
+                self.toggle_selected();
+                Action::Render
+            }
+            KeyCode::Enter if self.picked.is_empty() => {
+                Action::Error("Pick at least one project with Space".to_string())
+            }
+            KeyCode::Enter => {
+                self.confirming = true;
+                Action::Render
+            }
+            KeyCode::Char('r') => Action::LoadRootProjects,
+            _ => Action::Render,
+        };
+        Ok(Some(action))
+    }
+
+    fn tab_title(&self) -> String {
+        "Pick projects".to_string()
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> color_eyre::Result<()> {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(0),    // Table takes remaining space
+                Constraint::Length(2), // Footer height
+            ])
+            .split(area);
+
+        let header = Row::new(vec!["", "Project", "ID"])
+            .style(
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .height(1)
+            .bottom_margin(1);
+
+        let rows: Vec<Row> = match &self.projects {
+            None => vec![Row::new(vec!["", "Loading projects…"])],
+            Some(projects) if projects.items().is_empty() => {
+                vec![Row::new(vec!["", "No projects on the server"])]
+            }
+            Some(projects) => projects
+                .items()
+                .iter()
+                .map(|project| {
+                    let picked = self.picked.contains(&project.id);
+                    Row::new(vec![
+                        if picked { "[x]" } else { "[ ]" }.to_string(),
+                        project.name.clone(),
+                        project.id.clone(),
+                    ])
+                    .style(if picked {
+                        Style::default().add_modifier(Modifier::BOLD)
+                    } else {
+                        Style::default()
+                    })
+                })
+                .collect(),
+        };
+
+        let table = Table::new(
+            rows,
+            &[
+                Constraint::Length(3), // Picked
+                Constraint::Min(30),   // Project
+                Constraint::Min(30),   // ID
+            ],
+        )
+        .header(header)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("No projects are configured — pick the ones to monitor"),
+        )
+        .column_spacing(1)
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol(">> ");
+
+        match &mut self.projects {
+            Some(projects) => {
+                projects.set_viewport(chunks[0]);
+                frame.render_stateful_widget(table, chunks[0], &mut projects.state)
+            }
+            None => frame.render_widget(table, chunks[0]),
+        }
+
+        let footer = Paragraph::new(format!(
+            "j/k: Move  Space: Pick  Enter: Monitor the {} picked  r: Refresh  q: Quit",
+            self.picked.len()
+        ))
+        .style(Style::default().fg(Color::DarkGray))
+        .block(Block::default().padding(Padding::horizontal(1)));
+        frame.render_widget(footer, chunks[1]);
+
+        self.render_confirm(frame, area);
+        Ok(())
+    }
+}
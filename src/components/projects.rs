@@ -1,20 +1,81 @@
 use super::Component;
-use crate::teamcity::types::BuildType;
-use crate::utils::InputMode;
-use crate::{action::Action, config::Config};
+use super::context_menu::{ContextMenu, MENU_KEY, MenuEvent, MenuItem, replay_keys};
+use super::empty_state::render_empty_state;
+use super::filter_picker::{handle_filter_picker_key, render_filter_picker};
+use super::freshness::freshness_line;
+use super::prompt::{BranchPrompt, BranchPromptEvent, render_confirm_popup, render_prompt};
+use super::selectable_table::{FzfLabel, SelectableTable, fzf_options};
+use crate::export::{self, EXPORT_PROMPT_TITLE, expand_path};
+use crate::icons::IconSet;
+use crate::session::Session;
+use crate::utils::{InputMode, is_alternate_key};
+use crate::view::custom_actions::{build_type_placeholders, command_line};
+use crate::view::filter_query::FilterQuery;
+use crate::view::projects::{
+    EXPORT_COLUMNS, FILTER_FIELDS, ProjectRow, SortMode, empty_reasons, export_row,
+    filter_and_sort, icon_for, kind_icon, project_name, project_rows,
+};
+use crate::{
+    action::Action,
+    config::{Config, CustomAction, SavedFilter},
+};
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::Frame;
 use ratatui::layout::{Constraint, Direction, Layout, Rect, Size};
 use ratatui::style::{Color, Modifier, Style};
-use ratatui::widgets::{Block, Borders, Clear, Padding, Paragraph, Row, Table, TableState, Wrap};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Cell, Padding, Paragraph, Row, Table};
+use std::collections::HashSet;
+use std::path::Path;
+use std::time::Instant;
+use teamcity::ProjectFailure;
+use teamcity::types::BuildType;
+use time::{Duration, OffsetDateTime};
 use tokio::sync::mpsc::UnboundedSender;
 
+// pause in typing after which the filter being typed is applied
+const FILTER_DEBOUNCE: std::time::Duration = std::time::Duration::from_millis(200);
+
+/// Branch typed for a build about to be queued, completed from the branches of its configuration
+struct TriggerPrompt {
+    build_type: BuildType,
+    branch: BranchPrompt,
+}
+
 #[derive(Default)]
 pub struct Projects {
     build_types: Vec<BuildType>,
-    table_state: TableState,
+    // indices into build_types of the configurations matching the filters, in table order
+    visible: Vec<usize>,
+    // projects that failed to load, shown as error chips above the table
+    failed_projects: Vec<ProjectFailure>,
+    // build configuration IDs, most recently opened first
+    recent: Vec<String>,
+    sort_mode: SortMode,
+    filters: Vec<SavedFilter>,
+    custom_actions: Vec<CustomAction>,
+    active_filter: Option<SavedFilter>,
+    picking_filter: bool,
+    context_menu: Option<ContextMenu>,
+    trigger_prompt: Option<TriggerPrompt>,
+    // configuration waiting for the pause or resume to be confirmed, with its prompt
+    pending_pause: Option<(String, Action)>,
+    collapsed_groups: HashSet<String>,
+    // None until the build configurations are fetched
+    fetched_at: Option<OffsetDateTime>,
+    stale_after: Duration,
+    icons: IconSet,
+    table: SelectableTable<ProjectRow>,
     input_mode: InputMode,
     input_buffer: String,
+    // filter applied when the filter popup was opened, restored on Esc
+    filter_before_edit: Option<String>,
+    // last key typed in the filter popup that isn't applied yet
+    filter_typed_at: Option<Instant>,
+    // why the filter typed in the popup can't be applied, the previous one stays applied
+    filter_error: Option<String>,
+    // parsed filter_string
+    filter: Option<FilterQuery>,
     // buffer to hold KeyEvents for multi-key combinations
     last_events: Vec<KeyEvent>,
     pub filter_string: Option<String>,
@@ -22,105 +83,227 @@ pub struct Projects {
 }
 
 impl Projects {
-    pub fn new(build_configs: Vec<BuildType>) -> Self {
+    pub fn new(
+        build_configs: Vec<BuildType>,
+        failed_projects: Vec<ProjectFailure>,
+        recent: Vec<String>,
+        fetched_at: Option<OffsetDateTime>,
+    ) -> Self {
         Self {
             build_types: build_configs,
+            failed_projects,
+            recent,
+            fetched_at,
             filter_string: None,
             ..Self::default()
         }
+        .with_rows()
     }
 
-    fn icon_for(&self, bt: &BuildType) -> String {
-        match bt.kind.as_deref().map(|s| s.to_ascii_lowercase()) {
-            Some(ref k) if k == "regular" => "⚙️",
-            Some(ref k) if k == "composite" => "🧩",
-            Some(ref k) if k == "deployment" => "🚀",
-            _ => "📦",
-        }
-        .to_string()
+    fn with_rows(mut self) -> Self {
+        self.refresh_rows();
+        self
     }
 
-    fn get_build_types(&mut self) -> Vec<BuildType> {
-        self.build_types
-            .iter()
-            .filter(|build_type| {
-                if let Some(filter_string) = &self.filter_string {
-                    build_type.name.to_lowercase().contains(filter_string)
-                } else {
-                    true
-                }
-            })
-            .cloned()
-            .collect()
+    /// Build configurations matching the filters, in table order
+    fn visible_build_types(&self) -> impl Iterator<Item = &BuildType> {
+        self.visible.iter().map(|&index| &self.build_types[index])
     }
 
-    fn filter_build_types(&mut self, filter_string: Option<&String>) {
-        self.filter_string = filter_string.map(|s| s.to_lowercase());
+    /// Recomputes the rows of the table after the build configurations, filter, sort or folded
+    /// sections changed
+    fn refresh_rows(&mut self) {
+        let mut visible = filter_and_sort(
+            &self.build_types,
+            self.filter.as_ref(),
+            self.sort_mode,
+            &self.recent,
+        );
+        if let Some(filter) = &self.active_filter {
+            visible.retain(|&index| filter.matches_build_type(&self.build_types[index]));
+        }
+        self.table.set_items(project_rows(
+            &self.build_types,
+            &visible,
+            self.sort_mode,
+            &self.collapsed_groups,
+        ));
+        self.visible = visible;
     }
 
-    fn move_down(&mut self) {
-        let i = match self.table_state.selected() {
-            Some(i) => {
-                if i >= self.get_build_types().len() - 1 {
-                    0
-                } else {
-                    i + 1
-                }
+    /// Actions on the selected configuration or project group, then the ones of the whole view
+    fn context_menu(&self) -> ContextMenu {
+        let mut items = Vec::new();
+        let title = match self.table.selected() {
+            Some(&ProjectRow::BuildType(index)) => {
+                let build_type = &self.build_types[index];
+                items.extend([
+                    MenuItem::new(&[KeyCode::Enter], "Open builds"),
+                    MenuItem::new(&[KeyCode::Char('t')], "Queue build"),
+                    MenuItem::new(&[KeyCode::Char('o')], "Open in browser"),
+                    MenuItem::new(&[KeyCode::Char('e')], "Edit in browser"),
+                    MenuItem::new(&[KeyCode::Char('V')], "Open settings in VCS"),
+                    MenuItem::new(&[KeyCode::Char('a')], "Agent requirements"),
+                    MenuItem::new(&[KeyCode::Char('P')], "Parameters"),
+                    MenuItem::new(
+                        &[KeyCode::Char('p')],
+                        if build_type.paused == Some(true) {
+                            "Resume"
+                        } else {
+                            "Pause"
+                        },
+                    ),
+                    MenuItem::new(&[KeyCode::Char('M')], "Muted tests of the project"),
+                    MenuItem::new(&[KeyCode::Char('y'), KeyCode::Char('y')], "Copy URL"),
+                    MenuItem::new(&[KeyCode::Char('y'), KeyCode::Char('i')], "Copy ID"),
+                ]);
+                build_type.name.clone()
+            }
+            Some(ProjectRow::Group {
+                name, collapsed, ..
+            }) => {
+                let label = if *collapsed { "Unfold" } else { "Fold" };
+                items.push(MenuItem::new(&[KeyCode::Enter], label));
+                name.clone()
             }
-            None => 0,
+            None => "Projects".to_string(),
         };
-        self.table_state.select(Some(i));
+        items.extend([
+            MenuItem::new(&[KeyCode::Char('s')], "Change sorting"),
+            MenuItem::new(&[KeyCode::Char('F')], "Saved filters"),
+            MenuItem::new(&[KeyCode::Char('E')], "Export"),
+            MenuItem::new(&[KeyCode::Char('T')], "Timeline"),
+            MenuItem::new(&[KeyCode::Char('D')], "Favorites"),
+            MenuItem::new(&[KeyCode::Char('S')], "About server"),
+            MenuItem::new(&[KeyCode::Char('c')], "Edit config.toml"),
+            MenuItem::new(&[KeyCode::Char('r')], "Refresh"),
+        ]);
+        ContextMenu::new(title, items)
     }
 
-    fn move_end(&mut self) {
-        let n = self.get_build_types().len() - 1;
-        self.table_state.select(Some(n))
+    fn get_selected_build_type(&self) -> Option<&BuildType> {
+        match self.table.selected()? {
+            ProjectRow::BuildType(index) => self.build_types.get(*index),
+            ProjectRow::Group { .. } => None,
+        }
     }
 
-    fn move_begin(&mut self) {
-        self.table_state.select_first()
+    /// `action` sent to the server of the selected configuration, if any is selected
+    fn on_selected_server(&self, action: Action) -> Action {
+        match self.get_selected_build_type() {
+            Some(build_type) => action.on_server_of(build_type),
+            None => action,
+        }
     }
 
-    fn move_up(&mut self) {
-        let i = match self.table_state.selected() {
-            Some(i) => {
-                if i == 0 {
-                    self.get_build_types().len() - 1
-                } else {
-                    i - 1
-                }
+    fn confirm_pause(&mut self) -> Action {
+        let Some(build_type) = self.get_selected_build_type().cloned() else {
+            return Action::Error("No build configuration was selected".to_string());
+        };
+        let paused = build_type.paused != Some(true);
+        let prompt = format!(
+            "{} {}? y: Yes  n/Esc: No",
+            if paused { "Pause" } else { "Resume" },
+            build_type.name
+        );
+        let action = Action::SetPaused {
+            build_type_id: build_type.id.clone(),
+            paused,
+        };
+        self.pending_pause = Some((prompt, action.on_server_of(&build_type)));
+        Action::Render
+    }
+
+    fn render_confirm(&self, frame: &mut Frame, area: Rect) {
+        let Some((prompt, _)) = &self.pending_pause else {
+            return;
+        };
+
+        render_confirm_popup(frame, area, prompt);
+    }
+
+    /// Applies `filter_string`, keeping the current filter if it is invalid
+    fn filter_build_types(&mut self, filter_string: Option<&String>) -> Result<(), String> {
+        self.filter = filter_string
+            .map(|filter| FilterQuery::parse(filter, &FILTER_FIELDS))
+            .transpose()?;
+        self.filter_string = filter_string.cloned();
+        self.refresh_rows();
+        Ok(())
+    }
+
+    /// Applies the filter typed so far, keeping the selection if it still matches
+    fn apply_typed_filter(&mut self) {
+        self.filter_typed_at = None;
+        let selected = self.get_selected_build_type().map(|bt| bt.id.clone());
+        let buffer = self.input_buffer.clone();
+        self.filter_error = self
+            .filter_build_types(Some(&buffer).filter(|buffer| !buffer.is_empty()))
+            .err();
+        if self.filter_error.is_some() {
+            return;
+        }
+        match selected {
+            Some(build_type_id) if self.visible_build_types().any(|bt| bt.id == build_type_id) => {
+                self.select_build_type(&build_type_id)
             }
-            None => 0,
+            _ => self.table.move_begin(),
+        }
+    }
+
+    fn toggle_sort_mode(&mut self) {
+        let selected = self.get_selected_build_type().map(|bt| bt.id.clone());
+        self.sort_mode = self.sort_mode.next();
+        self.refresh_rows();
+        match selected {
+            Some(build_type_id) => self.select_build_type(&build_type_id),
+            None => self.table.select(Some(0)),
+        }
+    }
+
+    // Collapses or expands the section under the selected project header
+    fn toggle_selected_group(&mut self) -> bool {
+        let Some(ProjectRow::Group { name, .. }) = self.table.selected() else {
+            return false;
         };
-        self.table_state.select(Some(i));
+        let name = name.clone();
+        if !self.collapsed_groups.remove(&name) {
+            self.collapsed_groups.insert(name);
+        }
+        self.refresh_rows();
+        true
     }
 
-    fn open_selected_build(&mut self) {
-        if let Some(selected_index) = self.table_state.selected() {
-            if let Some(build_type) = self.get_build_types().get(selected_index) {
-                if let Some(web_url) = &build_type.web_url {
-                    let _ = open::that(web_url);
-                }
+    fn select_build_type(&mut self, build_type_id: &str) {
+        // expand the section holding the build type so it can be selected
+        if let Some(build_type) = self.build_types.iter().find(|bt| bt.id == build_type_id) {
+            let project_name = project_name(build_type).to_string();
+            if self.collapsed_groups.remove(&project_name) {
+                self.refresh_rows();
             }
         }
+        self.table.select_where(|row| {
+            matches!(row, ProjectRow::BuildType(index) if self.build_types[*index].id == build_type_id)
+        });
     }
 
-    fn edit_selected_build(&mut self) {
-        let build_types = self.get_build_types();
+    fn open_selected_build(&mut self) {
+        if let Some(build_type) = self.get_selected_build_type()
+            && let Some(web_url) = &build_type.web_url
+        {
+            let _ = open::that(web_url);
+        }
+    }
 
+    fn edit_selected_build(&mut self) {
         let web_setting_link = self
-            .table_state
-            .selected()
-            .and_then(|selected_index| build_types.get(selected_index))
+            .get_selected_build_type()
             .and_then(|build_type| build_type.links.as_ref())
             .and_then(|links| {
                 links
                     .links
-                    .iter().find(|link| link.kind == "webViewSettings")
-                    // .as_ref()
-                    // .and_then(|links|
-                    //     links.iter().find(|link| link.kind == "webViewSettings")
-                    // )
+                    .iter()
+                    .find(|link| link.kind == "webViewSettings")
             });
 
         if let Some(link) = web_setting_link {
@@ -128,56 +311,198 @@ impl Projects {
         }
     }
 
+    // Second key of a `y` yank sequence: y for the URL, i for the ID
+    fn yank_selected(&mut self, code: KeyCode) -> Option<Action> {
+        let build_type = self.get_selected_build_type()?;
+        let (label, text) = match code {
+            KeyCode::Char('y') => ("URL", build_type.web_url.clone()?),
+            KeyCode::Char('i') => ("configuration ID", build_type.id.clone()),
+            _ => return None,
+        };
+        Some(Action::CopyToClipboard {
+            label: label.to_string(),
+            text,
+        })
+    }
+
+    /// Writes the build configurations matching the filter to `path`
+    fn export_to(&self, path: &Path) -> Action {
+        let rows: Vec<Vec<String>> = self.visible_build_types().map(export_row).collect();
+        match export::write(path, &EXPORT_COLUMNS, &rows) {
+            Ok(_) => Action::Toast(format!(
+                "Exported {} build configurations to {}",
+                rows.len(),
+                path.display()
+            )),
+            Err(e) => Action::Error(format!("Failed to export to {}: {}", path.display(), e)),
+        }
+    }
+
+    fn start_trigger(&mut self) -> Action {
+        let Some(build_type) = self.get_selected_build_type().cloned() else {
+            return Action::Error("No build configuration was selected".to_string());
+        };
+        let action = Action::LoadBranches {
+            build_type_id: build_type.id.clone(),
+        }
+        .on_server_of(&build_type);
+        self.trigger_prompt = Some(TriggerPrompt {
+            build_type,
+            branch: BranchPrompt::default(),
+        });
+        action
+    }
+
+    fn handle_trigger_key(&mut self, key: KeyEvent) -> Action {
+        let Some(prompt) = &mut self.trigger_prompt else {
+            return Action::Render;
+        };
+        match prompt.branch.handle_key(key) {
+            BranchPromptEvent::Edited => Action::Render,
+            BranchPromptEvent::Cancelled => {
+                self.trigger_prompt = None;
+                Action::Render
+            }
+            BranchPromptEvent::Submitted(branch) => {
+                let action = Action::TriggerBuild {
+                    build_type_id: prompt.build_type.id.clone(),
+                    title: prompt.build_type.name.clone(),
+                    branch: Some(branch).filter(|branch| !branch.is_empty()),
+                }
+                .on_server_of(&prompt.build_type);
+                self.trigger_prompt = None;
+                action
+            }
+        }
+    }
+
+    fn render_trigger_prompt(&self, frame: &mut Frame, area: Rect) {
+        if let Some(prompt) = &self.trigger_prompt {
+            let title = format!(
+                "Queue {} on branch (↑/↓: Complete, Enter: Queue, Esc: Cancel)",
+                prompt.build_type.name
+            );
+            prompt.branch.render(frame, area, &title);
+        }
+    }
+
+    /// Command of the custom action bound to `key`, run on the selected build configuration
+    fn custom_action(&self, key: &KeyEvent) -> Option<Action> {
+        let custom = self
+            .custom_actions
+            .iter()
+            .find(|custom| custom.on_build_type.is_some() && custom.matches(key))?;
+        let Some(build_type) = self.get_selected_build_type() else {
+            return Some(Action::Error(
+                "No build configuration was selected".to_string(),
+            ));
+        };
+        Some(Action::RunExternal {
+            name: custom.name.clone(),
+            command: command_line(
+                custom.on_build_type.as_deref()?,
+                &build_type_placeholders(build_type),
+            ),
+        })
+    }
+
+    /// Why no configuration is shown and the keys to fix it, in place of the empty table
+    fn render_empty(&self, frame: &mut Frame, area: Rect, block: Block) {
+        let filtered = self.filter.is_some() || self.active_filter.is_some();
+        let mut keys = Vec::new();
+        if filtered {
+            keys.extend(["/: Change filter", "F: Saved filters"]);
+        }
+        if !self.failed_projects.is_empty() {
+            keys.push("R: Retry failed projects");
+        }
+        keys.extend(["c: Edit config.toml", "r: Refresh"]);
+        render_empty_state(
+            frame,
+            area,
+            block,
+            "No build configurations to show",
+            &empty_reasons(self.build_types.len(), filtered, self.failed_projects.len()),
+            &keys.join("  "),
+        );
+    }
+
+    fn table_title(&self) -> String {
+        let mut title = format!(
+            "Build Configurations — sorted by {}",
+            self.sort_mode.label()
+        );
+        if let Some(filter) = &self.active_filter {
+            title.push_str(&format!(" — filter: {}", filter.name));
+        }
+        title
+    }
+
     fn select_project(&mut self, selected_string: String) -> color_eyre::Result<()> {
-        if let Some((i, _selected_type)) =
-            self.get_build_types()
-                .iter()
-                .enumerate()
-                .find(|(_, build_type)| {
-                    let search_string =
-                        format!("{name} ({id})", name = build_type.name, id = build_type.id);
-                    search_string == selected_string
-                })
-        {
-            self.table_state.select(Some(i));
+        // build configurations in folded sections can be picked too, so look among all of them
+        let picked = self
+            .visible_build_types()
+            .find(|build_type| build_type.fzf_label().as_deref() == Some(&selected_string))
+            .map(|build_type| build_type.id.clone());
+        if let Some(build_type_id) = picked {
+            self.select_build_type(&build_type_id);
         }
         Ok(())
     }
 }
+
+impl FzfLabel for BuildType {
+    fn fzf_label(&self) -> Option<String> {
+        Some(format!("{name} ({id})", name = self.name, id = self.id))
+    }
+}
 pub trait ProjectsUiExt {
     fn render_input_popup(&self, frame: &mut Frame, area: Rect);
+    fn render_failed_projects(&self, frame: &mut Frame, area: Rect);
 }
 
 impl ProjectsUiExt for Projects {
     fn render_input_popup(&self, frame: &mut Frame, area: Rect) {
-        let popup_width = 70;
-        let popup_height = 3;
-
-        let popup_x = area.x + ((area.width.saturating_sub(popup_width)) / 2);
-        let popup_y = area.y + ((area.height.saturating_sub(popup_height)) / 2);
-
-        let input_area = Rect {
-            x: popup_x,
-            y: popup_y,
-            width: popup_width.min(area.width),
-            height: popup_height.min(area.height),
+        let title = if self.input_mode == InputMode::Exporting {
+            EXPORT_PROMPT_TITLE
+        } else {
+            &match &self.filter_error {
+                Some(error) => format!("Filter build types — {} (Esc: Cancel)", error),
+                None => format!(
+                    "Filter build types — {} matching (id:, project:, type:, /regex/; Enter: Keep, Esc: Cancel)",
+                    self.visible.len()
+                ),
+            }
         };
+        render_prompt(frame, area, title, &self.input_buffer);
+    }
 
-        let input = Paragraph::new(self.input_buffer.as_ref() as &str)
-            .style(Style::default().fg(Color::White).bg(Color::DarkGray))
-            .block(
-                Block::default()
-                    .title("Filter build types (press Enter to apply, Esc to cancel)")
-                    .borders(Borders::ALL),
-            )
-            .wrap(Wrap { trim: true });
-
-        frame.render_widget(Clear, input_area);
-        frame.render_widget(input, input_area);
-        frame.set_cursor_position((
-            input_area.x + self.input_buffer.len() as u16 + 1,
-            input_area.y + 1,
+    fn render_failed_projects(&self, frame: &mut Frame, area: Rect) {
+        let chip_style = Style::default().fg(Color::White).bg(Color::Red);
+
+        let mut spans = Vec::new();
+        for failure in &self.failed_projects {
+            spans.push(Span::styled(
+                format!(
+                    " {} {}: {} ",
+                    self.icons.warning(),
+                    failure.project_id,
+                    failure.error
+                ),
+                chip_style,
+            ));
+            spans.push(Span::raw(" "));
+        }
+        spans.push(Span::styled(
+            "R: Retry",
+            Style::default().fg(Color::DarkGray),
         ));
+
+        frame.render_widget(
+            Paragraph::new(Line::from(spans))
+                .block(Block::default().padding(Padding::horizontal(1))),
+            area,
+        );
     }
 }
 
@@ -188,56 +513,78 @@ impl Component for Projects {
     }
 
     fn register_config_handler(&mut self, config: Config) -> color_eyre::Result<()> {
+        let active_filter = config.active_filter().cloned();
+        if active_filter != self.active_filter {
+            self.active_filter = active_filter;
+            self.refresh_rows();
+            self.table.move_begin();
+        }
+        self.stale_after = config.stale_after();
+        self.icons = config.icons;
+        self.filters = config.filters;
+        self.custom_actions = config.custom_actions;
         Ok(())
     }
 
     fn init(&mut self, _area: Size) -> color_eyre::Result<()> {
-        if !self.build_types.is_empty() {
-            self.table_state.select(Some(0));
-        }
-
+        self.table.move_begin();
         Ok(())
     }
 
     fn handle_key_event(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>> {
+        if let Some(menu) = &mut self.context_menu {
+            return match menu.handle_key(&key) {
+                MenuEvent::Pending => Ok(Some(Action::Render)),
+                MenuEvent::Closed => {
+                    self.context_menu = None;
+                    Ok(Some(Action::Render))
+                }
+                MenuEvent::Picked(keys) => {
+                    self.context_menu = None;
+                    replay_keys(self, keys)
+                }
+            };
+        }
+        if let Some((_, action)) = self.pending_pause.take() {
+            let action = match key.code {
+                KeyCode::Char('y') => action,
+                _ => Action::Render,
+            };
+            return Ok(Some(action));
+        }
+        if self.picking_filter {
+            self.picking_filter = false;
+            return Ok(Some(handle_filter_picker_key(key.code, &self.filters)));
+        }
+        if self.trigger_prompt.is_some() {
+            return Ok(Some(self.handle_trigger_key(key)));
+        }
+        if self.input_mode == InputMode::Normal
+            && let Some(action) = self.custom_action(&key)
+        {
+            return Ok(Some(action));
+        }
+
         self.last_events.push(key);
 
+        if self.input_mode == InputMode::Normal
+            && let Some(previous_key) = self.last_events.iter().rev().nth(1)
+            && previous_key.code == KeyCode::Char('y')
+            && let Some(action) = self.yank_selected(key.code)
+        {
+            self.last_events.clear();
+            return Ok(Some(action));
+        }
+
+        if self.input_mode == InputMode::Normal && self.table.handle_navigation_key(&key) {
+            return Ok(Some(Action::Render));
+        }
+
         let action = if self.input_mode == InputMode::Normal {
             match key.code {
-                KeyCode::Char('G') => {
-                    self.move_end();
-                    Action::Render
-                }
-                KeyCode::Char('g') => {
-                    if let Some(previous_key) = self.last_events.iter().rev().nth(1) {
-                        if previous_key.code == KeyCode::Char('g') {
-                            self.move_begin();
-                            self.last_events.clear()
-                        }
-                    }
-                    Action::Render
-                }
-                KeyCode::Char('j') => {
-                    self.move_down();
-                    Action::Render
-                }
-                KeyCode::Char('k') => {
-                    self.move_up();
-                    Action::Render
-                }
-                KeyCode::Char('f') => {
-                    let build_types: Vec<String> = self
-                        .get_build_types()
-                        .iter()
-                        .map(|build_type: &BuildType| {
-                            format!("{name} ({id})", name = build_type.name, id = build_type.id)
-                        })
-                        .collect();
-
-                    Action::Fzf {
-                        options: build_types,
-                    }
-                }
+                KeyCode::Char('f') => Action::Fzf {
+                    options: fzf_options(self.visible_build_types()),
+                },
                 KeyCode::Char('o') => {
                     self.open_selected_build();
                     Action::Render
@@ -247,46 +594,130 @@ impl Component for Projects {
                     Action::Render
                 }
                 KeyCode::Enter => {
-                    if let Some(selected_index) = self.table_state.selected() {
-                        if let Some(build_type) = self.get_build_types().get(selected_index) {
-                            Action::LoadBuilds {
-                                project_id: build_type.id.clone(),
-                                title: build_type.name.clone(),
-                            }
-                        } else {
-                            Action::Render
+                    if self.toggle_selected_group() {
+                        Action::Render
+                    } else if let Some(build_type) = self.get_selected_build_type() {
+                        Action::LoadBuilds {
+                            project_id: build_type.id.clone(),
+                            title: build_type.name.clone(),
                         }
+                        .on_server_of(build_type)
                     } else {
                         Action::Render
                     }
                 }
+                MENU_KEY => {
+                    self.context_menu = Some(self.context_menu());
+                    Action::Render
+                }
+                KeyCode::Char('s') => {
+                    self.toggle_sort_mode();
+                    Action::Render
+                }
+                KeyCode::Char('V') => match self.get_selected_build_type() {
+                    Some(build_type) => Action::OpenSettingsFile {
+                        build_type: build_type.clone(),
+                    }
+                    .on_server_of(build_type),
+                    None => Action::Error("No build configuration was selected".to_string()),
+                },
+                KeyCode::Char('a') => match self.get_selected_build_type() {
+                    Some(build_type) => Action::LoadAgentCompatibility {
+                        build_type_id: build_type.id.clone(),
+                        title: build_type.name.clone(),
+                    }
+                    .on_server_of(build_type),
+                    None => Action::Error("No build configuration was selected".to_string()),
+                },
+                KeyCode::Char('P') => match self.get_selected_build_type() {
+                    Some(build_type) => Action::LoadParameters {
+                        build_type_id: build_type.id.clone(),
+                        title: build_type.name.clone(),
+                    }
+                    .on_server_of(build_type),
+                    None => Action::Error("No build configuration was selected".to_string()),
+                },
+                KeyCode::Char('p') => self.confirm_pause(),
+                KeyCode::Char('T') => self.on_selected_server(Action::LoadTimeline),
+                KeyCode::Char('D') => Action::ShowDashboard,
+                KeyCode::Char('S') => self.on_selected_server(Action::LoadServerOverview),
+                KeyCode::Char('c') => Action::EditConfig,
+                KeyCode::Char('t') => self.start_trigger(),
+                KeyCode::Char('M') => match self.get_selected_build_type() {
+                    Some(BuildType {
+                        project_id: Some(project_id),
+                        ..
+                    }) => self.on_selected_server(Action::LoadMutes {
+                        project_id: project_id.clone(),
+                        back: Box::new(Action::ShowProjects),
+                    }),
+                    _ => Action::Error("No project was selected".to_string()),
+                },
+                KeyCode::Char('F') => {
+                    self.picking_filter = true;
+                    Action::Render
+                }
                 KeyCode::Char('/') => {
                     self.input_mode = InputMode::Editing;
+                    self.filter_before_edit = self.filter_string.clone();
+                    self.filter_typed_at = None;
+                    self.input_buffer = self.filter_string.clone().unwrap_or_default();
+                    Action::Render
+                }
+                KeyCode::Char('E') => {
+                    self.input_mode = InputMode::Exporting;
+                    self.input_buffer = "build-configurations.csv".to_string();
                     Action::Render
                 }
+                KeyCode::Char('R') if !self.failed_projects.is_empty() => {
+                    Action::RetryFailedProjects
+                }
+                KeyCode::Char('r') => Action::LoadProjects { bypass_cache: true },
+                _ if is_alternate_key(&key) => Action::ShowPreviousBuilds { current: None },
+                KeyCode::Char('\'') => Action::OpenRecent { current: None },
                 _ => Action::Render,
             }
         } else {
             match key.code {
+                KeyCode::Esc if self.input_mode == InputMode::Editing => {
+                    self.input_buffer.clear();
+                    self.input_mode = InputMode::Normal;
+                    self.filter_typed_at = None;
+                    self.filter_error = None;
+                    let previous = self.filter_before_edit.take();
+                    // the filter applied before editing was valid
+                    let _ = self.filter_build_types(previous.as_ref());
+                    Action::Render
+                }
                 KeyCode::Esc => {
                     self.input_mode = InputMode::Normal;
                     Action::Render
                 }
                 KeyCode::Char(c) if c.is_alphanumeric() || c.is_ascii_graphic() || c == ' ' => {
                     self.input_buffer.push(c);
+                    self.filter_typed_at = Some(Instant::now());
                     Action::Render
                 }
                 KeyCode::Backspace => {
                     self.input_buffer.pop();
+                    self.filter_typed_at = Some(Instant::now());
                     Action::Render
                 }
+                KeyCode::Enter if self.input_mode == InputMode::Exporting => {
+                    let path = expand_path(&self.input_buffer);
+                    self.input_buffer.clear();
+                    self.input_mode = InputMode::Normal;
+                    self.export_to(&path)
+                }
                 KeyCode::Enter => {
-                    let buffer_clone = self.input_buffer.clone();
-                    if buffer_clone.is_empty() {
-                        self.filter_build_types(None);
-                    } else {
-                        self.filter_build_types(Some(&buffer_clone));
+                    if self.filter_typed_at.is_some() {
+                        self.apply_typed_filter();
+                    }
+                    // an invalid filter stays in the popup to be fixed
+                    if self.filter_error.is_some() {
+                        return Ok(Some(Action::Render));
                     }
+                    self.filter_before_edit = None;
                     self.input_buffer.clear();
                     self.input_mode = InputMode::Normal;
                     Action::Render
@@ -300,7 +731,14 @@ impl Component for Projects {
     fn update(&mut self, action: Action) -> color_eyre::Result<Option<Action>> {
         match action {
             Action::Tick => {
-                // add any logic here that should run on every tick
+                if self.input_mode == InputMode::Editing
+                    && self
+                        .filter_typed_at
+                        .is_some_and(|typed_at| typed_at.elapsed() >= FILTER_DEBOUNCE)
+                {
+                    self.apply_typed_filter();
+                    return Ok(Some(Action::Render));
+                }
             }
             Action::Render => {
                 // add any logic here that should run on every render
@@ -308,22 +746,76 @@ impl Component for Projects {
             Action::FzfSelected { selected } => {
                 self.select_project(selected)?;
             }
+            Action::ShowBranches {
+                build_type_id,
+                branches,
+            } => {
+                if let Some(prompt) = &mut self.trigger_prompt
+                    && prompt.build_type.id == build_type_id
+                {
+                    prompt.branch.branches = Some(branches);
+                    return Ok(Some(Action::Render));
+                }
+            }
+            Action::PausedChanged {
+                build_type_id,
+                paused,
+            } => {
+                if let Some(build_type) = self
+                    .build_types
+                    .iter_mut()
+                    .find(|build_type| build_type.id == build_type_id)
+                {
+                    build_type.paused = Some(paused);
+                    self.refresh_rows();
+                    return Ok(Some(Action::Render));
+                }
+            }
+            Action::ProjectsFetched {
+                build_types,
+                failed,
+            } => {
+                self.build_types = build_types;
+                self.failed_projects = failed;
+                self.fetched_at = Some(OffsetDateTime::now_utc());
+                self.refresh_rows();
+                return Ok(Some(Action::Render));
+            }
             _ => {}
         }
         Ok(None)
     }
 
+    fn tab_title(&self) -> String {
+        "Projects".to_string()
+    }
+
+    fn is_typing(&self) -> bool {
+        self.input_mode != InputMode::Normal || self.trigger_prompt.is_some()
+    }
+
+    fn session(&self) -> Option<Session> {
+        Some(Session::default())
+    }
+
     fn draw(&mut self, frame: &mut Frame, area: Rect) -> color_eyre::Result<()> {
+        let errors_height = if self.failed_projects.is_empty() {
+            0
+        } else {
+            1
+        };
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
-                // Constraint::Length(3), // Header height
-                Constraint::Min(0),    // Table takes remaining space
-                Constraint::Length(2), // Footer height
+                Constraint::Length(errors_height), // Failed project chips
+                Constraint::Min(0),                // Table takes remaining space
+                Constraint::Length(2),             // Footer height
             ])
             .split(area);
 
-        let header = Row::new(vec![
+        // configurations are only tagged with their server when several servers are configured
+        let show_server = self.build_types.iter().any(|bt| bt.server.is_some());
+        let mut header_cells = vec![
             format!(
                 "Name {}",
                 self.filter_string
@@ -332,72 +824,139 @@ impl Component for Projects {
                     .unwrap_or_default()
             ),
             "ID".to_string(),
-        ])
-        .style(
-            Style::default()
-                .fg(Color::Yellow)
-                .add_modifier(Modifier::BOLD),
-        )
-        .height(1)
-        .bottom_margin(1);
-
-        let project = if let Some(selected) = self.table_state.selected() {
-            if let Some(selected_project) = self.get_build_types().get(selected) {
-                let title = format!(
-                    "Root project: {}",
-                    selected_project.project_name.as_deref().unwrap_or("N/A")
-                );
-                let style = Style::default()
+        ];
+        if show_server {
+            header_cells.push("Server".to_string());
+        }
+        let header = Row::new(header_cells)
+            .style(
+                Style::default()
                     .fg(Color::Yellow)
-                    .add_modifier(Modifier::BOLD);
+                    .add_modifier(Modifier::BOLD),
+            )
+            .height(1)
+            .bottom_margin(1);
 
-                let row = Row::new(vec![title]).style(style).height(1).top_margin(1);
-                Some(row)
-            } else {
-                None
-            }
-        } else {
-            None
-        };
+        let project = self.get_selected_build_type().map(|selected_project| {
+            let title = format!("Root project: {}", project_name(selected_project));
+            let style = Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD);
+            Row::new(vec![title]).style(style).height(1).top_margin(1)
+        });
 
-        let footer = Paragraph::new(
+        let footer = Paragraph::new(format!(
             concat!(
-            "j/k: Move  gg/G: Top/Bottom  Enter: Open builds  f: Fuzzy  /: Filter  o: Open in Browser  e: Edit in Browser ",
-            "\n",
-            "Build Configuration type: Regular ⚙️, Composite 🧩, Deployment 🚀",
-            )
-        )
+                "j/k: Move  gg/G: Top/Bottom  Enter: Open builds/Fold project  s: Sort  f: Fuzzy  /: Filter  F: Saved filters  o: Open in Browser  e: Edit in Browser  V: Settings in VCS  a: Agents  P: Parameters  p: Pause/Resume  .: Menu  yy/yi: Copy URL/ID  t: Queue build  E: Export  T: Timeline  D: Favorites  S: Server  M: Muted tests  c: Edit config  r: Refresh  Ctrl-^: Last config  ': Recent ",
+                "\n",
+                "Build Configuration type: Regular {}, Composite {}, Deployment {}, Paused {}",
+            ),
+            kind_icon("regular", self.icons),
+            kind_icon("composite", self.icons),
+            kind_icon("deployment", self.icons),
+            self.icons.paused(),
+        ))
         .style(Style::default().fg(Color::DarkGray))
             .block(Block::default().padding(Padding::horizontal(1)));
 
+        let indent = if self.sort_mode == SortMode::Project {
+            "  "
+        } else {
+            ""
+        };
+        let filter = self.filter.as_ref();
+        let match_style = Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
         let rows: Vec<Row> = self
-            .get_build_types()
-            .into_iter()
-            .map(|build_type| {
-                let icon = self.icon_for(&build_type);
-                let name_with_icon = format!("{} {}", icon, build_type.name);
-                Row::new(vec![name_with_icon, build_type.id.clone()])
+            .table
+            .items()
+            .iter()
+            .map(|row| match row {
+                ProjectRow::Group {
+                    name,
+                    count,
+                    collapsed,
+                } => {
+                    let marker = if *collapsed {
+                        self.icons.arrow_right()
+                    } else {
+                        self.icons.arrow_down()
+                    };
+                    Row::new(vec![format!("{} {} ({})", marker, name, count)]).style(
+                        Style::default()
+                            .fg(Color::Cyan)
+                            .add_modifier(Modifier::BOLD),
+                    )
+                }
+                ProjectRow::BuildType(index) => {
+                    let build_type = &self.build_types[*index];
+                    let icon = icon_for(build_type, self.icons);
+                    let name = &build_type.name;
+                    let mut name_with_icon = vec![Span::raw(format!("{}{} ", indent, icon))];
+                    match filter.and_then(|filter| filter.name_match(name)) {
+                        Some(matched) => name_with_icon.extend([
+                            Span::raw(&name[..matched.start]),
+                            Span::styled(&name[matched.clone()], match_style),
+                            Span::raw(&name[matched.end..]),
+                        ]),
+                        None => name_with_icon.push(Span::raw(name.as_str())),
+                    }
+                    let mut cells = vec![
+                        Cell::from(Line::from(name_with_icon)),
+                        Cell::from(build_type.id.as_str()),
+                    ];
+                    if show_server {
+                        cells.push(Cell::from(build_type.server.as_deref().unwrap_or_default()));
+                    }
+                    let row = Row::new(cells);
+                    if build_type.paused == Some(true) {
+                        row.style(Style::default().fg(Color::DarkGray))
+                    } else {
+                        row
+                    }
+                }
             })
             .collect();
 
-        let table = Table::new(rows, &[Constraint::Min(0), Constraint::Min(30)])
+        let mut widths = vec![Constraint::Min(0), Constraint::Min(30)];
+        if show_server {
+            widths.push(Constraint::Length(20));
+        }
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(self.table_title())
+            .title_top(freshness_line(self.fetched_at, self.stale_after));
+        let table = Table::new(rows, widths)
             .header(header)
             .footer(project.unwrap_or_else(|| Row::new(vec!["No project selected"])))
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title("Build Configurations"),
-            )
+            .block(block.clone())
             .column_spacing(1)
             .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
             .highlight_symbol(">> ");
 
-        frame.render_stateful_widget(table, chunks[0], &mut self.table_state);
-        frame.render_widget(footer, chunks[1]);
+        if !self.failed_projects.is_empty() {
+            self.render_failed_projects(frame, chunks[0]);
+        }
+        self.table.set_viewport(chunks[1]);
+        if self.fetched_at.is_some() && self.table.items().is_empty() {
+            self.render_empty(frame, chunks[1], block);
+        } else {
+            frame.render_stateful_widget(table, chunks[1], &mut self.table.state);
+        }
+        frame.render_widget(footer, chunks[2]);
 
-        if self.input_mode == InputMode::Editing {
+        if self.input_mode != InputMode::Normal {
             self.render_input_popup(frame, area);
         }
+        if self.picking_filter {
+            render_filter_picker(frame, area, &self.filters, self.active_filter.as_ref());
+        }
+        self.render_trigger_prompt(frame, area);
+        self.render_confirm(frame, area);
+        if let Some(menu) = &mut self.context_menu {
+            menu.render(frame, area);
+        }
 
         Ok(())
     }
@@ -0,0 +1,169 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap};
+
+use crate::view::projects::branch_completions;
+
+const PROMPT_WIDTH: u16 = 70;
+const MAX_COMPLETIONS: u16 = 8;
+
+fn prompt_area(area: Rect) -> Rect {
+    let popup_width = PROMPT_WIDTH;
+    let popup_height = 3;
+
+    let popup_x = area.x + ((area.width.saturating_sub(popup_width)) / 2);
+    let popup_y = area.y + ((area.height.saturating_sub(popup_height)) / 2);
+
+    Rect {
+        x: popup_x,
+        y: popup_y,
+        width: popup_width.min(area.width),
+        height: popup_height.min(area.height),
+    }
+}
+
+/// Single-line text input centered over `area`, with the cursor after `text`
+pub fn render_prompt(frame: &mut Frame, area: Rect, title: &str, text: &str) {
+    let input_area = prompt_area(area);
+
+    let input = Paragraph::new(text)
+        .style(Style::default().fg(Color::White).bg(Color::DarkGray))
+        .block(Block::default().title(title).borders(Borders::ALL))
+        .wrap(Wrap { trim: true });
+
+    frame.render_widget(Clear, input_area);
+    frame.render_widget(input, input_area);
+    frame.set_cursor_position((
+        input_area.x + text.chars().count() as u16 + 1,
+        input_area.y + 1,
+    ));
+}
+
+/// Yellow "Confirm" popup centered over `area`, as wide as `prompt`
+pub fn render_confirm_popup(frame: &mut Frame, area: Rect, prompt: &str) {
+    let popup_width = (prompt.chars().count() as u16 + 4).min(area.width);
+    let popup_height = 3.min(area.height);
+    let popup_area = Rect {
+        x: area.x + (area.width.saturating_sub(popup_width)) / 2,
+        y: area.y + (area.height.saturating_sub(popup_height)) / 2,
+        width: popup_width,
+        height: popup_height,
+    };
+
+    let popup = Paragraph::new(prompt).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Confirm")
+            .border_style(Style::default().fg(Color::Yellow)),
+    );
+
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(popup, popup_area);
+}
+
+/// Completions listed under a prompt drawn with [`render_prompt`], `selected` highlighted
+pub fn render_completions(
+    frame: &mut Frame,
+    area: Rect,
+    completions: &[&str],
+    selected: Option<usize>,
+) {
+    if completions.is_empty() {
+        return;
+    }
+    let input_area = prompt_area(area);
+    let bottom = area.y + area.height;
+    let list_y = input_area.y + input_area.height;
+    let list_height = (completions.len() as u16).min(MAX_COMPLETIONS) + 2;
+    let list_area = Rect {
+        x: input_area.x,
+        y: list_y,
+        width: input_area.width,
+        height: list_height.min(bottom.saturating_sub(list_y)),
+    };
+
+    let items: Vec<ListItem> = completions.iter().map(|c| ListItem::new(*c)).collect();
+    let list = List::new(items)
+        .style(Style::default().fg(Color::White).bg(Color::DarkGray))
+        .block(Block::default().borders(Borders::ALL))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    let mut state = ListState::default().with_selected(selected);
+
+    frame.render_widget(Clear, list_area);
+    frame.render_stateful_widget(list, list_area, &mut state);
+}
+
+/// Branch typed in a prompt, completed from the branches of a configuration
+#[derive(Debug, Default)]
+pub struct BranchPrompt {
+    pub input: String,
+    // None while loading
+    pub branches: Option<Vec<String>>,
+    // highlighted completion, the typed branch is used if none is
+    selected: Option<usize>,
+}
+
+/// What a key did to a [`BranchPrompt`]
+#[derive(Debug, PartialEq, Eq)]
+pub enum BranchPromptEvent {
+    Edited,
+    Cancelled,
+    /// The highlighted completion or the typed branch, trimmed, empty if nothing was typed
+    Submitted(String),
+}
+
+impl BranchPrompt {
+    fn completions(&self) -> Vec<&str> {
+        self.branches
+            .as_deref()
+            .map(|branches| branch_completions(branches, &self.input))
+            .unwrap_or_default()
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> BranchPromptEvent {
+        let completions = self.completions().len();
+        match key.code {
+            KeyCode::Esc => return BranchPromptEvent::Cancelled,
+            KeyCode::Down if completions > 0 => {
+                self.selected = Some(self.selected.map_or(0, |i| (i + 1) % completions));
+            }
+            KeyCode::Up if completions > 0 => {
+                self.selected = Some(
+                    self.selected
+                        .map_or(completions - 1, |i| (i + completions - 1) % completions),
+                );
+            }
+            KeyCode::Char(c) => {
+                self.input.push(c);
+                self.selected = None;
+            }
+            KeyCode::Backspace => {
+                self.input.pop();
+                self.selected = None;
+            }
+            KeyCode::Enter => {
+                let completion = self
+                    .selected
+                    .and_then(|i| self.completions().get(i).map(|branch| branch.to_string()));
+                return BranchPromptEvent::Submitted(
+                    completion.unwrap_or_else(|| self.input.trim().to_string()),
+                );
+            }
+            _ => {}
+        }
+        BranchPromptEvent::Edited
+    }
+
+    /// Prompt titled `title` with the matching branches under it
+    pub fn render(&self, frame: &mut Frame, area: Rect, title: &str) {
+        let title = if self.branches.is_none() {
+            format!("{} — loading branches…", title)
+        } else {
+            title.to_string()
+        };
+        render_prompt(frame, area, &title, &self.input);
+        render_completions(frame, area, &self.completions(), self.selected);
+    }
+}
@@ -0,0 +1,106 @@
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{Frame, layout::Rect};
+
+use super::Component;
+use super::prompt::render_prompt;
+use crate::action::Action;
+
+/// Prompt for a new token, opened on top of the current view when the server rejects the
+/// credentials
+#[derive(Default)]
+pub struct Reauth {
+    // server the token is for, None for the main server
+    server: Option<String>,
+    status: u16,
+    // action the rejected request was sent for
+    retry: Option<Box<Action>>,
+    // None while closed
+    input: Option<String>,
+}
+
+impl Reauth {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.input.is_some()
+    }
+
+    fn title(&self) -> String {
+        let server = self
+            .server
+            .as_ref()
+            .map(|name| format!("{}: ", name))
+            .unwrap_or_default();
+        format!(
+            "{}Token rejected ({}), type a new one — Enter: Retry  Esc: Cancel",
+            server, self.status
+        )
+    }
+}
+
+impl Component for Reauth {
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        let Some(input) = &mut self.input else {
+            return Ok(None);
+        };
+        match key.code {
+            KeyCode::Esc => {
+                self.input = None;
+                self.retry = None;
+            }
+            KeyCode::Enter => {
+                let token = input.trim().to_string();
+                if token.is_empty() {
+                    return Ok(Some(Action::Render));
+                }
+                self.input = None;
+                return Ok(Some(Action::Reauthenticate {
+                    server: self.server.clone(),
+                    token,
+                    retry: self.retry.take(),
+                }));
+            }
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Char(c) => input.push(c),
+            _ => {}
+        }
+        Ok(Some(Action::Render))
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        // requests failing while the prompt is open keep it as it is
+        if let Action::AuthFailed {
+            server,
+            status,
+            retry,
+        } = action
+            && !self.is_open()
+        {
+            self.server = server;
+            self.status = status;
+            self.retry = retry;
+            self.input = Some(String::new());
+            return Ok(Some(Action::Render));
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let Some(input) = &self.input else {
+            return Ok(());
+        };
+        // the token isn't shown, only how long it is
+        render_prompt(
+            frame,
+            area,
+            &self.title(),
+            &"•".repeat(input.chars().count()),
+        );
+        Ok(())
+    }
+}
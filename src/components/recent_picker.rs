@@ -0,0 +1,231 @@
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::{Block, Borders, Clear, Row, Table};
+use teamcity::types::BuildType;
+
+use super::Component;
+use super::selectable_table::SelectableTable;
+use crate::action::Action;
+
+const PICKER_WIDTH: u16 = 64;
+// rows shown at most, the list scrolls past them
+const PICKER_ROWS: u16 = 12;
+
+/// Configuration of the recently viewed list
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecentEntry {
+    pub build_type_id: String,
+    pub name: String,
+    pub project: Option<String>,
+}
+
+impl RecentEntry {
+    /// Entries of the `recent` configuration IDs, named after `build_types`; configurations that
+    /// aren't loaded are listed by ID
+    pub fn list(recent: &[String], build_types: &[BuildType]) -> Vec<Self> {
+        recent
+            .iter()
+            .map(|id| match build_types.iter().find(|bt| bt.id == *id) {
+                Some(build_type) => Self {
+                    build_type_id: id.clone(),
+                    name: build_type.name.clone(),
+                    project: build_type.project_name.clone(),
+                },
+                None => Self {
+                    build_type_id: id.clone(),
+                    name: id.clone(),
+                    project: None,
+                },
+            })
+            .collect()
+    }
+}
+
+/// Recently viewed configurations, most recent first, opened with `'` on top of the current view
+#[derive(Default)]
+pub struct RecentPicker {
+    // None while closed
+    entries: Option<SelectableTable<RecentEntry>>,
+}
+
+impl RecentPicker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.entries.is_some()
+    }
+
+    /// Opens the picker on `entries` with the first one other than the `current` configuration
+    /// selected, to jump back and forth between two with `'` `Enter`
+    pub fn open(&mut self, entries: Vec<RecentEntry>, current: Option<&str>) {
+        let mut table = SelectableTable::new(entries);
+        table.select_where(|entry| Some(entry.build_type_id.as_str()) != current);
+        self.entries = Some(table);
+    }
+}
+
+impl Component for RecentPicker {
+    /// `j`/`k` move, `Enter` opens the selected configuration and `1`-`9` the one with that
+    /// number, `Esc` or `'` close the picker
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        let Some(entries) = &mut self.entries else {
+            return Ok(None);
+        };
+        if entries.handle_navigation_key(&key) {
+            return Ok(Some(Action::Render));
+        }
+        let picked = match key.code {
+            KeyCode::Esc | KeyCode::Char('\'') | KeyCode::Char('q') => None,
+            KeyCode::Enter => entries.selected().cloned(),
+            KeyCode::Char(c @ '1'..='9') => match entries.items().get(c as usize - '1' as usize) {
+                Some(entry) => Some(entry.clone()),
+                None => return Ok(Some(Action::Render)),
+            },
+            _ => return Ok(Some(Action::Render)),
+        };
+        self.entries = None;
+        Ok(Some(match picked {
+            Some(entry) => Action::LoadBuilds {
+                project_id: entry.build_type_id,
+                title: entry.name,
+            },
+            None => Action::Render,
+        }))
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let Some(entries) = &mut self.entries else {
+            return Ok(());
+        };
+        let rows: Vec<Row> = if entries.items().is_empty() {
+            vec![Row::new(vec![
+                String::new(),
+                "No configuration was opened yet".to_string(),
+            ])]
+        } else {
+            entries
+                .items()
+                .iter()
+                .enumerate()
+                .map(|(i, entry)| {
+                    let number = if i < 9 {
+                        (i + 1).to_string()
+                    } else {
+                        String::new()
+                    };
+                    Row::new(vec![
+                        number,
+                        entry.name.clone(),
+                        entry.project.clone().unwrap_or_default(),
+                    ])
+                })
+                .collect()
+        };
+
+        let popup_width = PICKER_WIDTH.min(area.width);
+        let popup_height = (rows.len() as u16)
+            .min(PICKER_ROWS)
+            .saturating_add(2)
+            .min(area.height);
+        let popup_area = Rect {
+            x: area.x + (area.width.saturating_sub(popup_width)) / 2,
+            y: area.y + (area.height.saturating_sub(popup_height)) / 2,
+            width: popup_width,
+            height: popup_height,
+        };
+        entries.set_viewport(popup_area);
+
+        let table = Table::new(
+            rows,
+            &[
+                Constraint::Length(2),
+                Constraint::Percentage(60),
+                Constraint::Min(0),
+            ],
+        )
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Recent configurations")
+                .title_bottom("Enter/1-9: Open  Esc: Close")
+                .border_style(Style::default().fg(Color::Cyan)),
+        )
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+        frame.render_widget(Clear, popup_area);
+        frame.render_stateful_widget(table, popup_area, &mut entries.state);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn entries() -> Vec<RecentEntry> {
+        let build_types = [BuildType {
+            id: "Web_Build".to_string(),
+            name: "Build".to_string(),
+            project_name: Some("Web".to_string()),
+            ..BuildType::default()
+        }];
+        RecentEntry::list(
+            &["Web_Build".to_string(), "Api_Test".to_string()],
+            &build_types,
+        )
+    }
+
+    #[test]
+    fn entries_are_named_after_loaded_configurations() {
+        let entries = entries();
+        assert_eq!(entries[0].name, "Build");
+        assert_eq!(entries[0].project.as_deref(), Some("Web"));
+        assert_eq!(
+            entries[1],
+            RecentEntry {
+                build_type_id: "Api_Test".to_string(),
+                name: "Api_Test".to_string(),
+                project: None,
+            }
+        );
+    }
+
+    #[test]
+    fn picking_opens_the_builds_of_a_configuration() {
+        let mut picker = RecentPicker::new();
+        picker.open(entries(), Some("Web_Build"));
+        assert_eq!(
+            picker.handle_key_event(key(KeyCode::Enter)).unwrap(),
+            Some(Action::LoadBuilds {
+                project_id: "Api_Test".to_string(),
+                title: "Api_Test".to_string(),
+            })
+        );
+        assert!(!picker.is_open());
+
+        picker.open(entries(), None);
+        assert_eq!(
+            picker.handle_key_event(key(KeyCode::Char('1'))).unwrap(),
+            Some(Action::LoadBuilds {
+                project_id: "Web_Build".to_string(),
+                title: "Build".to_string(),
+            })
+        );
+
+        picker.open(entries(), None);
+        picker.handle_key_event(key(KeyCode::Char('9'))).unwrap();
+        assert!(picker.is_open());
+        picker.handle_key_event(key(KeyCode::Char('\''))).unwrap();
+        assert!(!picker.is_open());
+    }
+}
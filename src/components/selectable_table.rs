@@ -0,0 +1,310 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::layout::Rect;
+use ratatui::widgets::TableState;
+
+/// Label of a row in the fzf picker, None for rows that can't be picked like section headers
+pub trait FzfLabel {
+    fn fzf_label(&self) -> Option<String>;
+}
+
+/// Labels of the pickable `rows`, in order, for `Action::Fzf`
+pub fn fzf_options<'a, T: FzfLabel + 'a>(rows: impl IntoIterator<Item = &'a T>) -> Vec<String> {
+    rows.into_iter().filter_map(FzfLabel::fzf_label).collect()
+}
+
+/// Rows in view of a bordered table drawn in `area` with a header line and its bottom margin
+pub fn visible_rows(area: Rect) -> usize {
+    area.height.saturating_sub(4) as usize
+}
+
+/// Rows `Ctrl-d`/`Ctrl-u` (half a page) and `Ctrl-f`/`Ctrl-b` (a page) move the selection by with
+/// `page` rows in view, None for any other key
+pub fn page_offset(key: &KeyEvent, page: usize) -> Option<isize> {
+    if !key.modifiers.contains(KeyModifiers::CONTROL) {
+        return None;
+    }
+    let page = page.max(1) as isize;
+    let half = (page / 2).max(1);
+    match key.code {
+        KeyCode::Char('d') => Some(half),
+        KeyCode::Char('u') => Some(-half),
+        KeyCode::Char('f') => Some(page),
+        KeyCode::Char('b') => Some(-page),
+        _ => None,
+    }
+}
+
+/// Row `offset` rows away from `selected`, stopping at the first and last of `len` rows rather
+/// than wrapping around
+pub fn paged(selected: Option<usize>, len: usize, offset: isize) -> Option<usize> {
+    if len == 0 {
+        return None;
+    }
+    Some(
+        selected
+            .unwrap_or(0)
+            .saturating_add_signed(offset)
+            .min(len - 1),
+    )
+}
+
+/// Rows of a list view with the selected one: wrap-around `j`/`k` navigation, `gg`/`G`, paging,
+/// filtering and picking a row with fzf
+#[derive(Debug)]
+pub struct SelectableTable<T> {
+    items: Vec<T>,
+    pub state: TableState,
+    // first `g` of a `gg` sequence was pressed
+    pending_g: bool,
+    // rows in view when last drawn, what paging moves by
+    page: usize,
+}
+
+impl<T> Default for SelectableTable<T> {
+    fn default() -> Self {
+        Self {
+            items: Vec::new(),
+            state: TableState::default(),
+            pending_g: false,
+            page: 0,
+        }
+    }
+}
+
+impl<T> SelectableTable<T> {
+    pub fn new(items: Vec<T>) -> Self {
+        let mut table = Self::default();
+        table.set_items(items);
+        table
+    }
+
+    pub fn items(&self) -> &[T] {
+        &self.items
+    }
+
+    /// Replaces the rows, keeping the selected position if it still exists
+    pub fn set_items(&mut self, items: Vec<T>) {
+        self.items = items;
+        self.clamp_selection();
+    }
+
+    /// Keeps only the rows matching `keep`
+    pub fn retain(&mut self, keep: impl FnMut(&T) -> bool) {
+        self.items.retain(keep);
+        self.clamp_selection();
+    }
+
+    /// Rows to reorder in place, the selection stays on the same position rather than the same row
+    pub fn items_mut(&mut self) -> &mut Vec<T> {
+        &mut self.items
+    }
+
+    fn clamp_selection(&mut self) {
+        let selected = match self.state.selected() {
+            _ if self.items.is_empty() => None,
+            Some(i) => Some(i.min(self.items.len() - 1)),
+            None => Some(0),
+        };
+        self.state.select(selected);
+    }
+
+    pub fn selected_index(&self) -> Option<usize> {
+        self.state.selected()
+    }
+
+    pub fn selected(&self) -> Option<&T> {
+        self.items.get(self.state.selected()?)
+    }
+
+    /// Selects the row at `index`, or the last one if it is past the end
+    pub fn select(&mut self, index: Option<usize>) {
+        let index = index.filter(|_| !self.items.is_empty());
+        self.state
+            .select(index.map(|i| i.min(self.items.len() - 1)));
+    }
+
+    /// Selects the first row matching `matches`, returns whether there was one
+    pub fn select_where(&mut self, matches: impl FnMut(&T) -> bool) -> bool {
+        match self.items.iter().position(matches) {
+            Some(i) => {
+                self.state.select(Some(i));
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn move_down(&mut self) {
+        let len = self.items.len();
+        self.state.select(match self.state.selected() {
+            _ if len == 0 => None,
+            Some(i) if i + 1 < len => Some(i + 1),
+            _ => Some(0),
+        });
+    }
+
+    pub fn move_up(&mut self) {
+        let len = self.items.len();
+        self.state.select(match self.state.selected() {
+            _ if len == 0 => None,
+            Some(0) | None => Some(len - 1),
+            Some(i) => Some(i - 1),
+        });
+    }
+
+    pub fn move_begin(&mut self) {
+        self.select(Some(0));
+    }
+
+    pub fn move_end(&mut self) {
+        self.select(Some(self.items.len().saturating_sub(1)));
+    }
+
+    /// Sets the page size from the `area` the table is drawn in, see [`visible_rows`]
+    pub fn set_viewport(&mut self, area: Rect) {
+        self.page = visible_rows(area);
+    }
+
+    /// Moves the selection for `j`/`k`, the arrow keys, `gg`, `G` and the paging keys, returns
+    /// whether `key` was one of them. Keys of a view must go through here first for `gg` to be
+    /// told from a lone `g`.
+    pub fn handle_navigation_key(&mut self, key: &KeyEvent) -> bool {
+        let pending_g = std::mem::take(&mut self.pending_g);
+        if let Some(offset) = page_offset(key, self.page) {
+            self.select(paged(self.selected_index(), self.items.len(), offset));
+            return true;
+        }
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => self.move_down(),
+            KeyCode::Char('k') | KeyCode::Up => self.move_up(),
+            KeyCode::Char('G') => self.move_end(),
+            KeyCode::Char('g') if pending_g => self.move_begin(),
+            KeyCode::Char('g') => self.pending_g = true,
+            _ => return false,
+        }
+        true
+    }
+}
+
+impl<T: FzfLabel> SelectableTable<T> {
+    /// Labels of the pickable rows for `Action::Fzf`
+    pub fn fzf_options(&self) -> Vec<String> {
+        fzf_options(&self.items)
+    }
+
+    /// Selects the row picked in fzf, returns whether it is still in the table
+    pub fn select_fzf(&mut self, selected: &str) -> bool {
+        self.select_where(|row| row.fzf_label().as_deref() == Some(selected))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+
+    impl FzfLabel for &str {
+        fn fzf_label(&self) -> Option<String> {
+            (!self.starts_with('#')).then(|| self.to_string())
+        }
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn navigation_wraps_around() {
+        let mut table = SelectableTable::new(vec!["a", "b", "c"]);
+        assert_eq!(table.selected(), Some(&"a"));
+
+        table.move_up();
+        assert_eq!(table.selected(), Some(&"c"));
+        table.move_down();
+        assert_eq!(table.selected(), Some(&"a"));
+
+        assert!(table.handle_navigation_key(&key(KeyCode::Char('G'))));
+        assert_eq!(table.selected(), Some(&"c"));
+        assert!(table.handle_navigation_key(&key(KeyCode::Char('g'))));
+        assert_eq!(table.selected(), Some(&"c"));
+        assert!(table.handle_navigation_key(&key(KeyCode::Char('g'))));
+        assert_eq!(table.selected(), Some(&"a"));
+
+        // a key in between breaks the gg sequence
+        table.move_end();
+        table.handle_navigation_key(&key(KeyCode::Char('g')));
+        assert!(!table.handle_navigation_key(&key(KeyCode::Char('x'))));
+        table.handle_navigation_key(&key(KeyCode::Char('g')));
+        assert_eq!(table.selected(), Some(&"c"));
+    }
+
+    #[test]
+    fn paging_moves_by_the_rows_in_view() {
+        let mut table = SelectableTable::new((0..50).collect::<Vec<_>>());
+        // a 20 rows high table area shows 16 rows under its borders and header
+        table.set_viewport(Rect::new(0, 0, 80, 20));
+        let ctrl = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL);
+
+        assert!(table.handle_navigation_key(&ctrl('d')));
+        assert_eq!(table.selected(), Some(&8));
+        assert!(table.handle_navigation_key(&ctrl('f')));
+        assert_eq!(table.selected(), Some(&24));
+        assert!(table.handle_navigation_key(&ctrl('u')));
+        assert_eq!(table.selected(), Some(&16));
+        // stops at the ends instead of wrapping around
+        table.handle_navigation_key(&ctrl('b'));
+        table.handle_navigation_key(&ctrl('b'));
+        assert_eq!(table.selected(), Some(&0));
+        table.move_end();
+        table.handle_navigation_key(&ctrl('f'));
+        assert_eq!(table.selected(), Some(&49));
+        assert!(!table.handle_navigation_key(&ctrl('x')));
+    }
+
+    #[test]
+    fn selection_is_kept_within_the_rows() {
+        let mut table = SelectableTable::new(vec!["a", "b", "c"]);
+        table.move_end();
+        table.retain(|row| *row != "c");
+        assert_eq!(table.selected(), Some(&"b"));
+
+        table.set_items(vec![]);
+        assert_eq!(table.selected_index(), None);
+        table.move_down();
+        assert_eq!(table.selected_index(), None);
+
+        table.set_items(vec!["x"]);
+        assert_eq!(table.selected(), Some(&"x"));
+    }
+
+    #[test]
+    fn navigating_an_empty_table_selects_nothing() {
+        let mut table = SelectableTable::<&str>::new(vec![]);
+        table.set_viewport(Rect::new(0, 0, 80, 20));
+        let ctrl = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL);
+
+        for key in [
+            key(KeyCode::Char('k')),
+            key(KeyCode::Char('j')),
+            key(KeyCode::Char('G')),
+            ctrl('u'),
+            ctrl('f'),
+        ] {
+            assert!(table.handle_navigation_key(&key));
+            assert_eq!(table.selected_index(), None);
+        }
+        table.move_begin();
+        table.select(Some(3));
+        assert_eq!(table.selected(), None);
+    }
+
+    #[test]
+    fn fzf_picks_labeled_rows() {
+        let mut table = SelectableTable::new(vec!["# header", "a", "b"]);
+        assert_eq!(table.fzf_options(), vec!["a", "b"]);
+        assert!(table.select_fzf("b"));
+        assert_eq!(table.selected(), Some(&"b"));
+        assert!(!table.select_fzf("z"));
+        assert_eq!(table.selected(), Some(&"b"));
+    }
+}
@@ -0,0 +1,366 @@
+use super::Component;
+use crate::action::Action;
+use crate::cli::Cli;
+use crate::view::setup::{normalize_url, project_choices};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Flex, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Padding, Paragraph, Wrap};
+use std::collections::HashSet;
+use teamcity::Auth;
+use teamcity::types::Project;
+
+const WIZARD_WIDTH: u16 = 80;
+const WIZARD_HEIGHT: u16 = 24;
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    #[default]
+    Url,
+    Token,
+    Username,
+    Password,
+    Connecting,
+    Projects,
+}
+
+/// First-run setup: server URL, credentials and the projects to monitor, picked from the server
+#[derive(Default)]
+pub struct SetupWizard {
+    step: Step,
+    url: String,
+    token: String,
+    username: String,
+    password: String,
+    // None until connected
+    projects: Option<Vec<Project>>,
+    // IDs of the projects to monitor
+    selected: HashSet<String>,
+    list_state: ListState,
+    // why the last connection attempt failed
+    error: Option<String>,
+}
+
+impl SetupWizard {
+    pub fn new(projects: Option<Vec<String>>) -> Self {
+        Self {
+            selected: projects.unwrap_or_default().into_iter().collect(),
+            ..Self::default()
+        }
+    }
+
+    /// Wizard for changing the projects of a saved configuration, connecting with its credentials
+    /// right away
+    pub fn editing(saved: &Cli) -> Self {
+        Self {
+            step: Step::Connecting,
+            url: saved.teamcity_url.clone().unwrap_or_default(),
+            token: saved.token.clone().unwrap_or_default(),
+            username: saved.username.clone().unwrap_or_default(),
+            password: saved.password.clone().unwrap_or_default(),
+            ..Self::new(saved.projects.clone())
+        }
+    }
+
+    pub fn is_connecting(&self) -> bool {
+        self.step == Step::Connecting
+    }
+
+    pub fn url(&self) -> String {
+        normalize_url(&self.url)
+    }
+
+    /// Token if one was entered, then username and password, guest access otherwise
+    pub fn auth(&self) -> Auth {
+        if !self.token.is_empty() {
+            Auth::Token(self.token.clone())
+        } else if !self.username.is_empty() {
+            Auth::Basic {
+                username: self.username.clone(),
+                password: self.password.clone(),
+            }
+        } else {
+            Auth::Guest
+        }
+    }
+
+    /// Configuration entered in the wizard, in the form it is saved in
+    pub fn cli(&self) -> Cli {
+        let (token, username, password, guest) = match self.auth() {
+            Auth::Token(token) => (Some(token), None, None, false),
+            Auth::Basic { username, password } => (None, Some(username), Some(password), false),
+            Auth::Guest => (None, None, None, true),
+        };
+        // keep the server's order rather than the order of the set
+        let projects: Vec<String> = self
+            .projects
+            .iter()
+            .flatten()
+            .filter(|project| self.selected.contains(&project.id))
+            .map(|project| project.id.clone())
+            .collect();
+        Cli {
+            teamcity_url: Some(self.url()),
+            token,
+            username,
+            password,
+            guest,
+            projects: (!projects.is_empty()).then_some(projects),
+            cache_dir: None,
+            fresh: false,
+            command: None,
+        }
+    }
+
+    fn input(&mut self) -> Option<&mut String> {
+        match self.step {
+            Step::Url => Some(&mut self.url),
+            Step::Token => Some(&mut self.token),
+            Step::Username => Some(&mut self.username),
+            Step::Password => Some(&mut self.password),
+            Step::Connecting | Step::Projects => None,
+        }
+    }
+
+    fn connect(&mut self) -> Action {
+        self.step = Step::Connecting;
+        self.error = None;
+        Action::SetupConnect
+    }
+
+    fn next_step(&mut self) -> Action {
+        match self.step {
+            Step::Url if self.url().is_empty() => {
+                self.error = Some("The server URL is required".to_string());
+                return Action::Render;
+            }
+            Step::Url => self.step = Step::Token,
+            Step::Token if self.token.is_empty() => self.step = Step::Username,
+            Step::Token | Step::Password => return self.connect(),
+            Step::Username if self.username.is_empty() => return self.connect(),
+            Step::Username => self.step = Step::Password,
+            Step::Connecting => {}
+            Step::Projects => return Action::SetupFinished,
+        }
+        self.error = None;
+        Action::Render
+    }
+
+    fn previous_step(&mut self) -> Action {
+        self.step = match self.step {
+            Step::Url => return Action::Quit,
+            Step::Token => Step::Url,
+            Step::Username => Step::Token,
+            Step::Password => Step::Username,
+            // back to the credentials, the server may need other ones
+            Step::Connecting | Step::Projects if !self.token.is_empty() => Step::Token,
+            Step::Connecting | Step::Projects if !self.username.is_empty() => Step::Password,
+            Step::Connecting | Step::Projects => Step::Username,
+        };
+        Action::Render
+    }
+
+    fn choice_count(&self) -> usize {
+        self.projects
+            .as_deref()
+            .map_or(0, |projects| project_choices(projects).len())
+    }
+
+    fn move_selection(&mut self, forward: bool) {
+        let len = self.choice_count();
+        if len == 0 {
+            return;
+        }
+        let i = match self.list_state.selected() {
+            Some(i) if forward => (i + 1) % len,
+            Some(i) => (i + len - 1) % len,
+            None => 0,
+        };
+        self.list_state.select(Some(i));
+    }
+
+    fn toggle_selected_project(&mut self) {
+        let Some(id) = self
+            .list_state
+            .selected()
+            .zip(self.projects.as_deref())
+            .and_then(|(i, projects)| project_choices(projects).get(i).map(|(_, p)| p.id.clone()))
+        else {
+            return;
+        };
+        if !self.selected.remove(&id) {
+            self.selected.insert(id);
+        }
+    }
+
+    fn handle_projects_key(&mut self, key: KeyEvent) -> Action {
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => self.move_selection(true),
+            KeyCode::Char('k') | KeyCode::Up => self.move_selection(false),
+            KeyCode::Char(' ') => self.toggle_selected_project(),
+            KeyCode::Enter => return self.next_step(),
+            KeyCode::Esc => return self.previous_step(),
+            _ => {}
+        }
+        Action::Render
+    }
+
+    fn input_line(&self, label: &str, step: Step, text: &str, masked: bool) -> Line<'_> {
+        let value = if masked {
+            "•".repeat(text.chars().count())
+        } else {
+            text.to_string()
+        };
+        let style = if self.step == step {
+            Style::default().fg(Color::Yellow)
+        } else {
+            Style::default().fg(Color::DarkGray)
+        };
+        let cursor = if self.step == step { "▏" } else { "" };
+        Line::from(vec![
+            Span::styled(format!("{:<16}", label), style),
+            Span::raw(value),
+            Span::styled(cursor, style),
+        ])
+    }
+
+    fn hint(&self) -> &'static str {
+        match self.step {
+            Step::Url => "Address of the TeamCity server, e.g. https://teamcity.example.com",
+            Step::Token => "Personal access token, leave empty to log in with a username",
+            Step::Username => "Leave empty for guest access",
+            Step::Password => "Password of the user",
+            Step::Connecting => "Connecting…",
+            Step::Projects => "Projects to monitor, none to pick top-level ones on startup",
+        }
+    }
+
+    fn render_projects(&mut self, frame: &mut Frame, area: Rect) {
+        let Some(projects) = &self.projects else {
+            return;
+        };
+        let items: Vec<ListItem> = project_choices(projects)
+            .into_iter()
+            .map(|(depth, project)| {
+                let mark = if self.selected.contains(&project.id) {
+                    "[x]"
+                } else {
+                    "[ ]"
+                };
+                ListItem::new(format!(
+                    "{} {}{} ({})",
+                    mark,
+                    "  ".repeat(depth),
+                    project.name,
+                    project.id
+                ))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::TOP).title("Projects"))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, area, &mut self.list_state);
+    }
+}
+
+impl Component for SetupWizard {
+    fn handle_key_event(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>> {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return Ok(Some(Action::Quit));
+        }
+        if self.step == Step::Projects {
+            return Ok(Some(self.handle_projects_key(key)));
+        }
+        let action = match key.code {
+            KeyCode::Enter => self.next_step(),
+            KeyCode::Esc => self.previous_step(),
+            KeyCode::Backspace => {
+                if let Some(input) = self.input() {
+                    input.pop();
+                }
+                Action::Render
+            }
+            KeyCode::Char(c) => {
+                if let Some(input) = self.input() {
+                    input.push(c);
+                }
+                Action::Render
+            }
+            _ => Action::Render,
+        };
+        Ok(Some(action))
+    }
+
+    fn update(&mut self, action: Action) -> color_eyre::Result<Option<Action>> {
+        match action {
+            Action::SetupProjectsFetched { projects } if self.step == Step::Connecting => {
+                self.projects = Some(projects);
+                self.list_state
+                    .select((self.choice_count() > 0).then_some(0));
+                self.step = Step::Projects;
+                Ok(Some(Action::Render))
+            }
+            Action::Error(msg) if self.step == Step::Connecting => {
+                self.error = Some(msg);
+                self.step = Step::Url;
+                Ok(Some(Action::Render))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> color_eyre::Result<()> {
+        let [area] = Layout::horizontal([Constraint::Length(WIZARD_WIDTH)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [area] = Layout::vertical([Constraint::Length(WIZARD_HEIGHT)])
+            .flex(Flex::Center)
+            .areas(area);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title("t9s setup")
+            .padding(Padding::horizontal(1));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let [fields_area, message_area, projects_area, footer_area] = Layout::vertical([
+            Constraint::Length(5),
+            Constraint::Length(2),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .areas(inner);
+
+        let fields = vec![
+            self.input_line("Server URL", Step::Url, &self.url, false),
+            self.input_line("Token", Step::Token, &self.token, true),
+            self.input_line("Username", Step::Username, &self.username, false),
+            self.input_line("Password", Step::Password, &self.password, true),
+        ];
+        frame.render_widget(Paragraph::new(fields), fields_area);
+
+        let message = match &self.error {
+            Some(error) => Line::styled(error.as_str(), Style::default().fg(Color::Red)),
+            None => Line::styled(self.hint(), Style::default().fg(Color::Cyan)),
+        };
+        frame.render_widget(
+            Paragraph::new(message).wrap(Wrap { trim: true }),
+            message_area,
+        );
+
+        self.render_projects(frame, projects_area);
+
+        let footer = if self.step == Step::Projects {
+            "j/k: Move  Space: Toggle  Enter: Save  Esc: Back  Ctrl-c: Quit"
+        } else {
+            "Enter: Next  Esc: Back  Ctrl-c: Quit"
+        };
+        frame.render_widget(
+            Paragraph::new(footer).style(Style::default().fg(Color::DarkGray)),
+            footer_area,
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+use color_eyre::Result;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Style},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use super::Component;
+use crate::action::Action;
+
+const FRAMES: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+/// Loading indicator shown in the middle of the screen while build configurations are fetched
+#[derive(Default)]
+pub struct Spinner {
+    message: Option<String>,
+    frame: usize,
+}
+
+impl Spinner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Component for Spinner {
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::LoadProjects { .. } => {
+                self.message = Some("Fetching build configurations from TeamCity…".to_string());
+                self.frame = 0;
+                return Ok(Some(Action::Render));
+            }
+            // a failed fetch is reported as an error
+            Action::ProjectsFetched { .. } | Action::Error(_) if self.message.is_some() => {
+                self.message = None;
+                return Ok(Some(Action::Render));
+            }
+            Action::Tick if self.message.is_some() => {
+                self.frame = (self.frame + 1) % FRAMES.len();
+                return Ok(Some(Action::Render));
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let Some(message) = &self.message else {
+            return Ok(());
+        };
+
+        let text = format!("{} {}", FRAMES[self.frame], message);
+        let width = (text.chars().count() as u16 + 4).min(area.width);
+        let height = 3.min(area.height);
+        let spinner_area = Rect {
+            x: area.x + area.width.saturating_sub(width) / 2,
+            y: area.y + area.height.saturating_sub(height) / 2,
+            width,
+            height,
+        };
+
+        let spinner = Paragraph::new(text).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan)),
+        );
+
+        frame.render_widget(Clear, spinner_area);
+        frame.render_widget(spinner, spinner_area);
+        Ok(())
+    }
+}
@@ -0,0 +1,254 @@
+use super::Component;
+use super::freshness::freshness_line;
+use crate::action::Action;
+use crate::config::Config;
+use crate::time::format_duration;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Bar, BarChart, BarGroup, Block, Borders, Padding, Paragraph, Sparkline};
+use teamcity::types::Build;
+use time::{Duration, OffsetDateTime};
+use tokio::sync::mpsc::UnboundedSender;
+
+// builds the "recent" average is taken over when comparing against the whole history
+const RECENT_BUILDS: usize = 10;
+const BAR_WIDTH: u16 = 3;
+
+/// Duration of a finished build, with its number and whether it succeeded
+struct BuildDuration {
+    number: String,
+    secs: u64,
+    success: bool,
+}
+
+/// Build time trend and success rate of the last finished builds of a configuration
+pub struct Statistics {
+    build_type_id: String,
+    title: String,
+    // None while the build history is being fetched
+    fetched_at: Option<OffsetDateTime>,
+    stale_after: Duration,
+    // oldest first
+    durations: Vec<BuildDuration>,
+    finished: usize,
+    succeeded: usize,
+    pub action_tx: Option<UnboundedSender<Action>>,
+}
+
+impl Statistics {
+    /// Empty statistics shown while the build history is being fetched
+    pub fn loading(build_type_id: String, title: String) -> Self {
+        let mut statistics = Self::new(build_type_id, title, vec![]);
+        statistics.fetched_at = None;
+        statistics
+    }
+
+    pub fn new(build_type_id: String, title: String, mut builds: Vec<Build>) -> Self {
+        builds.sort_by(|a, b| a.start_date.cmp(&b.start_date));
+
+        let durations = builds
+            .iter()
+            .filter_map(|build| {
+                let secs = (build.finish_date? - build.start_date?).whole_seconds();
+                Some(BuildDuration {
+                    number: build.build_number.clone().unwrap_or_default(),
+                    secs: secs.max(0) as u64,
+                    success: build.status.as_deref() == Some("SUCCESS"),
+                })
+            })
+            .collect();
+
+        Self {
+            build_type_id,
+            title,
+            fetched_at: Some(OffsetDateTime::now_utc()),
+            stale_after: Duration::ZERO,
+            durations,
+            finished: builds.len(),
+            succeeded: builds
+                .iter()
+                .filter(|b| b.status.as_deref() == Some("SUCCESS"))
+                .count(),
+            action_tx: None,
+        }
+    }
+
+    fn average(durations: &[BuildDuration]) -> Option<u64> {
+        if durations.is_empty() {
+            return None;
+        }
+        Some(durations.iter().map(|d| d.secs).sum::<u64>() / durations.len() as u64)
+    }
+
+    fn median(&self) -> Option<u64> {
+        let mut secs: Vec<u64> = self.durations.iter().map(|d| d.secs).collect();
+        secs.sort_unstable();
+        secs.get(secs.len() / 2).copied()
+    }
+
+    fn summary(&self) -> Vec<Line<'_>> {
+        if self.fetched_at.is_none() {
+            return vec![Line::from("Loading build history…")];
+        }
+        if self.finished == 0 {
+            return vec![Line::from("No finished builds found")];
+        }
+
+        let duration = |secs: Option<u64>| {
+            secs.and_then(|s| format_duration(s as i64).ok())
+                .unwrap_or_default()
+        };
+        let success_rate = self.succeeded as f64 * 100.0 / self.finished as f64;
+        let success_color = if success_rate >= 90.0 {
+            Color::Green
+        } else if success_rate >= 70.0 {
+            Color::Yellow
+        } else {
+            Color::Red
+        };
+
+        let mut lines = vec![
+            Line::from(format!(
+                "Success rate: {:.0}% ({} of {} finished builds)",
+                success_rate, self.succeeded, self.finished
+            ))
+            .style(Style::default().fg(success_color)),
+            Line::from(format!(
+                "Duration: avg {}  median {}  min {}  max {}",
+                duration(Self::average(&self.durations)),
+                duration(self.median()),
+                duration(self.durations.iter().map(|d| d.secs).min()),
+                duration(self.durations.iter().map(|d| d.secs).max()),
+            )),
+        ];
+
+        // compare the latest builds against the whole history to surface regressions
+        let recent = &self.durations[self.durations.len().saturating_sub(RECENT_BUILDS)..];
+        if let (Some(recent_avg), Some(avg)) =
+            (Self::average(recent), Self::average(&self.durations))
+            && avg > 0
+        {
+            let change = (recent_avg as f64 - avg as f64) * 100.0 / avg as f64;
+            let color = if change > 10.0 {
+                Color::Red
+            } else if change < -10.0 {
+                Color::Green
+            } else {
+                Color::Reset
+            };
+            lines.push(
+                Line::from(format!(
+                    "Last {} builds: avg {} ({:+.0}% vs all)",
+                    recent.len(),
+                    duration(Some(recent_avg)),
+                    change
+                ))
+                .style(Style::default().fg(color)),
+            );
+        }
+        lines
+    }
+
+    fn render_chart(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title("Durations of the latest builds");
+        // bars plus the gap between them
+        let fits = (block.inner(area).width / (BAR_WIDTH + 1)) as usize;
+        let visible = &self.durations[self.durations.len().saturating_sub(fits)..];
+
+        let bars: Vec<Bar> = visible
+            .iter()
+            .map(|d| {
+                let color = if d.success { Color::Green } else { Color::Red };
+                Bar::default()
+                    .value(d.secs)
+                    .text_value(format!("{}m", d.secs / 60))
+                    .label(Line::from(d.number.clone()))
+                    .style(Style::default().fg(color))
+            })
+            .collect();
+
+        let chart = BarChart::default()
+            .block(block)
+            .data(BarGroup::default().bars(&bars))
+            .bar_width(BAR_WIDTH)
+            .bar_gap(1);
+
+        frame.render_widget(chart, area);
+    }
+}
+
+impl Component for Statistics {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> color_eyre::Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> color_eyre::Result<()> {
+        self.stale_after = config.stale_after();
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>> {
+        let action = match key.code {
+            KeyCode::Char('r') => Action::LoadStatistics {
+                project_id: self.build_type_id.clone(),
+                title: self.title.clone(),
+            },
+            KeyCode::Esc | KeyCode::Char('h') => Action::LoadBuilds {
+                project_id: self.build_type_id.clone(),
+                title: self.title.clone(),
+            },
+            _ => Action::Render,
+        };
+        Ok(Some(action))
+    }
+
+    fn tab_title(&self) -> String {
+        format!("Statistics — {}", self.title)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> color_eyre::Result<()> {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(5), // Summary height
+                Constraint::Length(6), // Sparkline height
+                Constraint::Min(0),    // Bar chart takes remaining space
+                Constraint::Length(2), // Footer height
+            ])
+            .split(area);
+
+        let summary = Paragraph::new(self.summary()).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Statistics — {}", self.title))
+                .title_top(freshness_line(self.fetched_at, self.stale_after)),
+        );
+        frame.render_widget(summary, chunks[0]);
+
+        let secs: Vec<u64> = self.durations.iter().map(|d| d.secs).collect();
+        let sparkline = Sparkline::default()
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!("Duration trend — last {} builds", secs.len())),
+            )
+            .data(&secs)
+            .style(Style::default().fg(Color::Cyan));
+        frame.render_widget(sparkline, chunks[1]);
+
+        self.render_chart(frame, chunks[2]);
+
+        let footer = Paragraph::new("r: Refresh  h/Esc: Back")
+            .style(Style::default().fg(Color::DarkGray))
+            .block(Block::default().padding(Padding::horizontal(1)));
+
+        frame.render_widget(footer, chunks[3]);
+        Ok(())
+    }
+}
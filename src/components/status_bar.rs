@@ -0,0 +1,159 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use color_eyre::Result;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+};
+use time::OffsetDateTime;
+
+use super::Component;
+use crate::action::Action;
+use crate::config::Config;
+use crate::icons::IconSet;
+use crate::view::builds::exceeds_baseline;
+use crate::view::favorites::{changed_builds, state_label};
+use teamcity::types::Build;
+
+/// Latest build of each favorite configuration in a line at the bottom of the screen, kept up to
+/// date by the background poller whatever view is open
+#[derive(Default)]
+pub struct StatusBar {
+    // build configuration IDs, in the order of the config
+    favorites: Vec<String>,
+    names: HashMap<String, String>,
+    // latest build by build configuration ID
+    latest: HashMap<String, Build>,
+    // average duration by build configuration ID
+    baselines: BTreeMap<String, i64>,
+    long_running_percent: u32,
+    icons: IconSet,
+    // running builds already reported to run longer than usual
+    reported_long_running: HashSet<i64>,
+}
+
+impl StatusBar {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_visible(&self) -> bool {
+        !self.favorites.is_empty()
+    }
+
+    fn name<'a>(&'a self, build_type_id: &'a str) -> &'a str {
+        self.names
+            .get(build_type_id)
+            .map_or(build_type_id, String::as_str)
+    }
+
+    fn is_long_running(&self, build: &Build, now: OffsetDateTime) -> bool {
+        build
+            .build_type_id
+            .as_ref()
+            .and_then(|id| self.baselines.get(id))
+            .is_some_and(|baseline| {
+                exceeds_baseline(build, *baseline, self.long_running_percent, now)
+            })
+    }
+}
+
+impl Component for StatusBar {
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.latest.retain(|id, _| config.favorites.contains(id));
+        self.favorites = config.favorites;
+        self.long_running_percent = config.long_running.threshold_percent;
+        self.icons = config.icons;
+        Ok(())
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::ProjectsFetched {
+                ref build_types, ..
+            } => {
+                self.names = build_types
+                    .iter()
+                    .map(|bt| (bt.id.clone(), bt.name.clone()))
+                    .collect();
+                return Ok(Some(Action::Render));
+            }
+            Action::FavoriteBuildsPolled { builds, baselines } => {
+                self.baselines = baselines;
+                let mut changes: Vec<String> = changed_builds(&self.latest, &builds)
+                    .into_iter()
+                    .map(|build| {
+                        format!(
+                            "{} #{} {}",
+                            self.name(build.build_type_id.as_deref().unwrap_or_default()),
+                            build.build_number.as_deref().unwrap_or_default(),
+                            state_label(build)
+                        )
+                    })
+                    .collect();
+                let now = OffsetDateTime::now_utc();
+                for build in &builds {
+                    if let Some(id) = build.id
+                        && self.is_long_running(build, now)
+                        && self.reported_long_running.insert(id)
+                    {
+                        changes.push(format!(
+                            "{} #{} running {}% longer than usual",
+                            self.name(build.build_type_id.as_deref().unwrap_or_default()),
+                            build.build_number.as_deref().unwrap_or_default(),
+                            self.long_running_percent
+                        ));
+                    }
+                }
+                for build in builds {
+                    if let Some(build_type_id) = build.build_type_id.clone() {
+                        self.latest.insert(build_type_id, build);
+                    }
+                }
+                if !changes.is_empty() {
+                    return Ok(Some(Action::Toast(changes.join(", "))));
+                }
+                return Ok(Some(Action::Render));
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let now = OffsetDateTime::now_utc();
+        let mut spans = Vec::new();
+        for build_type_id in &self.favorites {
+            let (label, color) = match self.latest.get(build_type_id) {
+                Some(build) => {
+                    let color = match state_label(build) {
+                        _ if self.is_long_running(build, now) => Color::Magenta,
+                        "SUCCESS" => Color::Green,
+                        "FAILURE" | "ERROR" => Color::Red,
+                        "running" => Color::Yellow,
+                        _ => Color::DarkGray,
+                    };
+                    let label = format!(
+                        "{} {} #{}",
+                        self.icons.mark(),
+                        self.name(build_type_id),
+                        build.build_number.as_deref().unwrap_or_default()
+                    );
+                    (label, color)
+                }
+                None => (
+                    format!("{} {}", self.icons.empty_mark(), self.name(build_type_id)),
+                    Color::DarkGray,
+                ),
+            };
+            spans.push(Span::styled(label, Style::default().fg(color)));
+            spans.push(Span::raw("  "));
+        }
+
+        frame.render_widget(Paragraph::new(Line::from(spans)), area);
+        Ok(())
+    }
+}
@@ -0,0 +1,361 @@
+use super::Component;
+use super::freshness::freshness_line;
+use super::prompt::render_prompt;
+use super::selectable_table::SelectableTable;
+use crate::action::Action;
+use crate::config::Config;
+use crate::view::test_failures::{owning_team, teams, tests_of_team};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::{Block, Borders, Padding, Paragraph, Row, Table};
+use regex::Regex;
+use teamcity::types::{Build, MuteScope, TestOccurrence};
+use time::{Duration, OffsetDateTime};
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Comment typed for a test about to be muted, and whether it is muted in the whole project
+struct MutePrompt {
+    test_id: String,
+    in_project: bool,
+    comment: String,
+}
+
+/// Failed tests of a build with the teams owning them, with muting of the selected test
+pub struct TestFailures {
+    // configuration the tests were opened from, to go back to
+    build_type_id: String,
+    project_id: Option<String>,
+    title: String,
+    build: Build,
+    // every failed test, the shown ones are those of the team filtered on
+    all_tests: Vec<TestOccurrence>,
+    tests: SelectableTable<TestOccurrence>,
+    // test name patterns with the team owning the tests they match, from `test_owners`
+    owners: Vec<(Regex, String)>,
+    // None shows every test, Some(None) only those no team owns
+    team_filter: Option<Option<String>>,
+    // None while loading
+    fetched_at: Option<OffsetDateTime>,
+    stale_after: Duration,
+    mute_prompt: Option<MutePrompt>,
+    pub action_tx: Option<UnboundedSender<Action>>,
+}
+
+impl TestFailures {
+    pub fn new(
+        build_type_id: String,
+        project_id: Option<String>,
+        title: String,
+        build: Build,
+        tests: Option<Vec<TestOccurrence>>,
+    ) -> Self {
+        Self {
+            build_type_id,
+            project_id,
+            title,
+            build,
+            fetched_at: tests.is_some().then(OffsetDateTime::now_utc),
+            all_tests: tests.clone().unwrap_or_default(),
+            tests: SelectableTable::new(tests.unwrap_or_default()),
+            owners: Vec::new(),
+            team_filter: None,
+            stale_after: Duration::ZERO,
+            mute_prompt: None,
+            action_tx: None,
+        }
+    }
+
+    fn refresh_tests(&mut self) {
+        let tests = match &self.team_filter {
+            Some(team) => tests_of_team(&self.owners, &self.all_tests, team.as_deref()),
+            None => self.all_tests.clone(),
+        };
+        self.tests.set_items(tests);
+    }
+
+    /// Shows the tests of the next team owning some of them, then the unowned ones, then all again
+    fn next_team(&mut self) -> Action {
+        if self.owners.is_empty() {
+            return Action::Error(
+                "No teams own tests, map test names to them with [[test_owners]] in config.toml"
+                    .to_string(),
+            );
+        }
+        let filters: Vec<Option<Option<String>>> = std::iter::once(None)
+            .chain(teams(&self.owners, &self.all_tests).into_iter().map(Some))
+            .collect();
+        let current = filters
+            .iter()
+            .position(|filter| *filter == self.team_filter)
+            .unwrap_or_default();
+        self.team_filter = filters[(current + 1) % filters.len()].clone();
+        self.refresh_tests();
+        Action::Render
+    }
+
+    fn start_mute(&mut self) -> Action {
+        let Some(test) = self.tests.selected() else {
+            return Action::Error("No test was selected".to_string());
+        };
+        if test.muted == Some(true) {
+            return Action::Toast("Test is already muted".to_string());
+        }
+        let Some(test_id) = test.test.as_ref().and_then(|t| t.id.clone()) else {
+            return Action::Error("No id was found for selected test".to_string());
+        };
+        self.mute_prompt = Some(MutePrompt {
+            test_id,
+            in_project: false,
+            comment: String::new(),
+        });
+        Action::Render
+    }
+
+    fn handle_mute_key(&mut self, key: KeyEvent) -> Action {
+        let Some(prompt) = &mut self.mute_prompt else {
+            return Action::Render;
+        };
+        match key.code {
+            KeyCode::Esc => {
+                self.mute_prompt = None;
+                Action::Render
+            }
+            KeyCode::Up | KeyCode::Down if self.project_id.is_some() => {
+                prompt.in_project = !prompt.in_project;
+                Action::Render
+            }
+            KeyCode::Char(c) => {
+                prompt.comment.push(c);
+                Action::Render
+            }
+            KeyCode::Backspace => {
+                prompt.comment.pop();
+                Action::Render
+            }
+            KeyCode::Enter => {
+                let scope = match &self.project_id {
+                    Some(project_id) if prompt.in_project => MuteScope::project(project_id),
+                    _ => MuteScope::build_type(&self.build_type_id),
+                };
+                let action = Action::MuteTest {
+                    test_id: prompt.test_id.clone(),
+                    scope,
+                    comment: prompt.comment.clone(),
+                };
+                self.mute_prompt = None;
+                action
+            }
+            _ => Action::Render,
+        }
+    }
+
+    fn mute_prompt_title(&self, prompt: &MutePrompt) -> String {
+        let scope = if prompt.in_project {
+            "project"
+        } else {
+            "this configuration"
+        };
+        format!(
+            "Mute in {} — comment (↑/↓: Scope, Enter: Mute, Esc: Cancel)",
+            scope
+        )
+    }
+}
+
+impl Component for TestFailures {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> color_eyre::Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> color_eyre::Result<()> {
+        self.stale_after = config.stale_after();
+        self.owners = Vec::new();
+        for owner in &config.test_owners {
+            match owner.pattern_regex() {
+                Ok(pattern) => self.owners.push((pattern, owner.team.clone())),
+                Err(e) => {
+                    if let Some(tx) = &self.action_tx {
+                        let _ = tx.send(Action::Error(format!(
+                            "Invalid test pattern of {}: {}",
+                            owner.team, e
+                        )));
+                    }
+                }
+            }
+        }
+        self.refresh_tests();
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>> {
+        if self.mute_prompt.is_some() {
+            return Ok(Some(self.handle_mute_key(key)));
+        }
+
+        if self.tests.handle_navigation_key(&key) {
+            return Ok(Some(Action::Render));
+        }
+        let action = match key.code {
+            KeyCode::Char('M') => self.start_mute(),
+            KeyCode::Char('t') => self.next_team(),
+            KeyCode::Enter => match self.tests.selected().and_then(|t| t.test.clone()) {
+                Some(test) => Action::LoadTestHistory {
+                    build_type_id: self.build_type_id.clone(),
+                    title: self.title.clone(),
+                    build: self.build.clone(),
+                    test,
+                },
+                None => Action::Error("No test was selected".to_string()),
+            },
+            KeyCode::Char('m') => match &self.project_id {
+                Some(project_id) => Action::LoadMutes {
+                    project_id: project_id.clone(),
+                    back: Box::new(Action::LoadTestFailures {
+                        build_type_id: self.build_type_id.clone(),
+                        title: self.title.clone(),
+                        build: self.build.clone(),
+                    }),
+                },
+                None => Action::Error("No project was found for this configuration".to_string()),
+            },
+            KeyCode::Char('r') => Action::LoadTestFailures {
+                build_type_id: self.build_type_id.clone(),
+                title: self.title.clone(),
+                build: self.build.clone(),
+            },
+            KeyCode::Char('l') => match self.build.id {
+                Some(build_id) => Action::LoadBuildLog { build_id },
+                None => Action::Error("No id was found for this build".to_string()),
+            },
+            KeyCode::Esc | KeyCode::Char('h') => Action::LoadBuilds {
+                project_id: self.build_type_id.clone(),
+                title: self.title.clone(),
+            },
+            _ => Action::Render,
+        };
+        Ok(Some(action))
+    }
+
+    fn update(&mut self, action: Action) -> color_eyre::Result<Option<Action>> {
+        if let Action::TestMuted { test_id } = action {
+            for test in self.all_tests.iter_mut() {
+                if test.test.as_ref().and_then(|t| t.id.as_ref()) == Some(&test_id) {
+                    test.muted = Some(true);
+                }
+            }
+            self.refresh_tests();
+            return Ok(Some(Action::Render));
+        }
+        Ok(None)
+    }
+
+    fn tab_title(&self) -> String {
+        format!("Tests — {}", self.title)
+    }
+
+    fn is_typing(&self) -> bool {
+        self.mute_prompt.is_some()
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> color_eyre::Result<()> {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(0),    // Table takes remaining space
+                Constraint::Length(2), // Footer height
+            ])
+            .split(area);
+
+        let header = Row::new(vec!["Test", "Team", "Status", "Details"])
+            .style(
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .height(1)
+            .bottom_margin(1);
+
+        let rows: Vec<Row> = match self.tests.items() {
+            _ if self.fetched_at.is_none() => vec![Row::new(vec!["Loading failed tests…"])],
+            [] => vec![Row::new(vec!["No failed tests"])],
+            tests => tests
+                .iter()
+                .map(|test| {
+                    let muted = test.muted == Some(true);
+                    let status = if muted { "muted" } else { "FAILURE" };
+                    // only the first line of the stack trace fits
+                    let details = test
+                        .details
+                        .as_deref()
+                        .and_then(|d| d.lines().next())
+                        .unwrap_or_default();
+                    let row = Row::new(vec![
+                        test.name.clone().unwrap_or_default(),
+                        owning_team(&self.owners, test)
+                            .unwrap_or_default()
+                            .to_string(),
+                        status.to_string(),
+                        details.to_string(),
+                    ]);
+                    if muted {
+                        row.style(Style::default().fg(Color::DarkGray))
+                    } else {
+                        row.style(Style::default().fg(Color::Red))
+                    }
+                })
+                .collect(),
+        };
+
+        let table = Table::new(
+            rows,
+            &[
+                Constraint::Min(40),    // Test
+                Constraint::Length(16), // Team
+                Constraint::Length(8),  // Status
+                Constraint::Min(30),    // Details
+            ],
+        )
+        .header(header)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(
+                    "Failed tests — {} #{}{}",
+                    self.title,
+                    self.build.build_number.as_deref().unwrap_or_default(),
+                    match &self.team_filter {
+                        Some(Some(team)) => format!(" — {}", team),
+                        Some(None) => " — no team".to_string(),
+                        None => String::new(),
+                    }
+                ))
+                .title_top(freshness_line(self.fetched_at, self.stale_after)),
+        )
+        .column_spacing(1)
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol(">> ");
+
+        self.tests.set_viewport(chunks[0]);
+        frame.render_stateful_widget(table, chunks[0], &mut self.tests.state);
+
+        let footer = Paragraph::new(
+            "j/k: Move  gg/G: Top/Bottom  Enter: History  M: Mute  m: Muted tests  t: Team  l: Log  r: Refresh  h/Esc: Back",
+        )
+        .style(Style::default().fg(Color::DarkGray))
+        .block(Block::default().padding(Padding::horizontal(1)));
+        frame.render_widget(footer, chunks[1]);
+
+        if let Some(prompt) = &self.mute_prompt {
+            render_prompt(
+                frame,
+                area,
+                &self.mute_prompt_title(prompt),
+                &prompt.comment,
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,270 @@
+use super::Component;
+use super::freshness::freshness_line;
+use super::selectable_table::SelectableTable;
+use crate::action::Action;
+use crate::config::Config;
+use crate::icons::IconSet;
+use crate::time::{TimeDisplay, format_datetime};
+use crate::view::test_history::{HistorySummary, Outcome, chronological, outcome, summarize};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Padding, Paragraph, Row, Table};
+use teamcity::types::{Build, Test, TestOccurrence};
+use time::{Duration, OffsetDateTime};
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Pass/fail pattern of a test over the recent builds of a configuration, to tell flaky tests
+/// from broken ones
+pub struct TestHistory {
+    // configuration and build the failed tests were opened from, to go back to
+    build_type_id: String,
+    title: String,
+    build: Build,
+    test: Test,
+    // newest first
+    history: SelectableTable<TestOccurrence>,
+    summary: HistorySummary,
+    // None while loading
+    fetched_at: Option<OffsetDateTime>,
+    stale_after: Duration,
+    time_display: TimeDisplay,
+    icons: IconSet,
+    pub action_tx: Option<UnboundedSender<Action>>,
+}
+
+impl TestHistory {
+    pub fn new(
+        build_type_id: String,
+        title: String,
+        build: Build,
+        test: Test,
+        history: Option<Vec<TestOccurrence>>,
+    ) -> Self {
+        let history = history.map(|history| {
+            let mut history = chronological(history);
+            history.reverse();
+            history
+        });
+        Self {
+            build_type_id,
+            title,
+            build,
+            test,
+            summary: history.as_deref().map(summarize).unwrap_or_default(),
+            fetched_at: history.is_some().then(OffsetDateTime::now_utc),
+            history: SelectableTable::new(history.unwrap_or_default()),
+            stale_after: Duration::ZERO,
+            time_display: TimeDisplay::default(),
+            icons: IconSet::default(),
+            action_tx: None,
+        }
+    }
+
+    fn get_selected_build(&self) -> Option<&Build> {
+        self.history.selected()?.build.as_ref()
+    }
+
+    fn outcome_color(outcome: Outcome) -> Color {
+        match outcome {
+            Outcome::Passed => Color::Green,
+            Outcome::Failed => Color::Red,
+            Outcome::Ignored => Color::DarkGray,
+        }
+    }
+
+    /// Summary line and the outcomes of the builds, oldest on the left
+    fn summary_lines(&self) -> Vec<Line<'_>> {
+        if self.fetched_at.is_none() {
+            return vec![Line::from("Loading test history…")];
+        }
+        let history = self.history.items();
+        if history.is_empty() {
+            return vec![Line::from("This test has no runs in the recent builds")];
+        }
+
+        let verdict = if self.summary.is_flaky() {
+            Span::styled(
+                "flaky",
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )
+        } else if self.summary.failed == 0 {
+            Span::styled("stable", Style::default().fg(Color::Green))
+        } else {
+            Span::raw("not flaky")
+        };
+        let strip: Vec<Span> = history
+            .iter()
+            .rev()
+            .map(|occurrence| {
+                let outcome = outcome(occurrence);
+                Span::styled(
+                    outcome.symbol(self.icons),
+                    Style::default().fg(Self::outcome_color(outcome)),
+                )
+            })
+            .collect();
+        vec![
+            Line::from(vec![
+                Span::raw(format!(
+                    "Passed {}, failed {} of {} runs, changed status {} times — ",
+                    self.summary.passed,
+                    self.summary.failed,
+                    history.len(),
+                    self.summary.flips
+                )),
+                verdict,
+            ]),
+            Line::from(strip),
+        ]
+    }
+}
+
+impl Component for TestHistory {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> color_eyre::Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> color_eyre::Result<()> {
+        self.time_display = config.time_display;
+        self.stale_after = config.stale_after();
+        self.icons = config.icons;
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>> {
+        if self.history.handle_navigation_key(&key) {
+            return Ok(Some(Action::Render));
+        }
+        let action = match key.code {
+            KeyCode::Char('o') => {
+                if let Some(url) = self.get_selected_build().and_then(|b| b.web_url.as_ref()) {
+                    let _ = open::that(url);
+                }
+                Action::Render
+            }
+            KeyCode::Char('l') => match self.get_selected_build().and_then(|b| b.id) {
+                Some(build_id) => Action::LoadBuildLog { build_id },
+                None => Action::Error("No build was selected".to_string()),
+            },
+            KeyCode::Char('r') => Action::LoadTestHistory {
+                build_type_id: self.build_type_id.clone(),
+                title: self.title.clone(),
+                build: self.build.clone(),
+                test: self.test.clone(),
+            },
+            KeyCode::Esc | KeyCode::Char('h') => Action::LoadTestFailures {
+                build_type_id: self.build_type_id.clone(),
+                title: self.title.clone(),
+                build: self.build.clone(),
+            },
+            _ => Action::Render,
+        };
+        Ok(Some(action))
+    }
+
+    fn tab_title(&self) -> String {
+        format!("Test history — {}", self.title)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> color_eyre::Result<()> {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(4), // Summary height
+                Constraint::Min(0),    // Table takes remaining space
+                Constraint::Length(2), // Footer height
+            ])
+            .split(area);
+
+        let summary = Paragraph::new(self.summary_lines()).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(
+                    "History — {}",
+                    self.test.name.as_deref().unwrap_or_default()
+                ))
+                .title_top(freshness_line(self.fetched_at, self.stale_after)),
+        );
+        frame.render_widget(summary, chunks[0]);
+
+        let header = Row::new(vec!["Build", "Result", "Branch", "Started", "Details"])
+            .style(
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .height(1)
+            .bottom_margin(1);
+
+        let rows: Vec<Row> = self
+            .history
+            .items()
+            .iter()
+            .map(|occurrence| {
+                let build = occurrence.build.clone().unwrap_or_default();
+                let outcome = outcome(occurrence);
+                let result = match outcome {
+                    Outcome::Passed => "passed",
+                    Outcome::Failed if occurrence.muted == Some(true) => "muted",
+                    Outcome::Failed => "failed",
+                    Outcome::Ignored => "ignored",
+                };
+                let started = build
+                    .start_date
+                    .and_then(|date| format_datetime(&date, self.time_display).ok())
+                    .unwrap_or_default();
+                // only the first line of the stack trace fits
+                let details = occurrence
+                    .details
+                    .as_deref()
+                    .and_then(|d| d.lines().next())
+                    .unwrap_or_default();
+                Row::new(vec![
+                    format!("#{}", build.build_number.unwrap_or_default()),
+                    result.to_string(),
+                    build.branch_name.unwrap_or_default(),
+                    started,
+                    details.to_string(),
+                ])
+                .style(Style::default().fg(Self::outcome_color(outcome)))
+            })
+            .collect();
+
+        let table = Table::new(
+            rows,
+            &[
+                Constraint::Length(12), // Build
+                Constraint::Length(8),  // Result
+                Constraint::Length(20), // Branch
+                Constraint::Length(20), // Started
+                Constraint::Min(30),    // Details
+            ],
+        )
+        .header(header)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Recent builds of {}", self.title)),
+        )
+        .column_spacing(1)
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol(">> ");
+
+        self.history.set_viewport(chunks[1]);
+        frame.render_stateful_widget(table, chunks[1], &mut self.history.state);
+
+        let footer = Paragraph::new(
+            "j/k: Move  gg/G: Top/Bottom  o: Open build  l: Log  r: Refresh  h/Esc: Back",
+        )
+        .style(Style::default().fg(Color::DarkGray))
+        .block(Block::default().padding(Padding::horizontal(1)));
+        frame.render_widget(footer, chunks[2]);
+        Ok(())
+    }
+}
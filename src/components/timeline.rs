@@ -0,0 +1,368 @@
+use super::Component;
+use super::freshness::freshness_line;
+use super::selectable_table::{SelectableTable, visible_rows};
+use crate::action::Action;
+use crate::config::Config;
+use crate::time::format_clock_time;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Padding, Paragraph};
+use std::collections::HashMap;
+use teamcity::types::{Build, BuildType};
+use time::{Duration, OffsetDateTime};
+use tokio::sync::mpsc::UnboundedSender;
+
+const DEFAULT_WINDOW: Duration = Duration::hours(6);
+const MIN_WINDOW: Duration = Duration::minutes(15);
+const MAX_WINDOW: Duration = Duration::days(7);
+const MAX_LABEL_WIDTH: usize = 40;
+// columns between two time axis labels
+const TICK_SPACING: usize = 12;
+// concurrency levels drawn in the summary row, from one running build upwards
+const LOAD_LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+struct TimelineRow {
+    build_type_id: String,
+    title: String,
+    builds: Vec<Build>,
+}
+
+/// Gantt-style view of recent builds, one row per build configuration
+pub struct Timeline {
+    rows: SelectableTable<TimelineRow>,
+    // None while the builds are being fetched
+    fetched_at: Option<OffsetDateTime>,
+    stale_after: Duration,
+    // right edge of the visible time window
+    window_end: OffsetDateTime,
+    window: Duration,
+    pub action_tx: Option<UnboundedSender<Action>>,
+}
+
+impl Timeline {
+    /// Empty timeline shown while the builds are being fetched
+    pub fn loading(build_types: Vec<BuildType>) -> Self {
+        let mut timeline = Self::new(build_types, vec![]);
+        timeline.fetched_at = None;
+        timeline
+    }
+
+    pub fn new(build_types: Vec<BuildType>, builds: Vec<Build>) -> Self {
+        let mut by_build_type: HashMap<String, Vec<Build>> = HashMap::new();
+        for build in builds.into_iter().filter(|b| b.start_date.is_some()) {
+            let build_type_id = build.build_type_id.clone().unwrap_or_default();
+            by_build_type.entry(build_type_id).or_default().push(build);
+        }
+
+        // keep the order of the projects view, configurations without builds are skipped
+        let mut rows: Vec<TimelineRow> = build_types
+            .iter()
+            .filter_map(|bt| {
+                by_build_type.remove(&bt.id).map(|builds| TimelineRow {
+                    build_type_id: bt.id.clone(),
+                    title: format!(
+                        "{} / {}",
+                        bt.project_name.as_deref().unwrap_or_default(),
+                        bt.name
+                    ),
+                    builds,
+                })
+            })
+            .collect();
+        let mut unknown: Vec<TimelineRow> = by_build_type
+            .into_iter()
+            .map(|(build_type_id, builds)| TimelineRow {
+                title: build_type_id.clone(),
+                build_type_id,
+                builds,
+            })
+            .collect();
+        unknown.sort_by(|a, b| a.title.cmp(&b.title));
+        rows.extend(unknown);
+
+        Self {
+            rows: SelectableTable::new(rows),
+            fetched_at: Some(OffsetDateTime::now_utc()),
+            stale_after: Duration::ZERO,
+            window_end: OffsetDateTime::now_utc(),
+            window: DEFAULT_WINDOW,
+            action_tx: None,
+        }
+    }
+
+    fn pan(&mut self, fraction: f64) {
+        self.window_end += self.window * fraction;
+    }
+
+    fn zoom(&mut self, factor: f64) {
+        let window = (self.window * factor).clamp(MIN_WINDOW, MAX_WINDOW);
+        // zoom around the middle of the window
+        self.window_end += (window - self.window) / 2;
+        self.window = window;
+    }
+
+    fn window_start(&self) -> OffsetDateTime {
+        self.window_end - self.window
+    }
+
+    /// Column range `[start, end)` covered by `build` on an axis `width` columns wide
+    fn build_columns(&self, build: &Build, width: usize) -> Option<(usize, usize)> {
+        if width == 0 {
+            return None;
+        }
+        let start = build.start_date?;
+        let end = build.finish_date.unwrap_or_else(OffsetDateTime::now_utc);
+        let window_start = self.window_start();
+        if end < window_start || start > self.window_end {
+            return None;
+        }
+
+        let column = |t: OffsetDateTime| {
+            let ratio = (t - window_start) / self.window;
+            (ratio * width as f64).clamp(0.0, width as f64) as usize
+        };
+        let first = column(start).min(width.saturating_sub(1));
+        // short builds still get a single cell
+        let last = column(end).max(first + 1);
+        Some((first, last))
+    }
+
+    fn status_color(build: &Build) -> Color {
+        if build.state.as_deref() == Some("running") {
+            return Color::Yellow;
+        }
+        match build.status.as_deref() {
+            Some("SUCCESS") => Color::Green,
+            Some("FAILURE" | "ERROR" | "UNKNOWN") => Color::Red,
+            _ => Color::DarkGray,
+        }
+    }
+
+    fn render_bars(&self, row: &TimelineRow, width: usize) -> Vec<Span<'static>> {
+        let mut cells: Vec<Option<Color>> = vec![None; width];
+        for build in &row.builds {
+            if let Some((first, last)) = self.build_columns(build, width) {
+                for cell in &mut cells[first..last] {
+                    *cell = Some(Self::status_color(build));
+                }
+            }
+        }
+        cells_to_spans(&cells)
+    }
+
+    /// Number of builds running in each column, across all configurations
+    fn render_load(&self, width: usize) -> Vec<Span<'static>> {
+        let mut load = vec![0usize; width];
+        for build in self.rows.items().iter().flat_map(|row| row.builds.iter()) {
+            if let Some((first, last)) = self.build_columns(build, width) {
+                for cell in &mut load[first..last] {
+                    *cell += 1;
+                }
+            }
+        }
+
+        load.iter()
+            .map(|&count| match count {
+                0 => Span::raw(" "),
+                n => Span::styled(
+                    LOAD_LEVELS[(n - 1).min(LOAD_LEVELS.len() - 1)].to_string(),
+                    Style::default().fg(Color::Cyan),
+                ),
+            })
+            .collect()
+    }
+
+    fn render_axis(&self, width: usize) -> String {
+        let mut axis = String::new();
+        let mut column = 0;
+        while column + 5 <= width {
+            let t = self.window_start() + self.window * (column as f64 / width as f64);
+            let label = format_clock_time(&t).unwrap_or_default();
+            axis.push_str(&format!("{:<width$}", label, width = TICK_SPACING));
+            column += TICK_SPACING;
+        }
+        axis.chars().take(width).collect()
+    }
+}
+
+fn cells_to_spans(cells: &[Option<Color>]) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let mut i = 0;
+    while i < cells.len() {
+        let color = cells[i];
+        let run = cells[i..].iter().take_while(|c| **c == color).count();
+        spans.push(match color {
+            Some(color) => Span::styled("█".repeat(run), Style::default().fg(color)),
+            None => Span::styled("·".repeat(run), Style::default().fg(Color::DarkGray)),
+        });
+        i += run;
+    }
+    spans
+}
+
+impl Component for Timeline {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> color_eyre::Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> color_eyre::Result<()> {
+        self.stale_after = config.stale_after();
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> color_eyre::Result<Option<Action>> {
+        if self.rows.handle_navigation_key(&key) {
+            return Ok(Some(Action::Render));
+        }
+        let action = match key.code {
+            KeyCode::Char('h') | KeyCode::Left => {
+                self.pan(-0.25);
+                Action::Render
+            }
+            KeyCode::Char('l') | KeyCode::Right => {
+                self.pan(0.25);
+                Action::Render
+            }
+            KeyCode::Char('+') | KeyCode::Char('=') => {
+                self.zoom(0.5);
+                Action::Render
+            }
+            KeyCode::Char('-') => {
+                self.zoom(2.0);
+                Action::Render
+            }
+            KeyCode::Char('0') => {
+                self.window_end = OffsetDateTime::now_utc();
+                self.window = DEFAULT_WINDOW;
+                Action::Render
+            }
+            KeyCode::Enter => match self.rows.selected() {
+                Some(row) => Action::LoadBuilds {
+                    project_id: row.build_type_id.clone(),
+                    title: row.title.clone(),
+                },
+                None => Action::Render,
+            },
+            KeyCode::Char('r') => Action::LoadTimeline,
+            KeyCode::Esc => Action::ShowProjects,
+            _ => Action::Render,
+        };
+        Ok(Some(action))
+    }
+
+    fn tab_title(&self) -> String {
+        "Timeline".to_string()
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> color_eyre::Result<()> {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(0),    // Timeline takes remaining space
+                Constraint::Length(2), // Footer height
+            ])
+            .split(area);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Timeline — {} window", format_window(self.window)))
+            .title_top(freshness_line(self.fetched_at, self.stale_after));
+        let inner = block.inner(chunks[0]);
+        frame.render_widget(block, chunks[0]);
+
+        let label_width = self
+            .rows
+            .items()
+            .iter()
+            .map(|row| row.title.chars().count())
+            .max()
+            .unwrap_or(0)
+            .clamp(10, MAX_LABEL_WIDTH)
+            .min(inner.width as usize / 3);
+        let bar_width = (inner.width as usize).saturating_sub(label_width + 3);
+
+        let mut lines = vec![Line::from(vec![
+            Span::raw(format!("{:width$}   ", "", width = label_width)),
+            Span::styled(
+                self.render_axis(bar_width),
+                Style::default().fg(Color::Yellow),
+            ),
+        ])];
+
+        let mut load = vec![Span::styled(
+            format!("{:<width$}   ", "Running", width = label_width),
+            Style::default().add_modifier(Modifier::BOLD),
+        )];
+        load.extend(self.render_load(bar_width));
+        lines.push(Line::from(load));
+
+        if self.fetched_at.is_none() {
+            lines.push(Line::from("Loading builds…"));
+        } else if self.rows.items().is_empty() {
+            lines.push(Line::from("No builds found"));
+        }
+
+        // keep the selected row in view below the axis and load rows, as many as the rows of a
+        // table under its header
+        self.rows.set_viewport(chunks[0]);
+        let visible = visible_rows(chunks[0]).max(1);
+        let selected = self.rows.selected_index().unwrap_or(0);
+        let offset = self.rows.state.offset_mut();
+        if selected < *offset {
+            *offset = selected;
+        } else if selected >= *offset + visible {
+            *offset = selected + 1 - visible;
+        }
+        let offset = *offset;
+
+        for (i, row) in self
+            .rows
+            .items()
+            .iter()
+            .enumerate()
+            .skip(offset)
+            .take(visible)
+        {
+            let title: String = row.title.chars().take(label_width).collect();
+            let style = if Some(i) == self.rows.selected_index() {
+                Style::default().add_modifier(Modifier::REVERSED)
+            } else {
+                Style::default()
+            };
+            let mut spans = vec![Span::styled(
+                format!("{:<width$}", title, width = label_width),
+                style,
+            )];
+            spans.push(Span::raw(" │ "));
+            spans.extend(self.render_bars(row, bar_width));
+            lines.push(Line::from(spans));
+        }
+
+        frame.render_widget(Paragraph::new(lines), inner);
+
+        let footer = Paragraph::new(
+            "j/k: Move  gg/G: Top/Bottom  h/l: Pan  +/-: Zoom  0: Reset  Enter: Open builds  r: Refresh  Esc: Back",
+        )
+        .style(Style::default().fg(Color::DarkGray))
+        .block(Block::default().padding(Padding::horizontal(1)));
+
+        frame.render_widget(footer, chunks[1]);
+        Ok(())
+    }
+}
+
+fn format_window(window: Duration) -> String {
+    if window.whole_days() > 0 && window.whole_hours() % 24 == 0 {
+        format!("{}d", window.whole_days())
+    } else if window.whole_hours() > 0 && window.whole_minutes() % 60 == 0 {
+        format!("{}h", window.whole_hours())
+    } else if window.whole_hours() > 0 {
+        format!("{}h{}m", window.whole_hours(), window.whole_minutes() % 60)
+    } else {
+        format!("{}m", window.whole_minutes())
+    }
+}
@@ -0,0 +1,98 @@
+use std::time::{Duration, Instant};
+
+use color_eyre::Result;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Style},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+};
+
+use super::Component;
+use crate::action::Action;
+
+const TOAST_DURATION: Duration = Duration::from_secs(3);
+const TOAST_WIDTH: u16 = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ToastLevel {
+    Info,
+    Error,
+}
+
+/// Short-lived message shown in the bottom-right corner on top of the current view
+#[derive(Default)]
+pub struct Toast {
+    message: Option<(String, ToastLevel, Instant)>,
+}
+
+impl Toast {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn show(&mut self, message: String, level: ToastLevel) {
+        self.message = Some((message, level, Instant::now()));
+    }
+}
+
+impl Component for Toast {
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::Toast(message) => {
+                self.show(message, ToastLevel::Info);
+                return Ok(Some(Action::Render));
+            }
+            Action::Error(message) => {
+                self.show(message, ToastLevel::Error);
+                return Ok(Some(Action::Render));
+            }
+            Action::Tick => {
+                if let Some((_, _, shown_at)) = &self.message
+                    && shown_at.elapsed() >= TOAST_DURATION
+                {
+                    self.message = None;
+                    return Ok(Some(Action::Render));
+                }
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let Some((message, level, _)) = &self.message else {
+            return Ok(());
+        };
+
+        let (title, color) = match level {
+            ToastLevel::Info => ("Info", Color::Green),
+            ToastLevel::Error => ("Error", Color::Red),
+        };
+
+        let width = TOAST_WIDTH.min(area.width);
+        // wrap long messages across lines, plus the border
+        let text_width = width.saturating_sub(2).max(1) as usize;
+        let height = ((message.chars().count().div_ceil(text_width)) as u16 + 2).min(area.height);
+        // keep clear of the footer hints at the bottom
+        let toast_area = Rect {
+            x: area.x + area.width.saturating_sub(width + 1),
+            y: area.y + area.height.saturating_sub(height + 3),
+            width,
+            height,
+        };
+
+        let toast = Paragraph::new(message.as_str())
+            .wrap(Wrap { trim: true })
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(title)
+                    .border_style(Style::default().fg(color)),
+            );
+
+        frame.render_widget(Clear, toast_area);
+        frame.render_widget(toast, toast_area);
+        Ok(())
+    }
+}
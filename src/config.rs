@@ -1,19 +1,49 @@
 #![allow(dead_code)] // Remove this once you start using the code
 
-use std::{collections::HashMap, env, path::PathBuf};
+use std::{
+    collections::{BTreeMap, HashMap},
+    env,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
 
 use color_eyre::Result;
+use color_eyre::eyre::eyre;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use derive_deref::{Deref, DerefMut};
 use directories::ProjectDirs;
 use lazy_static::lazy_static;
 use ratatui::style::{Color, Modifier, Style};
+use regex::Regex;
 use serde::{Deserialize, de::Deserializer};
-use tracing::error;
+use tracing::{error, warn};
 
-use crate::{action::Action, app::Mode};
+use tokio::sync::mpsc::UnboundedSender;
+use toml_edit::DocumentMut;
+
+use crate::{action::Action, app::Mode, icons::IconSet, time::TimeDisplay};
+use teamcity::{
+    Auth, BranchFilter, DEFAULT_CACHE_MAX_SIZE, DEFAULT_CACHE_TTL, DEFAULT_MAX_CONCURRENT_REQUESTS,
+    types::{Build, BuildType},
+};
+pub use teamcity::{BuildsConfig, HttpConfig};
 
 const CONFIG: &str = include_str!("../.config/config.json5");
+const CONFIG_FILES: [(&str, config::FileFormat); 5] = [
+    ("config.json5", config::FileFormat::Json5),
+    ("config.json", config::FileFormat::Json),
+    ("config.yaml", config::FileFormat::Yaml),
+    ("config.toml", config::FileFormat::Toml),
+    ("config.ini", config::FileFormat::Ini),
+];
+// the config files are polled rather than watched through the platform's file events, a stat of
+// five files a second is cheap and works the same on every platform and network drive
+const CONFIG_POLL_INTERVAL: Duration = Duration::from_secs(1);
+// editors write a file in several steps, it is reloaded once it stays the same for this long
+const CONFIG_DEBOUNCE: Duration = Duration::from_millis(250);
+// written by t9s itself and read before the user's files, so their settings win; it isn't watched
+// for changes
+const LAYOUT_FILE: &str = "layout.json";
 
 #[derive(Clone, Debug, Deserialize, Default)]
 pub struct AppConfig {
@@ -23,11 +53,441 @@ pub struct AppConfig {
     pub config_dir: PathBuf,
 }
 
+#[derive(Clone, Debug, Deserialize, Default)]
+pub struct CacheConfig {
+    /// Default TTL in seconds for cached build configurations
+    #[serde(default)]
+    pub ttl_seconds: Option<u64>,
+    /// TTL overrides in seconds, keyed by project ID
+    #[serde(default)]
+    pub project_ttl_seconds: HashMap<String, u64>,
+    /// Size in MiB a server's cache file is kept under by evicting the least recently used
+    /// projects, 10 by default and unlimited if 0
+    #[serde(default)]
+    pub max_size_mb: Option<u64>,
+    /// Directory of the cache files, the platform cache directory (`$XDG_CACHE_HOME/t9s` on Linux)
+    /// by default
+    #[serde(default)]
+    pub dir: Option<PathBuf>,
+}
+
+impl CacheConfig {
+    pub fn default_ttl(&self) -> Duration {
+        self.ttl_seconds
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_CACHE_TTL)
+    }
+
+    /// Maximum size of a cache file in bytes, None if unlimited
+    pub fn max_size(&self) -> Option<u64> {
+        match self.max_size_mb {
+            Some(0) => None,
+            Some(mb) => Some(mb.saturating_mul(1024 * 1024)),
+            None => Some(DEFAULT_CACHE_MAX_SIZE),
+        }
+    }
+
+    pub fn dir(&self) -> PathBuf {
+        self.dir.clone().unwrap_or_else(get_cache_dir)
+    }
+
+    pub fn project_ttls(&self) -> HashMap<String, Duration> {
+        self.project_ttl_seconds
+            .iter()
+            .map(|(project_id, secs)| (project_id.clone(), Duration::from_secs(*secs)))
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ArtifactCleanupConfig {
+    /// Finished builds older than this are offered for artifact cleanup
+    #[serde(default = "ArtifactCleanupConfig::default_older_than_days")]
+    pub older_than_days: u32,
+}
+
+impl ArtifactCleanupConfig {
+    fn default_older_than_days() -> u32 {
+        30
+    }
+}
+
+impl Default for ArtifactCleanupConfig {
+    fn default() -> Self {
+        Self {
+            older_than_days: Self::default_older_than_days(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct StatisticsConfig {
+    /// Number of finished builds the statistics view is computed from
+    #[serde(default = "StatisticsConfig::default_build_count")]
+    pub build_count: u32,
+}
+
+impl StatisticsConfig {
+    fn default_build_count() -> u32 {
+        50
+    }
+}
+
+impl Default for StatisticsConfig {
+    fn default() -> Self {
+        Self {
+            build_count: Self::default_build_count(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct LogGrepConfig {
+    /// Number of finished builds whose logs are searched
+    #[serde(default = "LogGrepConfig::default_build_count")]
+    pub build_count: u32,
+}
+
+impl LogGrepConfig {
+    fn default_build_count() -> u32 {
+        20
+    }
+}
+
+impl Default for LogGrepConfig {
+    fn default() -> Self {
+        Self {
+            build_count: Self::default_build_count(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct PreviewConfig {
+    /// Build statistic values shown in the preview pane of the Builds view, by name
+    #[serde(default = "PreviewConfig::default_statistics")]
+    pub statistics: Vec<String>,
+}
+
+impl PreviewConfig {
+    fn default_statistics() -> Vec<String> {
+        [
+            "CodeCoverageL",
+            "ArtifactsSize",
+            "TotalTestCount",
+            "TimeSpentInQueue",
+        ]
+        .map(String::from)
+        .to_vec()
+    }
+}
+
+impl Default for PreviewConfig {
+    fn default() -> Self {
+        Self {
+            statistics: Self::default_statistics(),
+        }
+    }
+}
+
+/// Another TeamCity server whose build configurations are listed in the Projects view next to the
+/// ones of the main server
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct ServerConfig {
+    /// Shown in the Server column of the Projects view
+    pub name: String,
+    pub teamcity_url: String,
+    #[serde(default)]
+    pub token: Option<String>,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    #[serde(default)]
+    pub guest: bool,
+    /// Projects to monitor on this server
+    #[serde(default)]
+    pub projects: Vec<String>,
+}
+
+impl ServerConfig {
+    /// Same order as the main server: token, then username/password, then guest
+    pub fn auth(&self) -> Option<Auth> {
+        if let Some(token) = &self.token {
+            Some(Auth::Token(token.clone()))
+        } else if let (Some(username), Some(password)) = (&self.username, &self.password) {
+            Some(Auth::Basic {
+                username: username.clone(),
+                password: password.clone(),
+            })
+        } else if self.guest {
+            Some(Auth::Guest)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct StatusPollingConfig {
+    /// Seconds between polls of the latest builds of the favorite configurations
+    #[serde(default = "StatusPollingConfig::default_interval_secs")]
+    pub interval_secs: u64,
+}
+
+impl StatusPollingConfig {
+    fn default_interval_secs() -> u64 {
+        60
+    }
+
+    pub fn interval(&self) -> Duration {
+        // a zero interval would make tokio panic
+        Duration::from_secs(self.interval_secs.max(1))
+    }
+}
+
+impl Default for StatusPollingConfig {
+    fn default() -> Self {
+        Self {
+            interval_secs: Self::default_interval_secs(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct LongRunningConfig {
+    /// Running builds are flagged once they take this many percent longer than the average of
+    /// recent successful builds of their configuration
+    #[serde(default = "LongRunningConfig::default_threshold_percent")]
+    pub threshold_percent: u32,
+}
+
+impl LongRunningConfig {
+    fn default_threshold_percent() -> u32 {
+        50
+    }
+}
+
+impl Default for LongRunningConfig {
+    fn default() -> Self {
+        Self {
+            threshold_percent: Self::default_threshold_percent(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct QueueWatchdogConfig {
+    /// Minutes a queued build of a favorite configuration waits before a notification names the
+    /// likely causes, 0 to never notify
+    #[serde(default = "QueueWatchdogConfig::default_threshold_minutes")]
+    pub threshold_minutes: u64,
+}
+
+impl QueueWatchdogConfig {
+    fn default_threshold_minutes() -> u64 {
+        30
+    }
+
+    pub fn threshold(&self) -> Option<time::Duration> {
+        (self.threshold_minutes > 0).then(|| time::Duration::minutes(self.threshold_minutes as i64))
+    }
+}
+
+impl Default for QueueWatchdogConfig {
+    fn default() -> Self {
+        Self {
+            threshold_minutes: Self::default_threshold_minutes(),
+        }
+    }
+}
+
+/// Branch shown by default when opening the builds of a configuration
+#[derive(Clone, Debug, Deserialize, Default)]
+pub struct BranchPolicy {
+    /// Exact branch name, fetched with a server-side `branch:` locator
+    #[serde(default)]
+    pub branch: Option<String>,
+    /// Regex of branch names to prefer among builds of all branches
+    #[serde(default)]
+    pub prefer: Option<String>,
+}
+
+impl BranchPolicy {
+    pub fn branch_filter(&self) -> BranchFilter {
+        match (&self.branch, &self.prefer) {
+            (Some(branch), _) => BranchFilter::Named(branch.clone()),
+            (None, Some(_)) => BranchFilter::Any,
+            (None, None) => BranchFilter::Default,
+        }
+    }
+
+    pub fn prefer_regex(&self) -> Result<Option<Regex>, regex::Error> {
+        self.prefer.as_deref().map(Regex::new).transpose()
+    }
+}
+
+/// Team owning the tests whose names match a pattern, shown next to their failures
+#[derive(Clone, Debug, Deserialize, Default)]
+pub struct TestOwner {
+    pub team: String,
+    /// Regex of test names, which start with the package of the test
+    pub pattern: String,
+}
+
+impl TestOwner {
+    pub fn pattern_regex(&self) -> Result<Regex, regex::Error> {
+        Regex::new(&self.pattern)
+    }
+}
+
+/// Named filter, switched to with the filter picker; unset criteria match everything
+#[derive(Clone, Debug, Deserialize, Default, PartialEq, Eq)]
+pub struct SavedFilter {
+    pub name: String,
+    /// Substring of the branch name of builds
+    #[serde(default)]
+    pub branch: Option<String>,
+    /// Status or state of builds: `success`, `failure`, `running`, `queued`, ...
+    #[serde(default)]
+    pub status: Option<String>,
+    /// Substring of the build configuration name
+    #[serde(default)]
+    pub build_type: Option<String>,
+}
+
+impl SavedFilter {
+    pub fn matches_build(&self, build: &Build) -> bool {
+        let branch_matches = self.branch.as_deref().is_none_or(|branch| {
+            build
+                .branch_name
+                .as_deref()
+                .is_some_and(|name| contains_ignore_case(name, branch))
+        });
+        let status_matches = self.status.as_deref().is_none_or(|status| {
+            [build.status.as_deref(), build.state.as_deref()]
+                .into_iter()
+                .flatten()
+                .any(|s| s.eq_ignore_ascii_case(status))
+        });
+        branch_matches && status_matches
+    }
+
+    pub fn matches_build_type(&self, build_type: &BuildType) -> bool {
+        self.build_type
+            .as_deref()
+            .is_none_or(|name| contains_ignore_case(&build_type.name, name))
+    }
+}
+
+/// External command run with a key on the selected build in the Builds view or the selected
+/// configuration in the Projects view
+#[derive(Clone, Debug, Deserialize, Default, PartialEq, Eq)]
+pub struct CustomAction {
+    /// Shown in the notification when the command finishes
+    pub name: String,
+    /// Key the action is bound to, e.g. `<ctrl-n>`, taking precedence over the keys of the view
+    pub key: String,
+    /// Command run on the selected build, with placeholders like `{webUrl}`
+    #[serde(default)]
+    pub on_build: Option<String>,
+    /// Command run on the selected build configuration, with placeholders like `{id}`
+    #[serde(default)]
+    pub on_build_type: Option<String>,
+}
+
+impl CustomAction {
+    pub fn matches(&self, key: &KeyEvent) -> bool {
+        parse_key_sequence(&self.key).is_ok_and(|keys| keys == [*key])
+    }
+}
+
+fn contains_ignore_case(haystack: &str, needle: &str) -> bool {
+    haystack.to_lowercase().contains(&needle.to_lowercase())
+}
+
 #[derive(Clone, Debug, Default, Deserialize)]
 pub struct Config {
     #[serde(default, flatten)]
     pub config: AppConfig,
     #[serde(default)]
+    pub cache: CacheConfig,
+    #[serde(default)]
+    pub artifact_cleanup: ArtifactCleanupConfig,
+    #[serde(default)]
+    pub statistics: StatisticsConfig,
+    #[serde(default)]
+    pub log_grep: LogGrepConfig,
+    #[serde(default)]
+    pub preview: PreviewConfig,
+    #[serde(default)]
+    pub builds: BuildsConfig,
+    /// Build configuration IDs whose latest build is shown in the status bar
+    #[serde(default)]
+    pub favorites: Vec<String>,
+    #[serde(default)]
+    pub status_polling: StatusPollingConfig,
+    #[serde(default)]
+    pub long_running: LongRunningConfig,
+    #[serde(default)]
+    pub queue_watchdog: QueueWatchdogConfig,
+    #[serde(default)]
+    pub http: HttpConfig,
+    /// Name of the main server in the Server column of the Projects view, its host by default
+    #[serde(default)]
+    pub server_name: Option<String>,
+    /// Other servers whose configurations are listed in the Projects view
+    #[serde(default)]
+    pub servers: Vec<ServerConfig>,
+    /// Number of projects fetched from TeamCity at the same time
+    #[serde(default = "Config::default_max_concurrent_requests")]
+    pub max_concurrent_requests: usize,
+    /// Seconds after which the data shown in a view is marked stale
+    #[serde(default = "Config::default_stale_after_secs")]
+    pub stale_after_secs: u64,
+    /// TeamCity username used to find my changes and personal builds
+    #[serde(default)]
+    pub teamcity_username: Option<String>,
+    /// Username of basic auth, used when `teamcity_username` is not set
+    #[serde(skip)]
+    pub login_username: Option<String>,
+    /// Repository browser URL of a commit, with `{revision}` and `{vcs_root}` placeholders
+    #[serde(default)]
+    pub commit_url_template: Option<String>,
+    /// Directory build logs are downloaded to, `logs` in the data directory by default
+    #[serde(default)]
+    pub log_download_dir: Option<PathBuf>,
+    /// Directory offered for downloading artifact archives, `artifacts` in the data directory by
+    /// default
+    #[serde(default)]
+    pub artifact_download_dir: Option<PathBuf>,
+    /// Show build times as absolute local times or relative to now
+    #[serde(default)]
+    pub time_display: TimeDisplay,
+    /// Draw icons with emoji, Nerd Font glyphs, or ASCII for terminals and fonts without either
+    #[serde(default)]
+    pub icons: IconSet,
+    /// Default branch policies, keyed by build configuration ID
+    #[serde(default)]
+    pub default_branches: HashMap<String, BranchPolicy>,
+    #[serde(default)]
+    pub filters: Vec<SavedFilter>,
+    #[serde(default)]
+    pub custom_actions: Vec<CustomAction>,
+    /// Teams owning tests in the failed tests view, the first matching pattern wins
+    #[serde(default)]
+    pub test_owners: Vec<TestOwner>,
+    /// Widths of the Builds table columns by column name, saved when they are adjusted in the view
+    #[serde(default)]
+    pub column_widths: BTreeMap<String, u16>,
+    /// Wrap long lines in the build log viewer instead of scrolling them horizontally, saved when
+    /// it is toggled in the view
+    #[serde(default)]
+    pub log_wrap: bool,
+    /// Name of the saved filter applied to the Projects and Builds views
+    #[serde(skip)]
+    pub active_filter: Option<String>,
+    #[serde(default)]
     pub keybindings: KeyBindings,
     #[serde(default)]
     pub styles: Styles,
@@ -46,6 +506,41 @@ lazy_static! {
 }
 
 impl Config {
+    fn default_max_concurrent_requests() -> usize {
+        DEFAULT_MAX_CONCURRENT_REQUESTS
+    }
+
+    fn default_stale_after_secs() -> u64 {
+        300
+    }
+
+    pub fn stale_after(&self) -> time::Duration {
+        time::Duration::seconds(self.stale_after_secs as i64)
+    }
+
+    pub fn teamcity_username(&self) -> Option<&str> {
+        self.teamcity_username
+            .as_deref()
+            .or(self.login_username.as_deref())
+    }
+
+    pub fn active_filter(&self) -> Option<&SavedFilter> {
+        let name = self.active_filter.as_deref()?;
+        self.filters.iter().find(|filter| filter.name == name)
+    }
+
+    pub fn log_download_dir(&self) -> PathBuf {
+        self.log_download_dir
+            .clone()
+            .unwrap_or_else(|| self.config.data_dir.join("logs"))
+    }
+
+    pub fn artifact_download_dir(&self) -> PathBuf {
+        self.artifact_download_dir
+            .clone()
+            .unwrap_or_else(|| self.config.data_dir.join("artifacts"))
+    }
+
     pub fn new() -> Result<Self, config::ConfigError> {
         let default_config: Config = json5::from_str(CONFIG).unwrap();
         let data_dir = get_data_dir();
@@ -53,16 +548,12 @@ impl Config {
         let mut builder = config::Config::builder()
             .set_default("data_dir", data_dir.to_str().unwrap())?
             .set_default("config_dir", config_dir.to_str().unwrap())?;
+        if let Some(layout) = read_layout(&config_dir.join(LAYOUT_FILE)) {
+            builder = builder.add_source(config::File::from_str(&layout, config::FileFormat::Json));
+        }
 
-        let config_files = [
-            ("config.json5", config::FileFormat::Json5),
-            ("config.json", config::FileFormat::Json),
-            ("config.yaml", config::FileFormat::Yaml),
-            ("config.toml", config::FileFormat::Toml),
-            ("config.ini", config::FileFormat::Ini),
-        ];
         let mut found_config = false;
-        for (file, format) in &config_files {
+        for (file, format) in &CONFIG_FILES {
             let source = config::File::from(config_dir.join(file))
                 .format(*format)
                 .required(false);
@@ -96,26 +587,205 @@ impl Config {
     }
 }
 
+/// Saves the column widths of the Builds table next to the config files
+pub fn save_column_widths(widths: &BTreeMap<String, u16>) -> Result<()> {
+    save_layout("column_widths", serde_json::to_value(widths)?)
+}
+
+/// Saves whether the build log viewer wraps long lines next to the config files
+pub fn save_log_wrap(wrap: bool) -> Result<()> {
+    save_layout("log_wrap", serde_json::Value::Bool(wrap))
+}
+
+/// Content of `layout.json` at `path`, None with a warning if it can't be read or isn't a JSON
+/// object so the saved settings don't keep the config files from loading
+fn read_layout(path: &Path) -> Option<String> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return None,
+        Err(e) => {
+            warn!("Ignoring {}: {}", path.display(), e);
+            return None;
+        }
+    };
+    match serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(&content) {
+        Ok(_) => Some(content),
+        Err(e) => {
+            warn!("Ignoring {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+/// Sets `key` in `layout.json`, keeping the other saved settings
+fn save_layout(key: &str, value: serde_json::Value) -> Result<()> {
+    let config_dir = get_config_dir();
+    std::fs::create_dir_all(&config_dir)?;
+    let path = config_dir.join(LAYOUT_FILE);
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e.into()),
+    };
+    let layout = with_layout(&content, key, value)
+        .map_err(|e| eyre!("{} is left as it is, it isn't valid: {}", path.display(), e))?;
+    std::fs::write(path, layout)?;
+    Ok(())
+}
+
+/// `content` of `layout.json` with `key` set to `value`, an error if it isn't a JSON object so a
+/// file the user broke isn't overwritten
+fn with_layout(content: &str, key: &str, value: serde_json::Value) -> Result<String> {
+    let mut layout: serde_json::Map<String, serde_json::Value> = if content.trim().is_empty() {
+        serde_json::Map::new()
+    } else {
+        serde_json::from_str(content)?
+    };
+    layout.insert(key.to_string(), value);
+    Ok(serde_json::to_string_pretty(&layout)?)
+}
+
+/// Sets the monitored projects in `config.toml`, keeping its other settings
+pub fn save_projects(projects: &[String]) -> Result<()> {
+    edit_config_file(|content| with_projects(content, projects))
+}
+
+/// Projects monitored on the main server according to `config.toml`
+pub fn load_projects() -> Result<Vec<String>> {
+    let content = std::fs::read_to_string(get_config_dir().join("config.toml"))?;
+    let table: toml::Table = toml::from_str(&content)?;
+    Ok(table
+        .get("projects")
+        .and_then(|projects| projects.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|project| project.as_str())
+        .map(str::to_string)
+        .collect())
+}
+
+/// Sets the token of the main server, or of the server named `server`, in `config.toml`,
+/// keeping its other settings
+pub fn save_token(server: Option<&str>, token: &str) -> Result<()> {
+    edit_config_file(|content| with_token(content, server, token))
+}
+
+/// Replaces `config.toml` with what `edit` makes of its content, empty if there is no file yet
+fn edit_config_file(edit: impl FnOnce(&str) -> Result<String>) -> Result<()> {
+    let config_dir = get_config_dir();
+    std::fs::create_dir_all(&config_dir)?;
+    let path = config_dir.join("config.toml");
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e.into()),
+    };
+    std::fs::write(&path, edit(&content)?)?;
+    Ok(())
+}
+
+/// `content` of `config.toml` with `projects` as the monitored projects, its comments and the
+/// order of its settings kept as they are
+fn with_projects(content: &str, projects: &[String]) -> Result<String> {
+    let mut document: DocumentMut = content.parse()?;
+    document["projects"] = toml_edit::value(projects.iter().collect::<toml_edit::Array>());
+    Ok(document.to_string())
+}
+
+/// `content` of `config.toml` with `token` as the token of the main server, or of the
+/// `[[servers]]` entry named `server`, its comments and the order of its settings kept as they are
+fn with_token(content: &str, server: Option<&str>, token: &str) -> Result<String> {
+    let mut document: DocumentMut = content.parse()?;
+    let entry = match server {
+        None => document.as_table_mut(),
+        Some(name) => document
+            .get_mut("servers")
+            .and_then(|servers| servers.as_array_of_tables_mut())
+            .into_iter()
+            .flat_map(|servers| servers.iter_mut())
+            .find(|server| server.get("name").and_then(|n| n.as_str()) == Some(name))
+            .ok_or_else(|| eyre!("No server named {} in config.toml", name))?,
+    };
+    entry["token"] = toml_edit::value(token);
+    Ok(document.to_string())
+}
+
+/// What tells a config file apart from an earlier version of it. A file removed and written again
+/// within a poll may keep its modification time, its creation time tells them apart.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FileVersion {
+    modified: Option<SystemTime>,
+    created: Option<SystemTime>,
+    len: u64,
+}
+
+/// Version of each of the config files, None for those that don't exist
+fn config_file_versions() -> Vec<Option<FileVersion>> {
+    let config_dir = get_config_dir();
+    CONFIG_FILES
+        .iter()
+        .map(|(file, _)| {
+            std::fs::metadata(config_dir.join(file))
+                .ok()
+                .map(|m| FileVersion {
+                    modified: m.modified().ok(),
+                    created: m.created().ok(),
+                    len: m.len(),
+                })
+        })
+        .collect()
+}
+
+/// Polls the config files every [`CONFIG_POLL_INTERVAL`] and sends [`Action::ConfigReloaded`]
+/// whenever one of them is created, changed, removed or replaced, once it has settled
+pub fn watch(tx: UnboundedSender<Action>) {
+    tokio::spawn(async move {
+        let mut last_versions = config_file_versions();
+        let mut interval = tokio::time::interval(CONFIG_POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            let mut versions = config_file_versions();
+            if versions == last_versions {
+                continue;
+            }
+            loop {
+                tokio::time::sleep(CONFIG_DEBOUNCE).await;
+                let settled = config_file_versions();
+                if settled == versions {
+                    break;
+                }
+                versions = settled;
+            }
+            last_versions = versions;
+            if tx.send(Action::ConfigReloaded).is_err() {
+                break;
+            }
+        }
+    });
+}
+
 pub fn get_data_dir() -> PathBuf {
-    let directory = if let Some(s) = DATA_FOLDER.clone() {
+    if let Some(s) = DATA_FOLDER.clone() {
         s
     } else if let Some(proj_dirs) = project_directory() {
         proj_dirs.data_local_dir().to_path_buf()
     } else {
         PathBuf::from(".").join(".data")
-    };
-    directory
+    }
 }
 
 pub fn get_config_dir() -> PathBuf {
-    let directory = if let Some(s) = CONFIG_FOLDER.clone() {
+    if let Some(s) = CONFIG_FOLDER.clone() {
         s
     } else if let Some(proj_dirs) = project_directory() {
         proj_dirs.config_local_dir().to_path_buf()
     } else {
         PathBuf::from(".").join(".config")
-    };
-    directory
+    }
+}
+
+pub fn get_cache_dir() -> PathBuf {
+    teamcity::default_cache_dir()
 }
 
 fn project_directory() -> Option<ProjectDirs> {
@@ -297,8 +967,8 @@ pub fn parse_key_sequence(raw: &str) -> Result<Vec<KeyEvent>, String> {
     }
     let raw = if !raw.contains("><") {
         let raw = raw.strip_prefix('<').unwrap_or(raw);
-        let raw = raw.strip_prefix('>').unwrap_or(raw);
-        raw
+
+        raw.strip_prefix('>').unwrap_or(raw)
     } else {
         raw
     };
@@ -453,6 +1123,111 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn servers_authenticate_like_the_main_one() {
+        let config: Config = toml::from_str(
+            r#"
+            [[servers]]
+            name = "legacy"
+            teamcity_url = "https://old-tc.example.com"
+            username = "ci"
+            password = "secret"
+            projects = ["Legacy"]
+
+            [[servers]]
+            name = "oss"
+            teamcity_url = "https://tc.example.org"
+            guest = true
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.servers.len(), 2);
+        assert_eq!(
+            config.servers[0].auth(),
+            Some(Auth::Basic {
+                username: "ci".to_string(),
+                password: "secret".to_string(),
+            })
+        );
+        assert_eq!(config.servers[0].projects, vec!["Legacy"]);
+        assert_eq!(config.servers[1].auth(), Some(Auth::Guest));
+        assert!(config.servers[1].projects.is_empty());
+    }
+
+    #[test]
+    fn cache_size_of_zero_is_unlimited() {
+        let cache = |max_size_mb| CacheConfig {
+            max_size_mb,
+            ..CacheConfig::default()
+        };
+
+        assert_eq!(cache(None).max_size(), Some(DEFAULT_CACHE_MAX_SIZE));
+        assert_eq!(cache(Some(5)).max_size(), Some(5 * 1024 * 1024));
+        assert_eq!(cache(Some(0)).max_size(), None);
+    }
+
+    #[test]
+    fn saved_layout_keeps_the_other_settings_and_a_broken_file() {
+        let layout = with_layout("", "log_wrap", serde_json::Value::Bool(true)).unwrap();
+        let layout =
+            with_layout(&layout, "column_widths", serde_json::json!({"Branch": 50})).unwrap();
+        let saved: serde_json::Value = serde_json::from_str(&layout).unwrap();
+        assert_eq!(
+            saved,
+            serde_json::json!({"log_wrap": true, "column_widths": {"Branch": 50}})
+        );
+
+        assert!(
+            with_layout(
+                "{\"log_wrap\": tru",
+                "log_wrap",
+                serde_json::Value::Bool(false)
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn saved_projects_keep_the_other_settings() {
+        let content = with_projects(
+            "# main server\nteamcity_url = \"https://tc.example.com\"\nprojects = [\"Old\"]\n\n[http]\nproxy = \"http://proxy:3128\" # office\n",
+            &["Web".to_string(), "Api".to_string()],
+        )
+        .unwrap();
+        assert_eq!(
+            content,
+            "# main server\nteamcity_url = \"https://tc.example.com\"\nprojects = [\"Web\", \"Api\"]\n\n[http]\nproxy = \"http://proxy:3128\" # office\n"
+        );
+        let table: toml::Table = toml::from_str(&content).unwrap();
+
+        assert_eq!(
+            table["teamcity_url"].as_str(),
+            Some("https://tc.example.com")
+        );
+        assert_eq!(
+            table["projects"],
+            toml::Value::Array(vec!["Web".into(), "Api".into()])
+        );
+        assert_eq!(table["http"]["proxy"].as_str(), Some("http://proxy:3128"));
+    }
+
+    #[test]
+    fn saved_token_goes_to_the_rejected_server() {
+        let content = "# main server\ntoken = \"old\"\n\n[[servers]]\nname = \"eu\"\ntoken = \"expired\" # rotated monthly\nteamcity_url = \"https://eu.example.com\"\n";
+
+        assert_eq!(
+            with_token(content, None, "new").unwrap(),
+            "# main server\ntoken = \"new\"\n\n[[servers]]\nname = \"eu\"\ntoken = \"expired\" # rotated monthly\nteamcity_url = \"https://eu.example.com\"\n"
+        );
+        assert_eq!(
+            with_token(content, Some("eu"), "new").unwrap(),
+            "# main server\ntoken = \"old\"\n\n[[servers]]\nname = \"eu\"\ntoken = \"new\"\nteamcity_url = \"https://eu.example.com\"\n"
+        );
+
+        assert!(with_token(content, Some("us"), "new").is_err());
+    }
+
     #[test]
     fn test_parse_style_default() {
         let style = parse_style("");
@@ -595,4 +1370,85 @@ mod tests {
             KeyEvent::new(KeyCode::Enter, KeyModifiers::ALT)
         );
     }
+
+    #[test]
+    fn test_saved_filter_matches_branch_and_status() {
+        let filter = SavedFilter {
+            name: "Failed on main".to_string(),
+            branch: Some("Main".to_string()),
+            status: Some("failure".to_string()),
+            build_type: None,
+        };
+        let build = |branch: &str, status: &str, state: &str| Build {
+            branch_name: Some(branch.to_string()),
+            status: Some(status.to_string()),
+            state: Some(state.to_string()),
+            ..Build::default()
+        };
+
+        assert!(filter.matches_build(&build("refs/heads/main", "FAILURE", "finished")));
+        assert!(!filter.matches_build(&build("main", "SUCCESS", "finished")));
+        assert!(!filter.matches_build(&build("feature", "FAILURE", "finished")));
+        assert!(!filter.matches_build(&Build::default()));
+
+        let running = SavedFilter {
+            status: Some("running".to_string()),
+            ..SavedFilter::default()
+        };
+        assert!(running.matches_build(&build("main", "SUCCESS", "running")));
+    }
+
+    #[test]
+    fn test_saved_filter_matches_build_type_names() {
+        let filter = SavedFilter {
+            build_type: Some("deploy".to_string()),
+            ..SavedFilter::default()
+        };
+        let build_type = |name: &str| BuildType {
+            name: name.to_string(),
+            ..BuildType::default()
+        };
+
+        assert!(filter.matches_build_type(&build_type("Deploy to prod")));
+        assert!(!filter.matches_build_type(&build_type("Build")));
+        assert!(SavedFilter::default().matches_build_type(&build_type("Build")));
+    }
+
+    #[test]
+    fn test_active_filter_is_looked_up_by_name() {
+        let config = Config {
+            filters: vec![SavedFilter {
+                name: "Deploys".to_string(),
+                ..SavedFilter::default()
+            }],
+            active_filter: Some("Deploys".to_string()),
+            ..Config::default()
+        };
+        assert_eq!(
+            config.active_filter().map(|f| f.name.as_str()),
+            Some("Deploys")
+        );
+
+        let removed = Config {
+            active_filter: Some("Gone".to_string()),
+            ..config
+        };
+        assert_eq!(removed.active_filter(), None);
+    }
+
+    #[test]
+    fn test_custom_action_matches_its_key() {
+        let action = |key: &str| CustomAction {
+            key: key.to_string(),
+            ..CustomAction::default()
+        };
+        let ctrl_n = KeyEvent::new(KeyCode::Char('n'), KeyModifiers::CONTROL);
+
+        assert!(action("<ctrl-n>").matches(&ctrl_n));
+        assert!(!action("<n>").matches(&ctrl_n));
+        assert!(
+            action("<shift-n>").matches(&KeyEvent::new(KeyCode::Char('N'), KeyModifiers::SHIFT))
+        );
+        assert!(!action("<unknown-key>").matches(&ctrl_n));
+    }
 }
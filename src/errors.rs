@@ -1,8 +1,14 @@
+use std::backtrace::Backtrace;
 use std::env;
+use std::path::PathBuf;
 
 use color_eyre::Result;
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
 use tracing::error;
 
+use crate::config::get_data_dir;
+
 pub fn init() -> Result<()> {
     let (panic_hook, eyre_hook) = color_eyre::config::HookBuilder::default()
         .panic_section(format!(
@@ -15,24 +21,24 @@ pub fn init() -> Result<()> {
         .into_hooks();
     eyre_hook.install()?;
     std::panic::set_hook(Box::new(move |panic_info| {
-        if let Ok(mut t) = crate::tui::Tui::new() {
-            if let Err(r) = t.exit() {
-                error!("Unable to exit Terminal: {:?}", r);
-            }
+        // the terminal is restored first, or nothing printed below would be readable
+        if let Err(r) = crate::tui::restore_terminal() {
+            error!("Unable to restore the terminal: {:?}", r);
         }
 
-        #[cfg(not(debug_assertions))]
-        {
-            use human_panic::{handle_dump, metadata, print_msg};
-            let metadata = metadata!();
-            let file_path = handle_dump(&metadata, panic_info);
-            // prints human-panic message
-            print_msg(file_path, &metadata)
-                .expect("human-panic: printing error message to console failed");
-            eprintln!("{}", panic_hook.panic_report(panic_info)); // prints color-eyre stack trace to stderr
+        let msg = strip_ansi_escapes::strip_str(panic_hook.panic_report(panic_info).to_string());
+        error!("Error: {}", msg);
+        match write_crash_report(&msg) {
+            Ok(path) => eprintln!(
+                "t9s crashed, a report was written to {}\nThis is a bug. Consider reporting it at {} with the report attached.",
+                path.display(),
+                env!("CARGO_PKG_REPOSITORY")
+            ),
+            Err(e) => eprintln!("t9s crashed and failed to write a crash report: {}", e),
         }
-        let msg = format!("{}", panic_hook.panic_report(panic_info));
-        error!("Error: {}", strip_ansi_escapes::strip_str(msg));
+
+        #[cfg(not(debug_assertions))]
+        eprintln!("{}", panic_hook.panic_report(panic_info)); // prints color-eyre stack trace to stderr
 
         #[cfg(debug_assertions)]
         {
@@ -49,6 +55,30 @@ pub fn init() -> Result<()> {
     Ok(())
 }
 
+/// Writes a report of a panic with `message` and a backtrace to the `crashes` directory of the data
+/// dir, returns its path
+fn write_crash_report(message: &str) -> std::io::Result<PathBuf> {
+    let dir = get_data_dir().join("crashes");
+    std::fs::create_dir_all(&dir)?;
+    let now = OffsetDateTime::now_utc();
+    let path = dir.join(format!("crash-{}.log", now.unix_timestamp()));
+    let backtrace = Backtrace::force_capture().to_string();
+    std::fs::write(&path, crash_report(message, &backtrace, now))?;
+    Ok(path)
+}
+
+fn crash_report(message: &str, backtrace: &str, at: OffsetDateTime) -> String {
+    format!(
+        "t9s {} crashed at {} on {} {}\n\n{}\n\nBacktrace:\n{}\n",
+        env!("CARGO_PKG_VERSION"),
+        at.format(&Rfc3339).unwrap_or_default(),
+        env::consts::OS,
+        env::consts::ARCH,
+        message.trim_end(),
+        backtrace
+    )
+}
+
 /// Similar to the `std::dbg!` macro, but generates `tracing` events rather
 /// than printing to stdout.
 ///
@@ -76,3 +106,28 @@ macro_rules! trace_dbg {
                 trace_dbg!(level: tracing::Level::DEBUG, $ex)
         };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    #[test]
+    fn crash_reports_hold_the_version_message_and_backtrace() {
+        let report = crash_report(
+            "The application panicked (crashed).\n",
+            "   0: t9s::main",
+            datetime!(2025-02-01 10:00:00 UTC),
+        );
+
+        assert!(report.starts_with(&format!(
+            "t9s {} crashed at 2025-02-01T10:00:00Z on ",
+            env!("CARGO_PKG_VERSION")
+        )));
+        assert!(
+            report.contains(
+                "\n\nThe application panicked (crashed).\n\nBacktrace:\n   0: t9s::main\n"
+            )
+        );
+    }
+}
@@ -0,0 +1,128 @@
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+
+use color_eyre::Result;
+use color_eyre::eyre::eyre;
+use serde_json::{Map, Value};
+
+pub const EXPORT_PROMPT_TITLE: &str =
+    "Export to .csv or .json (press Enter to save, Esc to cancel)";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    /// Picks the format from the file extension
+    pub fn from_path(path: &Path) -> Result<Self> {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("csv") => Ok(ExportFormat::Csv),
+            Some(ext) if ext.eq_ignore_ascii_case("json") => Ok(ExportFormat::Json),
+            _ => Err(eyre!("Unknown export format, use a .csv or .json file")),
+        }
+    }
+}
+
+/// Expands a leading `~` to the home directory
+pub fn expand_path(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Table as CSV with a header line, or as a JSON array with an object per row
+pub fn render(format: ExportFormat, headers: &[&str], rows: &[Vec<String>]) -> Result<String> {
+    match format {
+        ExportFormat::Csv => {
+            let mut csv = String::new();
+            let lines = std::iter::once(headers.iter().map(|h| csv_field(h)).collect::<Vec<_>>())
+                .chain(
+                    rows.iter()
+                        .map(|row| row.iter().map(|field| csv_field(field)).collect()),
+                );
+            for line in lines {
+                csv.push_str(&line.join(","));
+                csv.push_str("\r\n");
+            }
+            Ok(csv)
+        }
+        ExportFormat::Json => {
+            let objects: Vec<Value> = rows
+                .iter()
+                .map(|row| {
+                    let object: Map<String, Value> = headers
+                        .iter()
+                        .zip(row)
+                        .map(|(header, field)| (header.to_string(), Value::from(field.as_str())))
+                        .collect();
+                    Value::Object(object)
+                })
+                .collect();
+            Ok(serde_json::to_string_pretty(&objects)?)
+        }
+    }
+}
+
+/// Writes the table to `path` in the format of its extension
+pub fn write(path: &Path, headers: &[&str], rows: &[Vec<String>]) -> Result<()> {
+    let format = ExportFormat::from_path(path)?;
+    std::fs::write(path, render(format, headers, rows)?)?;
+    Ok(())
+}
+
+// RFC 4180: fields with separators, quotes or line breaks are quoted, quotes are doubled
+fn csv_field(field: &str) -> Cow<'_, str> {
+    if field.contains([',', '"', '\r', '\n']) {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows() -> Vec<Vec<String>> {
+        vec![
+            vec!["1".to_string(), "Build".to_string()],
+            vec!["2".to_string(), "Deploy, \"prod\"".to_string()],
+        ]
+    }
+
+    #[test]
+    fn format_follows_the_extension() {
+        assert_eq!(
+            ExportFormat::from_path(Path::new("out.csv")).unwrap(),
+            ExportFormat::Csv
+        );
+        assert_eq!(
+            ExportFormat::from_path(Path::new("out.JSON")).unwrap(),
+            ExportFormat::Json
+        );
+        assert!(ExportFormat::from_path(Path::new("out.txt")).is_err());
+        assert!(ExportFormat::from_path(Path::new("out")).is_err());
+    }
+
+    #[test]
+    fn csv_quotes_fields_with_separators() {
+        let csv = render(ExportFormat::Csv, &["id", "name"], &rows()).unwrap();
+        assert_eq!(csv, "id,name\r\n1,Build\r\n2,\"Deploy, \"\"prod\"\"\"\r\n");
+    }
+
+    #[test]
+    fn json_has_an_object_per_row() {
+        let json = render(ExportFormat::Json, &["id", "name"], &rows()).unwrap();
+        let parsed: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            parsed,
+            serde_json::json!([
+                { "id": "1", "name": "Build" },
+                { "id": "2", "name": "Deploy, \"prod\"" },
+            ])
+        );
+    }
+}
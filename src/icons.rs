@@ -0,0 +1,124 @@
+use serde::Deserialize;
+
+use teamcity::types::Build;
+
+const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+const ASCII_SPINNER: [&str; 4] = ["|", "/", "-", "\\"];
+
+/// Glyphs icons are drawn with, for terminals and fonts that can't show emoji
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IconSet {
+    #[default]
+    Emoji,
+    /// Font Awesome glyphs of a Nerd Font, one column wide
+    #[serde(rename = "nerd")]
+    NerdFont,
+    /// Plain ASCII characters, one column wide
+    Ascii,
+}
+
+impl IconSet {
+    /// Picks the glyph of this set out of the emoji, Nerd Font and ASCII ones
+    pub fn pick(
+        self,
+        emoji: &'static str,
+        nerd: &'static str,
+        ascii: &'static str,
+    ) -> &'static str {
+        match self {
+            IconSet::Emoji => emoji,
+            IconSet::NerdFont => nerd,
+            IconSet::Ascii => ascii,
+        }
+    }
+
+    /// Icon of the state of `build`: its status once finished, a spinner turning with `frame`
+    /// while it runs
+    pub fn build_status(self, build: &Build, frame: usize) -> &'static str {
+        match build.state.as_deref() {
+            Some("queued") => self.pick("⏳", "\u{f017}", "."),
+            Some("running") => match self {
+                IconSet::Emoji | IconSet::NerdFont => SPINNER[frame % SPINNER.len()],
+                IconSet::Ascii => ASCII_SPINNER[frame % ASCII_SPINNER.len()],
+            },
+            _ => match build.status.as_deref() {
+                Some("SUCCESS") => self.pick("✅", "\u{f00c}", "+"),
+                Some("FAILURE" | "ERROR") => self.pick("❌", "\u{f00d}", "x"),
+                _ => self.pick("⚪", "\u{f128}", "?"),
+            },
+        }
+    }
+
+    pub fn warning(self) -> &'static str {
+        self.pick("⚠️", "\u{f071}", "!")
+    }
+
+    /// Marks a selected or present item, like a marked build or a favorite with a build
+    pub fn mark(self) -> &'static str {
+        self.pick("●", "\u{f111}", "*")
+    }
+
+    /// Counterpart of [`IconSet::mark`] for an item without anything to show
+    pub fn empty_mark(self) -> &'static str {
+        self.pick("○", "\u{f10c}", "o")
+    }
+
+    /// Marks a paused build configuration
+    pub fn paused(self) -> &'static str {
+        self.pick("⏸️", "\u{f04c}", "P")
+    }
+
+    pub fn arrow_up(self) -> &'static str {
+        self.pick("▲", "\u{f0d8}", "^")
+    }
+
+    pub fn arrow_down(self) -> &'static str {
+        self.pick("▼", "\u{f0d7}", "v")
+    }
+
+    pub fn arrow_right(self) -> &'static str {
+        self.pick("▶", "\u{f0da}", ">")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build(state: &str, status: Option<&str>) -> Build {
+        Build {
+            state: Some(state.to_string()),
+            status: status.map(str::to_string),
+            ..Build::default()
+        }
+    }
+
+    #[test]
+    fn build_status_icons_follow_state_and_status() {
+        let success = build("finished", Some("SUCCESS"));
+        let failure = build("finished", Some("FAILURE"));
+        let running = build("running", Some("SUCCESS"));
+        let queued = build("queued", None);
+
+        assert_eq!(IconSet::Emoji.build_status(&success, 0), "✅");
+        assert_eq!(IconSet::Emoji.build_status(&failure, 0), "❌");
+        assert_eq!(IconSet::Emoji.build_status(&queued, 0), "⏳");
+        assert_ne!(
+            IconSet::Emoji.build_status(&running, 0),
+            IconSet::Emoji.build_status(&running, 1)
+        );
+
+        assert_eq!(IconSet::Ascii.build_status(&success, 0), "+");
+        assert_eq!(IconSet::Ascii.build_status(&failure, 0), "x");
+        assert_eq!(IconSet::Ascii.build_status(&running, 5), "/");
+        assert_eq!(IconSet::Ascii.build_status(&queued, 0), ".");
+
+        assert_eq!(IconSet::NerdFont.build_status(&success, 0), "\u{f00c}");
+        assert_eq!(IconSet::NerdFont.build_status(&failure, 0), "\u{f00d}");
+        assert_eq!(
+            IconSet::NerdFont.build_status(&running, 3),
+            IconSet::Emoji.build_status(&running, 3)
+        );
+    }
+}
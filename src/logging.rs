@@ -1,12 +1,61 @@
+use std::collections::VecDeque;
+use std::io;
+use std::sync::{Arc, Mutex};
+
 use color_eyre::Result;
 use tracing_error::ErrorLayer;
-use tracing_subscriber::{EnvFilter, fmt, prelude::*};
+use tracing_subscriber::{EnvFilter, fmt, fmt::MakeWriter, prelude::*};
 
 use crate::config;
 
+// lines kept for the in-app log panel
+const MAX_LOG_LINES: usize = 1000;
+
 lazy_static::lazy_static! {
     pub static ref LOG_ENV: String = format!("{}_LOG_LEVEL", config::PROJECT_NAME.clone());
     pub static ref LOG_FILE: String = format!("{}.log", env!("CARGO_PKG_NAME"));
+    pub static ref LOG_LINES: LogBuffer = LogBuffer::default();
+}
+
+/// Most recent log lines, oldest first, written by the same subscriber as the log file
+#[derive(Clone, Default)]
+pub struct LogBuffer {
+    lines: Arc<Mutex<VecDeque<String>>>,
+}
+
+impl LogBuffer {
+    pub fn lines(&self) -> Vec<String> {
+        self.lines
+            .lock()
+            .map(|lines| lines.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+impl io::Write for LogBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if let Ok(mut lines) = self.lines.lock() {
+            for line in String::from_utf8_lossy(buf).lines() {
+                if lines.len() == MAX_LOG_LINES {
+                    lines.pop_front();
+                }
+                lines.push_back(line.to_string());
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> MakeWriter<'a> for LogBuffer {
+    type Writer = LogBuffer;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.clone()
+    }
 }
 
 pub fn init() -> Result<()> {
@@ -14,23 +63,32 @@ pub fn init() -> Result<()> {
     std::fs::create_dir_all(directory.clone())?;
     let log_path = directory.join(LOG_FILE.clone());
     let log_file = std::fs::File::create(log_path)?;
-    let env_filter = EnvFilter::builder().with_default_directive(tracing::Level::INFO.into());
-    // If the `RUST_LOG` environment variable is set, use that as the default, otherwise use the
-    // value of the `LOG_ENV` environment variable. If the `LOG_ENV` environment variable contains
-    // errors, then this will return an error.
-    let env_filter = env_filter
-        .try_from_env()
-        .or_else(|_| env_filter.with_env_var(LOG_ENV.clone()).from_env())?;
     let file_subscriber = fmt::layer()
         .with_file(true)
         .with_line_number(true)
         .with_writer(log_file)
         .with_target(false)
         .with_ansi(false)
-        .with_filter(env_filter);
+        .with_filter(env_filter()?);
+    let panel_subscriber = fmt::layer()
+        .with_writer(LOG_LINES.clone())
+        .with_target(false)
+        .with_ansi(false)
+        .with_filter(env_filter()?);
     tracing_subscriber::registry()
         .with(file_subscriber)
+        .with(panel_subscriber)
         .with(ErrorLayer::default())
         .try_init()?;
     Ok(())
 }
+
+fn env_filter() -> Result<EnvFilter> {
+    let env_filter = EnvFilter::builder().with_default_directive(tracing::Level::INFO.into());
+    // If the `RUST_LOG` environment variable is set, use that as the default, otherwise use the
+    // value of the `LOG_ENV` environment variable. If the `LOG_ENV` environment variable contains
+    // errors, then this will return an error.
+    Ok(env_filter
+        .try_from_env()
+        .or_else(|_| env_filter.with_env_var(LOG_ENV.clone()).from_env())?)
+}
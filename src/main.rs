@@ -1,8 +1,13 @@
-use crate::app::App;
-use crate::cli::Cli;
-use crate::teamcity::TeamCityClient;
+use crate::action::Action;
+use crate::app::{App, Server};
+use crate::cli::{Cli, Command, ProjectsCommand};
+use crate::config::Config;
 use clap::Parser;
 use color_eyre::Result;
+use color_eyre::eyre::eyre;
+use futures::future::join_all;
+use std::time::Duration;
+use teamcity::{Auth, TeamCityClient};
 
 mod action;
 mod app;
@@ -10,28 +15,78 @@ mod cli;
 mod components;
 mod config;
 mod errors;
+mod export;
+mod icons;
 mod logging;
-mod teamcity;
+mod metrics;
+mod session;
+mod setup;
 mod time;
 mod tui;
 mod utils;
+mod view;
+mod watch;
+
+fn main() -> Result<()> {
+    // the local offset can only be read while the process is single-threaded
+    time::init_local_offset();
+    let result = run();
+    if result.is_err() {
+        // the error is printed once main returns, to a terminal that must be usable again
+        let _ = tui::restore_terminal();
+    }
+    result
+}
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn run() -> Result<()> {
     errors::init()?;
     logging::init()?;
 
     let mut args = Cli::parse();
+    let fresh = args.fresh;
+    let cache_dir = args.cache_dir.clone();
+
+    match &args.command {
+        Some(Command::Completions { shell }) => {
+            return cli::write_completions(*shell, &mut std::io::stdout());
+        }
+        Some(Command::Man { dir }) => {
+            return cli::write_man_pages(dir.as_deref(), &mut std::io::stdout());
+        }
+        _ => {}
+    }
+
+    if let Some(Command::Projects {
+        action: ProjectsCommand::Edit,
+    }) = args.command
+    {
+        let edited = Cli::edit_projects().await?;
+        match edited.projects {
+            Some(projects) => println!("Monitoring projects: {}", projects.join(", ")),
+            None => println!("No projects are monitored, they are picked on startup"),
+        }
+        return Ok(());
+    }
+
+    if let Some(Command::Cache { action }) = &args.command {
+        let config = Config::new()?;
+        let dir = cache_dir.unwrap_or_else(|| config.cache.dir());
+        return action.run(&dir, config.cache.max_size()).await;
+    }
 
     // Try to load args from a config file
-    if args.teamcity_url.is_none() || args.token.is_none() {
+    if args.teamcity_url.is_none() || args.auth().is_none() {
         match Cli::load_cli_config() {
             Ok(loaded) => {
                 if args.teamcity_url.is_none() {
                     args.teamcity_url = loaded.teamcity_url;
                 }
-                if args.token.is_none() {
+                if args.auth().is_none() {
                     args.token = loaded.token;
+                    args.username = loaded.username;
+                    args.password = loaded.password;
+                    args.guest = loaded.guest;
                 }
                 if args.projects.is_none() {
                     args.projects = loaded.projects;
@@ -40,21 +95,100 @@ async fn main() -> Result<()> {
             Err(e) => {
                 // Consider that config file is missing and this is the first time the app is run
                 eprintln!("Warning: failed to load config: {e}");
-                args = Cli::init_config(&args.projects)?;
+                args = Cli::init_config(&args.projects).await?;
             }
         }
     }
 
-    let teamcity_url = args.teamcity_url.expect("Something went wrong and teamcity_url parameter wasn't set");
-    let token = args.token.expect("Somethings went wrong and token parameter wasn't set");
+    let auth = args
+        .auth()
+        .expect("Something went wrong and no credentials were set");
+    let teamcity_url = args
+        .teamcity_url
+        .expect("Something went wrong and teamcity_url parameter wasn't set");
     let projects = args.projects.unwrap_or_default();
 
-    let client = TeamCityClient::new(teamcity_url, token);
+    let mut config = Config::new()?;
+    config.login_username = args.username.clone();
+    let session = if fresh {
+        None
+    } else {
+        session::load(&config.config.data_dir)
+    };
+    if let Some(session) = &session {
+        config.active_filter = session.active_filter.clone();
+    }
+    if let Some(dir) = cache_dir {
+        config.cache.dir = Some(dir);
+    }
+    let client = connect(teamcity_url, auth, &config)?;
+    client.check_connection().await?;
+    let client = client.probe_server().await;
+
+    // the other servers aren't checked, an unreachable one only fails to list its projects
+    let mut clients = Vec::new();
+    for server in &config.servers {
+        let auth = server
+            .auth()
+            .ok_or_else(|| eyre!("No credentials were set for server {}", server.name))?;
+        clients.push(connect(server.teamcity_url.clone(), auth, &config)?);
+    }
+    let clients = join_all(clients.into_iter().map(|client| client.probe_server())).await;
+    let servers: Vec<Server> = config
+        .servers
+        .iter()
+        .zip(clients)
+        .map(|(server, client)| Server {
+            name: server.name.clone(),
+            client,
+            projects: server.projects.clone(),
+        })
+        .collect();
+
+    if let Some(Command::Builds {
+        locator,
+        json,
+        tui: false,
+    }) = &args.command
+    {
+        return cli::print_builds(&client, locator, *json).await;
+    }
 
-    println!("Fetching build configurations from TeamCity...");
-    let build_types = client.get_build_configurations_by_projects(&projects).await?;
+    if let Some(Command::ExportMetrics { listen, interval }) = args.command {
+        return metrics::serve(client, projects, listen, Duration::from_secs(interval)).await;
+    }
+
+    if let Some(Command::Watch {
+        build_types,
+        json,
+        interval,
+    }) = args.command
+    {
+        return watch::run(
+            client,
+            projects,
+            build_types,
+            Duration::from_secs(interval),
+            json,
+        )
+        .await;
+    }
 
-    let mut app = App::new(client, build_types)?;
+    let mut app = App::new(config, client, projects, servers, session)?;
+    if let Some(Command::Builds { locator, .. }) = args.command {
+        app = app.open(Action::LoadLocatorBuilds { locator });
+    }
     app.run().await?;
     Ok(())
 }
+
+/// Client of the server at `teamcity_url` with the cache and request settings of `config`
+fn connect(teamcity_url: String, auth: Auth, config: &Config) -> Result<TeamCityClient> {
+    Ok(TeamCityClient::new(teamcity_url, auth, &config.http)?
+        .cache_dir(&config.cache.dir())
+        .cache_max_size(config.cache.max_size().unwrap_or(u64::MAX))
+        .default_ttl(config.cache.default_ttl())
+        .project_ttls(config.cache.project_ttls())
+        .max_concurrent_requests(config.max_concurrent_requests)
+        .builds(config.builds.clone()))
+}
@@ -0,0 +1,373 @@
+//! Headless `export-metrics` mode: the state of the monitored configurations, the build queue and
+//! the agents as Prometheus metrics on a local port
+
+use std::fmt::Write as _;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use color_eyre::Result;
+use teamcity::TeamCityClient;
+use teamcity::types::{Agent, Build, BuildType};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::watch;
+use tracing::warn;
+
+use crate::view::builds::is_running;
+use crate::view::server_info::pool_summaries;
+
+const METRICS_PATH: &str = "/metrics";
+// version of the Prometheus text exposition format
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+// request heads are a few hundred bytes, anything longer isn't a scrape
+const MAX_REQUEST_HEAD: usize = 8192;
+
+/// Serves the metrics on `listen` until the process is stopped, polling the server every
+/// `interval`. Scrapes get the result of the last poll, so they never wait on TeamCity.
+pub async fn serve(
+    client: TeamCityClient,
+    projects: Vec<String>,
+    listen: SocketAddr,
+    interval: Duration,
+) -> Result<()> {
+    let listener = TcpListener::bind(listen).await?;
+    println!(
+        "Serving metrics on http://{}{}, polling {} every {}s",
+        listener.local_addr()?,
+        METRICS_PATH,
+        client.server_name(),
+        interval.as_secs()
+    );
+
+    let (tx, rx) = watch::channel(poll(&client, &projects).await);
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(interval).await;
+            if tx.send(poll(&client, &projects).await).is_err() {
+                break;
+            }
+        }
+    });
+
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let metrics = rx.borrow().clone();
+        tokio::spawn(respond(stream, metrics));
+    }
+}
+
+/// Metrics of the configurations of `projects`, with `teamcity_up` 0 if the server couldn't be
+/// read
+async fn poll(client: &TeamCityClient, projects: &[String]) -> String {
+    let build_types = if projects.is_empty() {
+        Vec::new()
+    } else {
+        match client.get_build_configurations_by_projects(projects).await {
+            Ok(loaded) => {
+                if let Some(summary) = loaded.failure_summary(projects.len()) {
+                    warn!("{}", summary);
+                }
+                loaded.build_types
+            }
+            Err(e) => {
+                warn!("Failed to fetch build configurations: {}", e);
+                return render_down();
+            }
+        }
+    };
+    let ids: Vec<String> = build_types.iter().map(|bt| bt.id.clone()).collect();
+    let overview = client.get_server_overview().await;
+    let latest = client.get_latest_started_builds(&ids).await;
+    match (overview, latest) {
+        (Ok(overview), Ok(latest)) => render(
+            &build_types,
+            &latest,
+            &overview.agents,
+            overview.queue_length,
+        ),
+        (Err(e), _) | (_, Err(e)) => {
+            warn!("Failed to poll the server for metrics: {}", e);
+            render_down()
+        }
+    }
+}
+
+/// Metrics in the Prometheus text format: the latest build of each of `build_types` in `latest`,
+/// the length of the build queue and the agents of each pool by state
+pub fn render(
+    build_types: &[BuildType],
+    latest: &[Build],
+    agents: &[Agent],
+    queue_length: u32,
+) -> String {
+    let mut text = String::new();
+    gauge(
+        &mut text,
+        "teamcity_up",
+        "Whether the last poll of the TeamCity server succeeded",
+    );
+    text.push_str("teamcity_up 1\n");
+
+    let builds: Vec<(&BuildType, &Build)> = build_types
+        .iter()
+        .filter_map(|bt| {
+            let build = latest
+                .iter()
+                .find(|build| build.build_type_id.as_deref() == Some(bt.id.as_str()))?;
+            Some((bt, build))
+        })
+        .collect();
+    gauge(
+        &mut text,
+        "teamcity_build_success",
+        "Whether the latest build of the configuration succeeded so far",
+    );
+    for (bt, build) in &builds {
+        let success = build.status.as_deref() == Some("SUCCESS");
+        sample(&mut text, "teamcity_build_success", bt, success as u8);
+    }
+    gauge(
+        &mut text,
+        "teamcity_build_running",
+        "Whether the latest build of the configuration is running",
+    );
+    for (bt, build) in &builds {
+        sample(
+            &mut text,
+            "teamcity_build_running",
+            bt,
+            is_running(build) as u8,
+        );
+    }
+    gauge(
+        &mut text,
+        "teamcity_build_finish_timestamp_seconds",
+        "Time the latest finished build of the configuration finished at",
+    );
+    for (bt, build) in &builds {
+        if let Some(finish) = build.finish_date {
+            sample(
+                &mut text,
+                "teamcity_build_finish_timestamp_seconds",
+                bt,
+                finish.unix_timestamp(),
+            );
+        }
+    }
+
+    gauge(
+        &mut text,
+        "teamcity_build_queue_length",
+        "Builds waiting in the build queue",
+    );
+    let _ = writeln!(text, "teamcity_build_queue_length {}", queue_length);
+
+    gauge(
+        &mut text,
+        "teamcity_agents",
+        "Build agents by pool and state",
+    );
+    for pool in pool_summaries(agents) {
+        let disconnected = pool.agents - pool.connected - pool.disabled - pool.unauthorized;
+        for (state, count) in [
+            ("idle", pool.idle()),
+            ("busy", pool.busy),
+            ("disconnected", disconnected),
+            ("disabled", pool.disabled),
+            ("unauthorized", pool.unauthorized),
+        ] {
+            let _ = writeln!(
+                text,
+                "teamcity_agents{{pool=\"{}\",state=\"{}\"}} {}",
+                escape_label(&pool.name),
+                state,
+                count
+            );
+        }
+    }
+    text
+}
+
+/// Metrics of a poll that failed, the previous values are stale rather than zero
+pub fn render_down() -> String {
+    let mut text = String::new();
+    gauge(
+        &mut text,
+        "teamcity_up",
+        "Whether the last poll of the TeamCity server succeeded",
+    );
+    text.push_str("teamcity_up 0\n");
+    text
+}
+
+fn gauge(text: &mut String, name: &str, help: &str) {
+    let _ = writeln!(text, "# HELP {} {}\n# TYPE {} gauge", name, help, name);
+}
+
+fn sample(text: &mut String, name: &str, build_type: &BuildType, value: impl std::fmt::Display) {
+    let _ = writeln!(
+        text,
+        "{}{{build_type=\"{}\",name=\"{}\",project=\"{}\"}} {}",
+        name,
+        escape_label(&build_type.id),
+        escape_label(&build_type.name),
+        escape_label(build_type.project_name.as_deref().unwrap_or_default()),
+        value
+    );
+}
+
+// label values escape backslashes, double quotes and line breaks
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Answers a request on `stream` with `metrics` if it asks for them, 404 otherwise
+async fn respond(mut stream: TcpStream, metrics: String) {
+    let mut head = Vec::new();
+    let mut chunk = [0; 1024];
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") && head.len() < MAX_REQUEST_HEAD {
+        match stream.read(&mut chunk).await {
+            Ok(0) | Err(_) => return,
+            Ok(read) => head.extend_from_slice(&chunk[..read]),
+        }
+    }
+    let head = String::from_utf8_lossy(&head);
+    let mut request_line = head.lines().next().unwrap_or_default().split_whitespace();
+    let (method, target) = (request_line.next(), request_line.next().unwrap_or_default());
+    let path = target.split('?').next().unwrap_or_default();
+
+    let (status, body) = match (method, path) {
+        (Some("GET"), METRICS_PATH) => ("200 OK", metrics),
+        (Some("GET"), _) => (
+            "404 Not Found",
+            format!("Metrics are at {}\n", METRICS_PATH),
+        ),
+        _ => ("405 Method Not Allowed", String::new()),
+    };
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        CONTENT_TYPE,
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use teamcity::types::EntityRef;
+    use time::macros::datetime;
+
+    fn build_type(id: &str, name: &str) -> BuildType {
+        BuildType {
+            id: id.to_string(),
+            name: name.to_string(),
+            project_name: Some("Web \"App\"".to_string()),
+            ..BuildType::default()
+        }
+    }
+
+    fn build(build_type_id: &str, status: &str, state: &str) -> Build {
+        Build {
+            build_type_id: Some(build_type_id.to_string()),
+            status: Some(status.to_string()),
+            state: Some(state.to_string()),
+            ..Build::default()
+        }
+    }
+
+    #[test]
+    fn latest_builds_are_reported_by_configuration() {
+        let build_types = [
+            build_type("Web_Build", "Build"),
+            build_type("Web_Deploy", "Deploy"),
+            build_type("Web_Docs", "Docs"),
+        ];
+        let latest = [
+            Build {
+                finish_date: Some(datetime!(2025-01-31 09:05:12 UTC)),
+                ..build("Web_Build", "SUCCESS", "finished")
+            },
+            build("Web_Deploy", "FAILURE", "running"),
+        ];
+
+        let text = render(&build_types, &latest, &[], 3);
+
+        let labels = |id: &str, name: &str| {
+            format!(
+                "{{build_type=\"{}\",name=\"{}\",project=\"Web \\\"App\\\"\"}}",
+                id, name
+            )
+        };
+        let lines: Vec<&str> = text.lines().collect();
+        assert!(lines.contains(&"teamcity_up 1"));
+        assert!(lines.contains(
+            &format!("teamcity_build_success{} 1", labels("Web_Build", "Build")).as_str()
+        ));
+        assert!(lines.contains(
+            &format!("teamcity_build_success{} 0", labels("Web_Deploy", "Deploy")).as_str()
+        ));
+        assert!(lines.contains(
+            &format!("teamcity_build_running{} 1", labels("Web_Deploy", "Deploy")).as_str()
+        ));
+        assert!(
+            lines.contains(
+                &format!(
+                    "teamcity_build_finish_timestamp_seconds{} 1738314312",
+                    labels("Web_Build", "Build")
+                )
+                .as_str()
+            )
+        );
+        // a configuration without builds has no samples rather than misleading zeros
+        assert!(!text.contains("Web_Docs"));
+        assert!(lines.contains(&"teamcity_build_queue_length 3"));
+        assert!(lines.contains(&"# TYPE teamcity_build_queue_length gauge"));
+    }
+
+    #[test]
+    fn agents_are_counted_by_pool_and_state() {
+        let agent = |connected: bool, busy: bool| Agent {
+            connected: Some(connected),
+            enabled: Some(true),
+            authorized: Some(true),
+            build: busy.then(Build::default),
+            pool: Some(EntityRef {
+                id: None,
+                name: Some("Linux".to_string()),
+            }),
+            ..Agent::default()
+        };
+        let agents = [agent(true, true), agent(true, false), agent(false, false)];
+
+        let text = render(&[], &[], &agents, 0);
+
+        for state in ["idle", "busy", "disconnected"] {
+            assert!(
+                text.contains(&format!(
+                    "teamcity_agents{{pool=\"Linux\",state=\"{}\"}} 1\n",
+                    state
+                )),
+                "{}",
+                text
+            );
+        }
+        assert!(text.contains("teamcity_agents{pool=\"Linux\",state=\"disabled\"} 0\n"));
+    }
+
+    #[test]
+    fn failed_polls_only_report_the_server_down() {
+        assert_eq!(
+            render_down(),
+            "# HELP teamcity_up Whether the last poll of the TeamCity server succeeded\n\
+             # TYPE teamcity_up gauge\n\
+             teamcity_up 0\n"
+        );
+    }
+}
@@ -0,0 +1,109 @@
+use std::path::Path;
+
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+const SESSION_FILE: &str = "session.json";
+const RECENT_FILE: &str = "recent.json";
+
+/// Configurations kept in the recently viewed list
+pub const MAX_RECENT: usize = 20;
+
+/// View the app was showing when it quit, reopened on the next launch
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Session {
+    /// Configuration whose builds were shown, None for the Projects view
+    #[serde(default)]
+    pub build_type_id: Option<String>,
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Build selected in the Builds view
+    #[serde(default)]
+    pub selected_build: Option<i64>,
+    /// Name of the saved filter that was applied
+    #[serde(default)]
+    pub active_filter: Option<String>,
+}
+
+/// Session saved in `data_dir` by the previous run, None if there is none or it can't be read
+pub fn load(data_dir: &Path) -> Option<Session> {
+    let content = std::fs::read_to_string(data_dir.join(SESSION_FILE)).ok()?;
+    match serde_json::from_str(&content) {
+        Ok(session) => Some(session),
+        Err(e) => {
+            warn!("Ignoring the saved session: {}", e);
+            None
+        }
+    }
+}
+
+pub fn save(data_dir: &Path, session: &Session) -> Result<()> {
+    std::fs::create_dir_all(data_dir)?;
+    std::fs::write(
+        data_dir.join(SESSION_FILE),
+        serde_json::to_string_pretty(session)?,
+    )?;
+    Ok(())
+}
+
+/// IDs of the configurations opened in `data_dir` by the previous runs, most recent first
+pub fn load_recent(data_dir: &Path) -> Vec<String> {
+    let Ok(content) = std::fs::read_to_string(data_dir.join(RECENT_FILE)) else {
+        return Vec::new();
+    };
+    match serde_json::from_str(&content) {
+        Ok(recent) => recent,
+        Err(e) => {
+            warn!("Ignoring the recently viewed configurations: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+pub fn save_recent(data_dir: &Path, recent: &[String]) -> Result<()> {
+    std::fs::create_dir_all(data_dir)?;
+    std::fs::write(
+        data_dir.join(RECENT_FILE),
+        serde_json::to_string_pretty(recent)?,
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saved_session_is_loaded_back() {
+        let dir = std::env::temp_dir().join(format!("t9s-session-{}", std::process::id()));
+        assert_eq!(load(&dir), None);
+
+        let session = Session {
+            build_type_id: Some("App_Build".to_string()),
+            title: Some("Build".to_string()),
+            selected_build: Some(42),
+            active_filter: Some("Failed on main".to_string()),
+        };
+        save(&dir, &session).unwrap();
+        assert_eq!(load(&dir), Some(session));
+
+        std::fs::write(dir.join(SESSION_FILE), "not json").unwrap();
+        assert_eq!(load(&dir), None);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn recent_configurations_are_loaded_back() {
+        let dir = std::env::temp_dir().join(format!("t9s-recent-{}", std::process::id()));
+        assert!(load_recent(&dir).is_empty());
+
+        let recent = ["Web_Build".to_string(), "Api_Test".to_string()];
+        save_recent(&dir, &recent).unwrap();
+        assert_eq!(load_recent(&dir), recent);
+
+        std::fs::write(dir.join(RECENT_FILE), "{}").unwrap();
+        assert!(load_recent(&dir).is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
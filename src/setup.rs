@@ -0,0 +1,78 @@
+use color_eyre::Result;
+use color_eyre::eyre::eyre;
+use tokio::sync::mpsc;
+
+use crate::action::Action;
+use crate::cli::Cli;
+use crate::components::Component;
+use crate::components::setup_wizard::SetupWizard;
+use crate::config::Config;
+use crate::tui::{Event, Tui};
+use teamcity::TeamCityClient;
+
+/// Runs the setup wizard in the terminal until the configuration is entered, or fails if the user
+/// quits it
+pub async fn run(mut wizard: SetupWizard) -> Result<Cli> {
+    // the config file may not exist yet either, the defaults do for connecting
+    let http = Config::new().map(|config| config.http).unwrap_or_default();
+    let (action_tx, mut action_rx) = mpsc::unbounded_channel();
+    if wizard.is_connecting() {
+        action_tx.send(Action::SetupConnect)?;
+    }
+
+    let mut tui = Tui::new()?.tick_rate(4.0).frame_rate(30.0);
+    tui.enter()?;
+
+    let result = loop {
+        if let Some(event) = tui.next_event().await {
+            match event {
+                Event::Quit => action_tx.send(Action::Quit)?,
+                Event::Render | Event::Resize(_, _) => action_tx.send(Action::Render)?,
+                _ => {}
+            }
+            if let Some(action) = wizard.handle_events(Some(event))? {
+                action_tx.send(action)?;
+            }
+        }
+
+        let mut finished = None;
+        while let Ok(action) = action_rx.try_recv() {
+            match action {
+                Action::Quit => finished = Some(Err(eyre!("Setup was canceled"))),
+                Action::SetupFinished => finished = Some(Ok(wizard.cli())),
+                Action::SetupConnect => {
+                    let tx = action_tx.clone();
+                    let client = TeamCityClient::new(wizard.url(), wizard.auth(), &http);
+                    tokio::spawn(async move {
+                        let projects = match client {
+                            Ok(client) => match client.check_connection().await {
+                                Ok(()) => client.get_projects().await,
+                                Err(e) => Err(e),
+                            },
+                            Err(e) => Err(e),
+                        };
+                        let _ = match projects {
+                            Ok(projects) => tx.send(Action::SetupProjectsFetched { projects }),
+                            Err(e) => tx.send(Action::Error(format!("Failed to connect: {}", e))),
+                        };
+                    });
+                }
+                Action::Render => {
+                    tui.draw(|frame| {
+                        let _ = wizard.draw(frame, frame.area());
+                    })?;
+                }
+                _ => {}
+            }
+            if let Some(action) = wizard.update(action)? {
+                action_tx.send(action)?;
+            }
+        }
+        if let Some(result) = finished {
+            break result;
+        }
+    };
+
+    tui.exit()?;
+    result
+}
@@ -1,30 +1,143 @@
-use color_eyre::eyre::anyhow;
+use std::sync::OnceLock;
+
 use color_eyre::Result;
-use time::OffsetDateTime;
-use time::format_description::{BorrowedFormatItem, parse as parse_fmt, FormatItem};
+use color_eyre::eyre::anyhow;
+use serde::Deserialize;
+use time::format_description::FormatItem;
 use time::macros::format_description;
+use time::{OffsetDateTime, UtcOffset};
 
-const TEAMCITY_DATETIME_FORMAT: &[FormatItem<'static>] = format_description!("[year][month][day]T[hour][minute][second][optional [.[subsecond]]][offset_hour sign:mandatory][offset_minute]");
-const HUMAN_READABLE_DATE_FORMAT: &[FormatItem<'static>] = format_description!("[day] [month repr:short] [hour repr:24]:[minute]");
-const DURATION_TIME_FORMAT: &[FormatItem<'static>] = format_description!("[hour]:[minute]:[second]");
+const HUMAN_READABLE_DATE_FORMAT: &[FormatItem<'static>] =
+    format_description!("[day] [month repr:short] [hour repr:24]:[minute]");
+const DURATION_TIME_FORMAT: &[FormatItem<'static>] =
+    format_description!("[hour]:[minute]:[second]");
 
-pub fn format_datetime_to_human_readable_string(date: &str) -> Result<String> {
-    let datetime = OffsetDateTime::parse(date, &TEAMCITY_DATETIME_FORMAT)?;
+static LOCAL_OFFSET: OnceLock<UtcOffset> = OnceLock::new();
 
-    datetime.format(&HUMAN_READABLE_DATE_FORMAT)
-        .map_err(|e| anyhow!(e))
+/// How build times are shown in tables
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeDisplay {
+    /// Date and time in the local time zone, e.g. "05 Mar 14:30"
+    #[default]
+    Absolute,
+    /// Time elapsed since, e.g. "2h 15m ago"
+    Relative,
+}
+
+/// Determines the local UTC offset, falling back to UTC if it cannot be determined.
+///
+/// Must be called before any other thread is spawned, the offset can't be read safely afterwards.
+pub fn init_local_offset() {
+    let offset = UtcOffset::current_local_offset().unwrap_or(UtcOffset::UTC);
+    let _ = LOCAL_OFFSET.set(offset);
+}
+
+pub fn local_offset() -> UtcOffset {
+    LOCAL_OFFSET.get().copied().unwrap_or(UtcOffset::UTC)
 }
 
-// Parse TeamCity datetime like "YYYYMMDDTHHMMSS+0000" (optionally with fractional seconds) into unix seconds
-pub fn parse_tc_datetime_to_epoch(s: &str) -> Result<i64> {
-    OffsetDateTime::parse(s, &TEAMCITY_DATETIME_FORMAT)
-        .map(|dt| dt.unix_timestamp())
+pub fn format_datetime_to_human_readable_string(datetime: &OffsetDateTime) -> Result<String> {
+    datetime
+        .to_offset(local_offset())
+        .format(&HUMAN_READABLE_DATE_FORMAT)
         .map_err(|e| anyhow!(e))
 }
 
+/// Time elapsed between `datetime` and `now`, e.g. "3m ago" or "2h 15m ago"
+pub fn format_relative(datetime: &OffsetDateTime, now: &OffsetDateTime) -> String {
+    let secs = (*now - *datetime).whole_seconds();
+    if secs < 0 {
+        return "just now".to_string();
+    }
+    format!("{} ago", format_span(secs))
+}
+
+/// Time a running build has left, e.g. "ETA 4m" or "ETA 1h 5m"
+pub fn format_remaining(secs: i64) -> String {
+    format!("ETA {}", format_span(secs.max(0)))
+}
+
+/// The two largest units of `secs`, e.g. "42s", "3m" or "2h 15m"
+fn format_span(secs: i64) -> String {
+    let (days, hours, minutes) = (secs / 86_400, secs % 86_400 / 3_600, secs % 3_600 / 60);
+    match (days, hours, minutes) {
+        (0, 0, 0) => format!("{}s", secs),
+        (0, 0, m) => format!("{}m", m),
+        (0, h, 0) => format!("{}h", h),
+        (0, h, m) => format!("{}h {}m", h, m),
+        (d, 0, _) => format!("{}d", d),
+        (d, h, _) => format!("{}d {}h", d, h),
+    }
+}
+
+/// Formats `datetime` for display according to `display`
+pub fn format_datetime(datetime: &OffsetDateTime, display: TimeDisplay) -> Result<String> {
+    match display {
+        TimeDisplay::Absolute => format_datetime_to_human_readable_string(datetime),
+        TimeDisplay::Relative => Ok(format_relative(datetime, &OffsetDateTime::now_utc())),
+    }
+}
+
 pub fn format_duration(secs: i64) -> Result<String> {
     let datetime = OffsetDateTime::from_unix_timestamp(secs)?;
 
-    datetime.format(&DURATION_TIME_FORMAT)
+    datetime
+        .format(&DURATION_TIME_FORMAT)
         .map_err(|e| anyhow!(e))
-}
\ No newline at end of file
+}
+
+const CLOCK_TIME_FORMAT: &[FormatItem<'static>] = format_description!("[hour repr:24]:[minute]");
+
+pub fn format_clock_time(datetime: &OffsetDateTime) -> Result<String> {
+    datetime
+        .to_offset(local_offset())
+        .format(&CLOCK_TIME_FORMAT)
+        .map_err(|e| anyhow!(e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::Duration;
+    use time::macros::datetime;
+
+    #[test]
+    fn relative_time_uses_the_two_largest_units() {
+        let now = datetime!(2024-03-05 14:30:00 UTC);
+        let ago = |d: Duration| format_relative(&(now - d), &now);
+
+        assert_eq!(ago(Duration::seconds(42)), "42s ago");
+        assert_eq!(ago(Duration::minutes(3)), "3m ago");
+        assert_eq!(ago(Duration::hours(2)), "2h ago");
+        assert_eq!(ago(Duration::minutes(135)), "2h 15m ago");
+        assert_eq!(ago(Duration::days(3)), "3d ago");
+        assert_eq!(ago(Duration::hours(50)), "2d 2h ago");
+    }
+
+    #[test]
+    fn remaining_time_uses_the_two_largest_units() {
+        assert_eq!(format_remaining(30), "ETA 30s");
+        assert_eq!(format_remaining(245), "ETA 4m");
+        assert_eq!(format_remaining(3_900), "ETA 1h 5m");
+    }
+
+    #[test]
+    fn durations_are_formatted_as_clock_time() {
+        assert_eq!(format_duration(0).unwrap(), "00:00:00");
+        assert_eq!(format_duration(61).unwrap(), "00:01:01");
+        assert_eq!(format_duration(3_725).unwrap(), "01:02:05");
+    }
+
+    #[test]
+    fn relative_time_compares_instants_across_offsets() {
+        let now = datetime!(2024-03-05 14:30:00 UTC);
+        let server_time = datetime!(2024-03-05 16:00:00 +03:00);
+
+        assert_eq!(format_relative(&server_time, &now), "1h 30m ago");
+        assert_eq!(
+            format_relative(&(now + Duration::minutes(1)), &now),
+            "just now"
+        );
+    }
+}
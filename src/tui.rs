@@ -14,8 +14,7 @@ use futures::{FutureExt, StreamExt};
 use ratatui::backend::CrosstermBackend as Backend;
 use serde::{Deserialize, Serialize};
 use std::env;
-use std::ffi::OsStr;
-use std::path::PathBuf;
+use std::path::Path;
 use std::process::{Command, Stdio};
 use std::{
     io::{Stdout, Write, stdout},
@@ -108,7 +107,7 @@ impl Tui {
         });
     }
 
-    pub fn run_fzf(&mut self, options: &Vec<String>) -> Result<String> {
+    pub fn run_fzf(&mut self, options: &[String]) -> Result<String> {
         self.exit()?;
 
         let mut child = Command::new("fzf")
@@ -118,7 +117,7 @@ impl Tui {
 
         if let Some(stdin) = child.stdin.as_mut() {
             let fzf_stdin = options.join("\n");
-            stdin.write(fzf_stdin.as_bytes())?;
+            stdin.write_all(fzf_stdin.as_bytes())?;
         }
 
         // Collect fzf's output
@@ -138,7 +137,7 @@ impl Tui {
         selected_line
     }
 
-    pub fn run_pager(&mut self, file: &PathBuf) -> Result<()> {
+    pub fn run_pager(&mut self, file: &Path) -> Result<()> {
         self.exit()?;
 
         let pager = env::var("PAGER").unwrap_or("less".to_string());
@@ -161,6 +160,31 @@ impl Tui {
         Ok(())
     }
 
+    /// Opens `file` in `$VISUAL` or `$EDITOR`, `vi` if neither is set
+    pub fn run_editor(&mut self, file: &Path) -> Result<()> {
+        self.exit()?;
+
+        let editor = env::var("VISUAL")
+            .or_else(|_| env::var("EDITOR"))
+            .unwrap_or("vi".to_string());
+        // the editor may come with arguments, like `code --wait`
+        let mut words = editor.split_whitespace();
+        let program = words.next().unwrap_or("vi");
+        let status = Command::new(program).args(words).arg(file).status();
+
+        self.terminal.clear()?;
+        self.enter()?;
+
+        let status = status.map_err(|e| eyre!("Failed to run {}: {}", program, e))?;
+        if !status.success() {
+            return Err(eyre!(
+                "Editor failed with status code {}",
+                status.code().unwrap_or_default()
+            ));
+        }
+        Ok(())
+    }
+
     async fn event_loop(
         event_tx: UnboundedSender<Event>,
         cancellation_token: CancellationToken,
@@ -287,6 +311,25 @@ impl DerefMut for Tui {
 
 impl Drop for Tui {
     fn drop(&mut self) {
-        self.exit().unwrap();
+        // a panic while unwinding would abort before the panic hook restores the terminal
+        if let Err(e) = self.exit() {
+            error!("Unable to exit the terminal: {:?}", e);
+        }
+    }
+}
+
+/// Leaves raw mode and the alternate screen whatever state the terminal was left in, for the panic
+/// hook and errors escaping `main` where no [`Tui`] is at hand
+pub fn restore_terminal() -> Result<()> {
+    if crossterm::terminal::is_raw_mode_enabled()? {
+        crossterm::execute!(
+            stdout(),
+            DisableBracketedPaste,
+            DisableMouseCapture,
+            LeaveAlternateScreen,
+            cursor::Show
+        )?;
+        crossterm::terminal::disable_raw_mode()?;
     }
+    Ok(())
 }
@@ -1,6 +1,34 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
 #[derive(Default, PartialEq, Clone, Debug)]
 pub enum InputMode {
     #[default]
     Normal,
     Editing,
+    // typing the path to export the table to
+    Exporting,
+}
+
+/// `Ctrl-^`, which most terminals report as `Ctrl-6`
+pub fn is_alternate_key(key: &KeyEvent) -> bool {
+    key.modifiers.contains(KeyModifiers::CONTROL)
+        && matches!(key.code, KeyCode::Char('^') | KeyCode::Char('6'))
+}
+
+/// Formats a byte count with a binary unit, e.g. `1.5 MiB`
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
 }
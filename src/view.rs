@@ -0,0 +1,23 @@
+//! Pure logic behind the tables of the components: filtering, sorting, grouping and formatting of
+//! rows. Nothing here touches the terminal, so the behavior of the views can be unit tested.
+
+pub mod agent_pools;
+pub mod agent_requirements;
+pub mod build_log;
+pub mod builds;
+pub mod change_builds;
+pub mod command;
+pub mod custom_actions;
+pub mod date_range;
+pub mod favorites;
+pub mod filter_query;
+pub mod log_grep;
+pub mod notification;
+pub mod parameters;
+pub mod projects;
+pub mod queue_watchdog;
+pub mod server_info;
+pub mod setup;
+pub mod test_failures;
+pub mod test_history;
+pub mod versioned_settings;
@@ -0,0 +1,119 @@
+use teamcity::types::{Agent, AgentPool};
+
+/// Row of the Agent pools table
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PoolRow {
+    // index of the pool
+    Pool(usize),
+    // indices of the pool and of the agent in it
+    Agent(usize, usize),
+}
+
+impl PoolRow {
+    pub fn pool(self) -> usize {
+        match self {
+            PoolRow::Pool(pool) | PoolRow::Agent(pool, _) => pool,
+        }
+    }
+}
+
+pub fn pool_name(pool: &AgentPool) -> &str {
+    pool.name.as_deref().unwrap_or_default()
+}
+
+pub fn pool_agents(pool: &AgentPool) -> &[Agent] {
+    pool.agents.as_ref().map_or(&[], |agents| &agents.agent)
+}
+
+/// Names of the projects assigned to `pool`, the root project standing for all of them
+pub fn pool_projects(pool: &AgentPool) -> Vec<&str> {
+    let projects = pool.projects.as_ref().map_or(&[][..], |p| &p.project);
+    if projects.iter().any(|project| project.id == "_Root") {
+        return vec!["all projects"];
+    }
+    projects
+        .iter()
+        .map(|project| project.name.as_str())
+        .collect()
+}
+
+/// Each pool followed by its agents ordered by name
+pub fn pool_rows(pools: &[AgentPool]) -> Vec<PoolRow> {
+    let mut rows = Vec::new();
+    for (i, pool) in pools.iter().enumerate() {
+        rows.push(PoolRow::Pool(i));
+        let mut agents: Vec<(usize, &Agent)> = pool_agents(pool).iter().enumerate().collect();
+        agents.sort_by(|(_, a), (_, b)| a.name.cmp(&b.name));
+        rows.extend(agents.into_iter().map(|(j, _)| PoolRow::Agent(i, j)));
+    }
+    rows
+}
+
+/// Pools an agent of the pool `from` can be moved to
+pub fn move_targets(pools: &[AgentPool], from: usize) -> Vec<usize> {
+    (0..pools.len())
+        .filter(|i| *i != from && pools[*i].id.is_some())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use teamcity::types::{Agents, Project, Projects};
+
+    fn agent(name: &str) -> Agent {
+        Agent {
+            name: Some(name.to_string()),
+            ..Agent::default()
+        }
+    }
+
+    fn pool(id: i64, name: &str, agents: Vec<Agent>, projects: &[&str]) -> AgentPool {
+        AgentPool {
+            id: Some(id),
+            name: Some(name.to_string()),
+            agents: Some(Agents {
+                count: Some(agents.len() as u32),
+                agent: agents,
+            }),
+            projects: Some(Projects {
+                count: Some(projects.len() as u32),
+                project: projects
+                    .iter()
+                    .map(|id| Project {
+                        id: id.to_string(),
+                        name: id.replace('_', ""),
+                        parent_project_id: None,
+                    })
+                    .collect(),
+            }),
+        }
+    }
+
+    #[test]
+    fn pools_are_followed_by_their_agents_by_name() {
+        let pools = [
+            pool(0, "Default", vec![], &["_Root"]),
+            pool(
+                3,
+                "Linux",
+                vec![agent("linux-2"), agent("linux-1")],
+                &["Web"],
+            ),
+        ];
+
+        assert_eq!(
+            pool_rows(&pools),
+            [
+                PoolRow::Pool(0),
+                PoolRow::Pool(1),
+                PoolRow::Agent(1, 1),
+                PoolRow::Agent(1, 0),
+            ]
+        );
+        assert_eq!(pool_projects(&pools[0]), ["all projects"]);
+        assert_eq!(pool_projects(&pools[1]), ["Web"]);
+        assert_eq!(move_targets(&pools, 1), [0]);
+        assert_eq!(PoolRow::Agent(1, 0).pool(), 1);
+    }
+}
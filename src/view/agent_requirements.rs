@@ -0,0 +1,100 @@
+use teamcity::types::{Agent, AgentRequirement};
+
+/// Condition of an agent requirement as it reads in the TeamCity UI, e.g. "does not equal"
+fn condition(kind: &str) -> String {
+    match kind {
+        "exists" => "exists".to_string(),
+        "not-exists" => "does not exist".to_string(),
+        "more-than" => ">".to_string(),
+        "no-more-than" => "<=".to_string(),
+        "less-than" => "<".to_string(),
+        "no-less-than" => ">=".to_string(),
+        "ver-more-than" => "version >".to_string(),
+        "ver-no-more-than" => "version <=".to_string(),
+        "ver-less-than" => "version <".to_string(),
+        "ver-no-less-than" => "version >=".to_string(),
+        other => other.replace('-', " "),
+    }
+}
+
+/// Requirement as one line, like `teamcity.agent.jvm.os.name equals Linux` or
+/// `docker.server.version exists`
+pub fn describe_requirement(requirement: &AgentRequirement) -> String {
+    let name = requirement
+        .properties
+        .get("property-name")
+        .unwrap_or("(no parameter)");
+    let condition = condition(requirement.kind.as_deref().unwrap_or("exists"));
+    match requirement.properties.get("property-value") {
+        Some(value) => format!("{} {} {}", name, condition, value),
+        None => format!("{} {}", name, condition),
+    }
+}
+
+/// Agents that can pick up a build right now: connected, enabled and authorized
+pub fn available(agents: &[Agent]) -> usize {
+    agents.iter().filter(|agent| is_available(agent)).count()
+}
+
+pub fn is_available(agent: &Agent) -> bool {
+    agent.connected == Some(true) && agent.enabled != Some(false) && agent.authorized != Some(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use teamcity::types::{Properties, Property};
+
+    fn requirement(kind: &str, name: &str, value: Option<&str>) -> AgentRequirement {
+        let mut property = vec![Property {
+            name: "property-name".to_string(),
+            value: Some(name.to_string()),
+        }];
+        if let Some(value) = value {
+            property.push(Property {
+                name: "property-value".to_string(),
+                value: Some(value.to_string()),
+            });
+        }
+        AgentRequirement {
+            kind: Some(kind.to_string()),
+            properties: Properties {
+                count: None,
+                property,
+            },
+            ..AgentRequirement::default()
+        }
+    }
+
+    #[test]
+    fn requirements_read_as_conditions() {
+        assert_eq!(
+            describe_requirement(&requirement(
+                "equals",
+                "teamcity.agent.jvm.os.name",
+                Some("Linux")
+            )),
+            "teamcity.agent.jvm.os.name equals Linux"
+        );
+        assert_eq!(
+            describe_requirement(&requirement("not-exists", "env.CI_LOCK", None)),
+            "env.CI_LOCK does not exist"
+        );
+        assert_eq!(
+            describe_requirement(&requirement(
+                "ver-no-less-than",
+                "env.JDK_VERSION",
+                Some("17")
+            )),
+            "env.JDK_VERSION version >= 17"
+        );
+        assert_eq!(
+            describe_requirement(&requirement(
+                "does-not-contain",
+                "system.agent.name",
+                Some("win")
+            )),
+            "system.agent.name does not contain win"
+        );
+    }
+}
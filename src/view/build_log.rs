@@ -0,0 +1,495 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+use teamcity::types::Build;
+
+lazy_static! {
+    // `[12:34:56]E: message`, the status is blank for regular lines
+    static ref LINE_PREFIX: Regex = Regex::new(r"^\[(\d{2}):(\d{2}):(\d{2})\]([A-Za-z ]):").unwrap();
+    static ref STEP_START: Regex = Regex::new(r"^\s*Step \d+/\d+: (.+)$").unwrap();
+    static ref BLOCK_MESSAGE: Regex =
+        Regex::new(r"##teamcity\[(blockOpened|blockClosed)\s+name='((?:[^'|]|\|.)*)'").unwrap();
+    // `##teamcity[name key='value' ...]`, values escape `'`, `|`, `[`, `]` and line breaks with `|`
+    static ref SERVICE_MESSAGE: Regex =
+        Regex::new(r"##teamcity\[(\w+)((?:\s+[\w.]+='(?:[^'|]|\|.)*')*)\s*\]").unwrap();
+    static ref ATTRIBUTE: Regex = Regex::new(r"([\w.]+)='((?:[^'|]|\|.)*)'").unwrap();
+    static ref ERROR_WORD: Regex = Regex::new(r"(?i)\b(error|exception|fatal)\b").unwrap();
+    static ref WARNING_WORD: Regex = Regex::new(r"(?i)\bwarn(ing)?\b").unwrap();
+}
+
+const SECS_PER_DAY: u32 = 86_400;
+
+/// How a line of a build log is highlighted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineKind {
+    Plain,
+    Error,
+    Warning,
+    // `##teamcity[...]` messages the build script sent to the server
+    ServiceMessage,
+    // start of a build step or an opened/closed block
+    StepBoundary,
+}
+
+/// A build step or block of a log, for the minimap of step durations
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogStep {
+    pub name: String,
+    // index of the line the step starts at
+    pub line: usize,
+    // nesting level, 0 for build steps and top-level blocks
+    pub depth: usize,
+    // None when the log has no timestamps for its start or end
+    pub duration_secs: Option<u32>,
+}
+
+/// What a build script reported through a `message` or `buildStatus` service message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IssueKind {
+    Error,
+    Warning,
+    // status text of a build that didn't fail
+    Status,
+}
+
+/// Error, warning or build status reported in a log, listed under its step in the steps panel
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogIssue {
+    pub kind: IssueKind,
+    pub text: String,
+    // index of the line it was reported at
+    pub line: usize,
+}
+
+/// Row of the steps panel, in the order of the log
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutlineItem<'a> {
+    Step(&'a LogStep),
+    Issue(&'a LogIssue),
+}
+
+/// Status letter and the rest of a line, without the `[HH:MM:SS]X:` prefix of TeamCity logs
+fn split_prefix(line: &str) -> (Option<char>, &str) {
+    match LINE_PREFIX.captures(line) {
+        Some(caps) => {
+            let status = caps[4].chars().next().filter(|c| *c != ' ');
+            (status, &line[caps[0].len()..])
+        }
+        None => (None, line),
+    }
+}
+
+/// Seconds since midnight of the timestamp a log line starts with
+fn timestamp(line: &str) -> Option<u32> {
+    let caps = LINE_PREFIX.captures(line)?;
+    let part = |i: usize| caps[i].parse::<u32>().ok();
+    Some(part(1)? * 3_600 + part(2)? * 60 + part(3)?)
+}
+
+/// Seconds from `start` to `end`, which wrap around at midnight
+fn elapsed(start: Option<u32>, end: Option<u32>) -> Option<u32> {
+    let (start, end) = (start?, end?);
+    Some((end + SECS_PER_DAY - start) % SECS_PER_DAY)
+}
+
+/// Name and unescaped attributes of the service message in `text`
+fn service_message(text: &str) -> Option<(&str, Vec<(&str, String)>)> {
+    let caps = SERVICE_MESSAGE.captures(text)?;
+    let attributes = ATTRIBUTE
+        .captures_iter(caps.get(2).map_or("", |m| m.as_str()))
+        .map(|attribute| {
+            let key = attribute.get(1).map_or("", |m| m.as_str());
+            (key, unescape(&attribute[2]))
+        })
+        .collect();
+    Some((caps.get(1).map_or("", |m| m.as_str()), attributes))
+}
+
+/// Value of a service message attribute without its `|` escapes
+fn unescape(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '|' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some('x') => unescaped.push('\u{0085}'),
+            Some('l') => unescaped.push('\u{2028}'),
+            Some('p') => unescaped.push('\u{2029}'),
+            Some('0') if chars.as_str().starts_with("x") => {
+                // `|0xXXXX`, the long form of a code point
+                let code: String = chars.by_ref().skip(1).take(4).collect();
+                unescaped.extend(u32::from_str_radix(&code, 16).ok().and_then(char::from_u32));
+            }
+            Some(escaped) => unescaped.push(escaped),
+            None => {}
+        }
+    }
+    unescaped
+}
+
+/// Issue of a `message` with an error or warning status, or of a `buildStatus` message
+fn issue(text: &str, line: usize) -> Option<LogIssue> {
+    let (name, attributes) = service_message(text)?;
+    let attribute = |key: &str| {
+        attributes
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, value)| value.as_str())
+    };
+    let status = attribute("status").unwrap_or_default();
+    let kind = match (name, status) {
+        ("message", "ERROR" | "FAILURE") | ("buildStatus", "FAILURE") => IssueKind::Error,
+        ("message", "WARNING") => IssueKind::Warning,
+        ("buildStatus", _) => IssueKind::Status,
+        _ => return None,
+    };
+    let text = attribute("text")?.lines().next().unwrap_or_default().trim();
+    Some(LogIssue {
+        kind,
+        text: text.to_string(),
+        line,
+    })
+}
+
+pub fn line_kind(line: &str) -> LineKind {
+    let (status, text) = split_prefix(line);
+    if BLOCK_MESSAGE.is_match(text) || STEP_START.is_match(text) {
+        return LineKind::StepBoundary;
+    }
+    if text.contains("##teamcity[") {
+        return match issue(text, 0).map(|issue| issue.kind) {
+            Some(IssueKind::Error) => LineKind::Error,
+            Some(IssueKind::Warning) => LineKind::Warning,
+            _ => LineKind::ServiceMessage,
+        };
+    }
+    match status {
+        Some('E') | Some('F') => LineKind::Error,
+        Some('W') => LineKind::Warning,
+        _ if ERROR_WORD.is_match(text) => LineKind::Error,
+        _ if WARNING_WORD.is_match(text) => LineKind::Warning,
+        _ => LineKind::Plain,
+    }
+}
+
+/// Build steps and blocks of a log in order of their start. A build step lasts until the next one
+/// starts, blocks from their blockOpened to their blockClosed message.
+pub fn log_steps(lines: &[String]) -> Vec<LogStep> {
+    let mut steps: Vec<LogStep> = Vec::new();
+    // open blocks as indices into `steps`, with their start time
+    let mut open: Vec<(usize, Option<u32>)> = Vec::new();
+    let mut current_step: Option<(usize, Option<u32>)> = None;
+    let mut last_time = None;
+
+    for (i, line) in lines.iter().enumerate() {
+        let time = timestamp(line).or(last_time);
+        let (_, text) = split_prefix(line);
+
+        if let Some(caps) = STEP_START.captures(text) {
+            if let Some((index, start)) = current_step.take() {
+                steps[index].duration_secs = elapsed(start, time);
+            }
+            open.clear();
+            current_step = Some((steps.len(), time));
+            steps.push(LogStep {
+                name: caps[1].trim().to_string(),
+                line: i,
+                depth: 0,
+                duration_secs: None,
+            });
+        } else if let Some(caps) = BLOCK_MESSAGE.captures(text) {
+            let name = caps[2].to_string();
+            if &caps[1] == "blockOpened" {
+                open.push((steps.len(), time));
+                steps.push(LogStep {
+                    name,
+                    line: i,
+                    depth: open.len() - 1 + usize::from(current_step.is_some()),
+                    duration_secs: None,
+                });
+            } else if let Some(pos) = open
+                .iter()
+                .rposition(|(index, _)| steps[*index].name == name)
+            {
+                // blocks left open inside the closed one are closed with it
+                for (index, start) in open.drain(pos..) {
+                    steps[index].duration_secs = elapsed(start, time);
+                }
+            }
+        }
+        last_time = time;
+    }
+
+    if let Some((index, start)) = current_step {
+        steps[index].duration_secs = elapsed(start, last_time);
+    }
+    steps
+}
+
+/// Errors, warnings and build statuses the build script reported through service messages, in
+/// order of the log
+pub fn log_issues(lines: &[String]) -> Vec<LogIssue> {
+    lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| line.contains("##teamcity["))
+        .filter_map(|(i, line)| issue(split_prefix(line).1, i))
+        .collect()
+}
+
+/// Steps and the issues reported inside them in order of the log, a step before an issue of the
+/// same line
+pub fn outline<'a>(steps: &'a [LogStep], issues: &'a [LogIssue]) -> Vec<OutlineItem<'a>> {
+    let mut items: Vec<OutlineItem> = steps
+        .iter()
+        .map(OutlineItem::Step)
+        .chain(issues.iter().map(OutlineItem::Issue))
+        .collect();
+    items.sort_by_key(|item| match item {
+        OutlineItem::Step(step) => (step.line, 0),
+        OutlineItem::Issue(issue) => (issue.line, 1),
+    });
+    items
+}
+
+/// First of `marks`, lines in ascending order, after `from`, or the last one before it
+pub fn next_mark(marks: &[usize], from: usize, forward: bool) -> Option<usize> {
+    if forward {
+        marks.iter().copied().find(|line| *line > from)
+    } else {
+        marks.iter().copied().rev().find(|line| *line < from)
+    }
+}
+
+/// Line of the next error after `from`, or the previous one before it, wrapping around the log
+pub fn find_error(kinds: &[LineKind], from: usize, forward: bool) -> Option<usize> {
+    let len = kinds.len();
+    (1..=len)
+        .map(|offset| {
+            if forward {
+                (from + offset) % len
+            } else {
+                (from + len - offset % len) % len
+            }
+        })
+        .find(|i| kinds[*i] == LineKind::Error)
+}
+
+/// First line of the last page of a log whose lines wrap at `width` columns into `height` rows
+pub fn last_page_start(lines: &[String], width: usize, height: usize) -> usize {
+    let mut rows = 0;
+    for (i, line) in lines.iter().enumerate().rev() {
+        rows += line.chars().count().div_ceil(width.max(1)).max(1);
+        if rows > height {
+            return i + 1;
+        }
+    }
+    0
+}
+
+/// Name of the file the log of `build` is saved to, from its configuration and number. Build
+/// numbers are free-form, so anything but letters, digits, `-`, `_` and `.` is replaced to keep
+/// the file in the download directory.
+pub fn log_file_name(build: &Build) -> String {
+    let number = build
+        .build_number
+        .clone()
+        .or_else(|| build.id.map(|id| id.to_string()))
+        .unwrap_or_default();
+    let name = format!(
+        "{}_{}",
+        build.build_type_id.as_deref().unwrap_or("build"),
+        number
+    );
+    let name: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{}.txt", name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(log: &str) -> Vec<String> {
+        log.lines().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn line_kind_uses_status_and_messages() {
+        assert_eq!(
+            line_kind("[10:00:00]E: compilation failed"),
+            LineKind::Error
+        );
+        assert_eq!(line_kind("[10:00:00]W: deprecated API"), LineKind::Warning);
+        assert_eq!(
+            line_kind("[10:00:00] : Step 1/2: Compile (Gradle)"),
+            LineKind::StepBoundary
+        );
+        assert_eq!(
+            line_kind("##teamcity[blockOpened name='Tests']"),
+            LineKind::StepBoundary
+        );
+        assert_eq!(
+            line_kind("[10:00:00] : ##teamcity[buildStatisticValue key='x' value='1']"),
+            LineKind::ServiceMessage
+        );
+        assert_eq!(
+            line_kind("java.lang.IllegalStateException: error"),
+            LineKind::Error
+        );
+        assert_eq!(line_kind("BUILD SUCCESSFUL, 0 errors"), LineKind::Plain);
+        assert_eq!(line_kind("[10:00:00]i: done"), LineKind::Plain);
+    }
+
+    #[test]
+    fn log_steps_measure_steps_and_blocks() {
+        let log = lines(
+            "[10:00:00] : Step 1/2: Compile (Gradle)\n\
+             [10:00:05] : ##teamcity[blockOpened name='compileJava']\n\
+             [10:01:05] : ##teamcity[blockClosed name='compileJava']\n\
+             [10:02:00] : Step 2/2: Test\n\
+             [10:02:30]i: done",
+        );
+
+        assert_eq!(
+            log_steps(&log),
+            vec![
+                LogStep {
+                    name: "Compile (Gradle)".to_string(),
+                    line: 0,
+                    depth: 0,
+                    duration_secs: Some(120),
+                },
+                LogStep {
+                    name: "compileJava".to_string(),
+                    line: 1,
+                    depth: 1,
+                    duration_secs: Some(60),
+                },
+                LogStep {
+                    name: "Test".to_string(),
+                    line: 3,
+                    depth: 0,
+                    duration_secs: Some(30),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn log_steps_close_unclosed_blocks_and_wrap_midnight() {
+        let log = lines(
+            "[23:59:50] : ##teamcity[blockOpened name='outer']\n\
+             [23:59:55] : ##teamcity[blockOpened name='inner']\n\
+             [00:00:10] : ##teamcity[blockClosed name='outer']",
+        );
+
+        let steps = log_steps(&log);
+        assert_eq!(steps[0].duration_secs, Some(20));
+        assert_eq!(steps[1].depth, 1);
+        assert_eq!(steps[1].duration_secs, Some(15));
+    }
+
+    #[test]
+    fn issues_come_from_messages_and_build_status() {
+        let log = lines(
+            "[10:00:00] : Step 1/1: Test\n\
+             [10:00:01] : ##teamcity[message text='Disk almost |'full|'' status='WARNING']\n\
+             [10:00:02] : ##teamcity[message text='progress' status='NORMAL']\n\
+             [10:00:03] : ##teamcity[message text='Tests failed|nsee report' errorDetails='x' status='ERROR']\n\
+             [10:00:04] : ##teamcity[buildStatus status='FAILURE' text='{build.status.text}, 3 |[flaky|]']\n\
+             [10:00:05] : ##teamcity[buildStatus text='Coverage 81|0x0025']",
+        );
+
+        let issues = log_issues(&log);
+        let issue = |kind, text: &str, line| LogIssue {
+            kind,
+            text: text.to_string(),
+            line,
+        };
+        assert_eq!(
+            issues,
+            vec![
+                issue(IssueKind::Warning, "Disk almost 'full'", 1),
+                issue(IssueKind::Error, "Tests failed", 3),
+                issue(IssueKind::Error, "{build.status.text}, 3 [flaky]", 4),
+                issue(IssueKind::Status, "Coverage 81%", 5),
+            ]
+        );
+        assert_eq!(line_kind(&log[1]), LineKind::Warning);
+        assert_eq!(line_kind(&log[2]), LineKind::ServiceMessage);
+        assert_eq!(line_kind(&log[3]), LineKind::Error);
+
+        let steps = log_steps(&log);
+        let outline = outline(&steps, &issues);
+        assert_eq!(outline.len(), 5);
+        assert_eq!(outline[0], OutlineItem::Step(&steps[0]));
+        assert_eq!(outline[4], OutlineItem::Issue(&issues[3]));
+    }
+
+    #[test]
+    fn next_mark_stops_at_the_ends() {
+        let marks = [2, 5, 9];
+
+        assert_eq!(next_mark(&marks, 0, true), Some(2));
+        assert_eq!(next_mark(&marks, 5, true), Some(9));
+        assert_eq!(next_mark(&marks, 9, true), None);
+        assert_eq!(next_mark(&marks, 5, false), Some(2));
+        assert_eq!(next_mark(&marks, 2, false), None);
+    }
+
+    #[test]
+    fn find_error_wraps_around() {
+        use LineKind::*;
+        let kinds = [Plain, Error, Plain, Error, Plain];
+
+        assert_eq!(find_error(&kinds, 1, true), Some(3));
+        assert_eq!(find_error(&kinds, 3, true), Some(1));
+        assert_eq!(find_error(&kinds, 1, false), Some(3));
+        assert_eq!(find_error(&kinds, 4, false), Some(3));
+        assert_eq!(find_error(&[Plain, Plain], 0, true), None);
+        assert_eq!(find_error(&[], 0, true), None);
+    }
+
+    #[test]
+    fn log_files_stay_in_the_download_directory() {
+        let build = |number: Option<&str>| Build {
+            id: Some(42),
+            build_type_id: Some("Web_Build".to_string()),
+            build_number: number.map(str::to_string),
+            ..Build::default()
+        };
+
+        assert_eq!(log_file_name(&build(Some("1.2.3"))), "Web_Build_1.2.3.txt");
+        assert_eq!(
+            log_file_name(&build(Some("../../etc/release 7"))),
+            "Web_Build_.._.._etc_release_7.txt"
+        );
+        assert_eq!(
+            log_file_name(&build(Some("main\\12"))),
+            "Web_Build_main_12.txt"
+        );
+        assert_eq!(log_file_name(&build(None)), "Web_Build_42.txt");
+    }
+
+    #[test]
+    fn last_page_start_counts_wrapped_rows() {
+        let log = lines("short\n0123456789\n\n0123456789abc");
+
+        assert_eq!(last_page_start(&log, 10, 3), 2);
+        assert_eq!(last_page_start(&log, 10, 4), 1);
+        assert_eq!(last_page_start(&log, 20, 10), 0);
+    }
+}
@@ -0,0 +1,1464 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+
+use crate::icons::IconSet;
+use crate::time::format_duration;
+use crate::utils::format_size;
+use crate::view::filter_query::{Field, FilterQuery};
+use teamcity::types::{Build, Change, Properties, Revision, TestOccurrence};
+
+pub const COLUMNS: [&str; 11] = [
+    "Number",
+    "Branch",
+    "Status",
+    "Last Changes",
+    "Start time",
+    "Duration",
+    "Triggered by",
+    "Revision",
+    "Tags",
+    "Queue",
+    "ETA",
+];
+
+// widths of COLUMNS unless configured otherwise, the status column also takes the space left over
+pub const DEFAULT_COLUMN_WIDTHS: [u16; 11] = [12, 30, 20, 40, 13, 9, 16, 11, 16, 5, 13];
+const MIN_COLUMN_WIDTH: u16 = 3;
+const MAX_COLUMN_WIDTH: u16 = 200;
+
+// narrower tables only show the number, status and duration of each build
+pub const NARROW_WIDTH: u16 = 100;
+
+// length of abbreviated commit SHAs, like `git log --oneline`
+const SHORT_REVISION_LEN: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortColumn {
+    Number,
+    Branch,
+    Status,
+    Changes,
+    StartTime,
+    Duration,
+}
+
+impl SortColumn {
+    // columns are toggled with the 1-6 keys, in the same order as the table header
+    pub fn from_key(c: char) -> Option<Self> {
+        match c {
+            '1' => Some(SortColumn::Number),
+            '2' => Some(SortColumn::Branch),
+            '3' => Some(SortColumn::Status),
+            '4' => Some(SortColumn::Changes),
+            '5' => Some(SortColumn::StartTime),
+            '6' => Some(SortColumn::Duration),
+            _ => None,
+        }
+    }
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Ascending order of `a` and `b` by this column; running builds are measured up to `now`
+    pub fn compare(self, a: &Build, b: &Build, now: OffsetDateTime) -> Ordering {
+        match self {
+            SortColumn::Number => {
+                let number = |b: &Build| {
+                    b.build_number
+                        .as_deref()
+                        .and_then(|n| n.parse::<u64>().ok())
+                };
+                number(a)
+                    .cmp(&number(b))
+                    .then_with(|| a.build_number.cmp(&b.build_number))
+                    .then_with(|| a.id.cmp(&b.id))
+            }
+            SortColumn::Branch => a.branch_name.cmp(&b.branch_name),
+            SortColumn::Status => a.status.cmp(&b.status),
+            SortColumn::Changes => changes_count(a).cmp(&changes_count(b)),
+            SortColumn::StartTime => a.start_date.cmp(&b.start_date),
+            SortColumn::Duration => duration_secs(a, now).cmp(&duration_secs(b, now)),
+        }
+    }
+}
+
+/// Width of each column of COLUMNS, from `configured` widths by column name or the defaults
+pub fn column_widths(configured: &BTreeMap<String, u16>) -> Vec<u16> {
+    COLUMNS
+        .iter()
+        .zip(DEFAULT_COLUMN_WIDTHS)
+        .map(|(name, default)| {
+            configured
+                .get(*name)
+                .copied()
+                .unwrap_or(default)
+                .clamp(MIN_COLUMN_WIDTH, MAX_COLUMN_WIDTH)
+        })
+        .collect()
+}
+
+/// `width` grown or shrunk by `delta` within the allowed column widths
+pub fn resize_column(width: u16, delta: i16) -> u16 {
+    width
+        .saturating_add_signed(delta)
+        .clamp(MIN_COLUMN_WIDTH, MAX_COLUMN_WIDTH)
+}
+
+/// Sorts `builds` in place; the sort is stable so ties keep their previous order
+pub fn sort_builds(
+    builds: &mut [Build],
+    column: SortColumn,
+    descending: bool,
+    now: OffsetDateTime,
+) {
+    builds.sort_by(|a, b| {
+        let ordering = column.compare(a, b, now);
+        if descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+}
+
+pub fn changes_count(build: &Build) -> usize {
+    build
+        .changes
+        .as_ref()
+        .and_then(|c| c.change.as_ref())
+        .map_or(0, |c| c.len())
+}
+
+/// Indices of the COLUMNS shown in a table `width` wide, scrolled to `first_column`
+pub fn visible_columns(width: u16, first_column: usize) -> Vec<usize> {
+    if width < NARROW_WIDTH {
+        vec![
+            SortColumn::Number.index(),
+            SortColumn::Status.index(),
+            SortColumn::Duration.index(),
+        ]
+    } else {
+        (first_column..COLUMNS.len()).collect()
+    }
+}
+
+/// Seconds between start and finish, running builds are measured up to `now`
+pub fn duration_secs(build: &Build, now: OffsetDateTime) -> Option<i64> {
+    let start = build.start_date?;
+    let end = build.finish_date.unwrap_or(now);
+    let elapsed = (end - start).whole_seconds();
+    (elapsed >= 0).then_some(elapsed)
+}
+
+/// Seconds a running build has left at `now` according to the server's estimate, None once it
+/// runs past the estimate or without one. Counts down from the start time between refreshes.
+pub fn remaining_secs(build: &Build, now: OffsetDateTime) -> Option<i64> {
+    if !is_running(build) {
+        return None;
+    }
+    let info = build.running_info.as_ref()?;
+    let elapsed = duration_secs(build, now).or(info.elapsed_seconds)?;
+    let left = info.estimated_total_seconds? - elapsed;
+    (left > 0).then_some(left)
+}
+
+/// Average duration of the finished successful `builds`, the usual duration of their configuration
+pub fn duration_baseline(builds: &[Build]) -> Option<i64> {
+    let durations: Vec<i64> = builds
+        .iter()
+        .filter(|build| build.status.as_deref() == Some("SUCCESS") && build.finish_date.is_some())
+        .filter_map(|build| duration_secs(build, OffsetDateTime::now_utc()))
+        .collect();
+    if durations.is_empty() {
+        return None;
+    }
+    Some(durations.iter().sum::<i64>() / durations.len() as i64)
+}
+
+/// Whether `build` is still running more than `threshold_percent` longer than `baseline` seconds
+pub fn exceeds_baseline(
+    build: &Build,
+    baseline: i64,
+    threshold_percent: u32,
+    now: OffsetDateTime,
+) -> bool {
+    is_running(build)
+        && duration_secs(build, now)
+            .is_some_and(|secs| secs * 100 > baseline * (100 + threshold_percent as i64))
+}
+
+/// Revision of the first VCS root the build was run against
+pub fn revision(build: &Build) -> Option<&Revision> {
+    build.revisions.as_ref()?.revision.first()
+}
+
+/// Names of the tags of `build`
+pub fn tag_names(build: &Build) -> Vec<&str> {
+    build
+        .tags
+        .as_ref()
+        .map(|tags| tags.tag.iter().map(|tag| tag.name.as_str()).collect())
+        .unwrap_or_default()
+}
+
+/// Abbreviated revision, with the number of other VCS roots if there are several
+pub fn short_revision(build: &Build) -> String {
+    let Some(version) = revision(build).and_then(|r| r.version.as_deref()) else {
+        return String::new();
+    };
+    let short: String = version.chars().take(SHORT_REVISION_LEN).collect();
+    match build.revisions.as_ref().map_or(0, |r| r.revision.len()) {
+        0 | 1 => short,
+        n => format!("{} +{}", short, n - 1),
+    }
+}
+
+/// Abbreviated commit of a change, or its ID if the server sent no commit
+pub fn change_revision(change: &Change) -> String {
+    match (&change.version, change.id) {
+        (Some(version), _) => version.chars().take(SHORT_REVISION_LEN).collect(),
+        (None, Some(id)) => format!("change {}", id),
+        (None, None) => "change".to_string(),
+    }
+}
+
+/// Commit URL from a template with `{revision}` and `{vcs_root}` placeholders
+pub fn commit_url(template: &str, revision: &Revision) -> Option<String> {
+    let version = revision.version.as_deref()?;
+    let vcs_root = revision
+        .vcs_root_instance
+        .as_ref()
+        .and_then(|root| root.vcs_root_id.as_deref())
+        .unwrap_or_default();
+    Some(
+        template
+            .replace("{revision}", version)
+            .replace("{vcs_root}", vcs_root),
+    )
+}
+
+/// `label value` of each statistic of `keys` the build has, e.g. `Line coverage 81.2%`, in the
+/// order of `keys`
+pub fn statistics_summary(statistics: &Properties, keys: &[String]) -> Vec<String> {
+    keys.iter()
+        .filter_map(|key| {
+            let value = statistics.get(key)?;
+            Some(format!(
+                "{} {}",
+                statistic_label(key),
+                format_statistic(key, value)
+            ))
+        })
+        .collect()
+}
+
+fn statistic_label(key: &str) -> &str {
+    match key {
+        "CodeCoverageL" => "Line coverage",
+        "CodeCoverageB" => "Block coverage",
+        "CodeCoverageM" => "Method coverage",
+        "CodeCoverageC" => "Class coverage",
+        "ArtifactsSize" => "Artifacts",
+        "TotalTestCount" => "Tests",
+        "FailedTestCount" => "Failed tests",
+        "IgnoredTestCount" => "Ignored tests",
+        "TimeSpentInQueue" => "Queue wait",
+        "BuildDuration" => "Duration",
+        other => other,
+    }
+}
+
+// percentages for coverage, bytes for sizes and milliseconds for times
+fn format_statistic(key: &str, value: &str) -> String {
+    if key.starts_with("CodeCoverage")
+        && let Ok(percent) = value.parse::<f64>()
+    {
+        return format!("{:.1}%", percent);
+    }
+    if key.ends_with("Size")
+        && let Ok(bytes) = value.parse::<u64>()
+    {
+        return format_size(bytes);
+    }
+    if matches!(
+        key,
+        "TimeSpentInQueue" | "BuildDuration" | "BuildDurationNetTime"
+    ) && let Ok(ms) = value.parse::<i64>()
+        && let Ok(duration) = format_duration(ms / 1000)
+    {
+        return duration;
+    }
+    value.to_string()
+}
+
+/// Tab of the web page of a build
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildTab {
+    Overview,
+    Log,
+    Changes,
+    Tests,
+    Dependencies,
+    Parameters,
+}
+
+impl BuildTab {
+    /// Tab opened by the key following `O` in the Builds view
+    pub fn from_key(key: char) -> Option<Self> {
+        match key {
+            'o' => Some(BuildTab::Overview),
+            'l' => Some(BuildTab::Log),
+            'x' => Some(BuildTab::Changes),
+            't' => Some(BuildTab::Tests),
+            'd' => Some(BuildTab::Dependencies),
+            'p' => Some(BuildTab::Parameters),
+            _ => None,
+        }
+    }
+
+    // `buildTab` of the current web UI
+    fn name(self) -> &'static str {
+        match self {
+            BuildTab::Overview => "overview",
+            BuildTab::Log => "log",
+            BuildTab::Changes => "changes",
+            BuildTab::Tests => "tests",
+            BuildTab::Dependencies => "dependencies",
+            BuildTab::Parameters => "parameters",
+        }
+    }
+
+    // `tab` of the classic `viewLog.html` page of older servers
+    fn classic_name(self) -> &'static str {
+        match self {
+            BuildTab::Overview => "buildResultsDiv",
+            BuildTab::Log => "buildLog",
+            BuildTab::Changes => "buildChangesDiv",
+            BuildTab::Tests => "testsInfo",
+            BuildTab::Dependencies => "dependencies",
+            BuildTab::Parameters => "buildParameters",
+        }
+    }
+}
+
+/// `web_url` of a build opened on `tab`, for the classic build page of older servers as well
+pub fn build_tab_url(web_url: &str, tab: BuildTab) -> String {
+    let separator = if web_url.contains('?') { '&' } else { '?' };
+    if web_url.contains("viewLog.html") {
+        format!("{}{}tab={}", web_url, separator, tab.classic_name())
+    } else {
+        format!("{}{}buildTab={}", web_url, separator, tab.name())
+    }
+}
+
+/// Build locator for a search query of space-separated terms: a build number (`123` or `#123`),
+/// `tag:<tag>`, `status:<success|failure|error|unknown>` or `number:<number>`
+pub fn search_locator(query: &str) -> Result<String, String> {
+    let mut dimensions = Vec::new();
+    for term in query.split_whitespace() {
+        let dimension = match term.split_once(':') {
+            Some(("tag", tag)) if !tag.is_empty() => format!("tag:{}", tag),
+            Some(("number", number)) if !number.is_empty() => format!("number:{}", number),
+            Some(("status", status)) => match status.to_ascii_uppercase().as_str() {
+                status @ ("SUCCESS" | "FAILURE" | "ERROR" | "UNKNOWN") => {
+                    format!("status:{}", status)
+                }
+                _ => return Err(format!("Unknown build status: {}", status)),
+            },
+            Some(_) => return Err(format!("Unknown search term: {}", term)),
+            None => format!("number:{}", term.trim_start_matches('#')),
+        };
+        dimensions.push(dimension);
+    }
+    if dimensions.is_empty() {
+        return Err("Type a build number, tag:<tag> or status:<status>".to_string());
+    }
+    Ok(dimensions.join(","))
+}
+
+/// Status text shown by TeamCity, falling back to the bare status; why a queued build waits
+pub fn status_text(build: &Build) -> String {
+    if is_queued(build) {
+        return build
+            .wait_reason
+            .clone()
+            .unwrap_or_else(|| "Queued".to_string());
+    }
+    build
+        .status_text
+        .clone()
+        .or(build.status.clone())
+        .unwrap_or_default()
+}
+
+/// What a queued build is waiting for, read from its wait reason
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaitKind {
+    Agent,
+    /// A shared resource or lock held by other builds
+    Resource,
+    /// Snapshot dependencies that haven't finished yet
+    Dependency,
+}
+
+impl WaitKind {
+    pub fn tag(self) -> &'static str {
+        match self {
+            WaitKind::Agent => "[agent]",
+            WaitKind::Resource => "[resource]",
+            WaitKind::Dependency => "[dependency]",
+        }
+    }
+}
+
+/// What a queued build is waiting for, None if it isn't queued or the reason is something else
+pub fn wait_kind(build: &Build) -> Option<WaitKind> {
+    if !is_queued(build) {
+        return None;
+    }
+    let reason = build.wait_reason.as_deref()?.to_lowercase();
+    if reason.contains("resource") || reason.contains("lock") {
+        Some(WaitKind::Resource)
+    } else if reason.contains("depend") || reason.contains("snapshot") {
+        Some(WaitKind::Dependency)
+    } else if reason.contains("agent") {
+        Some(WaitKind::Agent)
+    } else {
+        None
+    }
+}
+
+pub fn is_queued(build: &Build) -> bool {
+    build.state.as_deref() == Some("queued")
+}
+
+pub fn is_running(build: &Build) -> bool {
+    build.state.as_deref() == Some("running")
+}
+
+/// Queued builds on top of the fetched ones, which may already list some of them
+pub fn merge_queued(queued: Vec<Build>, builds: Vec<Build>) -> Vec<Build> {
+    let mut merged = queued;
+    let queued_ids: Vec<Option<i64>> = merged.iter().map(|build| build.id).collect();
+    merged.extend(
+        builds
+            .into_iter()
+            .filter(|build| !queued_ids.contains(&build.id)),
+    );
+    merged
+}
+
+/// Position of a queued build in the build queue, like `#3`
+pub fn queue_position(build: &Build) -> String {
+    build
+        .queue_position
+        .map(|position| format!("#{}", position))
+        .unwrap_or_default()
+}
+
+// if build status is None then it's in queue state
+pub fn is_failed(build: &Build) -> bool {
+    matches!(build.status.as_deref(), Some("FAILURE" | "UNKNOWN"))
+}
+
+/// Whether `build` has a change by `username` or is a personal build triggered by them
+pub fn is_mine(build: &Build, username: &str) -> bool {
+    let has_my_change = build
+        .changes
+        .as_ref()
+        .and_then(|c| c.change.as_deref())
+        .unwrap_or_default()
+        .iter()
+        .any(|c| c.username.as_deref() == Some(username));
+    has_my_change || (build.personal == Some(true) && triggered_by(build) == Some(username))
+}
+
+fn triggered_by(build: &Build) -> Option<&str> {
+    build.triggered.as_ref()?.user.as_ref()?.username.as_deref()
+}
+
+/// Builds shown in the table: personal builds of others are hidden, like in the TeamCity UI, and
+/// with `mine_only` only builds of `username` are kept
+pub fn visible_builds(builds: &[Build], username: Option<&str>, mine_only: bool) -> Vec<Build> {
+    builds
+        .iter()
+        .filter(|build| {
+            build.personal != Some(true)
+                || username.is_some_and(|username| triggered_by(build) == Some(username))
+        })
+        .filter(|build| !mine_only || username.is_some_and(|username| is_mine(build, username)))
+        .cloned()
+        .collect()
+}
+
+/// Rows of the Builds table: `builds` of the configuration sorted by `sort`, with queued builds on
+/// top as they have no start time or duration yet, then the builds each expanded composite build
+/// aggregated right under it, so these never get sorted among the builds of the configuration
+pub fn table_rows(
+    mut builds: Vec<Build>,
+    sort: Option<(SortColumn, bool)>,
+    constituents: &HashMap<i64, Vec<Build>>,
+    now: OffsetDateTime,
+) -> Vec<Build> {
+    if let Some((column, descending)) = sort {
+        sort_builds(&mut builds, column, descending, now);
+        builds.sort_by_key(|build| !is_queued(build));
+    }
+    insert_constituents(builds, constituents)
+}
+
+/// `builds` with the builds each expanded composite build aggregated right under it, by the ID of
+/// the composite build in `constituents`
+pub fn insert_constituents(
+    builds: Vec<Build>,
+    constituents: &HashMap<i64, Vec<Build>>,
+) -> Vec<Build> {
+    let mut rows = Vec::with_capacity(builds.len());
+    for build in builds {
+        let aggregated = build.id.and_then(|id| constituents.get(&id));
+        rows.push(build);
+        rows.extend(aggregated.into_iter().flatten().cloned());
+    }
+    rows
+}
+
+/// Fields a filter on builds can match: the branch without a prefix, the build number with `id:`,
+/// the build configuration ID with `type:` and any tag with `tag:`
+pub const FILTER_FIELDS: [Field; 4] = [Field::Name, Field::Id, Field::Type, Field::Tag];
+
+pub fn matches_filter(build: &Build, filter: &FilterQuery) -> bool {
+    filter.matches(|field| match field {
+        Field::Name => build.branch_name.as_deref().into_iter().collect(),
+        Field::Id => build.build_number.as_deref().into_iter().collect(),
+        Field::Type => build.build_type_id.as_deref().into_iter().collect(),
+        Field::Tag => tag_names(build),
+        Field::Project => Vec::new(),
+    })
+}
+
+/// Likely reasons no build is shown, most likely first: `total` builds were loaded on `branch`,
+/// started in the date `range` if one was picked, and `filtered` tells whether a filter, the
+/// search or `mine_only` narrowed them down
+pub fn empty_reasons(
+    total: usize,
+    filtered: bool,
+    branch: &str,
+    range: Option<&str>,
+) -> Vec<String> {
+    if total > 0 {
+        let reason = if filtered {
+            "No build matches the filter"
+        } else {
+            "Personal builds of others are hidden"
+        };
+        return vec![reason.to_string()];
+    }
+    let mut reasons = vec![match range {
+        Some(range) => format!("No build on the {} started {}", branch, range),
+        None => format!("No build ran on the {} yet", branch),
+    }];
+    if filtered {
+        reasons.push("No build matches the search".to_string());
+    }
+    reasons.push("The token or user lacks the permission to view the builds".to_string());
+    reasons
+}
+
+pub const EXPORT_COLUMNS: [&str; 14] = [
+    "id",
+    "number",
+    "branch",
+    "status",
+    "state",
+    "status_text",
+    "start",
+    "finish",
+    "duration_secs",
+    "changes",
+    "triggered_by",
+    "revision",
+    "tags",
+    "web_url",
+];
+
+/// Fields of `build` in the order of [`EXPORT_COLUMNS`], dates in RFC 3339
+pub fn export_row(build: &Build, now: OffsetDateTime) -> Vec<String> {
+    let date = |date: Option<OffsetDateTime>| {
+        date.and_then(|d| d.format(&Rfc3339).ok())
+            .unwrap_or_default()
+    };
+    vec![
+        build.id.map(|id| id.to_string()).unwrap_or_default(),
+        build.build_number.clone().unwrap_or_default(),
+        build.branch_name.clone().unwrap_or_default(),
+        build.status.clone().unwrap_or_default(),
+        build.state.clone().unwrap_or_default(),
+        build.status_text.clone().unwrap_or_default(),
+        date(build.start_date),
+        date(build.finish_date),
+        duration_secs(build, now)
+            .map(|secs| secs.to_string())
+            .unwrap_or_default(),
+        // exported files are read outside the terminal, they keep the default icons
+        summarize_changes(build, IconSet::default()),
+        triggered_text(build),
+        revision(build)
+            .and_then(|r| r.version.clone())
+            .unwrap_or_default(),
+        tag_names(build).join(" "),
+        build.web_url.clone().unwrap_or_default(),
+    ]
+}
+
+/// Who or what triggered `build`, for the "Triggered by" column
+pub fn triggered_text(build: &Build) -> String {
+    let Some(triggered) = &build.triggered else {
+        return String::new();
+    };
+    match triggered.kind.as_deref() {
+        Some("user") => triggered
+            .user
+            .as_ref()
+            .and_then(|user| user.name.clone().or_else(|| user.username.clone()))
+            .unwrap_or_else(|| "User".to_string()),
+        Some("vcs") => "VCS".to_string(),
+        Some("schedule") => "Schedule".to_string(),
+        Some("snapshotDependency") | Some("buildType") => match &triggered.build {
+            Some(upstream) => format!(
+                "{} #{}",
+                upstream.build_type_id.as_deref().unwrap_or("Build"),
+                upstream.build_number.as_deref().unwrap_or_default()
+            ),
+            None => "Dependency".to_string(),
+        },
+        Some("restApi") => "REST API".to_string(),
+        Some(kind) => kind.to_string(),
+        None => String::new(),
+    }
+}
+
+/// One-line summary of the changes in a build for the "Last Changes" column
+pub fn summarize_changes(build: &Build, icons: IconSet) -> String {
+    let changes = build
+        .changes
+        .as_ref()
+        .and_then(|c| c.change.as_deref())
+        .unwrap_or_default();
+
+    if changes.is_empty() {
+        return "No changes".to_string();
+    }
+
+    let users: Vec<&String> = changes.iter().filter_map(|c| c.username.as_ref()).collect();
+    if users.is_empty() {
+        format!("{} {} Changes from 0 users", icons.warning(), changes.len())
+    } else if users.len() == 1 {
+        format!("{}: {}", users[0], changes.len())
+    } else {
+        format!("{} Changes", changes.len())
+    }
+}
+
+/// Author and first comment line of each change in a build, for the change details popup
+pub fn change_details(build: &Build) -> Vec<(String, String)> {
+    build
+        .changes
+        .as_ref()
+        .and_then(|c| c.change.as_deref())
+        .unwrap_or_default()
+        .iter()
+        .map(|change| {
+            let author = change.username.as_deref().unwrap_or("unknown").to_string();
+            let comment = change
+                .comment
+                .as_deref()
+                .and_then(|comment| comment.lines().find(|line| !line.trim().is_empty()))
+                .unwrap_or_default()
+                .trim()
+                .to_string();
+            (author, comment)
+        })
+        .collect()
+}
+
+/// Last `count` lines of a build log, without trailing blank lines
+pub fn log_tail(log: &str, count: usize) -> Vec<String> {
+    let lines: Vec<&str> = log.trim_end().lines().collect();
+    lines[lines.len().saturating_sub(count)..]
+        .iter()
+        .map(|line| line.to_string())
+        .collect()
+}
+
+/// Status text of a failed build followed by its failed tests, muted ones marked
+pub fn failure_summary(build: &Build, tests: &[TestOccurrence]) -> Vec<String> {
+    let mut lines = vec![status_text(build)];
+    if !tests.is_empty() {
+        lines.push(String::new());
+        lines.push(format!("Failed tests ({}):", tests.len()));
+        lines.extend(tests.iter().map(|test| {
+            let name = test.name.as_deref().unwrap_or("N/A");
+            if test.muted == Some(true) {
+                format!("  {} (muted)", name)
+            } else {
+                format!("  {}", name)
+            }
+        }));
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use teamcity::types::{
+        Change, Changes, Property, Revisions, RunningInfo, Tag, Tags, Triggered, User,
+        VcsRootInstance,
+    };
+    use time::Duration;
+    use time::macros::datetime;
+
+    const NOW: OffsetDateTime = datetime!(2024-03-05 14:30:00 UTC);
+
+    fn build(id: i64, number: &str) -> Build {
+        Build {
+            id: Some(id),
+            build_number: Some(number.to_string()),
+            ..Build::default()
+        }
+    }
+
+    fn with_changes(usernames: &[Option<&str>]) -> Build {
+        Build {
+            changes: Some(Changes {
+                change: Some(
+                    usernames
+                        .iter()
+                        .map(|username| Change {
+                            username: username.map(str::to_string),
+                            ..Change::default()
+                        })
+                        .collect(),
+                ),
+            }),
+            ..Build::default()
+        }
+    }
+
+    fn numbers(builds: &[Build]) -> Vec<&str> {
+        builds
+            .iter()
+            .map(|b| b.build_number.as_deref().unwrap_or_default())
+            .collect()
+    }
+
+    #[test]
+    fn sort_columns_follow_the_header_order() {
+        for (i, key) in ['1', '2', '3', '4', '5', '6'].into_iter().enumerate() {
+            assert_eq!(SortColumn::from_key(key).map(SortColumn::index), Some(i));
+        }
+        assert_eq!(SortColumn::from_key('7'), None);
+        assert_eq!(SortColumn::from_key('a'), None);
+    }
+
+    #[test]
+    fn numbers_sort_numerically_then_lexically() {
+        let mut builds = vec![
+            build(1, "10"),
+            build(2, "9"),
+            build(3, "release-1"),
+            build(4, "100"),
+        ];
+
+        sort_builds(&mut builds, SortColumn::Number, false, NOW);
+        // non-numeric numbers have no parsed value and come first
+        assert_eq!(numbers(&builds), ["release-1", "9", "10", "100"]);
+
+        sort_builds(&mut builds, SortColumn::Number, true, NOW);
+        assert_eq!(numbers(&builds), ["100", "10", "9", "release-1"]);
+    }
+
+    #[test]
+    fn equal_numbers_are_ordered_by_id() {
+        let mut builds = vec![build(7, "1"), build(3, "1")];
+        sort_builds(&mut builds, SortColumn::Number, false, NOW);
+        assert_eq!(builds[0].id, Some(3));
+    }
+
+    #[test]
+    fn running_builds_are_measured_up_to_now() {
+        let finished = Build {
+            start_date: Some(NOW - Duration::minutes(30)),
+            finish_date: Some(NOW - Duration::minutes(20)),
+            ..Build::default()
+        };
+        let running = Build {
+            start_date: Some(NOW - Duration::minutes(5)),
+            ..Build::default()
+        };
+        let queued = Build::default();
+
+        assert_eq!(duration_secs(&finished, NOW), Some(600));
+        assert_eq!(duration_secs(&running, NOW), Some(300));
+        assert_eq!(duration_secs(&queued, NOW), None);
+
+        let mut builds = vec![finished, queued, running];
+        sort_builds(&mut builds, SortColumn::Duration, false, NOW);
+        assert_eq!(
+            builds
+                .iter()
+                .map(|b| duration_secs(b, NOW))
+                .collect::<Vec<_>>(),
+            [None, Some(300), Some(600)]
+        );
+    }
+
+    #[test]
+    fn clock_skew_does_not_produce_negative_durations() {
+        let skewed = Build {
+            start_date: Some(NOW),
+            finish_date: Some(NOW - Duration::seconds(1)),
+            ..Build::default()
+        };
+        assert_eq!(duration_secs(&skewed, NOW), None);
+    }
+
+    #[test]
+    fn change_details_show_the_first_comment_line() {
+        let mut build = with_changes(&[Some("alice"), None]);
+        if let Some(changes) = build.changes.as_mut().and_then(|c| c.change.as_mut()) {
+            changes[0].comment = Some("\nFix flaky login test\n\nIt timed out on CI".to_string());
+        }
+        assert_eq!(
+            change_details(&build),
+            vec![
+                ("alice".to_string(), "Fix flaky login test".to_string()),
+                ("unknown".to_string(), String::new()),
+            ]
+        );
+        assert!(change_details(&Build::default()).is_empty());
+    }
+
+    #[test]
+    fn changes_are_named_by_their_abbreviated_commit() {
+        let change = Change {
+            id: Some(4211),
+            version: Some("0a1b2c3d4e5f".to_string()),
+            ..Change::default()
+        };
+        assert_eq!(change_revision(&change), "0a1b2c3d");
+        assert_eq!(
+            change_revision(&Change {
+                version: None,
+                ..change
+            }),
+            "change 4211"
+        );
+    }
+
+    #[test]
+    fn builds_sort_by_number_of_changes() {
+        let mut builds = vec![
+            with_changes(&[Some("a"), Some("b")]),
+            Build::default(),
+            with_changes(&[Some("a")]),
+        ];
+        sort_builds(&mut builds, SortColumn::Changes, true, NOW);
+        assert_eq!(
+            builds.iter().map(changes_count).collect::<Vec<_>>(),
+            [2, 1, 0]
+        );
+    }
+
+    #[test]
+    fn status_text_falls_back_to_status() {
+        let with_text = Build {
+            status: Some("FAILURE".to_string()),
+            status_text: Some("Tests failed: 3".to_string()),
+            ..Build::default()
+        };
+        let without_text = Build {
+            status: Some("SUCCESS".to_string()),
+            ..Build::default()
+        };
+
+        assert_eq!(status_text(&with_text), "Tests failed: 3");
+        assert_eq!(status_text(&without_text), "SUCCESS");
+        assert_eq!(status_text(&Build::default()), "");
+    }
+
+    #[test]
+    fn failed_and_unknown_builds_are_failures() {
+        let status = |s: Option<&str>| Build {
+            status: s.map(str::to_string),
+            ..Build::default()
+        };
+
+        assert!(is_failed(&status(Some("FAILURE"))));
+        assert!(is_failed(&status(Some("UNKNOWN"))));
+        assert!(!is_failed(&status(Some("SUCCESS"))));
+        assert!(!is_failed(&status(None)));
+    }
+
+    #[test]
+    fn triggered_text_names_user_or_trigger() {
+        let triggered = |kind: &str| Build {
+            triggered: Some(Triggered {
+                kind: Some(kind.to_string()),
+                ..Triggered::default()
+            }),
+            ..Build::default()
+        };
+        let by_user = Build {
+            triggered: Some(Triggered {
+                kind: Some("user".to_string()),
+                user: Some(User {
+                    username: Some("jdoe".to_string()),
+                    name: Some("John Doe".to_string()),
+                }),
+                ..Triggered::default()
+            }),
+            ..Build::default()
+        };
+        let by_dependency = Build {
+            triggered: Some(Triggered {
+                kind: Some("snapshotDependency".to_string()),
+                build: Some(Box::new(Build {
+                    build_type_id: Some("Web_Deploy".to_string()),
+                    build_number: Some("57".to_string()),
+                    ..Build::default()
+                })),
+                ..Triggered::default()
+            }),
+            ..Build::default()
+        };
+
+        assert_eq!(triggered_text(&by_user), "John Doe");
+        assert_eq!(triggered_text(&triggered("vcs")), "VCS");
+        assert_eq!(triggered_text(&triggered("schedule")), "Schedule");
+        assert_eq!(triggered_text(&by_dependency), "Web_Deploy #57");
+        assert_eq!(
+            triggered_text(&triggered("snapshotDependency")),
+            "Dependency"
+        );
+        assert_eq!(triggered_text(&Build::default()), "");
+    }
+
+    #[test]
+    fn export_rows_match_the_export_columns() {
+        let build = Build {
+            id: Some(42),
+            build_number: Some("1.0.3".to_string()),
+            status: Some("SUCCESS".to_string()),
+            start_date: Some(NOW - Duration::minutes(2)),
+            finish_date: Some(NOW),
+            ..Build::default()
+        };
+        let row = export_row(&build, NOW);
+
+        assert_eq!(row.len(), EXPORT_COLUMNS.len());
+        assert_eq!(row[0], "42");
+        assert_eq!(row[6], "2024-03-05T14:28:00Z");
+        assert_eq!(row[8], "120");
+        assert_eq!(row[9], "No changes");
+    }
+
+    #[test]
+    fn changes_are_summarized_by_author() {
+        assert_eq!(
+            summarize_changes(&Build::default(), IconSet::Emoji),
+            "No changes"
+        );
+        assert_eq!(
+            summarize_changes(&with_changes(&[]), IconSet::Emoji),
+            "No changes"
+        );
+        assert_eq!(
+            summarize_changes(&with_changes(&[Some("alice")]), IconSet::Emoji),
+            "alice: 1"
+        );
+        assert_eq!(
+            summarize_changes(&with_changes(&[Some("alice"), Some("bob")]), IconSet::Emoji),
+            "2 Changes"
+        );
+    }
+
+    #[test]
+    fn changes_without_usernames_are_flagged() {
+        assert_eq!(
+            summarize_changes(&with_changes(&[None, None, None]), IconSet::Emoji),
+            "⚠️ 3 Changes from 0 users"
+        );
+        // a single known author among anonymous changes is still attributed
+        assert_eq!(
+            summarize_changes(&with_changes(&[None, Some("alice")]), IconSet::Emoji),
+            "alice: 2"
+        );
+    }
+
+    fn personal(triggered_by: &str) -> Build {
+        Build {
+            personal: Some(true),
+            triggered: Some(Triggered {
+                kind: Some("user".to_string()),
+                user: Some(User {
+                    username: Some(triggered_by.to_string()),
+                    name: None,
+                }),
+                ..Triggered::default()
+            }),
+            ..Build::default()
+        }
+    }
+
+    #[test]
+    fn my_builds_have_my_changes_or_are_my_personal_builds() {
+        assert!(is_mine(
+            &with_changes(&[Some("bob"), Some("alice")]),
+            "alice"
+        ));
+        assert!(!is_mine(&with_changes(&[Some("bob")]), "alice"));
+        assert!(is_mine(&personal("alice"), "alice"));
+        assert!(!is_mine(&personal("bob"), "alice"));
+        assert!(!is_mine(&Build::default(), "alice"));
+    }
+
+    #[test]
+    fn personal_builds_of_others_are_hidden() {
+        let builds = vec![
+            with_changes(&[Some("bob")]),
+            personal("alice"),
+            personal("bob"),
+        ];
+
+        assert_eq!(visible_builds(&builds, None, false), builds[..1]);
+        assert_eq!(visible_builds(&builds, Some("alice"), false), builds[..2]);
+    }
+
+    #[test]
+    fn mine_only_keeps_builds_of_the_user() {
+        let builds = vec![
+            with_changes(&[Some("bob")]),
+            with_changes(&[Some("alice")]),
+            personal("alice"),
+        ];
+
+        assert_eq!(visible_builds(&builds, Some("alice"), true), builds[1..]);
+        // without a configured username nothing is known to be ours
+        assert!(visible_builds(&builds, None, true).is_empty());
+    }
+
+    #[test]
+    fn constituents_follow_their_composite_build() {
+        let builds = vec![build(3, "3"), build(2, "2"), build(1, "1")];
+        let tests = Build {
+            build_type_id: Some("Web_Tests".to_string()),
+            ..build(21, "57")
+        };
+        let lint = Build {
+            build_type_id: Some("Web_Lint".to_string()),
+            ..build(22, "12")
+        };
+        let constituents = HashMap::from([(2, vec![tests.clone(), lint.clone()]), (9, vec![])]);
+
+        let rows = insert_constituents(builds.clone(), &constituents);
+
+        assert_eq!(
+            rows,
+            [
+                builds[0].clone(),
+                builds[1].clone(),
+                tests,
+                lint,
+                builds[2].clone()
+            ]
+        );
+        assert_eq!(insert_constituents(builds.clone(), &HashMap::new()), builds);
+    }
+
+    #[test]
+    fn constituents_stay_under_their_composite_build_when_sorted() {
+        let builds = vec![build(3, "3"), build(2, "2"), build(1, "1")];
+        let constituents = HashMap::from([(2, vec![build(20, "4"), build(21, "0")])]);
+
+        let rows = table_rows(
+            builds,
+            Some((SortColumn::Number, false)),
+            &constituents,
+            NOW,
+        );
+
+        let ids: Vec<_> = rows.iter().filter_map(|b| b.id).collect();
+        assert_eq!(ids, [1, 2, 20, 21, 3]);
+    }
+
+    #[test]
+    fn filters_match_branch_number_configuration_and_tags() {
+        let login = Build {
+            branch_name: Some("feature/Login".to_string()),
+            build_type_id: Some("Web_Deploy".to_string()),
+            tags: Some(Tags {
+                tag: vec![
+                    Tag {
+                        name: "release".to_string(),
+                    },
+                    Tag {
+                        name: "qa".to_string(),
+                    },
+                ],
+            }),
+            ..build(1042, "318")
+        };
+        let query = |query: &str| FilterQuery::parse(query, &FILTER_FIELDS).unwrap();
+
+        assert!(matches_filter(&login, &query("login")));
+        assert!(matches_filter(&login, &query("id:/^31\\d$/ type:web_")));
+        assert!(!matches_filter(&login, &query("/^login/")));
+        assert!(!matches_filter(&build(1, "1"), &query("main")));
+        assert!(matches_filter(&login, &query("tag:qa tag:/^rel/")));
+        assert!(!matches_filter(&login, &query("tag:hotfix")));
+        assert!(!matches_filter(&build(1, "1"), &query("tag:release")));
+        assert!(!matches_filter(&login, &query("project:web")));
+    }
+
+    #[test]
+    fn empty_reasons_blame_the_filter_only_if_builds_were_loaded() {
+        assert_eq!(
+            empty_reasons(3, true, "default branch", None),
+            ["No build matches the filter"]
+        );
+        assert_eq!(
+            empty_reasons(2, false, "default branch", None),
+            ["Personal builds of others are hidden"]
+        );
+        assert_eq!(
+            empty_reasons(0, false, "branch main", None),
+            [
+                "No build ran on the branch main yet",
+                "The token or user lacks the permission to view the builds"
+            ]
+        );
+        assert_eq!(empty_reasons(0, true, "default branch", None).len(), 3);
+        assert_eq!(
+            empty_reasons(0, false, "branch main", Some("last 7 days"))[0],
+            "No build on the branch main started last 7 days"
+        );
+    }
+
+    fn with_revisions(versions: &[&str]) -> Build {
+        Build {
+            revisions: Some(Revisions {
+                revision: versions
+                    .iter()
+                    .map(|version| Revision {
+                        version: Some(version.to_string()),
+                        vcs_branch_name: None,
+                        vcs_root_instance: Some(VcsRootInstance {
+                            name: None,
+                            vcs_root_id: Some("Web_Git".to_string()),
+                        }),
+                    })
+                    .collect(),
+            }),
+            ..Build::default()
+        }
+    }
+
+    #[test]
+    fn revisions_are_abbreviated() {
+        let sha = "3f2a9c1d4b5e6f708192a3b4c5d6e7f809112233";
+        assert_eq!(short_revision(&with_revisions(&[sha])), "3f2a9c1d");
+        assert_eq!(
+            short_revision(&with_revisions(&[sha, "abc"])),
+            "3f2a9c1d +1"
+        );
+        assert_eq!(short_revision(&with_revisions(&["1234"])), "1234");
+        assert_eq!(short_revision(&Build::default()), "");
+    }
+
+    #[test]
+    fn commit_urls_fill_in_the_template() {
+        let build = with_revisions(&["3f2a9c1d"]);
+        let revision = revision(&build).unwrap();
+
+        assert_eq!(
+            commit_url(
+                "https://git.example.com/{vcs_root}/commit/{revision}",
+                revision
+            ),
+            Some("https://git.example.com/Web_Git/commit/3f2a9c1d".to_string())
+        );
+        assert_eq!(
+            commit_url("https://github.com/acme/web/commit/{revision}", revision),
+            Some("https://github.com/acme/web/commit/3f2a9c1d".to_string())
+        );
+    }
+
+    #[test]
+    fn search_terms_become_locator_dimensions() {
+        assert_eq!(search_locator("123"), Ok("number:123".to_string()));
+        assert_eq!(search_locator("#1.2.3"), Ok("number:1.2.3".to_string()));
+        assert_eq!(
+            search_locator("tag:release status:failure"),
+            Ok("tag:release,status:FAILURE".to_string())
+        );
+        assert!(search_locator("status:broken").is_err());
+        assert!(search_locator("branch:main").is_err());
+        assert!(search_locator("  ").is_err());
+    }
+
+    #[test]
+    fn log_tail_keeps_last_lines() {
+        let log = "one\ntwo\nthree\n\n";
+        assert_eq!(log_tail(log, 2), vec!["two", "three"]);
+        assert_eq!(log_tail(log, 10), vec!["one", "two", "three"]);
+        assert!(log_tail("", 5).is_empty());
+    }
+
+    #[test]
+    fn failure_summary_lists_failed_tests() {
+        let build = Build {
+            status: Some("FAILURE".to_string()),
+            status_text: Some("Tests failed: 2".to_string()),
+            ..Build::default()
+        };
+        let test = |name: &str, muted: bool| TestOccurrence {
+            name: Some(name.to_string()),
+            muted: Some(muted),
+            ..TestOccurrence::default()
+        };
+
+        assert_eq!(
+            failure_summary(&build, &[test("LoginTest", false), test("FlakyTest", true)]),
+            vec![
+                "Tests failed: 2",
+                "",
+                "Failed tests (2):",
+                "  LoginTest",
+                "  FlakyTest (muted)",
+            ]
+        );
+        assert_eq!(failure_summary(&build, &[]), vec!["Tests failed: 2"]);
+    }
+
+    #[test]
+    fn merge_queued_puts_queued_builds_on_top_once() {
+        let queued = |id| Build {
+            state: Some("queued".to_string()),
+            ..build(id, "")
+        };
+        let merged = merge_queued(
+            vec![queued(5), queued(4)],
+            vec![queued(4), build(3, "3"), build(2, "2")],
+        );
+
+        let ids: Vec<Option<i64>> = merged.iter().map(|b| b.id).collect();
+        assert_eq!(ids, vec![Some(5), Some(4), Some(3), Some(2)]);
+    }
+
+    #[test]
+    fn status_text_of_queued_builds_is_the_wait_reason() {
+        let waiting = Build {
+            state: Some("queued".to_string()),
+            wait_reason: Some("Waiting for an agent".to_string()),
+            queue_position: Some(2),
+            ..Build::default()
+        };
+        let queued = Build {
+            state: Some("queued".to_string()),
+            ..Build::default()
+        };
+
+        assert_eq!(status_text(&waiting), "Waiting for an agent");
+        assert_eq!(status_text(&queued), "Queued");
+        assert_eq!(queue_position(&waiting), "#2");
+        assert_eq!(queue_position(&queued), "");
+    }
+
+    #[test]
+    fn wait_reasons_are_told_apart() {
+        let queued = |reason: &str| Build {
+            state: Some("queued".to_string()),
+            wait_reason: Some(reason.to_string()),
+            ..Build::default()
+        };
+
+        assert_eq!(
+            wait_kind(&queued(
+                "There are no idle compatible agents which can run this build"
+            )),
+            Some(WaitKind::Agent)
+        );
+        assert_eq!(
+            wait_kind(&queued(
+                "Build is waiting for the following resource to become available: deploy-lock"
+            )),
+            Some(WaitKind::Resource)
+        );
+        assert_eq!(
+            wait_kind(&queued("Build dependencies have not been built yet")),
+            Some(WaitKind::Dependency)
+        );
+        assert_eq!(
+            wait_kind(&queued("Build settings have not been finalized")),
+            None
+        );
+        assert_eq!(wait_kind(&Build::default()), None);
+    }
+
+    #[test]
+    fn column_widths_use_configured_widths_by_name() {
+        let configured = BTreeMap::from([("Branch".to_string(), 50), ("Status".to_string(), 1)]);
+
+        let widths = column_widths(&configured);
+        assert_eq!(widths.len(), COLUMNS.len());
+        assert_eq!(widths[0], DEFAULT_COLUMN_WIDTHS[0]);
+        assert_eq!(widths[1], 50);
+        assert_eq!(widths[2], MIN_COLUMN_WIDTH);
+    }
+
+    #[test]
+    fn resize_column_stays_within_limits() {
+        assert_eq!(resize_column(10, 2), 12);
+        assert_eq!(resize_column(4, -2), MIN_COLUMN_WIDTH);
+        assert_eq!(resize_column(MAX_COLUMN_WIDTH, 2), MAX_COLUMN_WIDTH);
+    }
+
+    #[test]
+    fn baseline_averages_successful_finished_builds() {
+        let finished = |status: &str, minutes: i64| Build {
+            status: Some(status.to_string()),
+            start_date: Some(NOW - Duration::minutes(minutes)),
+            finish_date: Some(NOW),
+            ..Build::default()
+        };
+        let running = Build {
+            state: Some("running".to_string()),
+            status: Some("SUCCESS".to_string()),
+            start_date: Some(NOW - Duration::minutes(60)),
+            ..Build::default()
+        };
+
+        let builds = [
+            finished("SUCCESS", 10),
+            finished("SUCCESS", 20),
+            finished("FAILURE", 1),
+            running,
+        ];
+        assert_eq!(duration_baseline(&builds), Some(15 * 60));
+        assert_eq!(duration_baseline(&builds[2..]), None);
+    }
+
+    #[test]
+    fn running_builds_exceed_baseline_past_threshold() {
+        let running = |minutes: i64| Build {
+            state: Some("running".to_string()),
+            start_date: Some(NOW - Duration::minutes(minutes)),
+            ..Build::default()
+        };
+
+        assert!(!exceeds_baseline(&running(15), 600, 50, NOW));
+        assert!(exceeds_baseline(&running(16), 600, 50, NOW));
+        let finished = Build {
+            state: Some("finished".to_string()),
+            finish_date: Some(NOW),
+            ..running(60)
+        };
+        assert!(!exceeds_baseline(&finished, 600, 50, NOW));
+    }
+
+    #[test]
+    fn remaining_time_comes_from_the_server_estimate() {
+        let running = |elapsed: i64, estimated: Option<i64>| Build {
+            state: Some("running".to_string()),
+            running_info: Some(RunningInfo {
+                elapsed_seconds: Some(elapsed),
+                estimated_total_seconds: estimated,
+            }),
+            ..Build::default()
+        };
+
+        assert_eq!(remaining_secs(&running(60, Some(300)), NOW), Some(240));
+        let started = Build {
+            start_date: Some(NOW - Duration::minutes(2)),
+            ..running(60, Some(300))
+        };
+        assert_eq!(remaining_secs(&started, NOW), Some(180));
+        // overdue builds and builds without an estimate count up instead
+        assert_eq!(remaining_secs(&running(400, Some(300)), NOW), None);
+        assert_eq!(remaining_secs(&running(60, None), NOW), None);
+        let finished = Build {
+            state: Some("finished".to_string()),
+            ..running(60, Some(300))
+        };
+        assert_eq!(remaining_secs(&finished, NOW), None);
+    }
+
+    #[test]
+    fn build_tabs_open_in_both_web_uis() {
+        assert_eq!(
+            build_tab_url(
+                "https://tc.example.com/buildConfiguration/Web_Build/42",
+                BuildTab::Tests
+            ),
+            "https://tc.example.com/buildConfiguration/Web_Build/42?buildTab=tests"
+        );
+        assert_eq!(
+            build_tab_url(
+                "https://tc.example.com/viewLog.html?buildId=42&buildTypeId=Web_Build",
+                BuildTab::Changes
+            ),
+            "https://tc.example.com/viewLog.html?buildId=42&buildTypeId=Web_Build&tab=buildChangesDiv"
+        );
+        assert_eq!(BuildTab::from_key('p'), Some(BuildTab::Parameters));
+        assert_eq!(BuildTab::from_key('q'), None);
+    }
+
+    #[test]
+    fn statistics_are_formatted_by_kind() {
+        let statistics = Properties {
+            count: Some(4),
+            property: [
+                ("ArtifactsSize", "1572864"),
+                ("CodeCoverageL", "81.234"),
+                ("TimeSpentInQueue", "65000"),
+                ("MyCustomValue", "7"),
+            ]
+            .map(|(name, value)| Property {
+                name: name.to_string(),
+                value: Some(value.to_string()),
+            })
+            .to_vec(),
+        };
+        let keys = [
+            "CodeCoverageL",
+            "TotalTestCount",
+            "ArtifactsSize",
+            "TimeSpentInQueue",
+            "MyCustomValue",
+        ]
+        .map(String::from);
+
+        assert_eq!(
+            statistics_summary(&statistics, &keys),
+            vec![
+                "Line coverage 81.2%",
+                "Artifacts 1.5 MiB",
+                "Queue wait 00:01:05",
+                "MyCustomValue 7"
+            ]
+        );
+    }
+
+    #[test]
+    fn narrow_tables_keep_number_status_and_duration() {
+        assert_eq!(visible_columns(80, 0), vec![0, 2, 5]);
+        assert_eq!(visible_columns(80, 3), vec![0, 2, 5]);
+        assert_eq!(visible_columns(120, 7), vec![7, 8, 9, 10]);
+        assert_eq!(visible_columns(120, 0).len(), COLUMNS.len());
+    }
+}
@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+
+use teamcity::types::Build;
+
+/// Change locators of a commit SHA or a TeamCity change ID pasted into the command palette, in
+/// the order to try them. All-digit input may be either, so the change ID comes first.
+pub fn change_locators(input: &str) -> Result<Vec<String>, String> {
+    let input = input.trim().trim_start_matches('#');
+    if input.is_empty() {
+        return Err("No commit or change ID was given".to_string());
+    }
+    if let Some(id) = input.strip_prefix("id:") {
+        return match id.parse::<i64>() {
+            Ok(id) => Ok(vec![format!("id:{}", id)]),
+            Err(_) => Err(format!("'{}' is not a change ID", id)),
+        };
+    }
+    if input.chars().all(|c| c.is_ascii_digit()) {
+        return Ok(vec![format!("id:{}", input), format!("version:{}", input)]);
+    }
+    if input.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Ok(vec![format!("version:{}", input.to_ascii_lowercase())]);
+    }
+    Err(format!("'{}' is not a commit SHA or change ID", input))
+}
+
+/// Build the change was first built in by configuration ID, out of the `first_built_in` IDs the
+/// server reported that are among `builds`
+pub fn first_built_in(builds: &[Build], first_built_in: &[i64]) -> HashMap<String, i64> {
+    builds
+        .iter()
+        .filter(|build| build.id.is_some_and(|id| first_built_in.contains(&id)))
+        .filter_map(|build| Some((build.build_type_id.clone()?, build.id?)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locators_tell_change_ids_from_revisions() {
+        let locators = |input| change_locators(input).map(|l| l.join(" "));
+        assert_eq!(locators("12345").as_deref(), Ok("id:12345 version:12345"));
+        assert_eq!(locators("#42").as_deref(), Ok("id:42 version:42"));
+        assert_eq!(locators("id:1234567").as_deref(), Ok("id:1234567"));
+        assert_eq!(
+            locators("12345678").as_deref(),
+            Ok("id:12345678 version:12345678")
+        );
+        assert_eq!(
+            locators(" 3F2a9C1d0b ").as_deref(),
+            Ok("version:3f2a9c1d0b")
+        );
+        assert!(change_locators("main").is_err());
+        assert!(change_locators("").is_err());
+    }
+
+    #[test]
+    fn first_builds_are_keyed_by_configuration() {
+        let build = |id: i64, build_type_id: &str| Build {
+            id: Some(id),
+            build_type_id: Some(build_type_id.to_string()),
+            ..Build::default()
+        };
+        let builds = vec![
+            build(3, "App_Build"),
+            build(2, "App_Build"),
+            build(5, "App_Deploy"),
+            build(4, "App_Test"),
+        ];
+        let first = first_built_in(&builds, &[2, 5, 99]);
+        assert_eq!(first.get("App_Build"), Some(&2));
+        assert_eq!(first.get("App_Deploy"), Some(&5));
+        assert_eq!(first.get("App_Test"), None);
+    }
+}
@@ -0,0 +1,264 @@
+use crate::action::Action;
+use teamcity::types::BuildType;
+
+/// Commands of the command palette with their usage
+pub const COMMANDS: [(&str, &str); 13] = [
+    ("builds", "builds <config>"),
+    ("trigger", "trigger <config> [branch=<name>]"),
+    ("log", "log <build id>"),
+    ("change", "change <commit sha or change id>"),
+    ("locator", "locator <build locator>"),
+    ("stats", "stats <config>"),
+    ("timeline", "timeline"),
+    ("favorites", "favorites"),
+    ("server", "server"),
+    ("pools", "pools"),
+    ("projects", "projects"),
+    ("config", "config"),
+    ("quit", "quit"),
+];
+
+/// Usage of the commands starting with the first word of `input`, all of them if it is empty
+pub fn command_completions(input: &str) -> Vec<&'static str> {
+    let word = input.split_whitespace().next().unwrap_or_default();
+    COMMANDS
+        .iter()
+        .filter(|(name, _)| name.starts_with(word))
+        .map(|(_, usage)| *usage)
+        .collect()
+}
+
+/// Configuration a command refers to by ID or name: an exact match, case-insensitively, or the
+/// only one whose ID or name contains `query`
+fn find_build_type<'a>(query: &str, build_types: &'a [BuildType]) -> Result<&'a BuildType, String> {
+    if query.is_empty() {
+        return Err("No build configuration was given".to_string());
+    }
+    let needle = query.to_lowercase();
+    if let Some(exact) = build_types
+        .iter()
+        .find(|bt| bt.id.to_lowercase() == needle || bt.name.to_lowercase() == needle)
+    {
+        return Ok(exact);
+    }
+    let matches: Vec<&BuildType> = build_types
+        .iter()
+        .filter(|bt| {
+            bt.id.to_lowercase().contains(&needle) || bt.name.to_lowercase().contains(&needle)
+        })
+        .collect();
+    match matches.as_slice() {
+        [build_type] => Ok(build_type),
+        [] => Err(format!("No build configuration matches '{}'", query)),
+        _ => Err(format!(
+            "'{}' matches {} build configurations: {}",
+            query,
+            matches.len(),
+            matches
+                .iter()
+                .map(|bt| bt.id.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
+/// Action a command typed in the command palette maps to. Arguments are the configuration, given
+/// by ID or name, and `key=value` options.
+pub fn parse_command(input: &str, build_types: &[BuildType]) -> Result<Action, String> {
+    let mut words = input.split_whitespace();
+    let Some(command) = words.next() else {
+        return Err("No command was given".to_string());
+    };
+    // the locator is passed through as typed, it has no options
+    if command == "locator" {
+        let locator = input.trim_start()[command.len()..].trim();
+        if locator.is_empty() {
+            return Err("No build locator was given".to_string());
+        }
+        return Ok(Action::LoadLocatorBuilds {
+            locator: locator.to_string(),
+        });
+    }
+    let (options, args): (Vec<&str>, Vec<&str>) = words.partition(|word| word.contains('='));
+    let query = args.join(" ");
+    let option = |key: &str| {
+        options
+            .iter()
+            .find_map(|option| option.strip_prefix(key)?.strip_prefix('='))
+            .map(str::to_string)
+    };
+    if let Some(unknown) = options.iter().find(|option| !option.starts_with("branch=")) {
+        return Err(format!("Unknown option '{}'", unknown));
+    }
+
+    match command {
+        "builds" | "b" => find_build_type(&query, build_types).map(|bt| {
+            Action::LoadBuilds {
+                project_id: bt.id.clone(),
+                title: bt.name.clone(),
+            }
+            .on_server_of(bt)
+        }),
+        "trigger" | "t" => find_build_type(&query, build_types).map(|bt| {
+            Action::TriggerBuild {
+                build_type_id: bt.id.clone(),
+                title: bt.name.clone(),
+                branch: option("branch"),
+            }
+            .on_server_of(bt)
+        }),
+        "stats" => find_build_type(&query, build_types).map(|bt| {
+            Action::LoadStatistics {
+                project_id: bt.id.clone(),
+                title: bt.name.clone(),
+            }
+            .on_server_of(bt)
+        }),
+        "log" | "l" => query
+            .trim_start_matches('#')
+            .parse()
+            .map(|build_id| Action::LoadBuildLog { build_id })
+            .map_err(|_| format!("'{}' is not a build id", query)),
+        "change" | "c" if query.is_empty() => Err("No commit or change ID was given".to_string()),
+        "change" | "c" => Ok(Action::LoadChangeBuilds { revision: query }),
+        "timeline" => Ok(Action::LoadTimeline),
+        "favorites" => Ok(Action::ShowDashboard),
+        "server" => Ok(Action::LoadServerOverview),
+        "pools" => Ok(Action::LoadAgentPools),
+        "projects" | "p" => Ok(Action::ShowProjects),
+        "config" => Ok(Action::EditConfig),
+        "quit" | "q" => Ok(Action::Quit),
+        _ => Err(format!("Unknown command '{}'", command)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_types() -> Vec<BuildType> {
+        [
+            ("App_Build", "Build"),
+            ("App_Deploy", "Deploy to staging"),
+            ("App_DeployProd", "Deploy to production"),
+        ]
+        .into_iter()
+        .map(|(id, name)| BuildType {
+            id: id.to_string(),
+            name: name.to_string(),
+            ..BuildType::default()
+        })
+        .collect()
+    }
+
+    #[test]
+    fn builds_finds_config_by_id_or_name() {
+        let build_types = build_types();
+        let load = |project_id: &str, title: &str| {
+            Ok(Action::LoadBuilds {
+                project_id: project_id.to_string(),
+                title: title.to_string(),
+            })
+        };
+
+        assert_eq!(
+            parse_command("builds app_build", &build_types),
+            load("App_Build", "Build")
+        );
+        assert_eq!(
+            parse_command("builds Deploy to staging", &build_types),
+            load("App_Deploy", "Deploy to staging")
+        );
+        assert_eq!(
+            parse_command("b production", &build_types),
+            load("App_DeployProd", "Deploy to production")
+        );
+        assert!(parse_command("builds deploy to", &build_types).is_err());
+        assert!(parse_command("builds nothing", &build_types).is_err());
+    }
+
+    #[test]
+    fn trigger_takes_a_branch_option() {
+        assert_eq!(
+            parse_command("trigger App_Build branch=main", &build_types()),
+            Ok(Action::TriggerBuild {
+                build_type_id: "App_Build".to_string(),
+                title: "Build".to_string(),
+                branch: Some("main".to_string()),
+            })
+        );
+        assert!(matches!(
+            parse_command("trigger App_Build", &build_types()),
+            Ok(Action::TriggerBuild { branch: None, .. })
+        ));
+        assert!(parse_command("trigger App_Build color=red", &build_types()).is_err());
+    }
+
+    #[test]
+    fn configurations_of_another_server_are_opened_on_it() {
+        let build_types = [BuildType {
+            id: "App_Build".to_string(),
+            name: "Build".to_string(),
+            server: Some("staging".to_string()),
+            ..BuildType::default()
+        }];
+
+        assert_eq!(
+            parse_command("builds App_Build", &build_types),
+            Ok(Action::OnServer {
+                server: "staging".to_string(),
+                action: Box::new(Action::LoadBuilds {
+                    project_id: "App_Build".to_string(),
+                    title: "Build".to_string(),
+                }),
+            })
+        );
+    }
+
+    #[test]
+    fn log_takes_a_build_id() {
+        assert_eq!(
+            parse_command("log #12345", &[]),
+            Ok(Action::LoadBuildLog { build_id: 12345 })
+        );
+        assert!(parse_command("log latest", &[]).is_err());
+        assert!(parse_command("frobnicate", &[]).is_err());
+        assert!(parse_command("   ", &[]).is_err());
+    }
+
+    #[test]
+    fn change_takes_a_revision() {
+        assert_eq!(
+            parse_command("change 3f2a9c1d", &[]),
+            Ok(Action::LoadChangeBuilds {
+                revision: "3f2a9c1d".to_string()
+            })
+        );
+        assert!(parse_command("change", &[]).is_err());
+    }
+
+    #[test]
+    fn locator_is_passed_through() {
+        assert_eq!(
+            parse_command(
+                "locator buildType:App_Build,property:(name:env.X,value:a=b)",
+                &[]
+            ),
+            Ok(Action::LoadLocatorBuilds {
+                locator: "buildType:App_Build,property:(name:env.X,value:a=b)".to_string()
+            })
+        );
+        assert!(parse_command("locator", &[]).is_err());
+    }
+
+    #[test]
+    fn completions_match_the_command_prefix() {
+        assert_eq!(
+            command_completions("t"),
+            vec!["trigger <config> [branch=<name>]", "timeline"]
+        );
+        assert_eq!(command_completions("").len(), COMMANDS.len());
+        assert!(command_completions("x").is_empty());
+    }
+}
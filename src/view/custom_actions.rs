@@ -0,0 +1,91 @@
+use teamcity::types::{Build, BuildType};
+
+/// Values of the placeholders in a custom action on a build
+pub fn build_placeholders(build: &Build) -> Vec<(&'static str, String)> {
+    let value = |value: &Option<String>| value.clone().unwrap_or_default();
+    vec![
+        ("id", build.id.map(|id| id.to_string()).unwrap_or_default()),
+        ("number", value(&build.build_number)),
+        ("status", value(&build.status)),
+        ("state", value(&build.state)),
+        ("statusText", value(&build.status_text)),
+        ("branch", value(&build.branch_name)),
+        ("buildTypeId", value(&build.build_type_id)),
+        ("webUrl", value(&build.web_url)),
+    ]
+}
+
+/// Values of the placeholders in a custom action on a build configuration
+pub fn build_type_placeholders(build_type: &BuildType) -> Vec<(&'static str, String)> {
+    let value = |value: &Option<String>| value.clone().unwrap_or_default();
+    vec![
+        ("id", build_type.id.clone()),
+        ("name", build_type.name.clone()),
+        ("projectId", value(&build_type.project_id)),
+        ("projectName", value(&build_type.project_name)),
+        ("webUrl", value(&build_type.web_url)),
+    ]
+}
+
+/// Program and arguments of a custom action: `template` split on whitespace, with the `{name}`
+/// placeholders in each word replaced. Values are passed as single arguments rather than through a
+/// shell, so branch names and status texts can't run commands of their own.
+pub fn command_line(template: &str, placeholders: &[(&str, String)]) -> Vec<String> {
+    template
+        .split_whitespace()
+        .map(|word| {
+            placeholders
+                .iter()
+                .fold(word.to_string(), |word, (name, value)| {
+                    word.replace(&format!("{{{}}}", name), value)
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn command_line_substitutes_build_placeholders() {
+        let build = Build {
+            id: Some(42),
+            status: Some("FAILURE".to_string()),
+            branch_name: Some("feature/a b; rm -rf ~".to_string()),
+            web_url: Some("https://ci/build/42".to_string()),
+            ..Build::default()
+        };
+
+        assert_eq!(
+            command_line(
+                "scripts/notify.sh {webUrl} --status={status} {branch} {unknown}",
+                &build_placeholders(&build)
+            ),
+            vec![
+                "scripts/notify.sh",
+                "https://ci/build/42",
+                "--status=FAILURE",
+                "feature/a b; rm -rf ~",
+                "{unknown}",
+            ]
+        );
+    }
+
+    #[test]
+    fn command_line_substitutes_build_type_placeholders() {
+        let build_type = BuildType {
+            id: "App_Build".to_string(),
+            name: "Build".to_string(),
+            ..BuildType::default()
+        };
+
+        assert_eq!(
+            command_line(
+                "open-dashboard {id}:{name}",
+                &build_type_placeholders(&build_type)
+            ),
+            vec!["open-dashboard", "App_Build:Build"]
+        );
+    }
+}
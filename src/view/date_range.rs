@@ -0,0 +1,115 @@
+use std::time::Duration;
+
+use teamcity::DateRange;
+use time::macros::format_description;
+use time::{Date, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
+
+const DAY: Duration = Duration::from_secs(24 * 3600);
+
+/// Ranges of the date range picker by their key, `0` going back to the latest builds
+pub const PRESETS: [(char, &str, Duration); 3] = [
+    ('1', "Last 24 hours", DAY),
+    ('2', "Last 7 days", Duration::from_secs(7 * 24 * 3600)),
+    ('3', "Last 30 days", Duration::from_secs(30 * 24 * 3600)),
+];
+
+pub fn preset(key: char) -> Option<DateRange> {
+    PRESETS
+        .iter()
+        .find(|(preset_key, _, _)| *preset_key == key)
+        .map(|(_, _, duration)| DateRange::Last(*duration))
+}
+
+/// Range typed as `since..until` in the time zone `offset`, either bound left out, or as a single
+/// date for the builds since then. Dates are `YYYY-MM-DD` or `YYYY-MM-DD HH:MM`; an end date
+/// without a time includes that whole day.
+pub fn parse_range(input: &str, offset: UtcOffset) -> Result<DateRange, String> {
+    let (since, until) = input.split_once("..").unwrap_or((input, ""));
+    let since = parse_bound(since, offset, false)?;
+    let until = parse_bound(until, offset, true)?;
+    match (since, until) {
+        (None, None) => Err("Type a date range like 2025-01-01..2025-01-31".to_string()),
+        (Some(since), Some(until)) if until <= since => {
+            Err("The date range ends before it starts".to_string())
+        }
+        (since, until) => Ok(DateRange::Between { since, until }),
+    }
+}
+
+fn parse_bound(
+    text: &str,
+    offset: UtcOffset,
+    end_of_day: bool,
+) -> Result<Option<OffsetDateTime>, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(None);
+    }
+    let datetime = PrimitiveDateTime::parse(
+        text,
+        format_description!("[year]-[month]-[day] [hour]:[minute]"),
+    )
+    .or_else(|_| {
+        Date::parse(text, format_description!("[year]-[month]-[day]")).map(|date| {
+            let midnight = date.with_time(Time::MIDNIGHT);
+            if end_of_day { midnight + DAY } else { midnight }
+        })
+    })
+    .map_err(|_| format!("Invalid date {}, use YYYY-MM-DD or YYYY-MM-DD HH:MM", text))?;
+    Ok(Some(datetime.assume_offset(offset)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::{datetime, offset};
+
+    #[test]
+    fn bounds_are_dates_with_an_optional_time() {
+        assert_eq!(
+            parse_range("2025-01-01..2025-01-31 18:30", offset!(+1)),
+            Ok(DateRange::Between {
+                since: Some(datetime!(2025-01-01 00:00 +1)),
+                until: Some(datetime!(2025-01-31 18:30 +1)),
+            })
+        );
+        assert_eq!(
+            parse_range("..2025-01-31", offset!(UTC)),
+            Ok(DateRange::Between {
+                since: None,
+                until: Some(datetime!(2025-02-01 00:00 UTC)),
+            })
+        );
+        assert_eq!(
+            parse_range(" 2025-01-01 ", offset!(UTC)),
+            Ok(DateRange::Between {
+                since: Some(datetime!(2025-01-01 00:00 UTC)),
+                until: None,
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_ranges_say_why() {
+        assert_eq!(
+            parse_range("yesterday", offset!(UTC)),
+            Err("Invalid date yesterday, use YYYY-MM-DD or YYYY-MM-DD HH:MM".to_string())
+        );
+        assert!(parse_range("..", offset!(UTC)).is_err());
+        assert_eq!(
+            parse_range("2025-02-01..2025-01-31", offset!(UTC)),
+            Err("The date range ends before it starts".to_string())
+        );
+    }
+
+    #[test]
+    fn presets_are_ranges_back_from_now() {
+        assert_eq!(
+            preset('2'),
+            Some(DateRange::Last(Duration::from_secs(7 * 24 * 3600)))
+        );
+        assert_eq!(preset('0'), None);
+        assert_eq!(DateRange::Last(DAY).label(), "last 24h");
+        assert_eq!(preset('3').unwrap().label(), "last 30 days");
+    }
+}
@@ -0,0 +1,132 @@
+use std::collections::HashMap;
+
+use teamcity::types::Build;
+
+/// State of a queued or running build, status of a finished one
+pub fn state_label(build: &Build) -> &str {
+    match build.state.as_deref() {
+        Some("finished") | None => build.status.as_deref().unwrap_or("UNKNOWN"),
+        Some(state) => state,
+    }
+}
+
+/// Builds of `latest` that are a new build or changed state since the `previous` poll, keyed by
+/// build configuration ID; configurations polled for the first time are not reported
+pub fn changed_builds<'a>(
+    previous: &HashMap<String, Build>,
+    latest: &'a [Build],
+) -> Vec<&'a Build> {
+    latest
+        .iter()
+        .filter(|build| {
+            let Some(build_type_id) = &build.build_type_id else {
+                return false;
+            };
+            previous
+                .get(build_type_id)
+                .is_some_and(|before| is_changed(before, build))
+        })
+        .collect()
+}
+
+/// Whether `build` is another build than `before` or the same one in another state
+pub fn is_changed(before: &Build, build: &Build) -> bool {
+    before.id != build.id || state_label(before) != state_label(build)
+}
+
+/// Favorite configuration with its latest polled build, None until one is polled
+#[derive(Debug, Clone, PartialEq)]
+pub struct FavoriteRow {
+    pub build_type_id: String,
+    pub build: Option<Build>,
+}
+
+/// Row of each of `favorites`, in their order, with its build among `latest`
+pub fn favorite_rows(favorites: &[String], latest: &HashMap<String, Build>) -> Vec<FavoriteRow> {
+    favorites
+        .iter()
+        .map(|build_type_id| FavoriteRow {
+            build_type_id: build_type_id.clone(),
+            build: latest.get(build_type_id).cloned(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build(build_type_id: &str, id: i64, state: &str, status: &str) -> Build {
+        Build {
+            id: Some(id),
+            build_type_id: Some(build_type_id.to_string()),
+            state: Some(state.to_string()),
+            status: Some(status.to_string()),
+            ..Build::default()
+        }
+    }
+
+    #[test]
+    fn labels_show_state_until_finished() {
+        assert_eq!(
+            state_label(&build("Web", 1, "running", "SUCCESS")),
+            "running"
+        );
+        assert_eq!(
+            state_label(&build("Web", 1, "finished", "FAILURE")),
+            "FAILURE"
+        );
+        assert_eq!(state_label(&Build::default()), "UNKNOWN");
+    }
+
+    #[test]
+    fn new_builds_and_state_changes_are_reported() {
+        let previous = HashMap::from([
+            ("Web".to_string(), build("Web", 1, "running", "SUCCESS")),
+            ("Api".to_string(), build("Api", 7, "finished", "SUCCESS")),
+            ("Docs".to_string(), build("Docs", 3, "finished", "SUCCESS")),
+        ]);
+        let latest = vec![
+            build("Web", 1, "finished", "FAILURE"),
+            build("Api", 8, "queued", "UNKNOWN"),
+            build("Docs", 3, "finished", "SUCCESS"),
+            build("New", 1, "finished", "SUCCESS"),
+        ];
+
+        let changed: Vec<Option<i64>> = changed_builds(&previous, &latest)
+            .iter()
+            .map(|b| b.id)
+            .collect();
+        assert_eq!(changed, [Some(1), Some(8)]);
+    }
+
+    #[test]
+    fn first_poll_reports_nothing() {
+        let latest = vec![build("Web", 1, "finished", "FAILURE")];
+        assert!(changed_builds(&HashMap::new(), &latest).is_empty());
+    }
+
+    #[test]
+    fn rows_follow_the_favorites_with_or_without_a_build() {
+        let latest = HashMap::from([
+            ("Api".to_string(), build("Api", 8, "running", "SUCCESS")),
+            ("Old".to_string(), build("Old", 2, "finished", "SUCCESS")),
+        ]);
+
+        let rows = favorite_rows(&["Web".to_string(), "Api".to_string()], &latest);
+
+        assert_eq!(
+            rows,
+            vec![
+                FavoriteRow {
+                    build_type_id: "Web".to_string(),
+                    build: None,
+                },
+                FavoriteRow {
+                    build_type_id: "Api".to_string(),
+                    build: Some(build("Api", 8, "running", "SUCCESS")),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,258 @@
+use regex::{Regex, RegexBuilder};
+use std::ops::Range;
+
+/// What a term of a filter query is matched against
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    // the term has no prefix
+    Name,
+    Id,
+    Project,
+    Type,
+    Tag,
+}
+
+impl Field {
+    fn prefix(self) -> &'static str {
+        match self {
+            Field::Name => "",
+            Field::Id => "id:",
+            Field::Project => "project:",
+            Field::Type => "type:",
+            Field::Tag => "tag:",
+        }
+    }
+}
+
+/// Case-insensitive substring, or a regular expression written as `/pattern/`
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    // in lowercase
+    Text(String),
+    Regex(Regex),
+}
+
+impl Pattern {
+    fn parse(pattern: &str) -> Result<Self, String> {
+        match pattern
+            .strip_prefix('/')
+            .and_then(|rest| rest.strip_suffix('/'))
+        {
+            Some(regex) => RegexBuilder::new(regex)
+                .case_insensitive(true)
+                .build()
+                .map(Pattern::Regex)
+                .map_err(|e| format!("Invalid pattern /{}/: {}", regex, error_summary(&e))),
+            None => Ok(Pattern::Text(pattern.to_lowercase())),
+        }
+    }
+
+    /// Byte range of the first match in `value`
+    pub fn find(&self, value: &str) -> Option<Range<usize>> {
+        match self {
+            Pattern::Text(text) => find_ignore_case(value, text),
+            Pattern::Regex(regex) => regex.find(value).map(|m| m.range()),
+        }
+    }
+
+    fn is_match(&self, value: &str) -> bool {
+        match self {
+            Pattern::Text(text) => value.to_lowercase().contains(text),
+            Pattern::Regex(regex) => regex.is_match(value),
+        }
+    }
+}
+
+/// Filter typed in a table: space-separated terms that all have to match, each a [`Pattern`] on
+/// the name or, prefixed with `id:`, `project:`, `type:` or `tag:`, on that field. A term with
+/// any other prefix is matched on the name as typed, and input of several words also matches
+/// names containing it as a whole, like the plain name filter.
+#[derive(Debug, Clone)]
+pub struct FilterQuery {
+    terms: Vec<(Field, Pattern)>,
+    // whole input in lowercase, if it has several words
+    phrase: Option<String>,
+}
+
+impl FilterQuery {
+    /// Parses `query` for a table whose rows have `fields`, failing on an invalid regular
+    /// expression
+    pub fn parse(query: &str, fields: &[Field]) -> Result<Self, String> {
+        let mut terms = Vec::new();
+        for term in query.split_whitespace() {
+            let field = term.split_once(':').and_then(|(prefix, pattern)| {
+                fields
+                    .iter()
+                    .copied()
+                    .filter(|field| *field != Field::Name)
+                    .find(|field| field.prefix().trim_end_matches(':') == prefix)
+                    .map(|field| (field, pattern))
+            });
+            // like `build:` in a name, the prefix is part of what's looked for
+            let (field, pattern) = field.unwrap_or((Field::Name, term));
+            if !pattern.is_empty() {
+                terms.push((field, Pattern::parse(pattern)?));
+            }
+        }
+        let query = query.trim();
+        let phrase = query
+            .contains(char::is_whitespace)
+            .then(|| query.to_lowercase());
+        Ok(Self { terms, phrase })
+    }
+
+    /// Whether every term matches one of the values `values` gives for its field, like one of the
+    /// tags of a build, or the name contains the whole input; a field without values matches no
+    /// term
+    pub fn matches<'a>(&self, values: impl Fn(Field) -> Vec<&'a str>) -> bool {
+        let terms_match = self.terms.iter().all(|(field, pattern)| {
+            values(*field)
+                .into_iter()
+                .any(|value| pattern.is_match(value))
+        });
+        terms_match
+            || self.phrase.as_ref().is_some_and(|phrase| {
+                values(Field::Name)
+                    .into_iter()
+                    .any(|name| name.to_lowercase().contains(phrase))
+            })
+    }
+
+    /// Range of `name` matched by the whole input or else the first term on the name, to
+    /// highlight it
+    pub fn name_match(&self, name: &str) -> Option<Range<usize>> {
+        self.phrase
+            .as_ref()
+            .and_then(|phrase| find_ignore_case(name, phrase))
+            .or_else(|| {
+                self.terms
+                    .iter()
+                    .filter(|(field, _)| *field == Field::Name)
+                    .find_map(|(_, pattern)| pattern.find(name))
+            })
+            .filter(|range| !range.is_empty())
+    }
+}
+
+// regex errors span several lines pointing at the faulty part, the last one says what's wrong
+fn error_summary(error: &regex::Error) -> String {
+    let message = error.to_string();
+    message
+        .lines()
+        .rev()
+        .find(|line| !line.trim().is_empty())
+        .unwrap_or_default()
+        .trim_start_matches("error: ")
+        .to_string()
+}
+
+/// Byte range of the first case-insensitive occurrence of `needle` in `haystack`; `needle` is
+/// expected in lowercase
+fn find_ignore_case(haystack: &str, needle: &str) -> Option<Range<usize>> {
+    if needle.is_empty() {
+        return None;
+    }
+    haystack.char_indices().find_map(|(start, _)| {
+        let mut wanted = needle.chars().peekable();
+        for (offset, c) in haystack[start..].char_indices() {
+            for lower in c.to_lowercase() {
+                if wanted.next() != Some(lower) {
+                    return None;
+                }
+            }
+            if wanted.peek().is_none() {
+                return Some(start..start + offset + c.len_utf8());
+            }
+        }
+        None
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIELDS: [Field; 5] = [
+        Field::Name,
+        Field::Id,
+        Field::Project,
+        Field::Type,
+        Field::Tag,
+    ];
+
+    fn matches(query: &str, name: &str, id: &str) -> bool {
+        FilterQuery::parse(query, &FIELDS)
+            .unwrap()
+            .matches(|field| match field {
+                Field::Name => vec![name],
+                Field::Id => vec![id],
+                Field::Project => vec!["Web"],
+                Field::Type => Vec::new(),
+                Field::Tag => vec!["nightly", "release"],
+            })
+    }
+
+    #[test]
+    fn terms_match_their_field_ignoring_case() {
+        assert!(matches("deploy", "Deploy to prod", "Web_Deploy"));
+        assert!(matches("id:web_ prod", "Deploy to prod", "Web_Deploy"));
+        assert!(matches("project:web", "Deploy", "Web_Deploy"));
+        assert!(!matches("id:api", "Deploy", "Web_Deploy"));
+        assert!(!matches("type:regular", "Deploy", "Web_Deploy"));
+        assert!(matches("", "Deploy", "Web_Deploy"));
+    }
+
+    #[test]
+    fn terms_match_any_value_of_their_field() {
+        assert!(matches("tag:release", "Deploy", "Web_Deploy"));
+        assert!(matches("tag:/^nightly$/ tag:rel", "Deploy", "Web_Deploy"));
+        assert!(!matches("tag:hotfix", "Deploy", "Web_Deploy"));
+    }
+
+    #[test]
+    fn slashes_make_a_regex() {
+        assert!(matches("/^dep.*prod$/", "Deploy to prod", "Web_Deploy"));
+        assert!(matches("id:/_deploy$/", "Deploy", "Web_Deploy"));
+        assert!(!matches("/^prod/", "Deploy to prod", "Web_Deploy"));
+    }
+
+    #[test]
+    fn invalid_patterns_say_why() {
+        let error = FilterQuery::parse("/[a-/", &FIELDS).unwrap_err();
+        assert!(error.starts_with("Invalid pattern /[a-/"), "{}", error);
+    }
+
+    #[test]
+    fn unknown_prefixes_and_phrases_match_the_name() {
+        assert!(matches("build:", "Build: Linux", "Web_Build"));
+        assert!(matches("build: linux", "Build: Linux", "Web_Build"));
+        assert!(!matches("branch:main", "Build: Linux", "Web_Build"));
+        assert!(matches("deploy to", "Deploy to prod", "Web_Deploy"));
+        // a known prefix in a name typed as is
+        assert!(matches("id:42 mirror", "Sync id:42 mirror", "Web_Sync"));
+        assert!(!matches("id:42", "Sync id:42 mirror", "Web_Sync"));
+    }
+
+    #[test]
+    fn name_match_spans_the_first_occurrence() {
+        let query = |query: &str| FilterQuery::parse(query, &FIELDS).unwrap();
+        assert_eq!(
+            query("id:x tests").name_match("Integration Tests"),
+            Some(12..17)
+        );
+        assert_eq!(
+            query("/t.st/").name_match("Integration Tests"),
+            Some(12..16)
+        );
+        assert_eq!(query("id:tests").name_match("Integration Tests"), None);
+        assert_eq!(query("n te").name_match("Integration Tests"), Some(10..14));
+    }
+
+    #[test]
+    fn find_ignore_case_handles_multibyte_characters() {
+        assert_eq!(find_ignore_case("Build build", "build"), Some(0..5));
+        assert_eq!(find_ignore_case("Déploiement", "ploi"), Some(3..7));
+        assert_eq!(find_ignore_case("Deploy", "api"), None);
+        assert_eq!(find_ignore_case("Deploy", ""), None);
+    }
+}
@@ -0,0 +1,122 @@
+use serde::{Deserialize, Serialize};
+
+use teamcity::types::Build;
+
+// matching lines kept per build for the preview, the count covers all of them
+pub const MAX_PREVIEW_LINES: usize = 50;
+
+/// A line of a build log containing the searched string, numbered from 1
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LineMatch {
+    pub number: usize,
+    pub text: String,
+}
+
+/// Lines of a build's log containing the searched string
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildMatches {
+    pub build: Build,
+    // all matching lines, `lines` holds the first of them
+    pub count: usize,
+    pub lines: Vec<LineMatch>,
+    // the log couldn't be downloaded
+    pub error: Option<String>,
+}
+
+impl BuildMatches {
+    pub fn failed(build: Build, error: String) -> Self {
+        Self {
+            build,
+            count: 0,
+            lines: Vec::new(),
+            error: Some(error),
+        }
+    }
+}
+
+/// Whether `line` contains `query`, ignoring case unless the query has an uppercase letter
+pub fn line_matches(line: &str, query: &str) -> bool {
+    if query.chars().any(char::is_uppercase) {
+        line.contains(query)
+    } else {
+        line.to_lowercase().contains(query)
+    }
+}
+
+/// Lines of the log of `build` containing `query`
+pub fn grep_log(build: Build, log: &str, query: &str) -> BuildMatches {
+    let mut count = 0;
+    let mut lines = Vec::new();
+    for (i, line) in log.lines().enumerate() {
+        if !line_matches(line, query) {
+            continue;
+        }
+        count += 1;
+        if lines.len() < MAX_PREVIEW_LINES {
+            lines.push(LineMatch {
+                number: i + 1,
+                text: line.trim_end().to_string(),
+            });
+        }
+    }
+    BuildMatches {
+        build,
+        count,
+        lines,
+        error: None,
+    }
+}
+
+/// Oldest build of the unbroken run of matching builds at the top of `results`, newest first:
+/// the build the string has been in the logs since
+pub fn present_since(results: &[BuildMatches]) -> Option<&BuildMatches> {
+    results
+        .iter()
+        .filter(|result| result.error.is_none())
+        .take_while(|result| result.count > 0)
+        .last()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOG: &str = "[10:00:00]i: Starting\n\
+                       [10:00:01] : warning: deprecated API\n\
+                       [10:00:02] : Compiling\n\
+                       [10:00:03]W: Warning: deprecated API";
+
+    fn build(number: &str) -> Build {
+        Build {
+            build_number: Some(number.to_string()),
+            ..Build::default()
+        }
+    }
+
+    #[test]
+    fn lowercase_queries_ignore_case() {
+        let result = grep_log(build("1"), LOG, "deprecated api");
+        assert_eq!(result.count, 2);
+        assert_eq!(result.lines[0].number, 2);
+        assert_eq!(result.lines[1].number, 4);
+
+        let result = grep_log(build("1"), LOG, "Warning");
+        assert_eq!(result.count, 1);
+        assert_eq!(result.lines[0].text, "[10:00:03]W: Warning: deprecated API");
+    }
+
+    #[test]
+    fn string_is_present_since_the_oldest_build_of_the_latest_run() {
+        let results = vec![
+            grep_log(build("5"), LOG, "deprecated"),
+            BuildMatches::failed(build("4"), "timed out".to_string()),
+            grep_log(build("3"), LOG, "deprecated"),
+            grep_log(build("2"), "clean", "deprecated"),
+            grep_log(build("1"), LOG, "deprecated"),
+        ];
+        let since = present_since(&results).unwrap();
+        assert_eq!(since.build.build_number.as_deref(), Some("3"));
+
+        assert!(present_since(&results[3..]).is_none());
+    }
+}
@@ -0,0 +1,24 @@
+/// Terminal bell followed by an OSC 9 notification with `message`, which terminals like iTerm2,
+/// Windows Terminal or kitty show as a desktop notification and others ignore
+pub fn notification_sequence(message: &str) -> String {
+    // a control character would end the sequence early
+    let message: String = message.chars().filter(|c| !c.is_control()).collect();
+    format!("\x07\x1b]9;{}\x07", message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn message_cannot_end_the_sequence() {
+        assert_eq!(
+            notification_sequence("Build #12 finished"),
+            "\x07\x1b]9;Build #12 finished\x07"
+        );
+        assert_eq!(
+            notification_sequence("Build\x07 #12\n finished"),
+            "\x07\x1b]9;Build #12 finished\x07"
+        );
+    }
+}
@@ -0,0 +1,97 @@
+use teamcity::types::Parameter;
+
+// TeamCity answers with this placeholder instead of the value of a password parameter
+const MASKED_VALUE: &str = "••••••";
+
+fn spec(parameter: &Parameter) -> &str {
+    parameter
+        .kind
+        .as_ref()
+        .and_then(|kind| kind.raw_value.as_deref())
+        .unwrap_or_default()
+}
+
+/// Password parameters, whose value the server never hands out
+pub fn is_secret(parameter: &Parameter) -> bool {
+    spec(parameter).starts_with("password")
+}
+
+/// Value as shown in the table, masked for password parameters
+pub fn display_value(parameter: &Parameter) -> String {
+    if is_secret(parameter) {
+        MASKED_VALUE.to_string()
+    } else {
+        parameter.value.clone().unwrap_or_default()
+    }
+}
+
+/// Flipped value of a boolean parameter, `None` for any other parameter. Checkbox parameters with
+/// custom checked and unchecked values are toggled between those.
+pub fn toggled_value(parameter: &Parameter) -> Option<String> {
+    let value = parameter.value.as_deref().unwrap_or_default();
+    let spec = spec(parameter);
+    if spec.starts_with("checkbox") {
+        let checked = spec_attribute(spec, "checkedValue").unwrap_or("true");
+        let unchecked = spec_attribute(spec, "uncheckedValue").unwrap_or("false");
+        return Some(if value == checked { unchecked } else { checked }.to_string());
+    }
+    match value {
+        "true" => Some("false".to_string()),
+        "false" => Some("true".to_string()),
+        _ => None,
+    }
+}
+
+/// Value of `name='value'` in a parameter spec like `checkbox checkedValue='on'`
+fn spec_attribute<'a>(spec: &'a str, name: &str) -> Option<&'a str> {
+    let start = spec.find(&format!("{}='", name))? + name.len() + 2;
+    let len = spec[start..].find('\'')?;
+    Some(&spec[start..start + len])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use teamcity::types::ParameterType;
+
+    fn parameter(value: &str, spec: Option<&str>) -> Parameter {
+        Parameter {
+            name: "env.FEATURE".to_string(),
+            value: Some(value.to_string()),
+            kind: spec.map(|spec| ParameterType {
+                raw_value: Some(spec.to_string()),
+            }),
+            ..Parameter::default()
+        }
+    }
+
+    #[test]
+    fn password_values_are_masked() {
+        let password = parameter("", Some("password display='hidden'"));
+        assert!(is_secret(&password));
+        assert_eq!(display_value(&password), MASKED_VALUE);
+        assert_eq!(display_value(&parameter("42", None)), "42");
+    }
+
+    #[test]
+    fn only_boolean_parameters_toggle() {
+        assert_eq!(
+            toggled_value(&parameter("true", None)).as_deref(),
+            Some("false")
+        );
+        assert_eq!(
+            toggled_value(&parameter("false", None)).as_deref(),
+            Some("true")
+        );
+        assert_eq!(toggled_value(&parameter("yes", None)), None);
+        let checkbox = Some("checkbox checkedValue='on' uncheckedValue='off'");
+        assert_eq!(
+            toggled_value(&parameter("on", checkbox)).as_deref(),
+            Some("off")
+        );
+        assert_eq!(
+            toggled_value(&parameter("", checkbox)).as_deref(),
+            Some("on")
+        );
+    }
+}
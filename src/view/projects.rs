@@ -0,0 +1,426 @@
+use std::collections::HashSet;
+
+use crate::icons::IconSet;
+use crate::view::filter_query::{Field, FilterQuery};
+use teamcity::types::BuildType;
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortMode {
+    // grouped under collapsible project headers
+    #[default]
+    Project,
+    Name,
+    Recent,
+}
+
+impl SortMode {
+    pub fn next(self) -> Self {
+        match self {
+            SortMode::Project => SortMode::Name,
+            SortMode::Name => SortMode::Recent,
+            SortMode::Recent => SortMode::Project,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SortMode::Project => "project",
+            SortMode::Name => "name",
+            SortMode::Recent => "recently used",
+        }
+    }
+}
+
+// A row of the table: either a project section header or the index of a build configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProjectRow {
+    Group {
+        name: String,
+        count: usize,
+        collapsed: bool,
+    },
+    BuildType(usize),
+}
+
+pub fn project_name(build_type: &BuildType) -> &str {
+    build_type.project_name.as_deref().unwrap_or("N/A")
+}
+
+/// Icon of the type of a configuration, or the paused icon if it is paused
+pub fn icon_for(build_type: &BuildType, icons: IconSet) -> &'static str {
+    if build_type.paused == Some(true) {
+        return icons.paused();
+    }
+    kind_icon(build_type.kind.as_deref().unwrap_or_default(), icons)
+}
+
+/// Icon of a build configuration type like "regular" or "composite", in any case
+pub fn kind_icon(kind: &str, icons: IconSet) -> &'static str {
+    match kind.to_ascii_lowercase().as_str() {
+        "regular" => icons.pick("⚙️", "\u{f013}", "R"),
+        "composite" => icons.pick("🧩", "\u{f12e}", "C"),
+        "deployment" => icons.pick("🚀", "\u{f135}", "D"),
+        _ => icons.pick("📦", "\u{f187}", "-"),
+    }
+}
+
+pub const EXPORT_COLUMNS: [&str; 6] = ["id", "name", "project", "project_id", "type", "web_url"];
+
+/// Fields of `build_type` in the order of [`EXPORT_COLUMNS`]
+pub fn export_row(build_type: &BuildType) -> Vec<String> {
+    vec![
+        build_type.id.clone(),
+        build_type.name.clone(),
+        build_type.project_name.clone().unwrap_or_default(),
+        build_type.project_id.clone().unwrap_or_default(),
+        build_type.kind.clone().unwrap_or_default(),
+        build_type.web_url.clone().unwrap_or_default(),
+    ]
+}
+
+/// Fields a filter on build configurations can match
+pub const FILTER_FIELDS: [Field; 4] = [Field::Name, Field::Id, Field::Project, Field::Type];
+
+/// Match of the name, ID, project name and type of the configuration
+pub fn matches_filter(build_type: &BuildType, filter: Option<&FilterQuery>) -> bool {
+    filter.is_none_or(|filter| {
+        filter.matches(|field| match field {
+            Field::Name => vec![&build_type.name],
+            Field::Id => vec![&build_type.id],
+            Field::Project => vec![project_name(build_type)],
+            Field::Type => build_type.kind.as_deref().into_iter().collect(),
+            Field::Tag => Vec::new(),
+        })
+    })
+}
+
+/// Indices into `build_types` of the configurations matching `filter`, ordered by `sort_mode`.
+///
+/// `recent` holds build configuration IDs, most recently opened first.
+pub fn filter_and_sort(
+    build_types: &[BuildType],
+    filter: Option<&FilterQuery>,
+    sort_mode: SortMode,
+    recent: &[String],
+) -> Vec<usize> {
+    let mut indices: Vec<usize> = build_types
+        .iter()
+        .enumerate()
+        .filter(|(_, build_type)| matches_filter(build_type, filter))
+        .map(|(index, _)| index)
+        .collect();
+
+    let by_name = |a: &BuildType, b: &BuildType| a.name.cmp(&b.name);
+    match sort_mode {
+        SortMode::Project => indices.sort_by(|&a, &b| {
+            let (a, b) = (&build_types[a], &build_types[b]);
+            project_name(a)
+                .cmp(project_name(b))
+                .then_with(|| by_name(a, b))
+        }),
+        SortMode::Name => indices.sort_by(|&a, &b| by_name(&build_types[a], &build_types[b])),
+        SortMode::Recent => {
+            let recency = |bt: &BuildType| {
+                recent
+                    .iter()
+                    .position(|id| *id == bt.id)
+                    .unwrap_or(usize::MAX)
+            };
+            indices.sort_by(|&a, &b| {
+                let (a, b) = (&build_types[a], &build_types[b]);
+                recency(a).cmp(&recency(b)).then_with(|| by_name(a, b))
+            })
+        }
+    }
+    indices
+}
+
+/// Table rows for the `indices` into `build_types` as sorted by [`filter_and_sort`]; in project
+/// mode each project gets a header row and the configurations of collapsed projects are left out
+pub fn project_rows(
+    build_types: &[BuildType],
+    indices: &[usize],
+    sort_mode: SortMode,
+    collapsed_groups: &HashSet<String>,
+) -> Vec<ProjectRow> {
+    if sort_mode != SortMode::Project {
+        return indices.iter().copied().map(ProjectRow::BuildType).collect();
+    }
+
+    // build types are sorted by project, so each group is a contiguous run
+    let mut groups: Vec<(&str, Vec<usize>)> = Vec::new();
+    for &index in indices {
+        let name = project_name(&build_types[index]);
+        match groups.last_mut() {
+            Some((group_name, group)) if *group_name == name => group.push(index),
+            _ => groups.push((name, vec![index])),
+        }
+    }
+
+    let mut rows = Vec::new();
+    for (name, group) in groups {
+        let collapsed = collapsed_groups.contains(name);
+        rows.push(ProjectRow::Group {
+            name: name.to_string(),
+            count: group.len(),
+            collapsed,
+        });
+        if !collapsed {
+            rows.extend(group.into_iter().map(ProjectRow::BuildType));
+        }
+    }
+    rows
+}
+
+/// Likely reasons no build configuration is shown, most likely first: `total` configurations were
+/// fetched, `filtered` tells whether a filter is applied and `failed` how many projects failed to
+/// load
+pub fn empty_reasons(total: usize, filtered: bool, failed: usize) -> Vec<&'static str> {
+    let mut reasons = Vec::new();
+    if total > 0 && filtered {
+        reasons.push("No build configuration matches the filter");
+    }
+    if failed > 0 {
+        reasons.push("Projects failed to load, see the errors above");
+    }
+    if total == 0 {
+        reasons.extend([
+            "A project ID in config.toml is wrong, IDs are shown in the project's URL on TeamCity",
+            "The token or user lacks the permission to view the projects",
+            "The projects have no build configurations yet",
+        ]);
+    }
+    reasons
+}
+
+/// Branches containing `input`, ignoring case, with those starting with it first
+pub fn branch_completions<'a>(branches: &'a [String], input: &str) -> Vec<&'a str> {
+    let input = input.to_lowercase();
+    let (mut prefixed, contained): (Vec<&str>, Vec<&str>) = branches
+        .iter()
+        .map(String::as_str)
+        .filter(|branch| branch.to_lowercase().contains(&input))
+        .partition(|branch| branch.to_lowercase().starts_with(&input));
+    prefixed.extend(contained);
+    prefixed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_type(id: &str, name: &str, project: Option<&str>) -> BuildType {
+        BuildType {
+            id: id.to_string(),
+            name: name.to_string(),
+            project_name: project.map(str::to_string),
+            ..BuildType::default()
+        }
+    }
+
+    fn fixtures() -> Vec<BuildType> {
+        vec![
+            build_type("Web_Deploy", "Deploy", Some("Web")),
+            build_type("Api_Build", "Build", Some("Api")),
+            build_type("Web_Build", "Build", Some("Web")),
+            build_type("Api_Tests", "Integration tests", Some("Api")),
+            build_type("Orphan", "Cleanup", None),
+        ]
+    }
+
+    fn query(query: &str) -> FilterQuery {
+        FilterQuery::parse(query, &FILTER_FIELDS).unwrap()
+    }
+
+    fn ids(build_types: &[BuildType], indices: &[usize]) -> Vec<String> {
+        indices.iter().map(|&i| build_types[i].id.clone()).collect()
+    }
+
+    #[test]
+    fn sort_modes_cycle() {
+        let mode = SortMode::default();
+        assert_eq!(mode, SortMode::Project);
+        assert_eq!(mode.next(), SortMode::Name);
+        assert_eq!(mode.next().next(), SortMode::Recent);
+        assert_eq!(mode.next().next().next(), SortMode::Project);
+    }
+
+    #[test]
+    fn filter_matches_names_case_insensitively() {
+        let tests = build_type("Api_Tests", "Integration Tests", Some("Api"));
+
+        assert!(matches_filter(&tests, None));
+        assert!(matches_filter(&tests, Some(&query("tests"))));
+        assert!(matches_filter(&tests, Some(&query(""))));
+        assert!(!matches_filter(&tests, Some(&query("api"))));
+    }
+
+    #[test]
+    fn filter_matches_qualified_fields() {
+        let tests = BuildType {
+            kind: Some("regular".to_string()),
+            ..build_type("Api_Tests", "Integration Tests", Some("Api"))
+        };
+
+        assert!(matches_filter(&tests, Some(&query("project:api"))));
+        assert!(matches_filter(&tests, Some(&query("id:/^api_/ type:reg"))));
+        assert!(!matches_filter(&tests, Some(&query("type:composite"))));
+        assert!(!matches_filter(
+            &build_type("Orphan", "Cleanup", None),
+            Some(&query("type:regular"))
+        ));
+    }
+
+    #[test]
+    fn project_mode_sorts_by_project_then_name() {
+        let build_types = fixtures();
+        let sorted = filter_and_sort(&build_types, None, SortMode::Project, &[]);
+        // configurations without a project sort under "N/A"
+        assert_eq!(
+            ids(&build_types, &sorted),
+            [
+                "Api_Build",
+                "Api_Tests",
+                "Orphan",
+                "Web_Build",
+                "Web_Deploy"
+            ]
+        );
+    }
+
+    #[test]
+    fn name_mode_sorts_by_name_only() {
+        let build_types = fixtures();
+        let sorted = filter_and_sort(&build_types, None, SortMode::Name, &[]);
+        assert_eq!(
+            sorted
+                .iter()
+                .map(|&i| build_types[i].name.as_str())
+                .collect::<Vec<_>>(),
+            ["Build", "Build", "Cleanup", "Deploy", "Integration tests"]
+        );
+    }
+
+    #[test]
+    fn recent_mode_puts_recently_used_first() {
+        let recent = vec!["Web_Deploy".to_string(), "Api_Tests".to_string()];
+        let build_types = fixtures();
+        let sorted = filter_and_sort(&build_types, None, SortMode::Recent, &recent);
+        assert_eq!(
+            ids(&build_types, &sorted),
+            [
+                "Web_Deploy",
+                "Api_Tests",
+                "Api_Build",
+                "Web_Build",
+                "Orphan"
+            ]
+        );
+    }
+
+    #[test]
+    fn filter_applies_before_sorting() {
+        let build_types = fixtures();
+        let sorted = filter_and_sort(&build_types, Some(&query("build")), SortMode::Project, &[]);
+        assert_eq!(ids(&build_types, &sorted), ["Api_Build", "Web_Build"]);
+    }
+
+    #[test]
+    fn project_rows_group_under_headers() {
+        let build_types = fixtures();
+        let sorted = filter_and_sort(&build_types, None, SortMode::Project, &[]);
+        let rows = project_rows(&build_types, &sorted, SortMode::Project, &HashSet::new());
+
+        let headers: Vec<(&str, usize)> = rows
+            .iter()
+            .filter_map(|row| match row {
+                ProjectRow::Group { name, count, .. } => Some((name.as_str(), *count)),
+                ProjectRow::BuildType(_) => None,
+            })
+            .collect();
+        assert_eq!(headers, [("Api", 2), ("N/A", 1), ("Web", 2)]);
+        assert_eq!(rows.len(), 8);
+        // rows point back into the unsorted configurations
+        assert_eq!(rows[1], ProjectRow::BuildType(1));
+    }
+
+    #[test]
+    fn collapsed_projects_keep_only_their_header() {
+        let build_types = fixtures();
+        let sorted = filter_and_sort(&build_types, None, SortMode::Project, &[]);
+        let collapsed = HashSet::from(["Api".to_string()]);
+        let rows = project_rows(&build_types, &sorted, SortMode::Project, &collapsed);
+
+        assert_eq!(
+            rows[0],
+            ProjectRow::Group {
+                name: "Api".to_string(),
+                count: 2,
+                collapsed: true,
+            }
+        );
+        assert!(matches!(&rows[1], ProjectRow::Group { name, .. } if name == "N/A"));
+        assert_eq!(rows.len(), 6);
+    }
+
+    #[test]
+    fn other_modes_have_no_headers() {
+        let build_types = fixtures();
+        let sorted = filter_and_sort(&build_types, None, SortMode::Name, &[]);
+        let rows = project_rows(&build_types, &sorted, SortMode::Name, &HashSet::new());
+        assert!(
+            rows.iter()
+                .all(|row| matches!(row, ProjectRow::BuildType(_)))
+        );
+        assert_eq!(rows.len(), 5);
+    }
+
+    #[test]
+    fn icons_follow_the_configuration_type() {
+        let kind = |kind: Option<&str>| BuildType {
+            kind: kind.map(str::to_string),
+            ..BuildType::default()
+        };
+
+        assert_eq!(icon_for(&kind(Some("regular")), IconSet::Emoji), "⚙️");
+        assert_eq!(icon_for(&kind(Some("COMPOSITE")), IconSet::Emoji), "🧩");
+        assert_eq!(icon_for(&kind(Some("deployment")), IconSet::Emoji), "🚀");
+        assert_eq!(icon_for(&kind(None), IconSet::Emoji), "📦");
+        assert_eq!(icon_for(&kind(Some("composite")), IconSet::Ascii), "C");
+        let paused = BuildType {
+            paused: Some(true),
+            ..kind(Some("regular"))
+        };
+        assert_eq!(icon_for(&paused, IconSet::Ascii), "P");
+    }
+
+    #[test]
+    fn empty_reasons_start_with_the_filter_or_failures() {
+        assert_eq!(
+            empty_reasons(5, true, 0),
+            ["No build configuration matches the filter"]
+        );
+        assert_eq!(
+            empty_reasons(0, false, 1)[0],
+            "Projects failed to load, see the errors above"
+        );
+        assert_eq!(empty_reasons(0, true, 0).len(), 3);
+    }
+
+    #[test]
+    fn branch_completions_match_ignoring_case_with_prefixes_first() {
+        let branches = vec![
+            "main".to_string(),
+            "feature/login".to_string(),
+            "Login-fix".to_string(),
+            "release/1.0".to_string(),
+        ];
+
+        assert_eq!(
+            branch_completions(&branches, "log"),
+            vec!["Login-fix", "feature/login"]
+        );
+        assert_eq!(branch_completions(&branches, "").len(), 4);
+        assert!(branch_completions(&branches, "hotfix").is_empty());
+    }
+}
@@ -0,0 +1,206 @@
+use std::collections::HashSet;
+
+use teamcity::AgentCompatibility;
+use teamcity::types::Build;
+use time::{Duration, OffsetDateTime};
+
+use crate::time::format_relative;
+use crate::view::agent_requirements::{available, is_available};
+use crate::view::builds::{WaitKind, is_queued, wait_kind};
+
+/// Whether `build` is still queued and was queued longer than `threshold` before `now`
+pub fn is_stuck(build: &Build, threshold: Duration, now: OffsetDateTime) -> bool {
+    is_queued(build)
+        && build
+            .queued_date
+            .is_some_and(|queued| now - queued > threshold)
+}
+
+/// Builds of the configuration `build_type_id` in `queue` that are stuck past `threshold` and
+/// not in `reported` yet
+pub fn newly_stuck(
+    queue: &[Build],
+    build_type_id: &str,
+    threshold: Duration,
+    now: OffsetDateTime,
+    reported: &HashSet<i64>,
+) -> Vec<Build> {
+    queue
+        .iter()
+        .filter(|build| build.build_type_id.as_deref() == Some(build_type_id))
+        .filter(|build| is_stuck(build, threshold, now))
+        .filter(|build| build.id.is_some_and(|id| !reported.contains(&id)))
+        .cloned()
+        .collect()
+}
+
+/// Forgets the builds of `reported` that have left `queue`, as they started or were removed
+pub fn forget_dequeued(reported: &mut HashSet<i64>, queue: &[Build]) {
+    reported.retain(|id| queue.iter().any(|build| build.id == Some(*id)));
+}
+
+/// Likely reasons a queued build doesn't start, from its wait reason and the agents of its
+/// configuration if they could be fetched
+pub fn stuck_causes(build: &Build, compatibility: Option<&AgentCompatibility>) -> Vec<String> {
+    let mut causes = Vec::new();
+    if let Some(compatibility) = compatibility {
+        let compatible = &compatibility.compatible;
+        let ready = available(compatible);
+        let idle = compatible
+            .iter()
+            .filter(|agent| is_available(agent) && agent.build.is_none())
+            .count();
+        if compatible.is_empty() {
+            causes.push(format!(
+                "no compatible agents, {} don't meet its requirements",
+                compatibility.incompatible.len()
+            ));
+        } else if ready == 0 {
+            causes.push(format!(
+                "none of the {} compatible agents is connected and enabled",
+                compatible.len()
+            ));
+        } else if idle == 0 {
+            causes.push(format!("all {} compatible agents are busy", ready));
+        }
+    }
+    match wait_kind(build) {
+        Some(WaitKind::Resource) => causes.push("a shared resource is locked".to_string()),
+        Some(WaitKind::Dependency) => {
+            causes.push("its snapshot dependencies haven't finished".to_string())
+        }
+        Some(WaitKind::Agent) | None => {}
+    }
+    if causes.is_empty() {
+        causes.push(
+            build
+                .wait_reason
+                .clone()
+                .unwrap_or_else(|| "the server gives no reason".to_string()),
+        );
+    }
+    causes
+}
+
+/// Notification about a build of the configuration `name` that has been queued for too long
+pub fn stuck_message(name: &str, build: &Build, causes: &[String], now: OffsetDateTime) -> String {
+    let queued = build
+        .queued_date
+        .map(|queued| format!(" {}", format_relative(&queued, &now)))
+        .unwrap_or_default();
+    format!(
+        "{} queued{} hasn't started: {}",
+        name,
+        queued,
+        causes.join("; ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use teamcity::types::Agent;
+    use time::macros::datetime;
+
+    const NOW: OffsetDateTime = datetime!(2025-01-31 10:00 UTC);
+
+    fn queued(minutes_ago: i64, wait_reason: &str) -> Build {
+        Build {
+            state: Some("queued".to_string()),
+            queued_date: Some(NOW - Duration::minutes(minutes_ago)),
+            wait_reason: Some(wait_reason.to_string()),
+            ..Build::default()
+        }
+    }
+
+    fn agent(connected: bool, busy: bool) -> Agent {
+        Agent {
+            connected: Some(connected),
+            build: busy.then(Build::default),
+            ..Agent::default()
+        }
+    }
+
+    fn compatibility(compatible: Vec<Agent>, incompatible: usize) -> AgentCompatibility {
+        AgentCompatibility {
+            compatible,
+            incompatible: vec![agent(true, false); incompatible],
+            requirements: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn builds_are_stuck_once_queued_past_the_threshold() {
+        let threshold = Duration::minutes(30);
+
+        assert!(is_stuck(&queued(45, ""), threshold, NOW));
+        assert!(!is_stuck(&queued(10, ""), threshold, NOW));
+        let started = Build {
+            state: Some("running".to_string()),
+            ..queued(45, "")
+        };
+        assert!(!is_stuck(&started, threshold, NOW));
+    }
+
+    #[test]
+    fn builds_are_reported_once_while_they_are_queued() {
+        let threshold = Duration::minutes(30);
+        let build = |id, build_type_id: &str, minutes_ago| Build {
+            id: Some(id),
+            build_type_id: Some(build_type_id.to_string()),
+            ..queued(minutes_ago, "")
+        };
+        let queue = [
+            build(1, "Deploy", 45),
+            build(2, "Deploy", 10),
+            build(3, "Web_Build", 45),
+        ];
+        let mut reported = HashSet::from([7]);
+
+        let stuck = newly_stuck(&queue, "Deploy", threshold, NOW, &reported);
+        assert_eq!(stuck.iter().map(|b| b.id).collect::<Vec<_>>(), [Some(1)]);
+        reported.insert(1);
+        assert!(newly_stuck(&queue, "Deploy", threshold, NOW, &reported).is_empty());
+
+        forget_dequeued(&mut reported, &queue[1..]);
+        assert!(reported.is_empty());
+    }
+
+    #[test]
+    fn causes_come_from_the_agents_and_the_wait_reason() {
+        let build = queued(45, "Waiting for a compatible agent");
+
+        assert_eq!(
+            stuck_causes(&build, Some(&compatibility(vec![], 3))),
+            ["no compatible agents, 3 don't meet its requirements"]
+        );
+        assert_eq!(
+            stuck_causes(&build, Some(&compatibility(vec![agent(false, false)], 0))),
+            ["none of the 1 compatible agents is connected and enabled"]
+        );
+        assert_eq!(
+            stuck_causes(
+                &build,
+                Some(&compatibility(
+                    vec![agent(true, true), agent(false, false)],
+                    0
+                ))
+            ),
+            ["all 1 compatible agents are busy"]
+        );
+        assert_eq!(
+            stuck_causes(&build, None),
+            ["Waiting for a compatible agent"]
+        );
+
+        let locked = queued(45, "Build is waiting for the shared resource 'db' lock");
+        assert_eq!(
+            stuck_causes(&locked, Some(&compatibility(vec![agent(true, false)], 0))),
+            ["a shared resource is locked"]
+        );
+        assert_eq!(
+            stuck_message("Deploy", &locked, &stuck_causes(&locked, None), NOW),
+            "Deploy queued 45m ago hasn't started: a shared resource is locked"
+        );
+    }
+}
@@ -0,0 +1,155 @@
+use teamcity::types::Agent;
+use time::OffsetDateTime;
+
+// pool of agents the server reports without one, e.g. unauthorized cloud agents
+const NO_POOL: &str = "(no pool)";
+
+/// Agents of a pool by state
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PoolSummary {
+    pub name: String,
+    pub agents: usize,
+    pub connected: usize,
+    // connected agents running a build
+    pub busy: usize,
+    pub disabled: usize,
+    pub unauthorized: usize,
+}
+
+impl PoolSummary {
+    /// Connected and enabled agents waiting for a build
+    pub fn idle(&self) -> usize {
+        self.connected - self.busy
+    }
+}
+
+/// State of an agent: unauthorized, disabled, disconnected, busy or idle
+pub fn agent_state(agent: &Agent) -> &'static str {
+    if agent.authorized == Some(false) {
+        "unauthorized"
+    } else if agent.enabled == Some(false) {
+        "disabled"
+    } else if agent.connected != Some(true) {
+        "disconnected"
+    } else if agent.build.is_some() {
+        "busy"
+    } else {
+        "idle"
+    }
+}
+
+/// Summary of each agent pool, ordered by name
+pub fn pool_summaries(agents: &[Agent]) -> Vec<PoolSummary> {
+    let mut pools: Vec<PoolSummary> = Vec::new();
+    for agent in agents {
+        let name = agent
+            .pool
+            .as_ref()
+            .and_then(|pool| pool.name.as_deref())
+            .unwrap_or(NO_POOL);
+        let pool = match pools.iter().position(|pool| pool.name == name) {
+            Some(i) => &mut pools[i],
+            None => {
+                pools.push(PoolSummary {
+                    name: name.to_string(),
+                    ..PoolSummary::default()
+                });
+                pools.last_mut().unwrap()
+            }
+        };
+        pool.agents += 1;
+        if agent.authorized == Some(false) {
+            pool.unauthorized += 1;
+        } else if agent.enabled == Some(false) {
+            pool.disabled += 1;
+        } else if agent.connected == Some(true) {
+            pool.connected += 1;
+            if agent.build.is_some() {
+                pool.busy += 1;
+            }
+        }
+    }
+    pools.sort_by(|a, b| a.name.cmp(&b.name));
+    pools
+}
+
+/// Time the server has been running, e.g. "12d 4h" or "3h 15m"
+pub fn format_uptime(start: &OffsetDateTime, now: &OffsetDateTime) -> String {
+    let secs = (*now - *start).whole_seconds().max(0);
+    let (days, hours, minutes) = (secs / 86_400, secs % 86_400 / 3_600, secs % 3_600 / 60);
+    match (days, hours) {
+        (0, 0) => format!("{}m", minutes),
+        (0, h) => format!("{}h {}m", h, minutes),
+        (d, h) => format!("{}d {}h", d, h),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use teamcity::types::{Build, EntityRef};
+    use time::macros::datetime;
+
+    fn agent(pool: Option<&str>, connected: bool, enabled: bool, busy: bool) -> Agent {
+        Agent {
+            connected: Some(connected),
+            enabled: Some(enabled),
+            authorized: Some(true),
+            build: busy.then(Build::default),
+            pool: pool.map(|name| EntityRef {
+                id: None,
+                name: Some(name.to_string()),
+            }),
+            ..Agent::default()
+        }
+    }
+
+    #[test]
+    fn agents_are_summarized_by_pool() {
+        let agents = [
+            agent(Some("Linux"), true, true, true),
+            agent(Some("Linux"), true, true, false),
+            agent(Some("Linux"), false, true, false),
+            agent(Some("Default"), true, false, false),
+            Agent {
+                authorized: Some(false),
+                ..agent(None, true, true, false)
+            },
+        ];
+        let pools = pool_summaries(&agents);
+
+        let names: Vec<&str> = pools.iter().map(|pool| pool.name.as_str()).collect();
+        assert_eq!(names, vec![NO_POOL, "Default", "Linux"]);
+        assert_eq!(pools[0].unauthorized, 1);
+        assert_eq!(pools[1].disabled, 1);
+        assert_eq!(
+            pools[2],
+            PoolSummary {
+                name: "Linux".to_string(),
+                agents: 3,
+                connected: 2,
+                busy: 1,
+                disabled: 0,
+                unauthorized: 0,
+            }
+        );
+        assert_eq!(pools[2].idle(), 1);
+    }
+
+    #[test]
+    fn uptime_shows_the_two_largest_units() {
+        let start = datetime!(2025-01-01 00:00 UTC);
+        assert_eq!(
+            format_uptime(&start, &datetime!(2025-01-01 00:42 UTC)),
+            "42m"
+        );
+        assert_eq!(
+            format_uptime(&start, &datetime!(2025-01-01 03:15 UTC)),
+            "3h 15m"
+        );
+        assert_eq!(
+            format_uptime(&start, &datetime!(2025-01-13 04:59 UTC)),
+            "12d 4h"
+        );
+    }
+}
@@ -0,0 +1,72 @@
+use teamcity::types::Project;
+
+// TeamCity's hidden root project, every other project is below it
+const ROOT_PROJECT_ID: &str = "_Root";
+
+/// Server URL as typed in the setup wizard, without surrounding spaces and trailing slashes
+pub fn normalize_url(input: &str) -> String {
+    input.trim().trim_end_matches('/').to_string()
+}
+
+/// Projects offered in the setup wizard, with the nesting depth of each below the root project
+pub fn project_choices(projects: &[Project]) -> Vec<(usize, &Project)> {
+    projects
+        .iter()
+        .filter(|project| project.id != ROOT_PROJECT_ID)
+        .map(|project| (depth(projects, project), project))
+        .collect()
+}
+
+fn depth(projects: &[Project], project: &Project) -> usize {
+    let mut depth = 0;
+    let mut parent = project.parent_project_id.as_deref();
+    while let Some(id) = parent.filter(|id| *id != ROOT_PROJECT_ID) {
+        depth += 1;
+        // guards against parent cycles in a malformed response
+        if depth > projects.len() {
+            break;
+        }
+        parent = projects
+            .iter()
+            .find(|p| p.id == id)
+            .and_then(|p| p.parent_project_id.as_deref());
+    }
+    depth
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(id: &str, parent: Option<&str>) -> Project {
+        Project {
+            id: id.to_string(),
+            name: id.to_string(),
+            parent_project_id: parent.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn normalize_url_trims_spaces_and_slashes() {
+        assert_eq!(
+            normalize_url(" https://ci.example.com// "),
+            "https://ci.example.com"
+        );
+    }
+
+    #[test]
+    fn project_choices_skip_root_and_nest_subprojects() {
+        let projects = vec![
+            project("_Root", None),
+            project("Web", Some("_Root")),
+            project("Web_Frontend", Some("Web")),
+            project("Api", Some("_Root")),
+        ];
+
+        let choices: Vec<(usize, &str)> = project_choices(&projects)
+            .into_iter()
+            .map(|(depth, project)| (depth, project.id.as_str()))
+            .collect();
+        assert_eq!(choices, vec![(0, "Web"), (1, "Web_Frontend"), (0, "Api")]);
+    }
+}
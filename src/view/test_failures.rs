@@ -0,0 +1,107 @@
+use regex::Regex;
+use teamcity::types::TestOccurrence;
+
+/// Team of the first of `owners`, each a test name pattern with its team, matching the name of
+/// `test`
+pub fn owning_team<'a>(owners: &'a [(Regex, String)], test: &TestOccurrence) -> Option<&'a str> {
+    let name = test.name.as_deref()?;
+    owners
+        .iter()
+        .find(|(pattern, _)| pattern.is_match(name))
+        .map(|(_, team)| team.as_str())
+}
+
+/// Teams owning `tests` in alphabetical order, then None if some tests have no owner
+pub fn teams(owners: &[(Regex, String)], tests: &[TestOccurrence]) -> Vec<Option<String>> {
+    let mut teams: Vec<Option<String>> = tests
+        .iter()
+        .map(|test| owning_team(owners, test).map(str::to_string))
+        .collect();
+    // Option sorts None first, the unowned tests go last instead
+    teams.sort_by(|a, b| (a.is_none(), a).cmp(&(b.is_none(), b)));
+    teams.dedup();
+    teams
+}
+
+/// Tests of `tests` owned by `team`, or those without an owner if None
+pub fn tests_of_team(
+    owners: &[(Regex, String)],
+    tests: &[TestOccurrence],
+    team: Option<&str>,
+) -> Vec<TestOccurrence> {
+    tests
+        .iter()
+        .filter(|test| owning_team(owners, test) == team)
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn owners() -> Vec<(Regex, String)> {
+        [
+            ("^com\\.acme\\.payments\\.refunds", "Refunds"),
+            ("^com\\.acme\\.payments", "Payments"),
+            ("Checkout", "Web"),
+        ]
+        .into_iter()
+        .map(|(pattern, team)| (Regex::new(pattern).unwrap(), team.to_string()))
+        .collect()
+    }
+
+    fn test(name: &str) -> TestOccurrence {
+        TestOccurrence {
+            name: Some(name.to_string()),
+            ..TestOccurrence::default()
+        }
+    }
+
+    #[test]
+    fn the_first_matching_pattern_owns_a_test() {
+        let owners = owners();
+
+        assert_eq!(
+            owning_team(
+                &owners,
+                &test("com.acme.payments.refunds.RefundTest.partial")
+            ),
+            Some("Refunds")
+        );
+        assert_eq!(
+            owning_team(&owners, &test("com.acme.payments.CardTest.charge")),
+            Some("Payments")
+        );
+        assert_eq!(
+            owning_team(&owners, &test("e2e: Checkout with a saved card")),
+            Some("Web")
+        );
+        assert_eq!(
+            owning_team(&owners, &test("com.acme.search.IndexTest")),
+            None
+        );
+        assert_eq!(owning_team(&owners, &TestOccurrence::default()), None);
+    }
+
+    #[test]
+    fn failures_are_filtered_by_team_with_the_unowned_ones_last() {
+        let owners = owners();
+        let tests = [
+            test("e2e: Checkout"),
+            test("com.acme.search.IndexTest"),
+            test("com.acme.payments.CardTest"),
+            test("e2e: Checkout with a saved card"),
+        ];
+
+        assert_eq!(
+            teams(&owners, &tests),
+            [Some("Payments".to_string()), Some("Web".to_string()), None]
+        );
+        assert_eq!(tests_of_team(&owners, &tests, Some("Web")).len(), 2);
+        assert_eq!(
+            tests_of_team(&owners, &tests, None),
+            [test("com.acme.search.IndexTest")]
+        );
+    }
+}
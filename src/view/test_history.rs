@@ -0,0 +1,140 @@
+use crate::icons::IconSet;
+use teamcity::types::TestOccurrence;
+
+// status changes between consecutive builds from which a test counts as flaky
+const FLAKY_FLIPS: usize = 3;
+
+/// Result of a test in one build
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Passed,
+    Failed,
+    // ignored or not run, doesn't count as a pass or failure
+    Ignored,
+}
+
+impl Outcome {
+    /// Symbol of the outcome in the pass/fail strip of the history view
+    pub fn symbol(self, icons: IconSet) -> &'static str {
+        match self {
+            Outcome::Passed => icons.mark(),
+            Outcome::Failed => icons.pick("✗", "\u{f00d}", "x"),
+            Outcome::Ignored => icons.pick("·", "\u{f068}", "."),
+        }
+    }
+}
+
+pub fn outcome(occurrence: &TestOccurrence) -> Outcome {
+    match occurrence.status.as_deref() {
+        Some("SUCCESS") => Outcome::Passed,
+        Some("FAILURE") => Outcome::Failed,
+        _ => Outcome::Ignored,
+    }
+}
+
+/// Passes, failures and status changes of a test over its recent builds
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct HistorySummary {
+    pub passed: usize,
+    pub failed: usize,
+    // changes between passing and failing, ignored runs in between are skipped
+    pub flips: usize,
+}
+
+impl HistorySummary {
+    /// Whether the test keeps switching between passing and failing, rather than broke or got fixed
+    /// once
+    pub fn is_flaky(&self) -> bool {
+        self.flips >= FLAKY_FLIPS
+    }
+}
+
+/// Occurrences of a test ordered by their build, oldest first
+pub fn chronological(mut history: Vec<TestOccurrence>) -> Vec<TestOccurrence> {
+    history.sort_by_key(|occurrence| occurrence.build.as_ref().and_then(|b| b.id));
+    history
+}
+
+/// Summary of `history`, which is ordered oldest first
+pub fn summarize(history: &[TestOccurrence]) -> HistorySummary {
+    let mut summary = HistorySummary::default();
+    let mut previous = None;
+    for outcome in history.iter().map(outcome) {
+        match outcome {
+            Outcome::Passed => summary.passed += 1,
+            Outcome::Failed => summary.failed += 1,
+            Outcome::Ignored => continue,
+        }
+        if previous.is_some_and(|previous| previous != outcome) {
+            summary.flips += 1;
+        }
+        previous = Some(outcome);
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use teamcity::types::Build;
+
+    fn occurrence(build_id: i64, status: &str) -> TestOccurrence {
+        TestOccurrence {
+            status: Some(status.to_string()),
+            build: Some(Build {
+                id: Some(build_id),
+                ..Build::default()
+            }),
+            ..TestOccurrence::default()
+        }
+    }
+
+    #[test]
+    fn chronological_orders_by_build() {
+        let history = chronological(vec![
+            occurrence(3, "SUCCESS"),
+            occurrence(1, "FAILURE"),
+            occurrence(2, "SUCCESS"),
+        ]);
+        let ids: Vec<_> = history
+            .iter()
+            .map(|o| o.build.as_ref().and_then(|b| b.id))
+            .collect();
+        assert_eq!(ids, vec![Some(1), Some(2), Some(3)]);
+    }
+
+    #[test]
+    fn summarize_counts_flips_across_ignored_runs() {
+        let history = [
+            occurrence(1, "SUCCESS"),
+            occurrence(2, "FAILURE"),
+            occurrence(3, "UNKNOWN"),
+            occurrence(4, "FAILURE"),
+            occurrence(5, "SUCCESS"),
+        ];
+        assert_eq!(
+            summarize(&history),
+            HistorySummary {
+                passed: 2,
+                failed: 2,
+                flips: 2,
+            }
+        );
+        assert!(!summarize(&history).is_flaky());
+    }
+
+    #[test]
+    fn alternating_results_are_flaky() {
+        let history: Vec<_> = (0..6)
+            .map(|i| occurrence(i, if i % 2 == 0 { "SUCCESS" } else { "FAILURE" }))
+            .collect();
+        assert!(summarize(&history).is_flaky());
+
+        let broken_once = [
+            occurrence(1, "SUCCESS"),
+            occurrence(2, "FAILURE"),
+            occurrence(3, "FAILURE"),
+        ];
+        assert!(!summarize(&broken_once).is_flaky());
+    }
+}
@@ -0,0 +1,371 @@
+//! Headless `watch` mode: a line on stdout for each build of the watched configurations that is
+//! queued, starts or finishes, as text or newline-delimited JSON
+
+use std::collections::HashMap;
+use std::io::{self, ErrorKind, Write};
+use std::time::Duration;
+
+use color_eyre::Result;
+use color_eyre::eyre::eyre;
+use serde::Serialize;
+use teamcity::TeamCityClient;
+use teamcity::types::Build;
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+
+use crate::view::favorites::{is_changed, state_label};
+
+// finished builds are fetched back this much further than the previous poll, the builds seen then
+// aren't reported again
+const CLOCK_MARGIN: time::Duration = time::Duration::minutes(1);
+
+/// Build of a watched configuration that is new or changed state since the previous poll
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BuildEvent {
+    // poll that saw the change, in RFC 3339
+    pub time: String,
+    pub build_type_id: String,
+    pub build_id: Option<i64>,
+    pub number: Option<String>,
+    pub branch: Option<String>,
+    // queued, running or finished
+    pub state: Option<String>,
+    pub status: Option<String>,
+    pub status_text: Option<String>,
+    pub web_url: Option<String>,
+    // the build at the previous poll, empty if it is new
+    pub previous_build_id: Option<i64>,
+    pub previous_state: Option<String>,
+    pub previous_status: Option<String>,
+}
+
+impl BuildEvent {
+    fn new(build: &Build, previous: Option<&Build>, time: OffsetDateTime) -> Self {
+        Self {
+            time: time.format(&Rfc3339).unwrap_or_default(),
+            build_type_id: build.build_type_id.clone().unwrap_or_default(),
+            build_id: build.id,
+            number: build.build_number.clone(),
+            branch: build.branch_name.clone(),
+            state: build.state.clone(),
+            status: build.status.clone(),
+            status_text: build.status_text.clone(),
+            web_url: build.web_url.clone(),
+            previous_build_id: previous.and_then(|build| build.id),
+            previous_state: previous.and_then(|build| build.state.clone()),
+            previous_status: previous.and_then(|build| build.status.clone()),
+        }
+    }
+
+    /// One line of text like `Web_Build #12 running -> FAILURE`, states given as in the TUI
+    pub fn text(&self) -> String {
+        let label = |state: &Option<String>, status: &Option<String>| {
+            state_label(&Build {
+                state: state.clone(),
+                status: status.clone(),
+                ..Build::default()
+            })
+            .to_string()
+        };
+        let mut line = format!(
+            "{} {} #{} {} -> {}",
+            self.time,
+            self.build_type_id,
+            self.number.as_deref().unwrap_or("?"),
+            if self.previous_build_id == self.build_id {
+                label(&self.previous_state, &self.previous_status)
+            } else {
+                "new".to_string()
+            },
+            label(&self.state, &self.status)
+        );
+        if let Some(branch) = &self.branch {
+            line.push_str(&format!(" on {}", branch));
+        }
+        if let Some(url) = &self.web_url {
+            line.push_str(&format!(" {}", url));
+        }
+        line
+    }
+}
+
+/// Events for the builds of `polled` that are new or changed state since `previous`, the builds
+/// by configuration ID of the previous polls. Configurations polled for the first time have none,
+/// so the first poll stays silent. Events are in the order of `build_types`, oldest build first,
+/// so a build finishing comes before the next one queued in the same poll.
+pub fn build_events(
+    previous: &HashMap<String, Vec<Build>>,
+    polled: &HashMap<String, Vec<Build>>,
+    build_types: &[String],
+    time: OffsetDateTime,
+) -> Vec<BuildEvent> {
+    let mut events = Vec::new();
+    for build_type_id in build_types {
+        let (Some(before), Some(builds)) = (previous.get(build_type_id), polled.get(build_type_id))
+        else {
+            continue;
+        };
+        let mut builds: Vec<&Build> = builds.iter().collect();
+        builds.sort_by_key(|build| build.id);
+        for build in builds {
+            let known = before
+                .iter()
+                .find(|known| known.id.is_some() && known.id == build.id);
+            if known.is_some_and(|known| !is_changed(known, build)) {
+                continue;
+            }
+            events.push(BuildEvent::new(build, known, time));
+        }
+    }
+    events
+}
+
+/// Builds by configuration ID after a poll that returned `polled`. A configuration missing from
+/// the poll keeps the builds it had, as its fetch may have failed.
+fn polled_builds(
+    mut previous: HashMap<String, Vec<Build>>,
+    polled: HashMap<String, Vec<Build>>,
+) -> HashMap<String, Vec<Build>> {
+    previous.extend(polled);
+    previous
+}
+
+fn print_event(out: &mut impl Write, event: &BuildEvent, json: bool) -> Result<()> {
+    if json {
+        writeln!(out, "{}", serde_json::to_string(event)?)?;
+    } else {
+        writeln!(out, "{}", event.text())?;
+    }
+    Ok(())
+}
+
+/// Whether `e` comes from writing to a pipe whose reader went away, like `head` once it has its
+/// lines
+fn is_broken_pipe(e: &color_eyre::Report) -> bool {
+    e.downcast_ref::<io::Error>()
+        .is_some_and(|e| e.kind() == ErrorKind::BrokenPipe)
+}
+
+/// Prints the events of `build_types`, or of the configurations of `projects` if none are given,
+/// polling the server every `interval` until the process is stopped or stdout is closed
+pub async fn run(
+    client: TeamCityClient,
+    projects: Vec<String>,
+    build_types: Vec<String>,
+    interval: Duration,
+    json: bool,
+) -> Result<()> {
+    let build_types = if build_types.is_empty() {
+        let loaded = client
+            .get_build_configurations_by_projects(&projects)
+            .await?;
+        if let Some(summary) = loaded.failure_summary(projects.len()) {
+            eprintln!("{}", summary);
+        }
+        loaded.build_types.into_iter().map(|bt| bt.id).collect()
+    } else {
+        build_types
+    };
+    if build_types.is_empty() {
+        return Err(eyre!(
+            "No build configurations to watch, pass --build-type or monitor a project"
+        ));
+    }
+    // stdout is kept for the events
+    eprintln!(
+        "Watching {} build configurations on {}, polling every {}s",
+        build_types.len(),
+        client.server_name(),
+        interval.as_secs()
+    );
+
+    let mut ticks = tokio::time::interval(interval);
+    let mut previous = HashMap::new();
+    let mut last_poll = OffsetDateTime::now_utc();
+    loop {
+        ticks.tick().await;
+        let now = OffsetDateTime::now_utc();
+        // builds finished since the previous poll, with a margin for the server clock
+        let finished_since = last_poll - CLOCK_MARGIN;
+        let polled = match client.get_active_builds(&build_types, finished_since).await {
+            Ok(polled) => polled,
+            Err(e) => {
+                eprintln!("Failed to poll {}: {}", client.server_name(), e);
+                continue;
+            }
+        };
+        last_poll = now;
+        let mut out = io::stdout().lock();
+        for event in build_events(&previous, &polled, &build_types, now) {
+            match print_event(&mut out, &event, json) {
+                Err(e) if is_broken_pipe(&e) => return Ok(()),
+                result => result?,
+            }
+        }
+        previous = polled_builds(previous, polled);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    fn build(id: i64, state: &str, status: &str) -> Build {
+        Build {
+            id: Some(id),
+            build_type_id: Some("Web_Build".to_string()),
+            build_number: Some(id.to_string()),
+            state: Some(state.to_string()),
+            status: Some(status.to_string()),
+            ..Build::default()
+        }
+    }
+
+    fn builds(builds: Vec<Build>) -> HashMap<String, Vec<Build>> {
+        HashMap::from([("Web_Build".to_string(), builds)])
+    }
+
+    fn build_types() -> [String; 1] {
+        ["Web_Build".to_string()]
+    }
+
+    #[test]
+    fn events_carry_the_build_before_the_change() {
+        let previous = builds(vec![build(12, "running", "SUCCESS")]);
+        let polled = builds(vec![Build {
+            branch_name: Some("main".to_string()),
+            ..build(12, "finished", "FAILURE")
+        }]);
+
+        let events = build_events(
+            &previous,
+            &polled,
+            &build_types(),
+            datetime!(2025-01-31 09:05:12 UTC),
+        );
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(
+            serde_json::to_string(&events[0]).unwrap(),
+            "{\"time\":\"2025-01-31T09:05:12Z\",\"build_type_id\":\"Web_Build\",\"build_id\":12,\
+             \"number\":\"12\",\"branch\":\"main\",\"state\":\"finished\",\"status\":\"FAILURE\",\
+             \"status_text\":null,\"web_url\":null,\"previous_build_id\":12,\
+             \"previous_state\":\"running\",\"previous_status\":\"SUCCESS\"}"
+        );
+        assert_eq!(
+            events[0].text(),
+            "2025-01-31T09:05:12Z Web_Build #12 running -> FAILURE on main"
+        );
+        assert!(
+            build_events(
+                &previous,
+                &builds(vec![build(12, "running", "SUCCESS")]),
+                &build_types(),
+                OffsetDateTime::UNIX_EPOCH
+            )
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn a_build_finishing_is_reported_with_the_next_one_queued_in_the_same_poll() {
+        let previous = builds(vec![build(12, "running", "SUCCESS")]);
+        let polled = builds(vec![
+            build(13, "queued", "UNKNOWN"),
+            build(12, "finished", "FAILURE"),
+        ]);
+
+        let events = build_events(
+            &previous,
+            &polled,
+            &build_types(),
+            OffsetDateTime::UNIX_EPOCH,
+        );
+
+        assert_eq!(
+            events.iter().map(BuildEvent::text).collect::<Vec<_>>(),
+            [
+                "1970-01-01T00:00:00Z Web_Build #12 running -> FAILURE",
+                "1970-01-01T00:00:00Z Web_Build #13 new -> queued"
+            ]
+        );
+        assert_eq!(events[1].previous_build_id, None);
+    }
+
+    #[test]
+    fn builds_started_and_finished_between_polls_are_new() {
+        let previous = builds(vec![build(12, "finished", "SUCCESS")]);
+        let polled = builds(vec![
+            build(12, "finished", "SUCCESS"),
+            build(13, "finished", "FAILURE"),
+        ]);
+
+        let events = build_events(
+            &previous,
+            &polled,
+            &build_types(),
+            OffsetDateTime::UNIX_EPOCH,
+        );
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(
+            events[0].text(),
+            "1970-01-01T00:00:00Z Web_Build #13 new -> FAILURE"
+        );
+    }
+
+    #[test]
+    fn the_first_build_of_a_configuration_is_new_but_the_first_poll_is_silent() {
+        let polled = builds(vec![build(1, "queued", "UNKNOWN")]);
+        assert!(
+            build_events(
+                &HashMap::new(),
+                &polled,
+                &build_types(),
+                OffsetDateTime::UNIX_EPOCH
+            )
+            .is_empty()
+        );
+        let previous = polled_builds(HashMap::new(), builds(Vec::new()));
+
+        let events = build_events(
+            &previous,
+            &polled,
+            &build_types(),
+            OffsetDateTime::UNIX_EPOCH,
+        );
+
+        assert_eq!(
+            events[0].text(),
+            "1970-01-01T00:00:00Z Web_Build #1 new -> queued"
+        );
+        let previous = polled_builds(previous, polled);
+        let previous = polled_builds(previous, HashMap::new());
+        assert_eq!(previous["Web_Build"][0].id, Some(1));
+    }
+
+    #[test]
+    fn events_stop_once_stdout_is_closed() {
+        struct Closed;
+        impl Write for Closed {
+            fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+                Err(ErrorKind::BrokenPipe.into())
+            }
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+        let event = &build_events(
+            &builds(Vec::new()),
+            &builds(vec![build(1, "queued", "UNKNOWN")]),
+            &build_types(),
+            OffsetDateTime::UNIX_EPOCH,
+        )[0];
+
+        let e = print_event(&mut Closed, event, false).unwrap_err();
+
+        assert!(is_broken_pipe(&e));
+        assert!(!is_broken_pipe(&eyre!("Failed to poll")));
+    }
+}